@@ -9,9 +9,11 @@ import (
 	"time"
 
 	"github.com/chzyer/readline"
+	"github.com/pelletier/go-toml/v2"
 	"github.com/scottgl9/thop/internal/config"
 	"github.com/scottgl9/thop/internal/logger"
 	"github.com/scottgl9/thop/internal/session"
+	"github.com/scottgl9/thop/internal/sshconfig"
 	"github.com/scottgl9/thop/internal/state"
 )
 
@@ -40,31 +42,50 @@ type App struct {
 	configPath     string
 	proxyMode      bool
 	proxyCommand   string // Command to execute in proxy mode (-c flag)
+	rawCommand     bool   // Run the -c command verbatim, without thop's wrapping
 	mcpMode        bool   // Run as MCP server
+	tuiMode        bool   // Run the full-screen dashboard (--tui)
 	restrictedMode bool   // Restrict dangerous/destructive operations for AI agents
+	restrictWarn   bool   // Log restricted operations instead of blocking them
 	jsonOutput     bool
 	showStatus     bool
+	dumpConfig     bool   // Print the effective config and exit (--dump-config)
 	completions    string // Shell name for completions
 	verbose        bool
 	quiet          bool
+	echoCommands   bool   // Print each command to stderr before running it (--echo, /echo)
+	fixPermissions bool   // chmod config and state files to 0600 (--fix-permissions)
+	importSSH      bool   // Add sessions for the hosts in ~/.ssh/config (--import-ssh-config)
+	runAs          string // Run commands on every session as this user via sudo -u (--as)
 
 	// readline instance for interactive mode (nil when not in interactive mode)
 	rl *readline.Instance
 
+	// Terminal output shared by the interactive loop and background jobs
+	term   *terminalOutput
+	termMu sync.Mutex
+
 	// Background job tracking
 	bgJobs    map[int]*BackgroundJob
 	bgJobsMu  sync.RWMutex
 	nextJobID int
+
+	// Baseline outputs for /watch-diff, keyed by session and command
+	watchBaselines map[string]string
+
+	// Commands entered in interactive mode on any session, oldest first
+	history []historyEntry
 }
 
 // NewApp creates a new App instance
 func NewApp(version, commit, buildTime string) *App {
 	return &App{
-		Version:   version,
-		GitCommit: commit,
-		BuildTime: buildTime,
-		bgJobs:    make(map[int]*BackgroundJob),
-		nextJobID: 1,
+		Version:        version,
+		GitCommit:      commit,
+		BuildTime:      buildTime,
+		bgJobs:         make(map[int]*BackgroundJob),
+		nextJobID:      1,
+		watchBaselines: make(map[string]string),
 	}
 }
 
@@ -87,6 +108,9 @@ func (a *App) Run(args []string) error {
 	}
 	a.config = cfg
 
+	// Proxy and MCP modes use automated_timeout, interactive mode interactive_timeout
+	cfg.SetAutomated(a.proxyMode || a.mcpMode)
+
 	// Initialize logger
 	logLevel := cfg.Settings.LogLevel
 	if a.verbose {
@@ -102,27 +126,51 @@ func (a *App) Run(args []string) error {
 	}); err != nil {
 		// Non-fatal, continue without file logging
 		if a.verbose {
-			fmt.Fprintf(os.Stderr, "Warning: failed to initialize logger: %v\n", err)
+			fmt.Fprintf(a.terminal().Stderr(), "Warning: failed to initialize logger: %v\n", err)
 		}
 	}
 
 	logger.Info("thop starting, version=%s", a.Version)
 	logger.Debug("config loaded from %s", a.configPath)
 
+	if a.fixPermissions {
+		return a.fixFilePermissions()
+	}
+	a.checkFilePermissions()
+
+	if a.importSSH || cfg.Settings.ImportSSHConfig {
+		a.importSSHSessions()
+	}
+
+	if a.dumpConfig {
+		return a.printConfig()
+	}
+
 	// Initialize state manager
 	a.state = state.NewManager(cfg.Settings.StateFile)
 	if err := a.state.Load(); err != nil {
 		// Non-fatal, continue with defaults
 		logger.Warn("failed to load state: %v", err)
 		if a.verbose {
-			fmt.Fprintf(os.Stderr, "Warning: failed to load state: %v\n", err)
+			fmt.Fprintf(a.terminal().Stderr(), "Warning: failed to load state: %v\n", err)
 		}
 	}
 
 	// Initialize session manager
 	a.sessions = session.NewManager(cfg, a.state)
-	a.sessions.SetRestrictedMode(a.restrictedMode)
-	logger.Debug("session manager initialized with %d sessions, restricted=%v", len(cfg.Sessions), a.restrictedMode)
+	a.sessions.SetRestrictedMode(a.restrictedMode || a.restrictWarn)
+	// --restricted takes precedence over --restrict-warn when both are given
+	a.sessions.SetRestrictionWarnOnly(a.restrictWarn && !a.restrictedMode)
+	logger.Debug("session manager initialized with %d sessions, restricted=%v, restrict_warn=%v", len(cfg.Sessions), a.restrictedMode, a.restrictWarn)
+
+	// --as overrides run_as on every session for this invocation
+	if a.runAs != "" {
+		for _, name := range a.sessions.SessionNames() {
+			if err := a.sessions.SetRunAs(name, a.runAs); err != nil {
+				return err
+			}
+		}
+	}
 
 	// Handle special flags
 	if a.showStatus {
@@ -134,11 +182,77 @@ func (a *App) Run(args []string) error {
 		return a.runMCP()
 	} else if a.proxyMode {
 		return a.runProxy()
+	} else if a.tuiMode {
+		return a.runTUI()
 	}
 
 	return a.runInteractive()
 }
 
+// importSSHSessions adds a session for every host in ~/.ssh/config that the
+// config doesn't already cover
+func (a *App) importSSHSessions() {
+	sshCfg, err := sshconfig.Load()
+	if err != nil {
+		logger.Warn("failed to read ~/.ssh/config: %v", err)
+		return
+	}
+	added := a.config.ImportSSHConfig(sshCfg)
+	logger.Debug("imported %d session(s) from ~/.ssh/config: %v", len(added), added)
+}
+
+// savedConfigPath returns the config file in use, which session changes
+// such as /add-session are saved to
+func (a *App) savedConfigPath() string {
+	if a.configPath != "" {
+		return a.configPath
+	}
+	return config.DefaultConfigPath()
+}
+
+// protectedFiles returns the config and state file paths, which may hold
+// hostnames, users and environment values
+func (a *App) protectedFiles() []string {
+	return []string{a.savedConfigPath(), a.config.Settings.StateFile}
+}
+
+// checkFilePermissions warns about config and state files that are readable
+// or writable by other users
+func (a *App) checkFilePermissions() {
+	for _, path := range a.protectedFiles() {
+		mode, insecure, err := config.CheckPermissions(path)
+		if err != nil {
+			logger.Debug("failed to check permissions on %s: %v", path, err)
+			continue
+		}
+		if !insecure {
+			continue
+		}
+		logger.Warn("%s has mode %04o and is accessible by other users", path, mode)
+		if !a.quiet {
+			fmt.Fprintf(a.terminal().Stderr(), "Warning: %s has mode %04o and is accessible by other users; run thop --fix-permissions to restrict it to 0600\n", path, mode)
+		}
+	}
+}
+
+// fixFilePermissions restricts the config and state files to mode 0600
+func (a *App) fixFilePermissions() error {
+	for _, path := range a.protectedFiles() {
+		mode, insecure, err := config.CheckPermissions(path)
+		if err != nil {
+			return fmt.Errorf("failed to check permissions on %s: %w", path, err)
+		}
+		if !insecure {
+			continue
+		}
+		if err := config.FixPermissions(path); err != nil {
+			return err
+		}
+		a.printf("%s: %04o -> %04o\n", path, mode, config.SecureFileMode)
+	}
+	return nil
+}
+
 // parseFlags parses command line flags
 func (a *App) parseFlags(args []string) error {
 	flags := flag.NewFlagSet("thop", flag.ContinueOnError)
@@ -148,9 +262,13 @@ func (a *App) parseFlags(args []string) error {
 
 	flags.BoolVar(&a.proxyMode, "proxy", false, "Run in proxy mode (for AI agents)")
 	flags.BoolVar(&a.mcpMode, "mcp", false, "Run as MCP server")
+	flags.BoolVar(&a.tuiMode, "tui", false, "Run the full-screen session dashboard")
 	flags.BoolVar(&a.restrictedMode, "restricted", false, "Restrict dangerous/destructive operations (for AI agents)")
+	flags.BoolVar(&a.restrictWarn, "restrict-warn", false, "Log dangerous/destructive operations without blocking them")
 	flags.StringVar(&a.proxyCommand, "c", "", "Execute command (for shell compatibility)")
+	flags.BoolVar(&a.rawCommand, "raw", false, "Run the -c command verbatim, without cwd/env wrapping")
 	flags.BoolVar(&a.showStatus, "status", false, "Show status and exit")
+	flags.BoolVar(&a.dumpConfig, "dump-config", false, "Print the effective config and exit")
 	flags.StringVar(&a.configPath, "config", "", "Path to config file")
 	flags.BoolVar(&a.jsonOutput, "json", false, "Output in JSON format")
 	flags.StringVar(&a.completions, "completions", "", "Generate shell completions (bash, zsh, fish)")
@@ -158,6 +276,10 @@ func (a *App) parseFlags(args []string) error {
 	flags.BoolVar(&a.verbose, "verbose", false, "Verbose output")
 	flags.BoolVar(&a.quiet, "q", false, "Quiet output")
 	flags.BoolVar(&a.quiet, "quiet", false, "Quiet output")
+	flags.BoolVar(&a.echoCommands, "echo", false, "Print each command to stderr before running it")
+	flags.BoolVar(&a.fixPermissions, "fix-permissions", false, "Restrict config and state files to mode 0600 and exit")
+	flags.BoolVar(&a.importSSH, "import-ssh-config", false, "Add a session for every host in ~/.ssh/config")
+	flags.StringVar(&a.runAs, "as", "", "Run commands as this user via sudo -u")
 	flags.BoolVar(&showVersion, "V", false, "Show version")
 	flags.BoolVar(&showVersion, "version", false, "Show version")
 	flags.BoolVar(&showHelp, "h", false, "Show help")
@@ -194,51 +316,76 @@ func (a *App) parseFlags(args []string) error {
 
 // printVersion prints version information
 func (a *App) printVersion() {
-	fmt.Printf("thop version %s\n", a.Version)
-	fmt.Printf("  commit: %s\n", a.GitCommit)
-	fmt.Printf("  built:  %s\n", a.BuildTime)
+	a.printf("thop version %s\n", a.Version)
+	a.printf("  commit: %s\n", a.GitCommit)
+	a.printf("  built:  %s\n", a.BuildTime)
 }
 
 // printHelp prints help information
 func (a *App) printHelp() {
-	fmt.Println(`thop - Terminal Hopper for Agents
+	a.println(`thop - Terminal Hopper for Agents
 
 USAGE:
     thop [OPTIONS]              Start interactive mode
     thop --proxy                Start proxy mode (for AI agents)
     thop --mcp                  Start MCP server mode
+    thop --tui                  Start the full-screen dashboard
     thop -c "command"           Execute command and exit
     thop --status               Show status and exit
+    thop --dump-config          Print the effective config and exit
 
 OPTIONS:
     --proxy           Run in proxy mode (SHELL compatible)
     --mcp             Run as MCP (Model Context Protocol) server
+    --tui             Full-screen dashboard of sessions, background jobs and
+                      command output (Tab switches session, /quit exits)
     --restricted      Block dangerous/destructive commands (for AI agents)
+    --restrict-warn   Log dangerous/destructive commands but still run them
     -c <command>      Execute command and exit with its exit code
+    --raw             With -c, run the command verbatim (no cwd/env wrapping;
+                      not allowed with --restricted)
     --status          Show all sessions and exit
+    --dump-config     Print the effective config (includes, environment
+                      overrides and defaults applied) and exit
     --config <path>   Use alternate config file
     --json            Output in JSON format
     --completions <s> Generate shell completions (bash, zsh, fish)
     -v, --verbose     Increase logging verbosity
     -q, --quiet       Suppress non-error output
+    --echo            Print each command (with its session) to stderr before
+                      running it, like set -x
+    --fix-permissions Restrict config and state files to mode 0600 and exit
+    --import-ssh-config
+                      Add a session for every host in ~/.ssh/config that
+                      isn't already configured
+    --as <user>       Run commands as user via "sudo -u <user>" (overrides
+                      run_as on every session)
     -h, --help        Print help information
     -V, --version     Print version
 
 RESTRICTED MODE:
     When --restricted is enabled, the following command categories are blocked:
-    
+
     Privilege Escalation:
       sudo, su, doas, pkexec
-    
+
     Destructive File Operations:
-      rm, rmdir, shred, wipe, srm, unlink, dd, truncate (to 0)
-    
+      rm -rf on /, ~ or a system directory (/etc, /usr, ...),
+      chmod -R 777 /, rmdir, shred, wipe, srm, unlink, dd, truncate (to 0)
+
     System Modifications:
       chmod, chown, chgrp, chattr, mkfs, fdisk, parted, mount, umount,
       shutdown, reboot, poweroff, halt, useradd, userdel, usermod,
       groupadd, groupdel, passwd, systemctl, service, insmod, rmmod,
       modprobe, setenforce, aa-enforce, aa-complain
 
+    Network Access:
+      curl, wget, nc, ncat, ssh, scp, ftp, telnet
+
+    With --restrict-warn, matching commands are logged as warnings (with the
+    rule category and command name) but still executed. Useful for auditing
+    agent behavior before enforcing restrictions.
+
 INTERACTIVE MODE COMMANDS:
     /connect <session>  Establish SSH connection
     /switch <session>   Change active context
@@ -286,11 +433,11 @@ func (a *App) printStatus() error {
 		if err != nil {
 			return err
 		}
-		fmt.Println(string(data))
+		a.println(string(data))
 		return nil
 	}
 
-	fmt.Println("Sessions:")
+	a.println("Sessions:")
 	for _, s := range sessions {
 		status := "disconnected"
 		if s.Connected {
@@ -303,15 +450,47 @@ func (a *App) printStatus() error {
 		}
 
 		if s.Type == "ssh" {
-			fmt.Printf("  %-12s %s@%s (%s)%s %s\n", s.Name, s.User, s.Host, status, active, s.CWD)
+			host := s.Host
+			if s.Port != 0 && s.Port != 22 {
+				host = fmt.Sprintf("%s:%d", s.Host, s.Port)
+			}
+			a.printf("  %-12s %s@%s (%s)%s %s\n", s.Name, s.User, host, status, active, s.CWD)
 		} else {
-			fmt.Printf("  %-12s local (%s)%s %s\n", s.Name, status, active, s.CWD)
+			a.printf("  %-12s local (%s)%s %s\n", s.Name, status, active, s.CWD)
+		}
+		if s.Description != "" {
+			a.printf("  %-12s %s\n", "", s.Description)
 		}
 	}
 
 	return nil
 }
 
+// printConfig prints the effective config: what was loaded, with includes,
+// environment overrides, imported sessions and defaults applied. It is TOML,
+// or JSON with the same keys when --json is set.
+func (a *App) printConfig() error {
+	data, err := toml.Marshal(a.config.Effective())
+	if err != nil {
+		return fmt.Errorf("failed to marshal config: %w", err)
+	}
+	if !a.jsonOutput {
+		a.print(string(data))
+		return nil
+	}
+
+	var tree map[string]interface{}
+	if err := toml.Unmarshal(data, &tree); err != nil {
+		return fmt.Errorf("failed to marshal config: %w", err)
+	}
+	out, err := json.MarshalIndent(tree, "", "  ")
+	if err != nil {
+		return err
+	}
+	a.println(string(out))
+	return nil
+}
+
 // outputError outputs an error in the appropriate format
 func (a *App) outputError(err error) {
 	if a.jsonOutput {
@@ -325,22 +504,22 @@ func (a *App) outputError(err error) {
 				"retryable":  sessionErr.Retryable,
 				"suggestion": sessionErr.Suggestion,
 			})
-			fmt.Fprintln(os.Stderr, string(data))
+			fmt.Fprintln(a.terminal().Stderr(), string(data))
 		} else {
 			data, _ := json.Marshal(map[string]interface{}{
 				"error":   true,
 				"message": err.Error(),
 			})
-			fmt.Fprintln(os.Stderr, string(data))
+			fmt.Fprintln(a.terminal().Stderr(), string(data))
 		}
 	} else {
 		if sessionErr, ok := err.(*session.Error); ok {
-			fmt.Fprintf(os.Stderr, "Error: %s\n", sessionErr.Message)
+			fmt.Fprintf(a.terminal().Stderr(), "Error: %s\n", sessionErr.Message)
 			if sessionErr.Suggestion != "" {
-				fmt.Fprintf(os.Stderr, "Suggestion: %s\n", sessionErr.Suggestion)
+				fmt.Fprintf(a.terminal().Stderr(), "Suggestion: %s\n", sessionErr.Suggestion)
 			}
 		} else {
-			fmt.Fprintf(os.Stderr, "Error: %v\n", err)
+			fmt.Fprintf(a.terminal().Stderr(), "Error: %v\n", err)
 		}
 	}
 }
@@ -186,6 +186,53 @@ func TestPrintStatus(t *testing.T) {
 	}
 }
 
+func TestPrintStatusDescription(t *testing.T) {
+	app := createTestApp(t)
+	testserver := app.config.Sessions["testserver"]
+	testserver.Description = "Staging API box, owned by platform team"
+	app.config.Sessions["testserver"] = testserver
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err := app.printStatus()
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	if err != nil {
+		t.Fatalf("printStatus failed: %v", err)
+	}
+
+	var buf bytes.Buffer
+	io.Copy(&buf, r)
+	if !strings.Contains(buf.String(), "Staging API box, owned by platform team") {
+		t.Errorf("expected description in status output, got:\n%s", buf.String())
+	}
+
+	// JSON status carries the description too
+	app.jsonOutput = true
+	r, w, _ = os.Pipe()
+	os.Stdout = w
+	err = app.printStatus()
+	w.Close()
+	os.Stdout = oldStdout
+	if err != nil {
+		t.Fatalf("printStatus failed: %v", err)
+	}
+
+	var sessions []session.SessionInfo
+	if err := json.NewDecoder(r).Decode(&sessions); err != nil {
+		t.Fatalf("invalid JSON status: %v", err)
+	}
+	for _, s := range sessions {
+		if s.Name == "testserver" && s.Description != testserver.Description {
+			t.Errorf("expected description %q, got %q", testserver.Description, s.Description)
+		}
+	}
+}
+
 func TestPrintStatusJSON(t *testing.T) {
 	app := createTestApp(t)
 	app.jsonOutput = true
@@ -294,3 +341,52 @@ func TestOutputErrorJSON(t *testing.T) {
 		t.Errorf("expected session 'prod', got '%v'", errData["session"])
 	}
 }
+
+func TestPrintConfig(t *testing.T) {
+	app := createTestApp(t)
+	app.config.Settings.MaxSessions = 0
+	app.config.Settings.CommandTimeout = 120
+
+	capture := func() string {
+		oldStdout := os.Stdout
+		r, w, _ := os.Pipe()
+		os.Stdout = w
+
+		err := app.printConfig()
+
+		w.Close()
+		os.Stdout = oldStdout
+		if err != nil {
+			t.Fatalf("printConfig failed: %v", err)
+		}
+		var buf bytes.Buffer
+		io.Copy(&buf, r)
+		return buf.String()
+	}
+
+	output := capture()
+	for _, want := range []string{"[sessions.local]", "max_sessions = 100", "interactive_timeout = 120", "C.UTF-8"} {
+		if !strings.Contains(output, want) {
+			t.Errorf("expected %q in dumped config:\n%s", want, output)
+		}
+	}
+
+	app.jsonOutput = true
+	var dumped struct {
+		Settings map[string]interface{}            `json:"settings"`
+		Sessions map[string]map[string]interface{} `json:"sessions"`
+	}
+	if err := json.Unmarshal([]byte(capture()), &dumped); err != nil {
+		t.Fatalf("output is not valid JSON: %v", err)
+	}
+	local, ok := dumped.Sessions["local"]
+	if !ok {
+		t.Fatal("expected the local session in the dumped config")
+	}
+	if local["command_timeout"] != float64(120) || local["type"] != "local" {
+		t.Errorf("expected the local session's resolved timeout and type, got %v", local)
+	}
+	if dumped.Settings["max_connections"] != float64(10) || dumped.Settings["default_session"] != "local" {
+		t.Errorf("expected resolved settings, got %v", dumped.Settings)
+	}
+}
@@ -0,0 +1,118 @@
+package cli
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+
+	"github.com/scottgl9/thop/internal/session"
+)
+
+// captureOptions control how /capture writes a command's output
+type captureOptions struct {
+	Append   bool // Append to the files instead of replacing them
+	Separate bool // Write stderr to <file>.stderr instead of after stdout
+}
+
+// captureSummary describes what /capture wrote
+type captureSummary struct {
+	Path        string
+	StderrPath  string // Set when stderr went to its own file
+	StdoutBytes int
+	StderrBytes int
+	ExitCode    int
+}
+
+// writeCapture writes a command result to the local file path: stdout
+// followed by stderr, or with opts.Separate, stderr to path + ".stderr"
+func writeCapture(path string, result *session.ExecuteResult, opts captureOptions) (captureSummary, error) {
+	summary := captureSummary{
+		Path:        path,
+		StdoutBytes: len(result.Stdout),
+		StderrBytes: len(result.Stderr),
+		ExitCode:    result.ExitCode,
+	}
+
+	if !opts.Separate {
+		return summary, writeCaptureFile(path, result.Stdout+result.Stderr, opts.Append)
+	}
+	if err := writeCaptureFile(path, result.Stdout, opts.Append); err != nil {
+		return summary, err
+	}
+	summary.StderrPath = path + ".stderr"
+	return summary, writeCaptureFile(summary.StderrPath, result.Stderr, opts.Append)
+}
+
+// writeCaptureFile writes or appends data to a local file
+func writeCaptureFile(path, data string, appendTo bool) error {
+	flags := os.O_WRONLY | os.O_CREATE | os.O_TRUNC
+	if appendTo {
+		flags = os.O_WRONLY | os.O_CREATE | os.O_APPEND
+	}
+	f, err := os.OpenFile(path, flags, 0644)
+	if err != nil {
+		return fmt.Errorf("failed to open capture file: %w", err)
+	}
+	if _, err := f.WriteString(data); err != nil {
+		_ = f.Close()
+		return fmt.Errorf("failed to write capture file: %w", err)
+	}
+	return f.Close()
+}
+
+// parseCaptureArgs splits /capture arguments into options, the local file
+// and the command
+func parseCaptureArgs(args []string) (captureOptions, string, string, error) {
+	var opts captureOptions
+	for len(args) > 0 && strings.HasPrefix(args[0], "-") {
+		switch args[0] {
+		case "-a", "--append":
+			opts.Append = true
+		case "-s", "--separate":
+			opts.Separate = true
+		default:
+			return opts, "", "", fmt.Errorf("unknown /capture option %s", args[0])
+		}
+		args = args[1:]
+	}
+	if len(args) < 2 {
+		return opts, "", "", fmt.Errorf("usage: /capture [-a|--append] [-s|--separate] <localfile> <command>")
+	}
+
+	path := args[0]
+	if strings.HasPrefix(path, "~/") {
+		if home, err := os.UserHomeDir(); err == nil {
+			path = filepath.Join(home, path[2:])
+		}
+	}
+	return opts, path, strings.Join(args[1:], " "), nil
+}
+
+// cmdCapture handles the /capture command: run a command on the active
+// session and write its output to a local file instead of the terminal
+func (a *App) cmdCapture(args []string) error {
+	opts, path, cmd, err := parseCaptureArgs(args)
+	if err != nil {
+		return err
+	}
+
+	result, err := a.executeWithSignalForwarding(cmd)
+	if err != nil {
+		a.printRestrictionCaret(cmd, err)
+		return err
+	}
+
+	summary, err := writeCapture(path, result, opts)
+	if err != nil {
+		return err
+	}
+	if summary.StderrPath != "" {
+		a.printf("Captured %d bytes of stdout to %s and %d bytes of stderr to %s (exit code %d)\n",
+			summary.StdoutBytes, summary.Path, summary.StderrBytes, summary.StderrPath, summary.ExitCode)
+	} else {
+		a.printf("Captured %d bytes (%d stdout, %d stderr) to %s (exit code %d)\n",
+			summary.StdoutBytes+summary.StderrBytes, summary.StdoutBytes, summary.StderrBytes, summary.Path, summary.ExitCode)
+	}
+	return nil
+}
@@ -0,0 +1,71 @@
+package cli
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+)
+
+func TestCaptureWritesOutput(t *testing.T) {
+	app := createInteractiveTestApp(t)
+	path := filepath.Join(t.TempDir(), "out.log")
+
+	opts, file, cmd, err := parseCaptureArgs([]string{path, "echo", "out;", "echo", "err", ">&2;", "exit", "3"})
+	if err != nil {
+		t.Fatalf("parseCaptureArgs failed: %v", err)
+	}
+	result, err := app.sessions.Execute(cmd)
+	if err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	summary, err := writeCapture(file, result, opts)
+	if err != nil {
+		t.Fatalf("writeCapture failed: %v", err)
+	}
+
+	data, err := os.ReadFile(path)
+	if err != nil {
+		t.Fatalf("ReadFile failed: %v", err)
+	}
+	if string(data) != "out\nerr\n" {
+		t.Errorf("captured %q, want stdout then stderr", data)
+	}
+	if summary.ExitCode != 3 || summary.StdoutBytes != 4 || summary.StderrBytes != 4 {
+		t.Errorf("summary = %+v, want exit code 3 and 4 bytes on each stream", summary)
+	}
+}
+
+func TestCaptureAppendAndSeparate(t *testing.T) {
+	app := createInteractiveTestApp(t)
+	path := filepath.Join(t.TempDir(), "out.log")
+	if err := os.WriteFile(path, []byte("earlier\n"), 0644); err != nil {
+		t.Fatal(err)
+	}
+
+	if err := app.handleSlashCommand("/capture -a -s " + path + " echo out; echo err >&2"); err != nil {
+		t.Fatalf("/capture failed: %v", err)
+	}
+
+	stdout, err := os.ReadFile(path)
+	if err != nil {
+		t.Fatalf("ReadFile failed: %v", err)
+	}
+	if string(stdout) != "earlier\nout\n" {
+		t.Errorf("stdout file = %q, want appended output", stdout)
+	}
+	stderr, err := os.ReadFile(path + ".stderr")
+	if err != nil {
+		t.Fatalf("ReadFile stderr failed: %v", err)
+	}
+	if string(stderr) != "err\n" {
+		t.Errorf("stderr file = %q, want %q", stderr, "err\n")
+	}
+}
+
+func TestParseCaptureArgsErrors(t *testing.T) {
+	for _, args := range [][]string{nil, {"out.log"}, {"-x", "out.log", "ls"}} {
+		if _, _, _, err := parseCaptureArgs(args); err == nil {
+			t.Errorf("parseCaptureArgs(%q) should fail", args)
+		}
+	}
+}
@@ -0,0 +1,259 @@
+package cli
+
+import (
+	"os"
+	"path"
+	"path/filepath"
+	"sort"
+	"strings"
+	"sync"
+	"time"
+
+	"github.com/scottgl9/thop/internal/session"
+)
+
+// slashCommands are the slash commands and aliases offered by tab completion
+var slashCommands = []string{
+	"/add", "/add-session", "/auth", "/b", "/back", "/bg", "/c", "/capture",
+	"/cat", "/changed", "/check", "/cleanup", "/clear", "/close", "/cls",
+	"/connect", "/copy", "/cp", "/d", "/disconnect", "/echo", "/edit", "/env",
+	"/exit", "/fg", "/h", "/help", "/history", "/info", "/jobs", "/journal",
+	"/kill", "/killproc", "/l", "/latency", "/limits", "/list", "/local", "/ls",
+	"/mktemp", "/ports", "/ps", "/q", "/quit", "/raw", "/read", "/realpath",
+	"/recent", "/remove", "/rename", "/rm-session", "/run", "/s", "/search",
+	"/sessions", "/sh", "/shell", "/snapshot", "/status", "/sw", "/switch",
+	"/tail", "/trust", "/watch-diff", "/wd", "/write",
+}
+
+// sessionArgCommands take a session name as their first argument
+var sessionArgCommands = map[string]bool{
+	"/connect": true, "/c": true,
+	"/switch": true, "/sw": true,
+	"/close": true, "/disconnect": true, "/d": true,
+	"/auth": true, "/trust": true, "/info": true, "/latency": true,
+	"/history": true, "/mktemp": true, "/cleanup": true, "/rename": true,
+	"/remove": true, "/rm-session": true, "/ports": true,
+}
+
+// pathArgCommands take a file path as their first argument
+var pathArgCommands = map[string]bool{
+	"/read": true, "/cat": true, "/write": true,
+}
+
+// fileSpecCommands take [session:]path arguments
+var fileSpecCommands = map[string]bool{
+	"/copy": true, "/cp": true,
+}
+
+// argChoices are the fixed first arguments of some commands
+var argChoices = map[string][]string{
+	"/echo":     {"off", "on"},
+	"/clear":    {"all"},
+	"/cls":      {"all"},
+	"/snapshot": {"list", "restore", "save"},
+}
+
+// remoteListingTTL is how long a remote directory listing is reused, so
+// completing a path doesn't cost a round trip per keystroke
+const remoteListingTTL = 5 * time.Second
+
+// slashCompleter completes slash command names, session-name arguments and
+// file path arguments, listing directories on the session they refer to. It
+// implements readline.AutoCompleter.
+type slashCompleter struct {
+	// sessionNames returns the configured sessions
+	sessionNames func() []string
+	// lookup returns the session a file spec names, "" being the active one
+	lookup func(name string) session.Session
+
+	mu    sync.Mutex
+	cache map[string]cachedListing // Remote listings by session and directory
+}
+
+// cachedListing is a remote directory listing and when it was made
+type cachedListing struct {
+	entries []session.DirEntry
+	at      time.Time
+}
+
+// newSlashCompleter returns a completer for the app's sessions
+func (a *App) newSlashCompleter() *slashCompleter {
+	return &slashCompleter{
+		sessionNames: a.sessions.SessionNames,
+		lookup: func(name string) session.Session {
+			active := a.sessions.GetActiveSession()
+			if name == "" || (name == "remote" && active != nil && active.Type() == "ssh") {
+				return active
+			}
+			sess, _ := a.sessions.GetSession(name)
+			return sess
+		},
+	}
+}
+
+// Do implements readline.AutoCompleter, returning the suffixes that complete
+// the word before the cursor and that word's length
+func (c *slashCompleter) Do(line []rune, pos int) ([][]rune, int) {
+	word, candidates := c.complete(string(line[:pos]))
+	var suffixes [][]rune
+	for _, candidate := range candidates {
+		suffix := candidate[len(word):]
+		if !strings.HasSuffix(candidate, "/") {
+			suffix += " "
+		}
+		suffixes = append(suffixes, []rune(suffix))
+	}
+	return suffixes, len([]rune(word))
+}
+
+// complete returns the partial word at the end of line and the sorted words
+// that could replace it. Directories end in "/".
+func (c *slashCompleter) complete(line string) (string, []string) {
+	fields := strings.Fields(line)
+	word := ""
+	if len(fields) > 0 && !strings.HasSuffix(line, " ") {
+		word = fields[len(fields)-1]
+		fields = fields[:len(fields)-1]
+	}
+
+	if !strings.HasPrefix(line, "/") {
+		// A shell command: only cd's directory argument is completed
+		if len(fields) == 1 && fields[0] == "cd" {
+			return word, c.completePath("", word, true)
+		}
+		return word, nil
+	}
+	if len(fields) == 0 {
+		return word, withPrefix(slashCommands, word)
+	}
+
+	cmd := strings.ToLower(fields[0])
+	args := fields[1:]
+	switch {
+	case sessionArgCommands[cmd] && len(args) == 0:
+		return word, withPrefix(c.sessionNames(), word)
+	case argChoices[cmd] != nil && len(args) == 0:
+		return word, withPrefix(argChoices[cmd], word)
+	case pathArgCommands[cmd] && isFirstPathArg(cmd, args) && !strings.HasPrefix(word, "-"):
+		return word, c.completePath("", word, false)
+	case fileSpecCommands[cmd] && len(args) < 2:
+		name, specPath := session.ParseFileSpec(word)
+		if name == "" {
+			return word, c.completePath("", word, false)
+		}
+		var candidates []string
+		for _, candidate := range c.completePath(name, specPath, false) {
+			candidates = append(candidates, name+":"+candidate)
+		}
+		return word, candidates
+	}
+	return word, nil
+}
+
+// isFirstPathArg reports whether the argument after args is a command's path,
+// skipping /write's mode option
+func isFirstPathArg(cmd string, args []string) bool {
+	if cmd == "/write" && len(args) == 2 && (args[0] == "-m" || args[0] == "--mode") {
+		return true
+	}
+	return len(args) == 0
+}
+
+// withPrefix returns the sorted words that start with prefix
+func withPrefix(words []string, prefix string) []string {
+	var matches []string
+	for _, w := range words {
+		if strings.HasPrefix(w, prefix) {
+			matches = append(matches, w)
+		}
+	}
+	sort.Strings(matches)
+	return matches
+}
+
+// completePath returns the paths on the named session that complete
+// partial, resolving relative paths against the session's working directory.
+// Hidden entries are only offered once the partial name starts with a dot.
+func (c *slashCompleter) completePath(sessionName, partial string, dirsOnly bool) []string {
+	sess := c.lookup(sessionName)
+	if sess == nil || !sess.IsConnected() {
+		return nil
+	}
+
+	dir, base := "", partial
+	if i := strings.LastIndex(partial, "/"); i >= 0 {
+		dir, base = partial[:i+1], partial[i+1:]
+	}
+	entries, err := c.listDir(sess, completionDir(sess, dir))
+	if err != nil {
+		return nil
+	}
+
+	var matches []string
+	for _, entry := range entries {
+		if !strings.HasPrefix(entry.Name, base) || (strings.HasPrefix(entry.Name, ".") && !strings.HasPrefix(base, ".")) {
+			continue
+		}
+		switch {
+		case entry.IsDir:
+			matches = append(matches, dir+entry.Name+"/")
+		case !dirsOnly:
+			matches = append(matches, dir+entry.Name)
+		}
+	}
+	sort.Strings(matches)
+	return matches
+}
+
+// completionDir returns the directory to list on sess for the directory part
+// of a partial path. SFTP resolves relative paths against the home directory,
+// so a remote ~ becomes a relative path.
+func completionDir(sess session.Session, dir string) string {
+	remote := sess.Type() == "ssh"
+	if strings.HasPrefix(dir, "~/") {
+		if remote {
+			return "./" + dir[2:]
+		}
+		if home, err := os.UserHomeDir(); err == nil {
+			return filepath.Join(home, dir[2:])
+		}
+	}
+	if path.IsAbs(dir) {
+		return dir
+	}
+	cwd := sess.GetCWD()
+	if cwd == "" || cwd == "~" {
+		if remote {
+			return "./" + dir
+		}
+		cwd = "."
+	}
+	return path.Join(cwd, dir)
+}
+
+// listDir lists dir on sess, reusing a recent listing for remote sessions
+func (c *slashCompleter) listDir(sess session.Session, dir string) ([]session.DirEntry, error) {
+	if sess.Type() != "ssh" {
+		return sess.ListDir(dir)
+	}
+
+	key := sess.Name() + ":" + dir
+	c.mu.Lock()
+	cached, ok := c.cache[key]
+	c.mu.Unlock()
+	if ok && time.Since(cached.at) < remoteListingTTL {
+		return cached.entries, nil
+	}
+
+	entries, err := sess.ListDir(dir)
+	if err != nil {
+		return nil, err
+	}
+	c.mu.Lock()
+	if c.cache == nil {
+		c.cache = make(map[string]cachedListing)
+	}
+	c.cache[key] = cachedListing{entries: entries, at: time.Now()}
+	c.mu.Unlock()
+	return entries, nil
+}
@@ -0,0 +1,163 @@
+package cli
+
+import (
+	"os"
+	"path/filepath"
+	"reflect"
+	"testing"
+
+	"github.com/scottgl9/thop/internal/session"
+)
+
+// newTestCompleter returns a completer whose active session is a local
+// session in cwd
+func newTestCompleter(t *testing.T, cwd string) *slashCompleter {
+	t.Helper()
+	local := session.NewLocalSession("local", "/bin/sh")
+	if err := local.SetCWD(cwd); err != nil {
+		t.Fatalf("SetCWD failed: %v", err)
+	}
+	return &slashCompleter{
+		sessionNames: func() []string { return []string{"staging", "prod", "local", "preview"} },
+		lookup: func(name string) session.Session {
+			if name == "" || name == "local" {
+				return local
+			}
+			return nil
+		},
+	}
+}
+
+// makeCompletionTree creates notes.txt, nginx.conf, .hidden and
+// nested/inner.log in a temp directory
+func makeCompletionTree(t *testing.T) string {
+	t.Helper()
+	dir := t.TempDir()
+	for _, name := range []string{"notes.txt", "nginx.conf", ".hidden"} {
+		if err := os.WriteFile(filepath.Join(dir, name), nil, 0600); err != nil {
+			t.Fatal(err)
+		}
+	}
+	if err := os.Mkdir(filepath.Join(dir, "nested"), 0700); err != nil {
+		t.Fatal(err)
+	}
+	if err := os.WriteFile(filepath.Join(dir, "nested", "inner.log"), nil, 0600); err != nil {
+		t.Fatal(err)
+	}
+	return dir
+}
+
+func TestSlashCompleterCandidates(t *testing.T) {
+	c := newTestCompleter(t, t.TempDir())
+
+	tests := []struct {
+		line     string
+		wantWord string
+		want     []string
+	}{
+		{"/con", "/con", []string{"/connect"}},
+		{"/sw", "/sw", []string{"/sw", "/switch"}},
+		{"/connect pr", "pr", []string{"preview", "prod"}},
+		{"/CONNECT st", "st", []string{"staging"}},
+		{"/switch ", "", []string{"local", "preview", "prod", "staging"}},
+		{"/close prod ", "", nil},
+		{"/echo o", "o", []string{"off", "on"}},
+		{"ls n", "n", nil},
+	}
+	for _, tt := range tests {
+		word, got := c.complete(tt.line)
+		if word != tt.wantWord || !reflect.DeepEqual(got, tt.want) {
+			t.Errorf("complete(%q) = %q, %q; want %q, %q", tt.line, word, got, tt.wantWord, tt.want)
+		}
+	}
+}
+
+func TestSlashCompleterLocalPaths(t *testing.T) {
+	dir := makeCompletionTree(t)
+	c := newTestCompleter(t, dir)
+
+	tests := []struct {
+		line     string
+		wantWord string
+		want     []string
+	}{
+		{"/read n", "n", []string{"nested/", "nginx.conf", "notes.txt"}},
+		{"/cat nested/", "nested/", []string{"nested/inner.log"}},
+		{"/read .h", ".h", []string{".hidden"}},
+		{"/read " + dir + "/no", dir + "/no", []string{dir + "/notes.txt"}},
+		{"/write -m 644 no", "no", []string{"notes.txt"}},
+		{"/write notes.txt n", "n", nil},
+		{"/copy nginx", "nginx", []string{"nginx.conf"}},
+		{"/copy local:ne", "local:ne", []string{"local:nested/"}},
+		{"/cp notes.txt local:nested/i", "local:nested/i", []string{"local:nested/inner.log"}},
+		{"/copy unknown:ne", "unknown:ne", nil},
+		{"cd n", "n", []string{"nested/"}},
+		{"cd nested/", "nested/", nil},
+	}
+	for _, tt := range tests {
+		word, got := c.complete(tt.line)
+		if word != tt.wantWord || !reflect.DeepEqual(got, tt.want) {
+			t.Errorf("complete(%q) = %q, %q; want %q, %q", tt.line, word, got, tt.wantWord, tt.want)
+		}
+	}
+}
+
+func TestSlashCompleterDo(t *testing.T) {
+	c := newTestCompleter(t, makeCompletionTree(t))
+
+	tests := []struct {
+		line       string
+		wantLength int
+		want       []string
+	}{
+		{"/connect pr", 2, []string{"eview ", "od "}},
+		{"/read ne", 2, []string{"sted/"}},
+	}
+	for _, tt := range tests {
+		line := []rune(tt.line)
+		suffixes, length := c.Do(line, len(line))
+		var got []string
+		for _, s := range suffixes {
+			got = append(got, string(s))
+		}
+		if length != tt.wantLength || !reflect.DeepEqual(got, tt.want) {
+			t.Errorf("Do(%q) = %q, %d; want %q, %d", tt.line, got, length, tt.want, tt.wantLength)
+		}
+	}
+}
+
+// fakeRemote is a connected SSH session whose directory listings are counted
+type fakeRemote struct {
+	session.Session
+	lists int
+}
+
+func (f *fakeRemote) Name() string      { return "prod" }
+func (f *fakeRemote) Type() string      { return "ssh" }
+func (f *fakeRemote) IsConnected() bool { return true }
+func (f *fakeRemote) GetCWD() string    { return "/srv" }
+
+func (f *fakeRemote) ListDir(path string) ([]session.DirEntry, error) {
+	f.lists++
+	if path != "/srv" {
+		return nil, os.ErrNotExist
+	}
+	return []session.DirEntry{{Name: "app", IsDir: true}, {Name: "app.log"}}, nil
+}
+
+func TestSlashCompleterCachesRemoteListings(t *testing.T) {
+	remote := &fakeRemote{}
+	c := &slashCompleter{
+		sessionNames: func() []string { return []string{"prod"} },
+		lookup:       func(name string) session.Session { return remote },
+	}
+
+	for _, line := range []string{"/read a", "/read ap", "/read app"} {
+		if _, got := c.complete(line); !reflect.DeepEqual(got, []string{"app.log", "app/"}) {
+			t.Errorf("complete(%q) = %q", line, got)
+		}
+	}
+	if remote.lists != 1 {
+		t.Errorf("remote directory listed %d times, want 1 (cached)", remote.lists)
+	}
+}
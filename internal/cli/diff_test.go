@@ -0,0 +1,76 @@
+package cli
+
+import (
+	"strings"
+	"testing"
+)
+
+func TestUnifiedDiffEqual(t *testing.T) {
+	if diff := unifiedDiff("a", "b", "one\ntwo\n", "one\ntwo\n"); diff != "" {
+		t.Errorf("expected no diff for equal input, got:\n%s", diff)
+	}
+	if diff := unifiedDiff("a", "b", "", ""); diff != "" {
+		t.Errorf("expected no diff for empty input, got:\n%s", diff)
+	}
+}
+
+func TestUnifiedDiff(t *testing.T) {
+	tests := []struct {
+		name     string
+		from     string
+		to       string
+		expected string
+	}{
+		{
+			name: "changed line",
+			from: "a\nb\nc\n",
+			to:   "a\nB\nc\n",
+			expected: "--- old\n+++ new\n" +
+				"@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n",
+		},
+		{
+			name: "added line",
+			from: "a\nb\n",
+			to:   "a\nb\nc\n",
+			expected: "--- old\n+++ new\n" +
+				"@@ -1,2 +1,3 @@\n a\n b\n+c\n",
+		},
+		{
+			name: "from empty",
+			from: "",
+			to:   "x\n",
+			expected: "--- old\n+++ new\n" +
+				"@@ -0,0 +1 @@\n+x\n",
+		},
+		{
+			name: "separate hunks",
+			from: "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n",
+			to:   "one\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\ntwelve\n",
+			expected: "--- old\n+++ new\n" +
+				"@@ -1,4 +1,4 @@\n-1\n+one\n 2\n 3\n 4\n" +
+				"@@ -9,4 +9,4 @@\n 9\n 10\n 11\n-12\n+twelve\n",
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			diff := unifiedDiff("old", "new", tt.from, tt.to)
+			if diff != tt.expected {
+				t.Errorf("unexpected diff:\n%s\nexpected:\n%s", diff, tt.expected)
+			}
+		})
+	}
+}
+
+func TestDiffLinesLargeInputFallback(t *testing.T) {
+	var a, b []string
+	for i := 0; i < 3000; i++ {
+		a = append(a, "a"+strings.Repeat("x", i%7))
+		b = append(b, "b"+strings.Repeat("y", i%5))
+	}
+
+	ops := diffLines(a, b)
+	if len(ops) != len(a)+len(b) {
+		t.Errorf("expected %d ops, got %d", len(a)+len(b), len(ops))
+	}
+}
@@ -0,0 +1,98 @@
+package cli
+
+import (
+	"crypto/sha256"
+	"fmt"
+	"os"
+	"os/exec"
+	"path/filepath"
+
+	"github.com/scottgl9/thop/internal/session"
+)
+
+// fileTransfer reads and writes files on sessions; *session.Manager
+// implements it
+type fileTransfer interface {
+	ReadFile(sessionName, path string) ([]byte, string, error)
+	ReplaceFile(sessionName, path string, data []byte, mode os.FileMode) (string, error)
+	FileMode(sessionName, path string) (os.FileMode, error)
+}
+
+// editFile fetches a file from a session into a private temp directory, runs
+// edit on the local copy and writes it back, with its original mode, only if
+// edit succeeded and changed the content, replacing the file atomically. It
+// returns the resolved path and whether the file was written back.
+func editFile(files fileTransfer, sessionName, path string, edit func(localPath string) error) (string, bool, error) {
+	mode, err := files.FileMode(sessionName, path)
+	if err != nil {
+		return path, false, err
+	}
+	data, resolved, err := files.ReadFile(sessionName, path)
+	if err != nil {
+		return resolved, false, err
+	}
+
+	dir, err := os.MkdirTemp("", "thop-edit-")
+	if err != nil {
+		return resolved, false, fmt.Errorf("failed to create temp directory: %w", err)
+	}
+	defer os.RemoveAll(dir)
+
+	// Keep the file name so the editor picks the right syntax highlighting
+	localPath := filepath.Join(dir, filepath.Base(resolved))
+	if err := os.WriteFile(localPath, data, 0600); err != nil {
+		return resolved, false, fmt.Errorf("failed to write temp file: %w", err)
+	}
+
+	if err := edit(localPath); err != nil {
+		return resolved, false, fmt.Errorf("editor failed, %s left unchanged: %w", resolved, err)
+	}
+
+	edited, err := os.ReadFile(localPath)
+	if err != nil {
+		return resolved, false, fmt.Errorf("failed to read edited file: %w", err)
+	}
+	if sha256.Sum256(edited) == sha256.Sum256(data) {
+		return resolved, false, nil
+	}
+
+	// path, not resolved: the session resolves it again the same way
+	if _, err := files.ReplaceFile(sessionName, path, edited, mode); err != nil {
+		return resolved, false, fmt.Errorf("failed to upload edited file (your changes are lost): %w", err)
+	}
+	return resolved, true, nil
+}
+
+// runEditor opens path in $EDITOR (vi if unset) attached to the terminal.
+// EDITOR may include arguments, as in "code --wait".
+func runEditor(path string) error {
+	editor := os.Getenv("EDITOR")
+	if editor == "" {
+		editor = "vi"
+	}
+	cmd := exec.Command("/bin/sh", "-c", editor+` "$1"`, "sh", path)
+	cmd.Stdin = os.Stdin
+	cmd.Stdout = os.Stdout
+	cmd.Stderr = os.Stderr
+	return cmd.Run()
+}
+
+// cmdEdit handles the /edit command: edit a file from any session in the
+// local $EDITOR
+func (a *App) cmdEdit(spec string) error {
+	sessionName, path := session.ParseFileSpec(spec)
+	if path == "" {
+		return fmt.Errorf("usage: /edit [session:]path")
+	}
+
+	resolved, uploaded, err := editFile(a.sessions, sessionName, path, runEditor)
+	if err != nil {
+		return err
+	}
+	if !uploaded {
+		a.printf("%s unchanged\n", resolved)
+		return nil
+	}
+	a.printf("Saved %s\n", resolved)
+	return nil
+}
@@ -0,0 +1,121 @@
+package cli
+
+import (
+	"errors"
+	"os"
+	"strings"
+	"testing"
+)
+
+// fakeTransfer is an in-memory fileTransfer that records uploads. It
+// resolves paths like an SSH session in /srv: ~/ becomes relative to the
+// home directory, and other relative paths are joined to the cwd.
+type fakeTransfer struct {
+	data      []byte
+	mode      os.FileMode
+	written   []byte
+	writes    int
+	gotMode   os.FileMode
+	readPath  string
+	writtenTo string
+}
+
+func (f *fakeTransfer) resolve(path string) string {
+	switch {
+	case strings.HasPrefix(path, "~/"):
+		return path[2:]
+	case strings.HasPrefix(path, "/"):
+		return path
+	default:
+		return "/srv/" + path
+	}
+}
+
+func (f *fakeTransfer) ReadFile(sessionName, path string) ([]byte, string, error) {
+	f.readPath = f.resolve(path)
+	return f.data, f.readPath, nil
+}
+
+func (f *fakeTransfer) ReplaceFile(sessionName, path string, data []byte, mode os.FileMode) (string, error) {
+	f.writes++
+	f.written = data
+	f.gotMode = mode
+	f.writtenTo = f.resolve(path)
+	return f.writtenTo, nil
+}
+
+func (f *fakeTransfer) FileMode(sessionName, path string) (os.FileMode, error) {
+	return f.mode, nil
+}
+
+func TestEditFile(t *testing.T) {
+	t.Run("unchanged is not uploaded", func(t *testing.T) {
+		files := &fakeTransfer{data: []byte("port = 80\n"), mode: 0640}
+		_, uploaded, err := editFile(files, "prod", "app.conf", func(string) error { return nil })
+		if err != nil {
+			t.Fatalf("editFile failed: %v", err)
+		}
+		if uploaded || files.writes != 0 {
+			t.Errorf("expected no upload, got %d writes", files.writes)
+		}
+	})
+
+	t.Run("rewritten with same content is not uploaded", func(t *testing.T) {
+		files := &fakeTransfer{data: []byte("port = 80\n"), mode: 0640}
+		_, uploaded, err := editFile(files, "prod", "app.conf", func(path string) error {
+			return os.WriteFile(path, []byte("port = 80\n"), 0600)
+		})
+		if err != nil {
+			t.Fatalf("editFile failed: %v", err)
+		}
+		if uploaded || files.writes != 0 {
+			t.Errorf("expected no upload, got %d writes", files.writes)
+		}
+	})
+
+	t.Run("changed is uploaded with original mode", func(t *testing.T) {
+		files := &fakeTransfer{data: []byte("port = 80\n"), mode: 0640}
+		resolved, uploaded, err := editFile(files, "prod", "app.conf", func(path string) error {
+			return os.WriteFile(path, []byte("port = 8080\n"), 0600)
+		})
+		if err != nil {
+			t.Fatalf("editFile failed: %v", err)
+		}
+		if !uploaded || files.writes != 1 {
+			t.Fatalf("expected one upload, got %d", files.writes)
+		}
+		if string(files.written) != "port = 8080\n" || files.gotMode != 0640 {
+			t.Errorf("uploaded %q with mode %o", files.written, files.gotMode)
+		}
+		if resolved != "/srv/app.conf" {
+			t.Errorf("expected resolved path /srv/app.conf, got %q", resolved)
+		}
+	})
+
+	t.Run("editor failure is not uploaded", func(t *testing.T) {
+		files := &fakeTransfer{data: []byte("port = 80\n"), mode: 0640}
+		_, uploaded, err := editFile(files, "prod", "app.conf", func(path string) error {
+			_ = os.WriteFile(path, []byte("half-edited"), 0600)
+			return errors.New("exit status 1")
+		})
+		if err == nil {
+			t.Fatal("expected an error when the editor fails")
+		}
+		if uploaded || files.writes != 0 {
+			t.Errorf("expected no upload, got %d writes", files.writes)
+		}
+	})
+
+	t.Run("home-relative path is written back to the file read", func(t *testing.T) {
+		files := &fakeTransfer{data: []byte("port = 80\n"), mode: 0640}
+		_, uploaded, err := editFile(files, "prod", "~/app.conf", func(path string) error {
+			return os.WriteFile(path, []byte("port = 8080\n"), 0600)
+		})
+		if err != nil {
+			t.Fatalf("editFile failed: %v", err)
+		}
+		if !uploaded || files.writtenTo != files.readPath {
+			t.Errorf("read %q but wrote %q", files.readPath, files.writtenTo)
+		}
+	})
+}
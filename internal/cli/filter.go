@@ -0,0 +1,66 @@
+package cli
+
+import (
+	"bytes"
+	"fmt"
+	"os"
+	"os/exec"
+	"strings"
+
+	"github.com/scottgl9/thop/internal/logger"
+	"github.com/scottgl9/thop/internal/session"
+)
+
+// outputFilter returns the output_filter command configured for the active
+// session, or "" if output is shown as is. JSON output is never filtered.
+func (a *App) outputFilter() string {
+	if a.jsonOutput {
+		return ""
+	}
+	return a.sessions.GetConfig().Sessions[a.sessions.GetActiveSessionName()].OutputFilter
+}
+
+// applyOutputFilter pipes output through filter, a shell command run locally,
+// and returns what it prints. If the filter fails, output is returned unchanged
+// along with the error.
+func applyOutputFilter(filter, output string) (string, error) {
+	cmd := exec.Command("/bin/sh", "-c", filter)
+	cmd.Stdin = strings.NewReader(output)
+	var stderr bytes.Buffer
+	cmd.Stderr = &stderr
+	filtered, err := cmd.Output()
+	if err != nil {
+		if msg := strings.TrimSpace(stderr.String()); msg != "" {
+			err = fmt.Errorf("%w: %s", err, msg)
+		}
+		return output, fmt.Errorf("output filter %q failed: %w", filter, err)
+	}
+	return string(filtered), nil
+}
+
+// displayStdout returns the stdout of result as it should be shown: passed
+// through the active session's output filter, if any. result itself keeps the
+// raw output.
+func (a *App) displayStdout(result *session.ExecuteResult) string {
+	return a.filterOutput(a.outputFilter(), result.Stdout)
+}
+
+// filterOutput applies filter to output, warning and falling back to the raw
+// output if the filter fails
+func (a *App) filterOutput(filter, output string) string {
+	if filter == "" || output == "" {
+		return output
+	}
+	filtered, err := applyOutputFilter(filter, output)
+	if err != nil {
+		logger.Warn("%v", err)
+		fmt.Fprintf(os.Stderr, "Warning: %v (showing unfiltered output)\n", err)
+	}
+	return filtered
+}
+
+// printResult prints a command's output, with stdout passed through the
+// active session's output filter
+func (a *App) printResult(result *session.ExecuteResult) {
+	a.terminal().PrintResult(a.displayStdout(result), result.Stderr)
+}
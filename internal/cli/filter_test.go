@@ -0,0 +1,51 @@
+package cli
+
+import (
+	"testing"
+)
+
+func TestOutputFilterTransformsDisplayedOutput(t *testing.T) {
+	app := createInteractiveTestApp(t)
+	local := app.config.Sessions["local"]
+	local.OutputFilter = "tr a-z A-Z"
+	app.config.Sessions["local"] = local
+
+	result, err := app.sessions.Execute("echo hello")
+	if err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+
+	if got := app.displayStdout(result); got != "HELLO\n" {
+		t.Errorf("displayed output = %q, want %q", got, "HELLO\n")
+	}
+	if result.Stdout != "hello\n" {
+		t.Errorf("result stdout = %q, want the raw %q", result.Stdout, "hello\n")
+	}
+
+	app.jsonOutput = true
+	if got := app.displayStdout(result); got != "hello\n" {
+		t.Errorf("JSON mode output = %q, want the raw %q", got, "hello\n")
+	}
+}
+
+func TestOutputFilterUnsetLeavesOutput(t *testing.T) {
+	app := createInteractiveTestApp(t)
+
+	result, err := app.sessions.Execute("echo hello")
+	if err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	if got := app.displayStdout(result); got != "hello\n" {
+		t.Errorf("displayed output = %q, want %q", got, "hello\n")
+	}
+}
+
+func TestApplyOutputFilterFailure(t *testing.T) {
+	got, err := applyOutputFilter("echo broken >&2; exit 3", "raw\n")
+	if err == nil {
+		t.Fatal("expected a failing filter to return an error")
+	}
+	if got != "raw\n" {
+		t.Errorf("output = %q, want the unfiltered %q", got, "raw\n")
+	}
+}
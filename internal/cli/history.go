@@ -0,0 +1,200 @@
+package cli
+
+import (
+	"bufio"
+	"fmt"
+	"os"
+	"path/filepath"
+	"regexp"
+	"strconv"
+	"strings"
+
+	"github.com/scottgl9/thop/internal/logger"
+)
+
+// maxSharedHistory caps the number of entries kept in the shared history file
+const maxSharedHistory = 1000
+
+// historyEntry is one line of the shared history: a command or slash command
+// and the session it was entered on
+type historyEntry struct {
+	Session string
+	Command string
+}
+
+// getSharedHistoryFile returns the path of the history file shared by all
+// sessions. Readline's up-arrow history stays per session (see getHistoryFile).
+func getSharedHistoryFile() string {
+	dir := getHistoryDir()
+	if dir == "" {
+		return ""
+	}
+	return filepath.Join(dir, "history")
+}
+
+// loadSharedHistory reads a shared history file, oldest entry first. Each
+// line is the session name and the command separated by a tab; malformed
+// lines are skipped. A missing file is an empty history.
+func loadSharedHistory(path string) ([]historyEntry, error) {
+	f, err := os.Open(path)
+	if err != nil {
+		if os.IsNotExist(err) {
+			return nil, nil
+		}
+		return nil, err
+	}
+	defer f.Close()
+
+	var entries []historyEntry
+	scanner := bufio.NewScanner(f)
+	scanner.Buffer(make([]byte, 64*1024), 1024*1024)
+	for scanner.Scan() {
+		sessionName, command, ok := strings.Cut(scanner.Text(), "\t")
+		if !ok || sessionName == "" || command == "" {
+			continue
+		}
+		entries = append(entries, historyEntry{Session: sessionName, Command: command})
+	}
+	return entries, scanner.Err()
+}
+
+// saveSharedHistory replaces a shared history file with entries
+func saveSharedHistory(path string, entries []historyEntry) error {
+	if err := os.MkdirAll(filepath.Dir(path), 0700); err != nil {
+		return err
+	}
+	var b strings.Builder
+	for _, entry := range entries {
+		b.WriteString(entry.Session + "\t" + entry.Command + "\n")
+	}
+	return os.WriteFile(path, []byte(b.String()), 0600)
+}
+
+// appendSharedHistory adds one entry to the end of a shared history file
+func appendSharedHistory(path string, entry historyEntry) error {
+	if err := os.MkdirAll(filepath.Dir(path), 0700); err != nil {
+		return err
+	}
+	f, err := os.OpenFile(path, os.O_APPEND|os.O_CREATE|os.O_WRONLY, 0600)
+	if err != nil {
+		return err
+	}
+	defer f.Close()
+	_, err = fmt.Fprintf(f, "%s\t%s\n", entry.Session, entry.Command)
+	return err
+}
+
+// loadHistory loads the shared history at startup, trimming the file to the
+// most recent maxSharedHistory entries
+func (a *App) loadHistory() {
+	path := getSharedHistoryFile()
+	if path == "" {
+		return
+	}
+	entries, err := loadSharedHistory(path)
+	if err != nil {
+		logger.Warn("failed to load history: %v", err)
+		return
+	}
+	if len(entries) > maxSharedHistory {
+		entries = entries[len(entries)-maxSharedHistory:]
+		if err := saveSharedHistory(path, entries); err != nil {
+			logger.Warn("failed to trim history: %v", err)
+		}
+	}
+	a.history = entries
+}
+
+// recordSharedHistory adds a line to the shared history, tagged with the
+// active session
+func (a *App) recordSharedHistory(line string) {
+	entry := historyEntry{Session: a.sessions.GetActiveSessionName(), Command: line}
+	a.history = append(a.history, entry)
+	if path := getSharedHistoryFile(); path != "" {
+		if err := appendSharedHistory(path, entry); err != nil {
+			logger.Debug("failed to record history: %v", err)
+		}
+	}
+}
+
+// historyMatch is a shared history entry found by /search, with its 1-based
+// index for /run
+type historyMatch struct {
+	Index int
+	historyEntry
+}
+
+// searchHistory returns the entries whose command matches pattern, oldest
+// first. pattern is a regular expression; if it doesn't compile it is matched
+// as a plain substring.
+func searchHistory(entries []historyEntry, pattern string) []historyMatch {
+	match := func(command string) bool { return strings.Contains(command, pattern) }
+	if re, err := regexp.Compile(pattern); err == nil {
+		match = re.MatchString
+	}
+
+	var matches []historyMatch
+	for i, entry := range entries {
+		if match(entry.Command) {
+			matches = append(matches, historyMatch{Index: i + 1, historyEntry: entry})
+		}
+	}
+	return matches
+}
+
+// historyAt returns the shared history entry with the given 1-based index
+func historyAt(entries []historyEntry, index string) (historyEntry, error) {
+	n, err := strconv.Atoi(index)
+	if err != nil || n < 1 || n > len(entries) {
+		return historyEntry{}, fmt.Errorf("no history entry %s (use /search to find one)", index)
+	}
+	return entries[n-1], nil
+}
+
+// cmdSearch handles the /search command, listing history entries from all
+// sessions that match a pattern
+func (a *App) cmdSearch(pattern string) error {
+	if pattern == "" {
+		return fmt.Errorf("usage: /search <pattern>")
+	}
+	matches := searchHistory(a.history, pattern)
+	if len(matches) == 0 {
+		a.printf("No history matches %q\n", pattern)
+		return nil
+	}
+	for _, m := range matches {
+		a.printf("%5d  [%s] %s\n", m.Index, m.Session, m.Command)
+	}
+	return nil
+}
+
+// cmdRun handles the /run command, re-executing a history entry on the
+// active session
+func (a *App) cmdRun(index string) error {
+	entry, err := historyAt(a.history, index)
+	if err != nil {
+		return err
+	}
+	if isRunCommand(entry.Command) {
+		return fmt.Errorf("history entry %s is itself a /run", index)
+	}
+
+	a.println(entry.Command)
+	if strings.HasPrefix(entry.Command, "/") {
+		return a.handleSlashCommand(entry.Command)
+	}
+	result, err := a.executeWithSignalForwarding(entry.Command)
+	if err != nil {
+		a.printRestrictionCaret(entry.Command, err)
+		return err
+	}
+	a.printResult(result)
+	return nil
+}
+
+// isRunCommand reports whether line is a /run command, which /run refuses to
+// repeat so an entry can't run itself
+func isRunCommand(line string) bool {
+	fields := strings.Fields(line)
+	return len(fields) > 0 && strings.ToLower(fields[0]) == "/run"
+}
@@ -0,0 +1,171 @@
+package cli
+
+import (
+	"os"
+	"path/filepath"
+	"reflect"
+	"testing"
+)
+
+func TestGetSharedHistoryFile(t *testing.T) {
+	dataHome := t.TempDir()
+	t.Setenv("XDG_DATA_HOME", dataHome)
+	if got, want := getSharedHistoryFile(), filepath.Join(dataHome, "thop", "history"); got != want {
+		t.Errorf("getSharedHistoryFile() = %q, want %q", got, want)
+	}
+
+	home := t.TempDir()
+	t.Setenv("XDG_DATA_HOME", "")
+	t.Setenv("HOME", home)
+	if got, want := getSharedHistoryFile(), filepath.Join(home, ".local", "share", "thop", "history"); got != want {
+		t.Errorf("getSharedHistoryFile() = %q, want %q", got, want)
+	}
+}
+
+func TestSharedHistoryRoundTrip(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "thop", "history")
+
+	entries, err := loadSharedHistory(path)
+	if err != nil || len(entries) != 0 {
+		t.Fatalf("loadSharedHistory(missing) = %v, %v; want empty", entries, err)
+	}
+
+	saved := []historyEntry{
+		{Session: "local", Command: "ls -la"},
+		{Session: "prod", Command: "/status"},
+	}
+	if err := saveSharedHistory(path, saved); err != nil {
+		t.Fatalf("saveSharedHistory failed: %v", err)
+	}
+	if err := appendSharedHistory(path, historyEntry{Session: "prod", Command: "printf 'a\\tb'"}); err != nil {
+		t.Fatalf("appendSharedHistory failed: %v", err)
+	}
+
+	entries, err = loadSharedHistory(path)
+	if err != nil {
+		t.Fatalf("loadSharedHistory failed: %v", err)
+	}
+	want := append(saved, historyEntry{Session: "prod", Command: "printf 'a\\tb'"})
+	if !reflect.DeepEqual(entries, want) {
+		t.Errorf("entries = %v, want %v", entries, want)
+	}
+
+	info, err := os.Stat(path)
+	if err != nil {
+		t.Fatalf("Stat failed: %v", err)
+	}
+	if perm := info.Mode().Perm(); perm != 0600 {
+		t.Errorf("history file mode = %o, want 600", perm)
+	}
+}
+
+func TestLoadSharedHistorySkipsMalformedLines(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "history")
+	if err := os.WriteFile(path, []byte("local\tpwd\nno-tab\n\tempty-session\nprod\tuptime\n"), 0600); err != nil {
+		t.Fatal(err)
+	}
+
+	entries, err := loadSharedHistory(path)
+	if err != nil {
+		t.Fatalf("loadSharedHistory failed: %v", err)
+	}
+	want := []historyEntry{{Session: "local", Command: "pwd"}, {Session: "prod", Command: "uptime"}}
+	if !reflect.DeepEqual(entries, want) {
+		t.Errorf("entries = %v, want %v", entries, want)
+	}
+}
+
+func TestRecordHistoryTagsSharedHistory(t *testing.T) {
+	t.Setenv("XDG_DATA_HOME", t.TempDir())
+	app := createInteractiveTestApp(t)
+
+	app.recordHistory("echo hi")
+	app.recordHistory("/status")
+
+	want := []historyEntry{{Session: "local", Command: "echo hi"}, {Session: "local", Command: "/status"}}
+	if !reflect.DeepEqual(app.history, want) {
+		t.Errorf("app.history = %v, want %v", app.history, want)
+	}
+
+	reloaded := createInteractiveTestApp(t)
+	reloaded.loadHistory()
+	if !reflect.DeepEqual(reloaded.history, want) {
+		t.Errorf("reloaded history = %v, want %v", reloaded.history, want)
+	}
+}
+
+func TestSearchHistory(t *testing.T) {
+	entries := []historyEntry{
+		{Session: "local", Command: "ls -la"},
+		{Session: "prod", Command: "tail -f /var/log/app.log"},
+		{Session: "prod", Command: "grep -c ERROR /var/log/app.log"},
+		{Session: "local", Command: "echo (x"},
+	}
+
+	tests := []struct {
+		pattern string
+		want    []int
+	}{
+		{"app.log", []int{2, 3}},
+		{"^tail", []int{2}},
+		{"ERROR|ls", []int{1, 3}},
+		{"(x", []int{4}}, // invalid regex, matched as a substring
+		{"missing", nil},
+	}
+	for _, tt := range tests {
+		var got []int
+		for _, m := range searchHistory(entries, tt.pattern) {
+			got = append(got, m.Index)
+			if m.historyEntry != entries[m.Index-1] {
+				t.Errorf("searchHistory(%q) match %d = %v, want %v", tt.pattern, m.Index, m.historyEntry, entries[m.Index-1])
+			}
+		}
+		if !reflect.DeepEqual(got, tt.want) {
+			t.Errorf("searchHistory(%q) indices = %v, want %v", tt.pattern, got, tt.want)
+		}
+	}
+}
+
+func TestHistoryAt(t *testing.T) {
+	entries := []historyEntry{{Session: "local", Command: "pwd"}, {Session: "prod", Command: "uptime"}}
+
+	entry, err := historyAt(entries, "2")
+	if err != nil {
+		t.Fatalf("historyAt(2) failed: %v", err)
+	}
+	if entry.Command != "uptime" || entry.Session != "prod" {
+		t.Errorf("historyAt(2) = %v", entry)
+	}
+
+	for _, index := range []string{"0", "3", "-1", "abc"} {
+		if _, err := historyAt(entries, index); err == nil {
+			t.Errorf("historyAt(%q) should fail", index)
+		}
+	}
+}
+
+func TestRunHistoryEntry(t *testing.T) {
+	t.Setenv("XDG_DATA_HOME", t.TempDir())
+	app := createInteractiveTestApp(t)
+	dir := t.TempDir()
+	app.history = []historyEntry{
+		{Session: "local", Command: "cd " + dir},
+		{Session: "local", Command: "/run 1"},
+	}
+
+	if err := app.handleSlashCommand("/run 1"); err != nil {
+		t.Fatalf("/run 1 failed: %v", err)
+	}
+	wantDir, _ := filepath.EvalSymlinks(dir)
+	gotDir, _ := filepath.EvalSymlinks(app.sessions.GetActiveSession().GetCWD())
+	if gotDir != wantDir {
+		t.Errorf("cwd after /run = %q, want %q", gotDir, wantDir)
+	}
+
+	if err := app.handleSlashCommand("/run 2"); err == nil {
+		t.Error("expected /run of a /run entry to fail")
+	}
+	if err := app.handleSlashCommand("/run 9"); err == nil {
+		t.Error("expected /run of a missing entry to fail")
+	}
+}
@@ -1,20 +1,27 @@
 package cli
 
 import (
+	"bufio"
 	"context"
+	"encoding/json"
 	"fmt"
 	"io"
 	"os"
 	"os/signal"
 	"path/filepath"
+	"sort"
 	"strconv"
 	"strings"
 	"syscall"
 	"time"
+	"unicode/utf8"
 
 	"github.com/chzyer/readline"
 	"github.com/scottgl9/thop/internal/config"
+	"github.com/scottgl9/thop/internal/logger"
+	"github.com/scottgl9/thop/internal/restriction"
 	"github.com/scottgl9/thop/internal/session"
+	"golang.org/x/crypto/ssh"
 	"golang.org/x/term"
 )
 
@@ -41,6 +48,58 @@ func getHistoryFile(sessionName string) string {
 	return filepath.Join(dir, "history_"+safeName)
 }
 
+// recordHistory appends a line to the active session's history file and to
+// the shared history. Lines are recorded explicitly rather than by readline's
+// auto-save so that each command lands in the history of the session it was
+// entered on, with or without readline.
+func (a *App) recordHistory(line string) {
+	a.recordSharedHistory(line)
+	if a.rl != nil {
+		_ = a.rl.SaveHistory(line)
+		return
+	}
+	appendHistory(a.sessions.GetActiveSessionName(), line)
+}
+
+// appendHistory appends a line to a session's history file
+func appendHistory(sessionName, line string) {
+	path := getHistoryFile(sessionName)
+	if path == "" {
+		return
+	}
+	if err := os.MkdirAll(filepath.Dir(path), 0700); err != nil {
+		return
+	}
+	f, err := os.OpenFile(path, os.O_APPEND|os.O_CREATE|os.O_WRONLY, 0600)
+	if err != nil {
+		return
+	}
+	defer f.Close()
+	_, _ = f.WriteString(line + "\n")
+}
+
+// readHistory returns the recorded history lines of a session, oldest first
+func readHistory(sessionName string) ([]string, error) {
+	path := getHistoryFile(sessionName)
+	if path == "" {
+		return nil, nil
+	}
+	data, err := os.ReadFile(path)
+	if err != nil {
+		if os.IsNotExist(err) {
+			return nil, nil
+		}
+		return nil, err
+	}
+	var lines []string
+	for _, line := range strings.Split(string(data), "\n") {
+		if line != "" {
+			lines = append(lines, line)
+		}
+	}
+	return lines, nil
+}
+
 // switchHistory switches the command history to a different session
 func (a *App) switchHistory(sessionName string) {
 	if a.rl == nil {
@@ -59,69 +118,21 @@ func (a *App) runInteractive() error {
 	if historyDir != "" {
 		_ = os.MkdirAll(historyDir, 0700)
 	}
+	a.loadHistory()
 
 	// Get history file for initial session
 	initialSession := a.sessions.GetActiveSessionName()
 	historyFile := getHistoryFile(initialSession)
 
-	// Create completer for slash commands
-	completer := readline.NewPrefixCompleter(
-		readline.PcItem("/connect",
-			readline.PcItemDynamic(a.sessionCompleter()),
-		),
-		readline.PcItem("/switch",
-			readline.PcItemDynamic(a.sessionCompleter()),
-		),
-		readline.PcItem("/close",
-			readline.PcItemDynamic(a.sessionCompleter()),
-		),
-		readline.PcItem("/auth",
-			readline.PcItemDynamic(a.sessionCompleter()),
-		),
-		readline.PcItem("/trust",
-			readline.PcItemDynamic(a.sessionCompleter()),
-		),
-		readline.PcItem("/copy"),
-		readline.PcItem("/cp"),
-		readline.PcItem("/add-session"),
-		readline.PcItem("/add"),
-		readline.PcItem("/read"),
-		readline.PcItem("/cat"),
-		readline.PcItem("/write"),
-		readline.PcItem("/env"),
-		readline.PcItem("/bg"),
-		readline.PcItem("/jobs"),
-		readline.PcItem("/fg"),
-		readline.PcItem("/kill"),
-		readline.PcItem("/shell"),
-		readline.PcItem("/sh"),
-		readline.PcItem("/local"),
-		readline.PcItem("/status"),
-		readline.PcItem("/help"),
-		readline.PcItem("/exit"),
-		readline.PcItem("/c",
-			readline.PcItemDynamic(a.sessionCompleter()),
-		),
-		readline.PcItem("/sw",
-			readline.PcItemDynamic(a.sessionCompleter()),
-		),
-		readline.PcItem("/d",
-			readline.PcItemDynamic(a.sessionCompleter()),
-		),
-		readline.PcItem("/l"),
-		readline.PcItem("/s"),
-		readline.PcItem("/h"),
-		readline.PcItem("/q"),
-	)
-
 	// Create readline instance
 	rl, err := readline.NewEx(&readline.Config{
-		Prompt:            a.getPrompt(),
-		HistoryFile:       historyFile,
-		AutoComplete:      completer,
-		InterruptPrompt:   "^C",
-		EOFPrompt:         "exit",
-		HistorySearchFold: true,
+		Prompt:                 a.getPrompt(),
+		HistoryFile:            historyFile,
+		DisableAutoSaveHistory: true,
+		AutoComplete:           a.newSlashCompleter(),
+		InterruptPrompt:        "^C",
+		EOFPrompt:              "exit",
+		HistorySearchFold:      true,
 	})
 	if err != nil {
 		// Fall back to simple mode if readline fails
@@ -130,15 +141,18 @@ func (a *App) runInteractive() error {
 	defer func() {
 		rl.Close()
 		a.rl = nil
+		a.setTerminal(nil)
 	}()
 
 	// Store readline instance for session switching
 	a.rl = rl
+	// readline's writers clear and redraw a half-typed line around each write
+	a.setTerminal(&terminalOutput{stdout: rl.Stdout(), stderr: rl.Stderr()})
 
 	if !a.quiet {
-		fmt.Println("thop - Terminal Hopper for Agents")
-		fmt.Println("Type /help for available commands")
-		fmt.Println()
+		a.println("thop - Terminal Hopper for Agents")
+		a.println("Type /help for available commands")
+		a.println()
 	}
 
 	for {
@@ -154,7 +168,7 @@ func (a *App) runInteractive() error {
 			}
 			if err == io.EOF {
 				// Ctrl+D - exit
-				fmt.Println()
+				a.println()
 				return nil
 			}
 			return err
@@ -164,6 +178,7 @@ func (a *App) runInteractive() error {
 		if input == "" {
 			continue
 		}
+		a.recordHistory(input)
 
 		// Check for slash commands
 		if strings.HasPrefix(input, "/") {
@@ -177,25 +192,19 @@ func (a *App) runInteractive() error {
 		result, err := a.executeWithSignalForwarding(input)
 		if err != nil {
 			a.outputError(err)
+			a.printRestrictionCaret(input, err)
 			continue
 		}
 
 		// Print output
-		if result.Stdout != "" {
-			fmt.Print(result.Stdout)
-			if !strings.HasSuffix(result.Stdout, "\n") {
-				fmt.Println()
-			}
-		}
-		if result.Stderr != "" {
-			fmt.Fprint(os.Stderr, result.Stderr)
-			if !strings.HasSuffix(result.Stderr, "\n") {
-				fmt.Fprintln(os.Stderr)
-			}
-		}
+		a.printResult(result)
 	}
 }
 
+// restrictionBypassPrefix marks an interactive command that should run even if
+// restricted mode would block it (requires allow_restriction_bypass)
+const restrictionBypassPrefix = "!! "
+
 // executeWithSignalForwarding executes a command with Ctrl+C forwarding
 func (a *App) executeWithSignalForwarding(cmd string) (*session.ExecuteResult, error) {
 	// Create a cancellable context
@@ -218,28 +227,59 @@ func (a *App) executeWithSignalForwarding(cmd string) (*session.ExecuteResult, e
 		}
 	}()
 
+	a.echoCommand(cmd)
+	if bypassed, ok := strings.CutPrefix(cmd, restrictionBypassPrefix); ok {
+		return a.sessions.ExecuteBypassWithContext(ctx, strings.TrimSpace(bypassed))
+	}
 	return a.sessions.ExecuteWithContext(ctx, cmd)
 }
 
+// printRestrictionCaret underlines the part of cmd that triggered a
+// restriction error, so it is clear which segment of a chain was blocked
+func (a *App) printRestrictionCaret(cmd string, err error) {
+	sessionErr, ok := err.(*session.Error)
+	if !ok || sessionErr.Code != session.ErrCommandRestricted || a.jsonOutput {
+		return
+	}
+	if _, _, span := a.sessions.CheckCommand(cmd); span != nil {
+		fmt.Fprint(a.terminal().Stderr(), restrictionCaret(cmd, span))
+	}
+}
+
+// restrictionCaret renders cmd with a caret underline beneath span
+func restrictionCaret(cmd string, span []int) string {
+	pad := utf8.RuneCountInString(cmd[:span[0]])
+	width := max(utf8.RuneCountInString(cmd[span[0]:span[1]]), 1)
+	return "  " + cmd + "\n  " + strings.Repeat(" ", pad) + strings.Repeat("^", width) + "\n"
+}
+
 // runInteractiveSimple runs interactive mode without readline (fallback)
 func (a *App) runInteractiveSimple() error {
 	reader := readline.NewCancelableStdin(os.Stdin)
 	defer reader.Close()
 
+	// Without readline, messages from background jobs start on a new line
+	// and are followed by a fresh prompt
+	a.setTerminal(&terminalOutput{
+		notifyPrefix: "\n",
+		redraw:       func() { fmt.Fprint(os.Stdout, a.getPrompt()) },
+	})
+	defer a.setTerminal(nil)
+
 	if !a.quiet {
-		fmt.Println("thop - Terminal Hopper for Agents")
-		fmt.Println("Type /help for available commands")
-		fmt.Println()
+		a.println("thop - Terminal Hopper for Agents")
+		a.println("Type /help for available commands")
+		a.println()
 	}
 
 	buf := make([]byte, 4096)
 	for {
-		fmt.Print(a.getPrompt())
+		a.print(a.getPrompt())
 
 		n, err := reader.Read(buf)
 		if err != nil {
 			if err == io.EOF {
-				fmt.Println()
+				a.println()
 				return nil
 			}
 			return err
@@ -249,6 +289,7 @@ func (a *App) runInteractiveSimple() error {
 		if input == "" {
 			continue
 		}
+		a.recordHistory(input)
 
 		if strings.HasPrefix(input, "/") {
 			if cmdErr := a.handleSlashCommand(input); cmdErr != nil {
@@ -260,21 +301,11 @@ func (a *App) runInteractiveSimple() error {
 		result, err := a.executeWithSignalForwarding(input)
 		if err != nil {
 			a.outputError(err)
+			a.printRestrictionCaret(input, err)
 			continue
 		}
 
-		if result.Stdout != "" {
-			fmt.Print(result.Stdout)
-			if !strings.HasSuffix(result.Stdout, "\n") {
-				fmt.Println()
-			}
-		}
-		if result.Stderr != "" {
-			fmt.Fprint(os.Stderr, result.Stderr)
-			if !strings.HasSuffix(result.Stderr, "\n") {
-				fmt.Fprintln(os.Stderr)
-			}
-		}
+		a.printResult(result)
 	}
 }
 
@@ -289,13 +320,6 @@ func (a *App) getPrompt() string {
 	return session.FormatPrompt(sessionName, cwd)
 }
 
-// sessionCompleter returns a function that provides session name completions
-func (a *App) sessionCompleter() func(string) []string {
-	return func(line string) []string {
-		return a.sessions.SessionNames()
-	}
-}
-
 // handleSlashCommand handles slash commands
 func (a *App) handleSlashCommand(input string) error {
 	parts := strings.Fields(input)
@@ -329,6 +353,24 @@ func (a *App) handleSlashCommand(input string) error {
 	case "/local", "/l":
 		return a.cmdSwitch("local")
 
+	case "/back", "/b":
+		return a.cmdBack()
+
+	case "/history":
+		return a.cmdHistory(args)
+
+	case "/recent":
+		return a.cmdRecent()
+
+	case "/search":
+		return a.cmdSearch(strings.Join(args, " "))
+
+	case "/run":
+		if len(args) != 1 {
+			return fmt.Errorf("usage: /run <index>")
+		}
+		return a.cmdRun(args[0])
+
 	case "/close", "/disconnect", "/d":
 		if len(args) == 0 {
 			return fmt.Errorf("usage: /close <session>")
@@ -339,7 +381,7 @@ func (a *App) handleSlashCommand(input string) error {
 		return a.printStatus()
 
 	case "/exit", "/quit", "/q":
-		fmt.Println("Goodbye!")
+		a.println("Goodbye!")
 		os.Exit(0)
 		return nil
 
@@ -358,9 +400,19 @@ func (a *App) handleSlashCommand(input string) error {
 		}
 		return a.cmdTrust(args[0])
 
+	case "/info":
+		name := a.sessions.GetActiveSessionName()
+		if len(args) > 0 {
+			name = args[0]
+		}
+		return a.cmdInfo(name)
+
+	case "/limits":
+		return a.cmdLimits()
+
 	case "/copy", "/cp":
 		if len(args) < 2 {
-			return fmt.Errorf("usage: /copy <source> <destination>\n  Examples:\n    /copy local:/path/to/file remote:/path/to/file\n    /copy remote:/path/to/file local:/path/to/file\n    /copy myserver:/path/to/file local:/path/to/file")
+			return fmt.Errorf("usage: /copy <source> <destination>\n  Examples:\n    /copy local:/path/to/file remote:/path/to/file\n    /copy remote:/path/to/file local:/path/to/file\n    /copy myserver:/path/to/file local:/path/to/file\n    /copy local:./logs/*.log remote:/tmp/")
 		}
 		return a.cmdCopy(args[0], args[1])
 
@@ -370,6 +422,21 @@ func (a *App) handleSlashCommand(input string) error {
 		}
 		return a.cmdAddSession(args[0], args[1])
 
+	case "/remove", "/rm-session":
+		if len(args) < 1 || len(args) > 2 || (len(args) == 2 && args[1] != "--save") {
+			return fmt.Errorf("usage: /remove <name> [--save]")
+		}
+		return a.cmdRemove(args[0], len(args) == 2)
+
+	case "/snapshot":
+		return a.cmdSnapshot(args)
+
+	case "/rename":
+		if len(args) != 2 {
+			return fmt.Errorf("usage: /rename <old> <new>")
+		}
+		return a.cmdRename(args[0], args[1])
+
 	case "/read", "/cat":
 		if len(args) < 1 {
 			return fmt.Errorf("usage: /read <path>")
@@ -377,10 +444,25 @@ func (a *App) handleSlashCommand(input string) error {
 		return a.cmdRead(args[0])
 
 	case "/write":
+		var mode os.FileMode
+		if len(args) > 0 && (args[0] == "--mode" || args[0] == "-m") {
+			if len(args) < 2 {
+				return fmt.Errorf("usage: /write [--mode <octal>] <path> <content>")
+			}
+			parsed, err := parseFileMode(args[1])
+			if err != nil {
+				return err
+			}
+			mode = parsed
+			args = args[2:]
+		}
 		if len(args) < 1 {
-			return fmt.Errorf("usage: /write <path> (content from stdin in proxy mode)")
+			return fmt.Errorf("usage: /write [--mode <octal>] <path> <content>")
 		}
-		return a.cmdWrite(args[0], args[1:])
+		return a.cmdWrite(args[0], args[1:], mode)
+
+	case "/capture":
+		return a.cmdCapture(args)
 
 	case "/bg":
 		if len(args) == 0 {
@@ -392,6 +474,43 @@ func (a *App) handleSlashCommand(input string) error {
 	case "/jobs":
 		return a.cmdJobs()
 
+	case "/check":
+		if len(args) == 0 {
+			return fmt.Errorf("usage: /check <command>\n  Reports whether a command would be blocked by restricted mode, without running it")
+		}
+		return a.cmdCheck(strings.Join(args, " "))
+
+	case "/realpath":
+		if len(args) == 0 {
+			return fmt.Errorf("usage: /realpath <path>\n  Resolves a path to its canonical absolute form on the current session")
+		}
+		return a.cmdRealpath(strings.Join(args, " "))
+
+	case "/mktemp":
+		name := a.sessions.GetActiveSessionName()
+		if len(args) > 0 {
+			name = args[0]
+		}
+		return a.cmdMktemp(name)
+
+	case "/cleanup":
+		name := a.sessions.GetActiveSessionName()
+		if len(args) > 0 {
+			name = args[0]
+		}
+		return a.cmdCleanup(name)
+
+	case "/watch-diff", "/wd":
+		update := false
+		if len(args) > 0 && (args[0] == "--update" || args[0] == "-u") {
+			update = true
+			args = args[1:]
+		}
+		if len(args) == 0 {
+			return fmt.Errorf("usage: /watch-diff [--update] <command>\n  Runs command and shows a diff against the first run's output")
+		}
+		return a.cmdWatchDiff(strings.Join(args, " "), update)
+
 	case "/fg":
 		if len(args) == 0 {
 			return fmt.Errorf("usage: /fg <job_id>")
@@ -410,11 +529,343 @@ func (a *App) handleSlashCommand(input string) error {
 		}
 		return a.cmdShell(strings.Join(args, " "))
 
+	case "/clear", "/cls":
+		scrollback := len(args) > 0 && (args[0] == "all" || args[0] == "--scrollback")
+		a.print(clearScreenSequence(scrollback))
+		return nil
+
+	case "/echo":
+		return a.cmdEcho(args)
+
+	case "/latency":
+		name := ""
+		if len(args) > 0 {
+			name = args[0]
+		}
+		return a.cmdLatency(name)
+
+	case "/changed":
+		if len(args) < 2 {
+			return fmt.Errorf("usage: /changed <dir> <since>\n  since is a relative time (10m, 2h, 3d) or a date (2006-01-02)")
+		}
+		return a.cmdChanged(args[0], args[1])
+
+	case "/ports":
+		name := ""
+		if len(args) > 0 {
+			name = args[0]
+		}
+		return a.cmdPorts(name)
+
+	case "/ps":
+		return a.cmdPs(strings.Join(args, " "))
+
+	case "/killproc":
+		return a.cmdKillProc(args)
+
+	case "/tail":
+		return a.cmdTail(args)
+
+	case "/journal":
+		return a.cmdJournal(args)
+
+	case "/edit":
+		if len(args) != 1 {
+			return fmt.Errorf("usage: /edit [session:]path\n  Opens the file in $EDITOR and writes it back if it changed")
+		}
+		return a.cmdEdit(args[0])
+
+	case "/ls":
+		path := "."
+		if len(args) > 0 {
+			path = args[0]
+		}
+		return a.cmdLs(path)
+
+	case "/raw":
+		if len(args) == 0 {
+			return fmt.Errorf("usage: /raw <command>\n  Runs command verbatim, without cwd/env wrapping (not allowed in restricted mode)")
+		}
+		// Keep the command exactly as typed, including inner spacing
+		return a.cmdRaw(strings.TrimSpace(strings.TrimSpace(input)[len(parts[0]):]))
+
 	default:
 		return fmt.Errorf("unknown command: %s (use /help for available commands)", cmd)
 	}
 }
 
+// latencySamples is how many probes /latency averages over
+const latencySamples = 5
+
+// cmdLatency handles the /latency command, timing a no-op command over the
+// session's existing connection
+func (a *App) cmdLatency(name string) error {
+	if name == "" {
+		name = a.sessions.GetActiveSessionName()
+	}
+
+	stats, err := a.sessions.MeasureLatency(context.Background(), name, latencySamples)
+	if err != nil {
+		return err
+	}
+
+	a.printf("Latency to %s (%d samples): min %s / avg %s / max %s\n",
+		name, stats.Samples, formatMillis(stats.Min), formatMillis(stats.Avg), formatMillis(stats.Max))
+	return nil
+}
+
+// formatMillis formats a duration as fractional milliseconds
+func formatMillis(d time.Duration) string {
+	return fmt.Sprintf("%.1f ms", float64(d)/float64(time.Millisecond))
+}
+
+// cmdChanged handles the /changed command, listing files under dir on the
+// current session that were modified after since
+func (a *App) cmdChanged(dir, since string) error {
+	cutoff, err := session.ParseSince(since, time.Now())
+	if err != nil {
+		return err
+	}
+
+	changed, err := a.sessions.ChangedFiles("", dir, cutoff)
+	if err != nil {
+		return err
+	}
+
+	if a.jsonOutput {
+		data, err := json.MarshalIndent(changed, "", "  ")
+		if err != nil {
+			return err
+		}
+		a.println(string(data))
+		return nil
+	}
+
+	if len(changed) == 0 {
+		a.printf("No files changed in %s since %s\n", dir, cutoff.Format("2006-01-02 15:04:05"))
+		return nil
+	}
+	for _, file := range changed {
+		a.printf("%s  %s\n", file.ModTime.Format("2006-01-02 15:04:05"), file.Path)
+	}
+	return nil
+}
+
+// cmdPorts handles the /ports command, listing the listening ports on the
+// named session (the current session if name is empty)
+func (a *App) cmdPorts(name string) error {
+	ports, err := a.sessions.ListeningPorts(name)
+	if err != nil {
+		return err
+	}
+
+	if a.jsonOutput {
+		if ports == nil {
+			ports = []session.ListeningPort{}
+		}
+		data, err := json.MarshalIndent(ports, "", "  ")
+		if err != nil {
+			return err
+		}
+		a.println(string(data))
+		return nil
+	}
+
+	if len(ports) == 0 {
+		a.println("No listening ports")
+		return nil
+	}
+	a.printf("%-5s %-24s %6s  %s\n", "PROTO", "ADDRESS", "PORT", "PROCESS")
+	for _, port := range ports {
+		process := "-"
+		if port.PID != 0 {
+			process = fmt.Sprintf("%s (%d)", port.Program, port.PID)
+		}
+		a.printf("%-5s %-24s %6d  %s\n", port.Proto, port.LocalAddr, port.Port, process)
+	}
+	return nil
+}
+
+// cmdPs handles the /ps command, listing the processes on the current
+// session whose command line contains filter
+func (a *App) cmdPs(filter string) error {
+	processes, err := a.sessions.ListProcesses("", filter)
+	if err != nil {
+		return err
+	}
+
+	if a.jsonOutput {
+		if processes == nil {
+			processes = []session.Process{}
+		}
+		data, err := json.MarshalIndent(processes, "", "  ")
+		if err != nil {
+			return err
+		}
+		a.println(string(data))
+		return nil
+	}
+
+	if len(processes) == 0 {
+		a.println("No matching processes")
+		return nil
+	}
+	a.printf("%7s %-12s %5s %5s  %s\n", "PID", "USER", "%CPU", "%MEM", "COMMAND")
+	for _, p := range processes {
+		a.printf("%7d %-12s %5.1f %5.1f  %s\n", p.PID, p.User, p.CPU, p.Mem, p.Command)
+	}
+	return nil
+}
+
+// tailUsage is the error shown for malformed /tail arguments
+const tailUsage = "usage: /tail <path> [-n N] [-f]"
+
+// cmdTail handles the /tail command, showing the end of a file on the current
+// session and, with -f, following it until Ctrl+C
+func (a *App) cmdTail(args []string) error {
+	path := ""
+	lines := session.DefaultTailLines
+	follow := false
+	for i := 0; i < len(args); i++ {
+		switch args[i] {
+		case "-f":
+			follow = true
+		case "-n":
+			if i+1 >= len(args) {
+				return fmt.Errorf(tailUsage)
+			}
+			i++
+			n, err := strconv.Atoi(args[i])
+			if err != nil || n < 0 {
+				return fmt.Errorf("invalid line count: %s", args[i])
+			}
+			lines = n
+		default:
+			if path != "" {
+				return fmt.Errorf(tailUsage)
+			}
+			path = args[i]
+		}
+	}
+	if path == "" {
+		return fmt.Errorf(tailUsage)
+	}
+
+	if !follow {
+		data, _, err := a.sessions.Tail("", path, lines)
+		if err != nil {
+			return err
+		}
+		if len(data) > 0 {
+			a.print(withTrailingNewline(string(data)))
+		}
+		return nil
+	}
+
+	ctx, cancel := context.WithCancel(context.Background())
+	defer cancel()
+
+	// Ctrl+C stops following rather than exiting thop
+	sigChan := make(chan os.Signal, 1)
+	signal.Notify(sigChan, syscall.SIGINT)
+	defer signal.Stop(sigChan)
+	go func() {
+		select {
+		case <-sigChan:
+			cancel()
+		case <-ctx.Done():
+		}
+	}()
+
+	out := a.terminal()
+	return a.sessions.FollowFile(ctx, "", path, lines, out.Stdout(), out.Stderr())
+}
+
+// cmdLs handles the /ls command, listing a directory on the current session
+func (a *App) cmdLs(path string) error {
+	entries, err := a.sessions.ListDir("", path)
+	if err != nil {
+		return err
+	}
+
+	if a.jsonOutput {
+		data, err := json.MarshalIndent(entries, "", "  ")
+		if err != nil {
+			return err
+		}
+		a.println(string(data))
+		return nil
+	}
+
+	for _, entry := range entries {
+		kind, name := "file", entry.Name
+		if entry.IsDir {
+			kind, name = "dir", entry.Name+"/"
+		}
+		a.printf("%-4s %10d  %s  %s\n", kind, entry.Size, entry.ModTime.Format("2006-01-02 15:04"), name)
+	}
+	return nil
+}
+
+// cmdEcho handles the /echo command, toggling whether commands are printed
+// to stderr before they run
+func (a *App) cmdEcho(args []string) error {
+	if len(args) > 0 {
+		switch args[0] {
+		case "on":
+			a.echoCommands = true
+		case "off":
+			a.echoCommands = false
+		default:
+			return fmt.Errorf("usage: /echo [on|off]")
+		}
+	}
+
+	state := "off"
+	if a.echoCommands {
+		state = "on"
+	}
+	a.printf("Command echo is %s\n", state)
+	return nil
+}
+
+// clearScreenSequence returns the ANSI sequence that homes the cursor and
+// clears the screen, optionally also clearing the terminal scrollback
+func clearScreenSequence(scrollback bool) string {
+	seq := "\033[H\033[2J"
+	if scrollback {
+		seq += "\033[3J"
+	}
+	return seq
+}
+
+// cmdRaw handles the /raw command, running a command on the current session
+// with none of thop's cwd or environment wrapping
+func (a *App) cmdRaw(command string) error {
+	result, err := a.sessions.ExecuteVerbatim(context.Background(), "", command)
+	if err != nil {
+		return err
+	}
+
+	if result.Stdout != "" {
+		a.print(result.Stdout)
+		if !strings.HasSuffix(result.Stdout, "\n") {
+			a.println()
+		}
+	}
+	if result.Stderr != "" {
+		fmt.Fprint(a.terminal().Stderr(), result.Stderr)
+		if !strings.HasSuffix(result.Stderr, "\n") {
+			fmt.Fprintln(a.terminal().Stderr())
+		}
+	}
+	if result.ExitCode != 0 {
+		a.printf("(exit code %d)\n", result.ExitCode)
+	}
+
+	return nil
+}
+
 // cmdEnv handles the /env command for setting environment variables
 func (a *App) cmdEnv(args []string) error {
 	sess := a.sessions.GetActiveSession()
@@ -426,11 +877,11 @@ func (a *App) cmdEnv(args []string) error {
 	if len(args) == 0 {
 		env := sess.GetEnv()
 		if len(env) == 0 {
-			fmt.Println("No environment variables set")
+			a.println("No environment variables set")
 		} else {
-			fmt.Println("Environment variables:")
+			a.println("Environment variables:")
 			for k, v := range env {
-				fmt.Printf("  %s=%s\n", k, v)
+				a.printf("  %s=%s\n", k, v)
 			}
 		}
 		return nil
@@ -445,7 +896,7 @@ func (a *App) cmdEnv(args []string) error {
 		if err := a.sessions.SetSessionEnv(key, value); err != nil {
 			return err
 		}
-		fmt.Printf("Set %s=%s\n", key, value)
+		a.printf("Set %s=%s\n", key, value)
 		return nil
 	}
 
@@ -454,24 +905,53 @@ func (a *App) cmdEnv(args []string) error {
 
 // printSlashHelp prints help for slash commands
 func (a *App) printSlashHelp() {
-	fmt.Println(`Available commands:
+	a.println(`Available commands:
   /connect <session>  Connect to an SSH session
   /switch <session>   Switch to a session
   /local              Switch to local shell (alias for /switch local)
+  /back               Switch back to the previously active session
+  /recent             List recently active sessions
+  /history [session] [n]  Show the last n commands entered on a session
+  /search <pattern>   Search the history of all sessions (regex or substring)
+  /run <index>        Re-run a history entry found by /search
   /status             Show all sessions
   /close <session>    Close an SSH connection
   /auth <session>     Set password for SSH session
   /trust <session>    Trust host key for SSH session
+  /info [session]     Show session details (default: current session)
+  /latency [session]  Measure command round-trip time (default: current session)
+  /limits             Show timeouts and restrictions in effect for the current session
   /copy <src> <dst>   Copy file between sessions (session:path format)
   /add-session <name> <host>  Add new SSH session to config
+  /rename <old> <new> Rename a session (also in the config file)
+  /remove <name> [--save]  Remove a session (--save also removes it from the config file)
+  /snapshot save|restore <name>  Save or restore the session's cwd and env
+  /snapshot list      List saved snapshots
   /read <path>        Read file contents (from current session)
-  /write <path> <content>  Write content to file (on current session)
+  /ls [path]          List a directory (on current session)
+  /changed <dir> <since>  List files modified since a time (10m, 2h, 2006-01-02)
+  /ports [session]    List listening TCP and UDP ports
+  /ps [filter]        List processes, optionally only those matching filter
+  /killproc <pid|name> [signal]  Signal a process (default TERM), asking first if a name matches several
+  /tail <path> [-n N] [-f]  Show the last N lines of a file (-f follows it until Ctrl+C)
+  /journal <unit> [-n N] [-f] [-m]  Show a systemd unit's journal (-f follows, -m messages only)
+  /write [-m mode] <path> <content>  Write content to file (on current session)
+  /edit [session:]path  Edit a file in $EDITOR and write it back if changed
   /env [KEY=VALUE]    Show or set environment variables
   /shell <command>    Run interactive command with PTY (vim, top, etc.)
+  /raw <command>      Run command verbatim, without cwd/env wrapping
+  /clear [all]        Clear the screen (with "all", also the scrollback)
+  /echo [on|off]      Print each command to stderr before running it
+  /capture [-a] [-s] <file> <command>  Run command, writing its output to a local file
   /bg <command>       Run command in background
   /jobs               List background jobs
   /fg <job_id>        Wait for job and show output
   /kill <job_id>      Kill a running background job
+  /watch-diff [-u] <command>  Diff command output against its baseline run
+  /realpath <path>    Resolve a path to its canonical absolute form
+  /check <command>    Check a command against restricted mode without running it
+  /mktemp [session]   Create a tracked temp directory (default: current session)
+  /cleanup [session]  Remove temp directories created by /mktemp
   /help               Show this help
   /exit               Exit thop
 
@@ -479,18 +959,24 @@ Shortcuts:
   /c    = /connect
   /sw   = /switch
   /l    = /local
+  /b    = /back
   /s    = /status
   /d    = /close (disconnect)
   /cp   = /copy
   /add  = /add-session
+  /rm-session = /remove
   /cat  = /read
   /sh   = /shell
+  /wd   = /watch-diff
+  /cls  = /clear
   /q    = /exit
 
 Copy examples:
   /copy local:/path/file remote:/path/file    Upload to active SSH session
   /copy remote:/path/file local:/path/file    Download from active SSH session
   /copy server1:/path/file server2:/path/file Copy between two SSH sessions
+  /copy local:./logs/*.log remote:/tmp/       Copy every match into a directory
+  /copy local:app.conf remote:.               Copy into the remote cwd
 
 Add session examples:
   /add-session myserver user@example.com      Add SSH session (port 22)
@@ -512,6 +998,9 @@ Background jobs:
   /fg 1                          Wait for job 1 and show output
   /kill 1                        Kill running job 1
 
+Restriction bypass (requires allow_restriction_bypass in [settings]):
+  !! rm -rf build                Run a restricted command anyway (audited)
+
 Keyboard shortcuts:
   Ctrl+D  Exit
   Ctrl+C  Interrupt running command
@@ -534,14 +1023,16 @@ func (a *App) cmdShell(command string) error {
 		// Reinitialize readline after interactive command
 		historyFile := getHistoryFile(a.sessions.GetActiveSessionName())
 		newRl, rlErr := readline.NewEx(&readline.Config{
-			Prompt:            a.getPrompt(),
-			HistoryFile:       historyFile,
-			InterruptPrompt:   "^C",
-			EOFPrompt:         "exit",
-			HistorySearchFold: true,
+			Prompt:                 a.getPrompt(),
+			HistoryFile:            historyFile,
+			DisableAutoSaveHistory: true,
+			InterruptPrompt:        "^C",
+			EOFPrompt:              "exit",
+			HistorySearchFold:      true,
 		})
 		if rlErr == nil {
 			a.rl = newRl
+			a.setTerminal(&terminalOutput{stdout: newRl.Stdout(), stderr: newRl.Stderr()})
 		}
 	}
 
@@ -550,7 +1041,7 @@ func (a *App) cmdShell(command string) error {
 	}
 
 	if exitCode != 0 {
-		fmt.Printf("Command exited with code %d\n", exitCode)
+		a.printf("Command exited with code %d\n", exitCode)
 	}
 
 	return nil
@@ -568,12 +1059,12 @@ func (a *App) cmdConnect(name string) error {
 
 	sess, _ := a.sessions.GetSession(name)
 	if sess.Type() == "local" {
-		fmt.Printf("Session '%s' is local, no connection needed\n", name)
+		a.printf("Session '%s' is local, no connection needed\n", name)
 		return nil
 	}
 
 	if sess.IsConnected() {
-		fmt.Printf("Session '%s' is already connected\n", name)
+		a.printf("Session '%s' is already connected\n", name)
 		// Even if already connected, switch to it
 		if err := a.sessions.SetActiveSession(name); err != nil {
 			return err
@@ -582,23 +1073,54 @@ func (a *App) cmdConnect(name string) error {
 		return nil
 	}
 
-	fmt.Printf("Connecting to %s...\n", name)
-	if err := a.sessions.Connect(name); err != nil {
+	a.printf("Connecting to %s...\n", name)
+	if err := a.connectWithPassphrase(name); err != nil {
 		return err
 	}
 
-	fmt.Printf("Connected to %s\n", name)
+	a.printf("Connected to %s\n", name)
 
 	// Auto-switch to the newly connected session
 	if err := a.sessions.SetActiveSession(name); err != nil {
 		return err
 	}
 	a.switchHistory(name)
-	fmt.Printf("Switched to %s\n", name)
+	a.printf("Switched to %s\n", name)
 
 	return nil
 }
 
+// maxPassphraseAttempts bounds how often a key passphrase is prompted for
+const maxPassphraseAttempts = 3
+
+// connectWithPassphrase connects a session, prompting for the private key
+// passphrase whenever the connection fails because an encrypted key could not
+// be unlocked
+func (a *App) connectWithPassphrase(name string) error {
+	err := a.sessions.Connect(name)
+	for attempt := 0; attempt < maxPassphraseAttempts; attempt++ {
+		sessionErr, ok := err.(*session.Error)
+		if !ok || sessionErr.Code != session.ErrAuthPassphraseRequired {
+			return err
+		}
+
+		a.println(sessionErr.Message)
+		passphrase, readErr := a.readPassword(fmt.Sprintf("Passphrase for %s: ", name))
+		if readErr != nil {
+			return fmt.Errorf("failed to read passphrase: %w", readErr)
+		}
+		if passphrase == "" {
+			return err
+		}
+
+		if setErr := a.sessions.SetSessionPassphrase(name, passphrase); setErr != nil {
+			return setErr
+		}
+		err = a.sessions.Connect(name)
+	}
+	return err
+}
+
 // cmdSwitch handles the /switch command
 func (a *App) cmdSwitch(name string) error {
 	if !a.sessions.HasSession(name) {
@@ -613,11 +1135,11 @@ func (a *App) cmdSwitch(name string) error {
 
 	// For SSH sessions, connect if not connected
 	if sess.Type() == "ssh" && !sess.IsConnected() {
-		fmt.Printf("Connecting to %s...\n", name)
-		if err := a.sessions.Connect(name); err != nil {
+		a.printf("Connecting to %s...\n", name)
+		if err := a.connectWithPassphrase(name); err != nil {
 			return err
 		}
-		fmt.Printf("Connected to %s\n", name)
+		a.printf("Connected to %s\n", name)
 	}
 
 	if err := a.sessions.SetActiveSession(name); err != nil {
@@ -628,17 +1150,102 @@ func (a *App) cmdSwitch(name string) error {
 	a.switchHistory(name)
 
 	if !a.quiet {
-		fmt.Printf("Switched to %s\n", name)
+		a.printf("Switched to %s\n", name)
 	}
 
 	return nil
 }
 
-// cmdClose handles the /close command
-func (a *App) cmdClose(name string) error {
-	if !a.sessions.HasSession(name) {
-		return &session.Error{
-			Code:    session.ErrSessionNotFound,
+// cmdBack handles the /back command, returning to the previously active session
+func (a *App) cmdBack() error {
+	name, ok := a.sessions.PreviousSession()
+	if !ok {
+		return fmt.Errorf("no previous session to go back to")
+	}
+
+	// For SSH sessions, connect if not connected
+	if sess, _ := a.sessions.GetSession(name); sess.Type() == "ssh" && !sess.IsConnected() {
+		a.printf("Connecting to %s...\n", name)
+		if err := a.sessions.Connect(name); err != nil {
+			return err
+		}
+		a.printf("Connected to %s\n", name)
+	}
+
+	if _, err := a.sessions.Back(); err != nil {
+		return err
+	}
+	a.switchHistory(name)
+
+	if !a.quiet {
+		a.printf("Switched to %s\n", name)
+	}
+
+	return nil
+}
+
+// cmdRecent handles the /recent command, listing previously active sessions
+func (a *App) cmdRecent() error {
+	recent := a.sessions.RecentSessions()
+	if len(recent) == 0 {
+		a.println("No previously active sessions")
+		return nil
+	}
+
+	a.printf("Current: %s\n", a.sessions.GetActiveSessionName())
+	for i, name := range recent {
+		a.printf("  %d. %s\n", i+1, name)
+	}
+	return nil
+}
+
+// defaultHistoryCount is how many entries /history shows when no count is given
+const defaultHistoryCount = 20
+
+// cmdHistory handles the /history command, showing the commands entered on a
+// session (the active session unless one is named)
+func (a *App) cmdHistory(args []string) error {
+	name := a.sessions.GetActiveSessionName()
+	count := defaultHistoryCount
+	for _, arg := range args {
+		if n, err := strconv.Atoi(arg); err == nil && n > 0 {
+			count = n
+			continue
+		}
+		if !a.sessions.HasSession(arg) {
+			return &session.Error{
+				Code:    session.ErrSessionNotFound,
+				Message: fmt.Sprintf("Session '%s' not found", arg),
+				Session: arg,
+			}
+		}
+		name = arg
+	}
+
+	lines, err := readHistory(name)
+	if err != nil {
+		return fmt.Errorf("failed to read history for %s: %w", name, err)
+	}
+	if len(lines) == 0 {
+		a.printf("No history for session %s\n", name)
+		return nil
+	}
+
+	start := 0
+	if len(lines) > count {
+		start = len(lines) - count
+	}
+	for i := start; i < len(lines); i++ {
+		a.printf("%5d  %s\n", i+1, lines[i])
+	}
+	return nil
+}
+
+// cmdClose handles the /close command
+func (a *App) cmdClose(name string) error {
+	if !a.sessions.HasSession(name) {
+		return &session.Error{
+			Code:    session.ErrSessionNotFound,
 			Message: fmt.Sprintf("Session '%s' not found", name),
 			Session: name,
 		}
@@ -646,12 +1253,12 @@ func (a *App) cmdClose(name string) error {
 
 	sess, _ := a.sessions.GetSession(name)
 	if sess.Type() == "local" {
-		fmt.Printf("Cannot close local session\n")
+		a.printf("Cannot close local session\n")
 		return nil
 	}
 
 	if !sess.IsConnected() {
-		fmt.Printf("Session '%s' is not connected\n", name)
+		a.printf("Session '%s' is not connected\n", name)
 		return nil
 	}
 
@@ -659,12 +1266,12 @@ func (a *App) cmdClose(name string) error {
 		return err
 	}
 
-	fmt.Printf("Disconnected from %s\n", name)
+	a.printf("Disconnected from %s\n", name)
 
 	// Switch to local if we closed the active session
 	if a.sessions.GetActiveSessionName() == name {
 		_ = a.sessions.SetActiveSession("local")
-		fmt.Println("Switched to local")
+		a.println("Switched to local")
 	}
 
 	return nil
@@ -685,217 +1292,148 @@ func (a *App) cmdAuth(name string) error {
 		return fmt.Errorf("session '%s' is not an SSH session", name)
 	}
 
-	// Get the SSH session
-	sshSess, ok := sess.(*session.SSHSession)
-	if !ok {
-		return fmt.Errorf("session '%s' is not an SSH session", name)
-	}
-
 	// Prompt for password securely (no echo)
-	fmt.Printf("Password for %s: ", name)
-	password, err := readPassword()
+	password, err := a.readPassword(fmt.Sprintf("Password for %s: ", name))
 	if err != nil {
 		return fmt.Errorf("failed to read password: %w", err)
 	}
-	fmt.Println() // Newline after password input
 
 	if password == "" {
 		return fmt.Errorf("password cannot be empty")
 	}
 
 	// Set the password on the session
-	sshSess.SetPassword(password)
-	fmt.Printf("Password set for %s\n", name)
+	if err := a.sessions.SetSessionPassword(name, password); err != nil {
+		return err
+	}
+	a.printf("Password set for %s\n", name)
 
 	// If not connected, offer to connect now
 	if !sess.IsConnected() {
-		fmt.Printf("Connecting to %s...\n", name)
+		a.printf("Connecting to %s...\n", name)
 		if err := a.sessions.Connect(name); err != nil {
 			return err
 		}
-		fmt.Printf("Connected to %s\n", name)
+		a.printf("Connected to %s\n", name)
 	}
 
 	return nil
 }
 
-// readPassword reads a password from stdin without echoing
-func readPassword() (string, error) {
-	// Use x/term for cross-platform password reading
-	password, err := term.ReadPassword(int(os.Stdin.Fd()))
-	if err != nil {
-		return "", err
-	}
-	return string(password), nil
-}
-
-// cmdCopy handles the /copy command for file transfer between sessions
-func (a *App) cmdCopy(src, dst string) error {
-	// Parse source and destination (format: session:path or just path for active session)
-	srcSession, srcPath := parseFileSpec(src)
-	dstSession, dstPath := parseFileSpec(dst)
-
-	// Default to active session if not specified
-	activeSession := a.sessions.GetActiveSessionName()
-	if srcSession == "" {
-		srcSession = activeSession
-	}
-	if dstSession == "" {
-		dstSession = activeSession
-	}
-
-	// Handle "remote" as alias for active SSH session
-	if srcSession == "remote" {
-		if activeSession == "local" {
-			return fmt.Errorf("no remote session active - use session name instead")
-		}
-		srcSession = activeSession
-	}
-	if dstSession == "remote" {
-		if activeSession == "local" {
-			return fmt.Errorf("no remote session active - use session name instead")
+// readPassword prints prompt and reads a password without echoing it,
+// ending the line afterwards. In readline mode the line editor reads it, so
+// the two don't compete for stdin.
+func (a *App) readPassword(prompt string) (string, error) {
+	if a.rl != nil {
+		password, err := a.rl.ReadPassword(prompt)
+		if err != nil {
+			return "", err
 		}
-		dstSession = activeSession
+		return trimPassword(string(password)), nil
 	}
 
-	// Validate sessions exist
-	if !a.sessions.HasSession(srcSession) {
-		return fmt.Errorf("source session '%s' not found", srcSession)
-	}
-	if !a.sessions.HasSession(dstSession) {
-		return fmt.Errorf("destination session '%s' not found", dstSession)
-	}
-
-	srcSess, _ := a.sessions.GetSession(srcSession)
-	dstSess, _ := a.sessions.GetSession(dstSession)
-
-	// Handle different transfer scenarios
-	if srcSess.Type() == "local" && dstSess.Type() == "local" {
-		return fmt.Errorf("both source and destination are local - use regular cp command")
-	}
+	a.print(prompt)
+	password, err := readPasswordFrom(int(os.Stdin.Fd()), os.Stdin)
+	a.println() // Newline after password input
+	return password, err
+}
 
-	if srcSess.Type() == "local" && dstSess.Type() == "ssh" {
-		// Upload: local -> remote
-		sshSess, ok := dstSess.(*session.SSHSession)
-		if !ok {
-			return fmt.Errorf("destination is not an SSH session")
+// readPasswordFrom reads a password from the terminal fd with echo turned
+// off, restoring the terminal afterwards even if the read fails or is
+// interrupted. If fd isn't a terminal (input is piped, or the platform has no
+// terminal support), a line is read from r as is.
+func readPasswordFrom(fd int, r io.Reader) (string, error) {
+	if !term.IsTerminal(fd) {
+		line, err := bufio.NewReader(r).ReadString('\n')
+		if err != nil && err != io.EOF {
+			return "", err
 		}
-		if !sshSess.IsConnected() {
-			fmt.Printf("Connecting to %s...\n", dstSession)
-			if err := a.sessions.Connect(dstSession); err != nil {
-				return err
+		return trimPassword(line), nil
+	}
+
+	if state, err := term.GetState(fd); err == nil {
+		defer func() { _ = term.Restore(fd, state) }()
+
+		// Ctrl+C would otherwise exit with echo still off
+		sigChan := make(chan os.Signal, 1)
+		signal.Notify(sigChan, syscall.SIGINT)
+		done := make(chan struct{})
+		defer func() {
+			signal.Stop(sigChan)
+			close(done)
+		}()
+		go func() {
+			select {
+			case <-sigChan:
+				_ = term.Restore(fd, state)
+				fmt.Println()
+				os.Exit(130)
+			case <-done:
 			}
-		}
-		fmt.Printf("Uploading %s to %s:%s...\n", srcPath, dstSession, dstPath)
-		if err := sshSess.UploadFile(srcPath, dstPath); err != nil {
-			return err
-		}
-		fmt.Printf("Upload complete\n")
-		return nil
+		}()
 	}
 
-	if srcSess.Type() == "ssh" && dstSess.Type() == "local" {
-		// Download: remote -> local
-		sshSess, ok := srcSess.(*session.SSHSession)
-		if !ok {
-			return fmt.Errorf("source is not an SSH session")
-		}
-		if !sshSess.IsConnected() {
-			fmt.Printf("Connecting to %s...\n", srcSession)
-			if err := a.sessions.Connect(srcSession); err != nil {
-				return err
-			}
-		}
-		fmt.Printf("Downloading %s:%s to %s...\n", srcSession, srcPath, dstPath)
-		if err := sshSess.DownloadFile(srcPath, dstPath); err != nil {
-			return err
-		}
-		fmt.Printf("Download complete\n")
-		return nil
+	password, err := term.ReadPassword(fd)
+	if err != nil {
+		return "", err
 	}
+	return trimPassword(string(password)), nil
+}
 
-	if srcSess.Type() == "ssh" && dstSess.Type() == "ssh" {
-		// Remote to remote: download then upload
-		srcSSH, _ := srcSess.(*session.SSHSession)
-		dstSSH, _ := dstSess.(*session.SSHSession)
-
-		// Connect both if needed
-		if !srcSSH.IsConnected() {
-			fmt.Printf("Connecting to %s...\n", srcSession)
-			if err := a.sessions.Connect(srcSession); err != nil {
-				return err
-			}
-		}
-		if !dstSSH.IsConnected() {
-			fmt.Printf("Connecting to %s...\n", dstSession)
-			if err := a.sessions.Connect(dstSession); err != nil {
-				return err
-			}
-		}
-
-		// Read from source
-		fmt.Printf("Reading %s:%s...\n", srcSession, srcPath)
-		data, err := srcSSH.ReadFile(srcPath)
-		if err != nil {
-			return fmt.Errorf("failed to read from %s: %w", srcSession, err)
-		}
+// trimPassword strips the line ending from a password read as a line
+func trimPassword(password string) string {
+	return strings.TrimRight(password, "\r\n")
+}
 
-		// Write to destination
-		fmt.Printf("Writing to %s:%s...\n", dstSession, dstPath)
-		if err := dstSSH.WriteFile(dstPath, data, 0644); err != nil {
-			return fmt.Errorf("failed to write to %s: %w", dstSession, err)
-		}
+// cmdCopy handles the /copy command for file transfer between sessions
+func (a *App) cmdCopy(src, dst string) error {
+	if _, path := session.ParseFileSpec(src); session.HasGlob(path) {
+		return a.cmdCopyGlob(src, dst)
+	}
 
-		fmt.Printf("Copy complete (%d bytes)\n", len(data))
-		return nil
+	a.printf("Copying %s to %s...\n", src, dst)
+	copied, err := a.sessions.CopyFile(src, dst)
+	if err != nil {
+		return err
 	}
 
-	return fmt.Errorf("unsupported copy operation")
+	a.printf("Copy complete (%d bytes)\n", copied)
+	return nil
 }
 
-// cmdRead handles the /read command to read and output file contents
-func (a *App) cmdRead(path string) error {
-	sess := a.sessions.GetActiveSession()
-	if sess == nil {
-		return fmt.Errorf("no active session")
-	}
+// cmdCopyGlob copies every file matching a glob source into the destination
+// directory
+func (a *App) cmdCopyGlob(src, dst string) error {
+	a.printf("Copying %s to %s...\n", src, dst)
+	copies, err := a.sessions.CopyGlob(src, dst)
 
-	if sess.Type() == "local" {
-		// Read local file
-		data, err := os.ReadFile(path)
-		if err != nil {
-			return fmt.Errorf("failed to read file: %w", err)
-		}
-		fmt.Print(string(data))
-		return nil
+	var total int64
+	for _, c := range copies {
+		a.printf("  %s -> %s (%d bytes)\n", c.Source, c.Destination, c.Bytes)
+		total += c.Bytes
 	}
-
-	// Read remote file via SFTP
-	sshSess, ok := sess.(*session.SSHSession)
-	if !ok {
-		return fmt.Errorf("session is not an SSH session")
+	if err != nil {
+		return err
 	}
 
-	if !sshSess.IsConnected() {
-		return fmt.Errorf("session is not connected")
-	}
+	a.printf("Copy complete (%d files, %d bytes)\n", len(copies), total)
+	return nil
+}
 
-	data, err := sshSess.ReadFile(path)
+// cmdRead handles the /read command to read and output file contents
+func (a *App) cmdRead(path string) error {
+	data, _, err := a.sessions.ReadFile("", path)
 	if err != nil {
 		return err
 	}
 
-	fmt.Print(string(data))
+	a.print(string(data))
 	return nil
 }
 
-// cmdWrite handles the /write command to write content to a file
-func (a *App) cmdWrite(path string, content []string) error {
-	sess := a.sessions.GetActiveSession()
-	if sess == nil {
-		return fmt.Errorf("no active session")
-	}
-
+// cmdWrite handles the /write command to write content to a file.
+// A zero mode keeps the default permissions, which honor the umask.
+func (a *App) cmdWrite(path string, content []string, mode os.FileMode) error {
 	// If content provided as arguments, join them
 	var data string
 	if len(content) > 0 {
@@ -903,36 +1441,27 @@ func (a *App) cmdWrite(path string, content []string) error {
 	} else {
 		// In interactive mode, we can't easily read from stdin
 		// This is mainly useful in proxy mode where content comes via arguments
-		return fmt.Errorf("usage: /write <path> <content>")
+		return fmt.Errorf("usage: /write [--mode <octal>] <path> <content>")
 	}
 
-	if sess.Type() == "local" {
-		// Write local file
-		if err := os.WriteFile(path, []byte(data), 0644); err != nil {
-			return fmt.Errorf("failed to write file: %w", err)
-		}
-		fmt.Printf("Wrote %d bytes to %s\n", len(data), path)
-		return nil
-	}
-
-	// Write remote file via SFTP
-	sshSess, ok := sess.(*session.SSHSession)
-	if !ok {
-		return fmt.Errorf("session is not an SSH session")
-	}
-
-	if !sshSess.IsConnected() {
-		return fmt.Errorf("session is not connected")
-	}
-
-	if err := sshSess.WriteFile(path, []byte(data), 0644); err != nil {
+	written, err := a.sessions.WriteFile("", path, []byte(data), mode)
+	if err != nil {
 		return err
 	}
 
-	fmt.Printf("Wrote %d bytes to %s\n", len(data), path)
+	a.printf("Wrote %d bytes to %s\n", len(data), written)
 	return nil
 }
 
+// parseFileMode parses an octal permission string such as "0600"
+func parseFileMode(s string) (os.FileMode, error) {
+	mode, err := strconv.ParseUint(s, 8, 32)
+	if err != nil || mode > 0777 {
+		return 0, fmt.Errorf("invalid mode %q: expected octal permissions such as 0600", s)
+	}
+	return os.FileMode(mode), nil
+}
+
 // cmdAddSession handles the /add-session command to add a new SSH session
 func (a *App) cmdAddSession(name, hostSpec string) error {
 	// Check if session already exists
@@ -962,16 +1491,97 @@ func (a *App) cmdAddSession(name, hostSpec string) error {
 		return err
 	}
 
-	if err := cfg.Save(""); err != nil {
+	if err := cfg.Save(a.configPath); err != nil {
 		return fmt.Errorf("session added but failed to save config: %w", err)
 	}
 
-	fmt.Printf("Added SSH session '%s' (%s@%s", name, user, host)
+	a.printf("Added SSH session '%s' (%s@%s", name, user, host)
 	if port != 0 && port != 22 {
-		fmt.Printf(":%d", port)
+		a.printf(":%d", port)
+	}
+	a.println(")")
+	a.printf("Config saved to %s\n", a.savedConfigPath())
+	return nil
+}
+
+// cmdRename handles the /rename command, renaming a session without
+// disconnecting it and saving the new name to the config file
+func (a *App) cmdRename(oldName, newName string) error {
+	if err := a.sessions.RenameSession(oldName, newName); err != nil {
+		return err
+	}
+	a.printf("Renamed session '%s' to '%s'\n", oldName, newName)
+
+	if err := a.sessions.GetConfig().Save(a.configPath); err != nil {
+		return fmt.Errorf("session renamed but failed to save config: %w", err)
+	}
+	return nil
+}
+
+// cmdRemove handles the /remove command, disconnecting and forgetting a
+// session. With save, the config file is rewritten without it; otherwise it
+// comes back the next time thop starts.
+func (a *App) cmdRemove(name string, save bool) error {
+	if err := a.sessions.RemoveSession(name); err != nil {
+		return err
+	}
+	a.printf("Removed session '%s'\n", name)
+
+	if !save {
+		return nil
+	}
+	if err := a.sessions.GetConfig().Save(a.configPath); err != nil {
+		return fmt.Errorf("session removed but failed to save config: %w", err)
+	}
+	return nil
+}
+
+// cmdSnapshot handles the /snapshot command, saving the active session's cwd
+// and environment under a name or restoring them later
+func (a *App) cmdSnapshot(args []string) error {
+	usage := fmt.Errorf("usage: /snapshot save <name> | restore <name> | list")
+	if len(args) == 1 && args[0] == "list" {
+		return a.listSnapshots()
+	}
+	if len(args) != 2 {
+		return usage
+	}
+
+	switch args[0] {
+	case "save":
+		snapshot, err := a.sessions.SaveSnapshot(args[1])
+		if err != nil {
+			return err
+		}
+		a.printf("Saved snapshot '%s' of %s (%s, %d env vars)\n", args[1], snapshot.Session, snapshot.CWD, len(snapshot.Env))
+	case "restore":
+		snapshot, err := a.sessions.RestoreSnapshot(args[1])
+		if err != nil {
+			return err
+		}
+		a.printf("Restored snapshot '%s' on %s (%s, %d env vars)\n", args[1], a.sessions.GetActiveSessionName(), snapshot.CWD, len(snapshot.Env))
+	default:
+		return usage
+	}
+	return nil
+}
+
+// listSnapshots prints the saved snapshots
+func (a *App) listSnapshots() error {
+	snapshots := a.sessions.Snapshots()
+	if len(snapshots) == 0 {
+		a.println("No snapshots saved")
+		return nil
+	}
+	names := make([]string, 0, len(snapshots))
+	for name := range snapshots {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	for _, name := range names {
+		s := snapshots[name]
+		a.printf("  %-16s %-12s %s (%d env vars, %s)\n", name, s.Session, s.CWD, len(s.Env), s.CreatedAt.Format("2006-01-02 15:04"))
 	}
-	fmt.Println(")")
-	fmt.Printf("Config saved to %s\n", config.DefaultConfigPath())
 	return nil
 }
 
@@ -998,33 +1608,104 @@ func parseHostSpec(spec string) (user, host string, port int) {
 
 	// Default user if not specified
 	if user == "" {
-		// Try to get current user
-		if currentUser := os.Getenv("USER"); currentUser != "" {
-			user = currentUser
-		} else {
-			user = "root"
-		}
+		user = config.DefaultUser()
 	}
 
 	return user, host, port
 }
 
-// parseFileSpec parses a file specification in the format "session:path" or just "path"
-func parseFileSpec(spec string) (session, path string) {
-	// Handle Windows-style paths (C:\...) by checking if it looks like a drive letter
-	if len(spec) >= 2 && spec[1] == ':' && (spec[0] >= 'A' && spec[0] <= 'Z' || spec[0] >= 'a' && spec[0] <= 'z') {
-		// Windows absolute path
-		return "", spec
+// cmdInfo handles the /info command for showing session details
+func (a *App) cmdInfo(name string) error {
+	sess, ok := a.sessions.GetSession(name)
+	if !ok {
+		return &session.Error{
+			Code:    session.ErrSessionNotFound,
+			Message: fmt.Sprintf("Session '%s' not found", name),
+			Session: name,
+		}
 	}
 
-	// Look for session:path format
-	idx := strings.Index(spec, ":")
-	if idx > 0 {
-		return spec[:idx], spec[idx+1:]
+	status := "disconnected"
+	if sess.IsConnected() {
+		status = "connected"
 	}
 
-	// Just a path, no session specified
-	return "", spec
+	a.printf("Session: %s\n", sess.Name())
+	a.printf("  Type:   %s\n", sess.Type())
+	a.printf("  Status: %s\n", status)
+	a.printf("  CWD:    %s\n", sess.GetCWD())
+	if cfg, ok := a.sessions.GetConfig().GetSession(name); ok && cfg.Description != "" {
+		a.printf("  About:  %s\n", cfg.Description)
+	}
+
+	if sshSess, ok := sess.(*session.SSHSession); ok {
+		a.printf("  Host:   %s@%s:%d\n", sshSess.User(), sshSess.Host(), sshSess.Port())
+		authMethod := sshSess.AuthMethod()
+		if authMethod == "" {
+			authMethod = "-"
+		}
+		a.printf("  Auth:   %s\n", authMethod)
+		if shell := sshSess.RemoteShell(); shell != "" {
+			a.printf("  Shell:  %s\n", shell)
+		}
+	}
+
+	return nil
+}
+
+// cmdLimits prints the limits in effect for the active session in interactive mode
+func (a *App) cmdLimits() error {
+	name := a.sessions.GetActiveSessionName()
+	cfg := a.sessions.GetConfig()
+	settings := cfg.Settings
+
+	timeoutSource := "command_timeout"
+	if settings.InteractiveTimeout > 0 {
+		timeoutSource = "interactive_timeout"
+	} else if settings.CommandTimeout <= 0 {
+		timeoutSource = "default"
+	}
+
+	// A session's own command_timeout only applies to MCP tool calls, where
+	// automated_timeout replaces the interactive default
+	mcpTimeout, mcpSource := 300, "default"
+	if sessionCfg, ok := cfg.GetSession(name); ok && sessionCfg.CommandTimeout > 0 {
+		mcpTimeout, mcpSource = sessionCfg.CommandTimeout, fmt.Sprintf("sessions.%s.command_timeout", name)
+	} else if settings.AutomatedTimeout > 0 {
+		mcpTimeout, mcpSource = settings.AutomatedTimeout, "automated_timeout"
+	} else if settings.CommandTimeout > 0 {
+		mcpTimeout, mcpSource = settings.CommandTimeout, "command_timeout"
+	}
+
+	keepalive := "off"
+	if settings.KeepaliveInterval > 0 {
+		keepalive = fmt.Sprintf("%ds", settings.KeepaliveInterval)
+	}
+
+	restrictions := "off"
+	if a.sessions.IsSessionRestricted(name) {
+		restrictions = "on (session restricted = true)"
+	} else if a.sessions.IsRestrictedMode() {
+		restrictions = "on (--restricted)"
+	}
+	if restrictions != "off" && a.sessions.IsRestrictionWarnOnly() {
+		restrictions += ", warn-only"
+	}
+
+	bypass := "disabled"
+	if settings.AllowRestrictionBypass {
+		bypass = "allowed (!! prefix)"
+	}
+
+	a.printf("Limits for session '%s' (interactive mode):\n", name)
+	a.printf("  Command timeout: %ds (%s)\n", cfg.DefaultTimeout(), timeoutSource)
+	a.printf("  MCP timeout:     %ds (%s)\n", mcpTimeout, mcpSource)
+	a.printf("  SSH keepalive:   %s\n", keepalive)
+	a.printf("  Max sessions:    %d\n", cfg.SessionLimit())
+	a.printf("  Restrictions:    %s\n", restrictions)
+	a.printf("  Bypass:          %s\n", bypass)
+
+	return nil
 }
 
 // cmdTrust handles the /trust command for host key verification
@@ -1049,17 +1730,27 @@ func (a *App) cmdTrust(name string) error {
 	}
 
 	// Fetch the host key and fingerprint
-	fmt.Printf("Fetching host key from %s:%d...\n", sshSess.Host(), sshSess.Port())
-	keyType, fingerprint, err := sshSess.FetchHostKey()
+	a.printf("Fetching host key from %s:%d...\n", sshSess.Host(), sshSess.Port())
+	key, status, err := sshSess.ProbeHostKey()
 	if err != nil {
 		return fmt.Errorf("failed to fetch host key: %w", err)
 	}
 
-	// Display the fingerprint and ask for confirmation
-	fmt.Printf("\nHost key for %s:\n", name)
-	fmt.Printf("  Type:        %s\n", keyType)
-	fmt.Printf("  Fingerprint: %s\n", fingerprint)
-	fmt.Printf("\nAre you sure you want to trust this host? (yes/no): ")
+	// Display the fingerprint
+	a.printf("\nHost key for %s:\n", name)
+	a.printf("  Type:        %s\n", key.Type())
+	a.printf("  Fingerprint: %s\n", ssh.FingerprintSHA256(key))
+
+	switch status {
+	case session.HostKeyTrusted:
+		a.println("\nHost key is already in known_hosts.")
+		return nil
+	case session.HostKeyMismatch:
+		// Never replace a known key from here; the user must remove it deliberately
+		return sshSess.HostKeyMismatchError()
+	}
+
+	a.printf("\nAre you sure you want to trust this host? (yes/no): ")
 
 	var answer string
 	_, err = fmt.Scanln(&answer)
@@ -1069,21 +1760,130 @@ func (a *App) cmdTrust(name string) error {
 
 	answer = strings.ToLower(strings.TrimSpace(answer))
 	if answer != "yes" && answer != "y" {
-		fmt.Println("Host key not trusted.")
+		a.println("Host key not trusted.")
 		return nil
 	}
 
-	// Add the host key to known_hosts
-	if err := sshSess.AddHostKey(); err != nil {
-		return fmt.Errorf("failed to add host key: %w", err)
+	// Add the confirmed host key to known_hosts
+	if err := sshSess.TrustHostKey(key); err != nil {
+		return err
+	}
+
+	a.printf("Host key added to known_hosts for %s\n", name)
+	return nil
+}
+
+// cmdMktemp handles the /mktemp command
+func (a *App) cmdMktemp(name string) error {
+	dir, err := a.sessions.CreateTempDir(name)
+	if err != nil {
+		return err
+	}
+	a.println(dir)
+	return nil
+}
+
+// cmdCheck handles the /check command. It reports whether cmd matches a
+// restriction rule without executing it.
+func (a *App) cmdCheck(cmd string) error {
+	// Allow the command to be quoted as a single argument
+	if len(cmd) >= 2 && (cmd[0] == '"' || cmd[0] == '\'') && cmd[len(cmd)-1] == cmd[0] {
+		cmd = cmd[1 : len(cmd)-1]
+	}
+
+	allowed, rule, span := a.sessions.CheckCommand(cmd)
+	if allowed {
+		a.println("Allowed: no restriction rule matches")
+		return nil
+	}
+
+	a.printf("Blocked: %s (%s)\n", restriction.CategoryDescription(rule.Category), rule.Category)
+	a.printf("  Rule: %s - %s\n", rule.Command, rule.Description)
+	if span != nil {
+		a.print(restrictionCaret(cmd, span))
+	}
+
+	active := a.sessions.GetActiveSessionName()
+	switch {
+	case !a.sessions.IsRestrictedMode() && !a.sessions.IsSessionRestricted(active):
+		a.printf("  Note: restricted mode is off for %s, so this command would currently run\n", active)
+	case a.sessions.IsRestrictionWarnOnly():
+		a.println("  Note: warn-only mode is on, so this command would be logged but still run")
+	}
+	return nil
+}
+
+// cmdRealpath handles the /realpath command
+func (a *App) cmdRealpath(path string) error {
+	resolved, err := a.sessions.RealPath(a.sessions.GetActiveSessionName(), path)
+	if err != nil {
+		return err
+	}
+	a.println(resolved)
+	return nil
+}
+
+// cmdCleanup handles the /cleanup command
+func (a *App) cmdCleanup(name string) error {
+	removed, err := a.sessions.CleanupTempDirs(name)
+	for _, dir := range removed {
+		a.printf("Removed %s\n", dir)
+	}
+	if err != nil {
+		return err
+	}
+	if len(removed) == 0 {
+		a.printf("No temp directories to clean up on %s\n", name)
+	}
+	return nil
+}
+
+// cmdWatchDiff handles the /watch-diff command. The first run stores the
+// output as a baseline; later runs show a unified diff against it. With
+// update set, the baseline is replaced by the latest output.
+func (a *App) cmdWatchDiff(command string, update bool) error {
+	sessionName := a.sessions.GetActiveSessionName()
+	key := sessionName + "\x00" + command
+
+	result, err := a.sessions.Execute(command)
+	if err != nil {
+		return err
+	}
+	output := result.Stdout + result.Stderr
+
+	baseline, ok := a.watchBaselines[key]
+	if !ok {
+		a.watchBaselines[key] = output
+		a.print(output)
+		a.printf("Baseline saved for '%s' on %s\n", command, sessionName)
+		return nil
+	}
+
+	diff := unifiedDiff("baseline", "current", baseline, output)
+	if diff == "" {
+		a.println("No changes since baseline")
+	} else {
+		a.print(diff)
+	}
+
+	if update {
+		a.watchBaselines[key] = output
+		a.println("Baseline updated")
 	}
 
-	fmt.Printf("Host key added to known_hosts for %s\n", name)
 	return nil
 }
 
 // cmdBg runs a command in the background
 func (a *App) cmdBg(command string) error {
+	job := a.startBackgroundJob(command)
+	a.printf("[%d] Started in background: %s\n", job.ID, command)
+	return nil
+}
+
+// startBackgroundJob runs command on the active session in a goroutine,
+// tracked as a new background job
+func (a *App) startBackgroundJob(command string) *BackgroundJob {
 	// Get current session info
 	sessionName := a.sessions.GetActiveSessionName()
 
@@ -1102,8 +1902,6 @@ func (a *App) cmdBg(command string) error {
 	a.bgJobs[jobID] = job
 	a.bgJobsMu.Unlock()
 
-	fmt.Printf("[%d] Started in background: %s\n", jobID, command)
-
 	// Run the command in a goroutine
 	go func() {
 		ctx := context.Background()
@@ -1128,16 +1926,30 @@ func (a *App) cmdBg(command string) error {
 			job.ExitCode = result.ExitCode
 		}
 
-		// Print notification that job completed
-		duration := job.EndTime.Sub(job.StartTime).Round(time.Millisecond)
-		if job.Status == "completed" {
-			fmt.Printf("\n[%d] Done (%s): %s\n", jobID, duration, command)
-		} else {
-			fmt.Printf("\n[%d] Failed (%s): %s\n", jobID, duration, command)
-		}
+		a.notifyJobDone(job)
 	}()
 
-	return nil
+	return job
+}
+
+// notifyJobDone announces that a background job finished. At the interactive
+// prompt the notice goes through readline so a half-typed line is redrawn
+// below it. In proxy and MCP mode, or with quiet_jobs set, nothing is printed
+// and the outcome is only available from /jobs; the --tui dashboard shows it
+// in its jobs pane.
+func (a *App) notifyJobDone(job *BackgroundJob) {
+	duration := job.EndTime.Sub(job.StartTime).Round(time.Millisecond)
+	logger.Debug("background job %d %s after %s: %s", job.ID, job.Status, duration, job.Command)
+
+	if a.proxyMode || a.mcpMode || a.tuiMode || (a.config != nil && a.config.Settings.QuietJobs) {
+		return
+	}
+
+	outcome := "Done"
+	if job.Status != "completed" {
+		outcome = "Failed"
+	}
+	a.terminal().Notify(fmt.Sprintf("[%d] %s (%s): %s", job.ID, outcome, duration, job.Command))
 }
 
 // cmdJobs lists all background jobs
@@ -1145,31 +1957,86 @@ func (a *App) cmdJobs() error {
 	a.bgJobsMu.RLock()
 	defer a.bgJobsMu.RUnlock()
 
-	if len(a.bgJobs) == 0 {
-		fmt.Println("No background jobs")
-		return nil
+	jobs := make([]*BackgroundJob, 0, len(a.bgJobs))
+	for _, job := range a.bgJobs {
+		jobs = append(jobs, job)
 	}
+	sort.Slice(jobs, func(i, j int) bool { return jobs[i].ID < jobs[j].ID })
 
-	fmt.Println("Background jobs:")
-	for _, job := range a.bgJobs {
-		var status string
-		switch job.Status {
-		case "running":
-			duration := time.Since(job.StartTime).Round(time.Second)
-			status = fmt.Sprintf("running (%s)", duration)
-		case "completed":
-			duration := job.EndTime.Sub(job.StartTime).Round(time.Millisecond)
-			status = fmt.Sprintf("completed (exit %d, %s)", job.ExitCode, duration)
-		case "failed":
-			duration := job.EndTime.Sub(job.StartTime).Round(time.Millisecond)
-			status = fmt.Sprintf("failed (%s)", duration)
+	if a.jsonOutput {
+		infos := make([]jobInfo, 0, len(jobs))
+		for _, job := range jobs {
+			infos = append(infos, newJobInfo(job, time.Now()))
 		}
-		fmt.Printf("  [%d] %-12s %s  %s\n", job.ID, job.Session, status, truncateString(job.Command, 40))
+		data, err := json.MarshalIndent(infos, "", "  ")
+		if err != nil {
+			return err
+		}
+		a.println(string(data))
+		return nil
+	}
+
+	if len(jobs) == 0 {
+		a.println("No background jobs")
+		return nil
+	}
+
+	a.println("Background jobs:")
+	for _, job := range jobs {
+		a.printf("  [%d] %-12s %s  %s\n", job.ID, job.Session, jobStatus(job, time.Now()), truncateString(job.Command, 40))
 	}
 
 	return nil
 }
 
+// jobStatus describes a background job's state as of now, e.g.
+// "running (5s)" or "completed (exit 0, 1.2s)"
+func jobStatus(job *BackgroundJob, now time.Time) string {
+	switch job.Status {
+	case "running":
+		return fmt.Sprintf("running (%s)", now.Sub(job.StartTime).Round(time.Second))
+	case "completed":
+		duration := job.EndTime.Sub(job.StartTime).Round(time.Millisecond)
+		return fmt.Sprintf("completed (exit %d, %s)", job.ExitCode, duration)
+	case "failed":
+		duration := job.EndTime.Sub(job.StartTime).Round(time.Millisecond)
+		return fmt.Sprintf("failed (%s)", duration)
+	}
+	return job.Status
+}
+
+// jobInfo is the JSON form of a background job listed by /jobs
+type jobInfo struct {
+	ID              int        `json:"id"`
+	Command         string     `json:"command"`
+	Session         string     `json:"session"`
+	Status          string     `json:"status"`
+	ExitCode        *int       `json:"exit_code"` // null while running
+	StartTime       time.Time  `json:"start_time"`
+	EndTime         *time.Time `json:"end_time"` // null while running
+	DurationSeconds float64    `json:"duration_seconds"`
+}
+
+// newJobInfo describes job as of now; running jobs report elapsed time so far
+func newJobInfo(job *BackgroundJob, now time.Time) jobInfo {
+	info := jobInfo{
+		ID:        job.ID,
+		Command:   job.Command,
+		Session:   job.Session,
+		Status:    job.Status,
+		StartTime: job.StartTime,
+	}
+	end := now
+	if job.Status != "running" {
+		exitCode, endTime := job.ExitCode, job.EndTime
+		info.ExitCode = &exitCode
+		info.EndTime = &endTime
+		end = job.EndTime
+	}
+	info.DurationSeconds = end.Sub(job.StartTime).Seconds()
+	return info
+}
+
 // cmdFg waits for a background job and displays its output
 func (a *App) cmdFg(jobIDStr string) error {
 	jobID, err := strconv.Atoi(jobIDStr)
@@ -1187,7 +2054,7 @@ func (a *App) cmdFg(jobIDStr string) error {
 
 	// If job is still running, wait for it
 	if job.Status == "running" {
-		fmt.Printf("Waiting for job %d: %s\n", jobID, job.Command)
+		a.printf("Waiting for job %d: %s\n", jobID, job.Command)
 
 		// Poll until done (simple approach - could be improved with channels)
 		for {
@@ -1202,17 +2069,17 @@ func (a *App) cmdFg(jobIDStr string) error {
 	}
 
 	// Display output
-	fmt.Printf("Job %d (%s):\n", jobID, job.Status)
+	a.printf("Job %d (%s):\n", jobID, job.Status)
 	if job.Stdout != "" {
-		fmt.Print(job.Stdout)
+		a.print(job.Stdout)
 		if !strings.HasSuffix(job.Stdout, "\n") {
-			fmt.Println()
+			a.println()
 		}
 	}
 	if job.Stderr != "" {
-		fmt.Fprint(os.Stderr, job.Stderr)
+		fmt.Fprint(a.terminal().Stderr(), job.Stderr)
 		if !strings.HasSuffix(job.Stderr, "\n") {
-			fmt.Fprintln(os.Stderr)
+			fmt.Fprintln(a.terminal().Stderr())
 		}
 	}
 
@@ -1253,7 +2120,7 @@ func (a *App) cmdKillJob(jobIDStr string) error {
 	delete(a.bgJobs, jobID)
 	a.bgJobsMu.Unlock()
 
-	fmt.Printf("Job %d killed\n", jobID)
+	a.printf("Job %d killed\n", jobID)
 
 	// Note: The actual goroutine will continue running until the command finishes,
 	// but we've removed it from tracking. For proper cancellation, we'd need to
@@ -2,8 +2,10 @@ package cli
 
 import (
 	"bytes"
+	"encoding/json"
 	"io"
 	"os"
+	"path/filepath"
 	"strings"
 	"testing"
 	"time"
@@ -668,6 +670,43 @@ func TestHandleSlashCommandWrite(t *testing.T) {
 	}
 }
 
+func TestHandleSlashCommandWriteMode(t *testing.T) {
+	app := createInteractiveTestApp(t)
+	testFile := filepath.Join(t.TempDir(), "secret.txt")
+
+	// Suppress stdout
+	oldStdout := os.Stdout
+	_, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err := app.handleSlashCommand("/write --mode 0600 " + testFile + " s3cret")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	if err != nil {
+		t.Fatalf("/write --mode should not error: %v", err)
+	}
+
+	info, err := os.Stat(testFile)
+	if err != nil {
+		t.Fatalf("failed to stat written file: %v", err)
+	}
+	if perm := info.Mode().Perm(); perm != 0600 {
+		t.Errorf("expected mode 0600, got %o", perm)
+	}
+
+	// Invalid modes are rejected before writing
+	for _, mode := range []string{"rw", "0999", "01777"} {
+		if err := app.handleSlashCommand("/write --mode " + mode + " " + testFile + " x"); err == nil {
+			t.Errorf("expected error for mode %q", mode)
+		}
+	}
+	if err := app.handleSlashCommand("/write --mode"); err == nil {
+		t.Error("expected error for /write --mode without a value")
+	}
+}
+
 func TestHandleSlashCommandEnv(t *testing.T) {
 	app := createInteractiveTestApp(t)
 
@@ -715,32 +754,6 @@ func TestHandleSlashCommandEnv(t *testing.T) {
 	}
 }
 
-func TestParseFileSpec(t *testing.T) {
-	tests := []struct {
-		input           string
-		expectedSession string
-		expectedPath    string
-	}{
-		{"local:/path/to/file", "local", "/path/to/file"},
-		{"remote:/home/user/file", "remote", "/home/user/file"},
-		{"server1:/etc/config", "server1", "/etc/config"},
-		{"/absolute/path", "", "/absolute/path"},
-		{"relative/path", "", "relative/path"},
-		{"C:/Windows/path", "", "C:/Windows/path"},     // Windows path
-		{"D:\\Windows\\path", "", "D:\\Windows\\path"}, // Windows path with backslash
-	}
-
-	for _, tt := range tests {
-		sess, path := parseFileSpec(tt.input)
-		if sess != tt.expectedSession {
-			t.Errorf("parseFileSpec(%q) session = %q, want %q", tt.input, sess, tt.expectedSession)
-		}
-		if path != tt.expectedPath {
-			t.Errorf("parseFileSpec(%q) path = %q, want %q", tt.input, path, tt.expectedPath)
-		}
-	}
-}
-
 func TestParseHostSpec(t *testing.T) {
 	// Save and restore USER env
 	oldUser := os.Getenv("USER")
@@ -1061,3 +1074,623 @@ func TestHandleSlashCommandShell(t *testing.T) {
 	// Note: Can't fully test interactive commands in unit tests
 	// because they require a real TTY
 }
+
+func TestHandleSlashCommandInfo(t *testing.T) {
+	app := createInteractiveTestApp(t)
+
+	// Info on non-existent session
+	err := app.handleSlashCommand("/info nonexistent")
+	if err == nil {
+		t.Fatal("expected error for nonexistent session")
+	}
+	if sessionErr, ok := err.(*session.Error); !ok || sessionErr.Code != session.ErrSessionNotFound {
+		t.Errorf("expected %s error, got %v", session.ErrSessionNotFound, err)
+	}
+
+	// Capture stdout
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	errActive := app.handleSlashCommand("/info")
+	errSSH := app.handleSlashCommand("/info testserver")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	if errActive != nil {
+		t.Errorf("/info should not error: %v", errActive)
+	}
+	if errSSH != nil {
+		t.Errorf("/info testserver should not error: %v", errSSH)
+	}
+
+	var buf bytes.Buffer
+	io.Copy(&buf, r)
+	output := buf.String()
+
+	if !strings.Contains(output, "Session: local") {
+		t.Errorf("expected active session info, got: %s", output)
+	}
+	if !strings.Contains(output, "testuser@example.com:22") {
+		t.Errorf("expected SSH host info, got: %s", output)
+	}
+	if !strings.Contains(output, "Auth:   -") {
+		t.Errorf("expected empty auth method for disconnected session, got: %s", output)
+	}
+}
+
+func TestHandleSlashCommandWatchDiff(t *testing.T) {
+	app := createInteractiveTestApp(t)
+
+	// Without a command
+	if err := app.handleSlashCommand("/watch-diff"); err == nil {
+		t.Error("expected error for /watch-diff without command")
+	}
+	if err := app.handleSlashCommand("/watch-diff --update"); err == nil {
+		t.Error("expected error for /watch-diff --update without command")
+	}
+
+	dataFile := t.TempDir() + "/data.txt"
+	if err := os.WriteFile(dataFile, []byte("alpha\nbeta\n"), 0644); err != nil {
+		t.Fatalf("failed to write data file: %v", err)
+	}
+	watchCmd := "/watch-diff cat " + dataFile
+
+	run := func(cmd string) string {
+		t.Helper()
+		oldStdout := os.Stdout
+		r, w, _ := os.Pipe()
+		os.Stdout = w
+
+		err := app.handleSlashCommand(cmd)
+
+		w.Close()
+		os.Stdout = oldStdout
+
+		if err != nil {
+			t.Fatalf("%s failed: %v", cmd, err)
+		}
+		var buf bytes.Buffer
+		io.Copy(&buf, r)
+		return buf.String()
+	}
+
+	// First run stores the baseline
+	output := run(watchCmd)
+	if !strings.Contains(output, "Baseline saved") {
+		t.Errorf("expected baseline to be saved, got: %s", output)
+	}
+
+	// Unchanged output shows no diff
+	output = run(watchCmd)
+	if !strings.Contains(output, "No changes since baseline") {
+		t.Errorf("expected no changes, got: %s", output)
+	}
+
+	// Changed output shows the difference
+	if err := os.WriteFile(dataFile, []byte("alpha\ngamma\n"), 0644); err != nil {
+		t.Fatalf("failed to update data file: %v", err)
+	}
+	output = run(watchCmd)
+	if !strings.Contains(output, "-beta") || !strings.Contains(output, "+gamma") {
+		t.Errorf("expected diff with -beta/+gamma, got: %s", output)
+	}
+
+	// Without --update the baseline is unchanged, so the diff repeats
+	output = run("/watch-diff --update cat " + dataFile)
+	if !strings.Contains(output, "+gamma") || !strings.Contains(output, "Baseline updated") {
+		t.Errorf("expected diff and baseline update, got: %s", output)
+	}
+
+	// After updating, output matches the new baseline
+	output = run(watchCmd)
+	if !strings.Contains(output, "No changes since baseline") {
+		t.Errorf("expected no changes after update, got: %s", output)
+	}
+}
+
+func TestHandleSlashCommandMktempCleanup(t *testing.T) {
+	app := createInteractiveTestApp(t)
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	errMktemp := app.handleSlashCommand("/mktemp")
+	dirs := app.sessions.TempDirs("local")
+	errCleanup := app.handleSlashCommand("/cleanup local")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	if errMktemp != nil {
+		t.Fatalf("/mktemp failed: %v", errMktemp)
+	}
+	if errCleanup != nil {
+		t.Fatalf("/cleanup failed: %v", errCleanup)
+	}
+
+	var buf bytes.Buffer
+	io.Copy(&buf, r)
+	output := buf.String()
+
+	if len(dirs) != 1 {
+		t.Fatalf("expected one tracked temp dir, got %v", dirs)
+	}
+	if !strings.Contains(output, "Removed "+dirs[0]) {
+		t.Errorf("expected cleanup to report %s, got: %s", dirs[0], output)
+	}
+	if _, err := os.Stat(dirs[0]); !os.IsNotExist(err) {
+		t.Errorf("expected %s to be removed", dirs[0])
+	}
+
+	// Unknown session
+	if err := app.handleSlashCommand("/mktemp nonexistent"); err == nil {
+		t.Error("expected error for /mktemp on unknown session")
+	}
+}
+
+func TestHandleSlashCommandRealpath(t *testing.T) {
+	app := createInteractiveTestApp(t)
+
+	if err := app.handleSlashCommand("/realpath"); err == nil {
+		t.Error("expected usage error for /realpath without a path")
+	}
+
+	tmpDir, err := filepath.EvalSymlinks(t.TempDir())
+	if err != nil {
+		t.Fatal(err)
+	}
+	sess := app.sessions.GetActiveSession()
+	if err := sess.SetCWD(tmpDir); err != nil {
+		t.Fatal(err)
+	}
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err = app.handleSlashCommand("/realpath .")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	if err != nil {
+		t.Fatalf("/realpath failed: %v", err)
+	}
+	var buf bytes.Buffer
+	io.Copy(&buf, r)
+	if got := strings.TrimSpace(buf.String()); got != tmpDir {
+		t.Errorf("expected %s, got %s", tmpDir, got)
+	}
+
+	if err := app.handleSlashCommand("/realpath does-not-exist"); err == nil {
+		t.Error("expected error for a missing path")
+	}
+}
+
+func TestHandleSlashCommandCheck(t *testing.T) {
+	app := createInteractiveTestApp(t)
+
+	if err := app.handleSlashCommand("/check"); err == nil {
+		t.Error("expected usage error for /check without a command")
+	}
+
+	tests := []struct {
+		name     string
+		input    string
+		contains []string
+	}{
+		{"blocked", `/check "sudo ls"`, []string{"Blocked: Privilege escalation (privilege_escalation)", "Rule: sudo", "^^^^"}},
+		{"allowed", `/check "ls"`, []string{"Allowed"}},
+		{"unquoted", `/check ls -la`, []string{"Allowed"}},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			oldStdout := os.Stdout
+			r, w, _ := os.Pipe()
+			os.Stdout = w
+
+			err := app.handleSlashCommand(tt.input)
+
+			w.Close()
+			os.Stdout = oldStdout
+
+			if err != nil {
+				t.Fatalf("%s failed: %v", tt.input, err)
+			}
+			var buf bytes.Buffer
+			io.Copy(&buf, r)
+			for _, want := range tt.contains {
+				if !strings.Contains(buf.String(), want) {
+					t.Errorf("expected output to contain %q, got:\n%s", want, buf.String())
+				}
+			}
+		})
+	}
+}
+
+func TestRestrictionCaret(t *testing.T) {
+	got := restrictionCaret("ls; sudo rm x", []int{4, 8})
+	expected := "  ls; sudo rm x\n      ^^^^\n"
+	if got != expected {
+		t.Errorf("expected:\n%q\ngot:\n%q", expected, got)
+	}
+}
+
+func TestExecuteRestrictionBypassPrefix(t *testing.T) {
+	app := createInteractiveTestApp(t)
+	app.sessions.SetRestrictedMode(true)
+
+	target := filepath.Join(t.TempDir(), "victim")
+	if err := os.WriteFile(target, []byte("x"), 0644); err != nil {
+		t.Fatalf("failed to create file: %v", err)
+	}
+
+	// Without the config flag the prefix is refused
+	if _, err := app.executeWithSignalForwarding("!! unlink " + target); err == nil {
+		t.Fatal("expected bypass to be refused when allow_restriction_bypass is off")
+	}
+	if _, err := os.Stat(target); err != nil {
+		t.Fatalf("file should not have been removed: %v", err)
+	}
+
+	app.config.Settings.AllowRestrictionBypass = true
+	if _, err := app.executeWithSignalForwarding("unlink " + target); err == nil {
+		t.Fatal("expected restricted command without the prefix to be blocked")
+	}
+
+	result, err := app.executeWithSignalForwarding("!! unlink " + target)
+	if err != nil {
+		t.Fatalf("expected bypass to run the command, got %v", err)
+	}
+	if result.ExitCode != 0 {
+		t.Fatalf("expected exit code 0, got %d: %s", result.ExitCode, result.Stderr)
+	}
+	if _, err := os.Stat(target); !os.IsNotExist(err) {
+		t.Errorf("expected file to be removed, stat returned %v", err)
+	}
+}
+
+func TestHandleSlashCommandLimits(t *testing.T) {
+	tmpDir := t.TempDir()
+	cfg := &config.Config{
+		Settings: config.Settings{
+			DefaultSession:         "box",
+			CommandTimeout:         300,
+			InteractiveTimeout:     600,
+			KeepaliveInterval:      15,
+			MaxSessions:            7,
+			AllowRestrictionBypass: true,
+			StateFile:              filepath.Join(tmpDir, "state.json"),
+		},
+		Sessions: map[string]config.Session{
+			"local": {Type: "local", Shell: "/bin/sh"},
+			"box":   {Type: "local", Shell: "/bin/sh", CommandTimeout: 42, Restricted: true},
+		},
+	}
+	stateMgr := state.NewManager(cfg.Settings.StateFile)
+	stateMgr.Load()
+
+	app := NewApp("1.0.0", "test", "test")
+	app.config = cfg
+	app.state = stateMgr
+	app.sessions = session.NewManager(cfg, stateMgr)
+	app.sessions.SetRestrictionWarnOnly(true)
+
+	capture := func() string {
+		t.Helper()
+		oldStdout := os.Stdout
+		r, w, _ := os.Pipe()
+		os.Stdout = w
+
+		err := app.handleSlashCommand("/limits")
+
+		w.Close()
+		os.Stdout = oldStdout
+
+		if err != nil {
+			t.Fatalf("/limits failed: %v", err)
+		}
+		var buf bytes.Buffer
+		io.Copy(&buf, r)
+		return buf.String()
+	}
+
+	output := capture()
+	for _, want := range []string{
+		"Limits for session 'box'",
+		"Command timeout: 600s (interactive_timeout)",
+		"MCP timeout:     42s (sessions.box.command_timeout)",
+		"SSH keepalive:   15s",
+		"Max sessions:    7",
+		"Restrictions:    on (session restricted = true), warn-only",
+		"Bypass:          allowed",
+	} {
+		if !strings.Contains(output, want) {
+			t.Errorf("expected output to contain %q, got:\n%s", want, output)
+		}
+	}
+
+	if err := app.sessions.SetActiveSession("local"); err != nil {
+		t.Fatalf("failed to switch session: %v", err)
+	}
+	output = capture()
+	// Without a session override MCP tools use the global default
+	for _, want := range []string{"Limits for session 'local'", "MCP timeout:     300s (command_timeout)", "Restrictions:    off"} {
+		if !strings.Contains(output, want) {
+			t.Errorf("expected output to contain %q, got:\n%s", want, output)
+		}
+	}
+}
+
+func TestClearScreenSequence(t *testing.T) {
+	if got := clearScreenSequence(false); got != "\033[H\033[2J" {
+		t.Errorf("expected home+clear sequence, got %q", got)
+	}
+	if got := clearScreenSequence(true); got != "\033[H\033[2J\033[3J" {
+		t.Errorf("expected home+clear+scrollback sequence, got %q", got)
+	}
+
+	app := createInteractiveTestApp(t)
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err := app.handleSlashCommand("/cls all")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	if err != nil {
+		t.Fatalf("/cls failed: %v", err)
+	}
+	var buf bytes.Buffer
+	io.Copy(&buf, r)
+	if buf.String() != "\033[H\033[2J\033[3J" {
+		t.Errorf("expected /cls all to emit the clear sequence, got %q", buf.String())
+	}
+}
+
+func TestHandleSlashCommandBack(t *testing.T) {
+	app := createInteractiveTestApp(t)
+	for _, name := range []string{"a", "b", "c"} {
+		if err := app.sessions.AddSession(name, config.Session{Type: "local", Shell: "/bin/sh"}); err != nil {
+			t.Fatalf("AddSession %s failed: %v", name, err)
+		}
+		if err := app.handleSlashCommand("/switch " + name); err != nil {
+			t.Fatalf("/switch %s failed: %v", name, err)
+		}
+	}
+
+	for _, want := range []string{"b", "a"} {
+		if err := app.handleSlashCommand("/back"); err != nil {
+			t.Fatalf("/back failed: %v", err)
+		}
+		if active := app.sessions.GetActiveSessionName(); active != want {
+			t.Errorf("expected /back to switch to %s, got %s", want, active)
+		}
+	}
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err := app.handleSlashCommand("/recent")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	if err != nil {
+		t.Fatalf("/recent failed: %v", err)
+	}
+	var buf bytes.Buffer
+	io.Copy(&buf, r)
+	if !strings.Contains(buf.String(), "Current: a") || !strings.Contains(buf.String(), "1. local") {
+		t.Errorf("unexpected /recent output:\n%s", buf.String())
+	}
+}
+
+func TestHistoryIsRecordedPerSession(t *testing.T) {
+	t.Setenv("XDG_DATA_HOME", t.TempDir())
+	app := createInteractiveTestApp(t)
+	if err := app.sessions.AddSession("other", config.Session{Type: "local", Shell: "/bin/sh"}); err != nil {
+		t.Fatalf("AddSession failed: %v", err)
+	}
+
+	for _, input := range []string{"echo on-local", "/switch other", "echo on-other", "/local", "pwd"} {
+		app.recordHistory(input)
+		if strings.HasPrefix(input, "/") {
+			if err := app.handleSlashCommand(input); err != nil {
+				t.Fatalf("%s failed: %v", input, err)
+			}
+		}
+	}
+
+	local, err := readHistory("local")
+	if err != nil {
+		t.Fatalf("readHistory local failed: %v", err)
+	}
+	if want := []string{"echo on-local", "/switch other", "pwd"}; strings.Join(local, "|") != strings.Join(want, "|") {
+		t.Errorf("local history = %q, want %q", local, want)
+	}
+	other, err := readHistory("other")
+	if err != nil {
+		t.Fatalf("readHistory other failed: %v", err)
+	}
+	if want := []string{"echo on-other", "/local"}; strings.Join(other, "|") != strings.Join(want, "|") {
+		t.Errorf("other history = %q, want %q", other, want)
+	}
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err = app.handleSlashCommand("/history other")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	if err != nil {
+		t.Fatalf("/history failed: %v", err)
+	}
+	var buf bytes.Buffer
+	io.Copy(&buf, r)
+	if !strings.Contains(buf.String(), "echo on-other") || strings.Contains(buf.String(), "echo on-local") {
+		t.Errorf("/history other should only show its own commands:\n%s", buf.String())
+	}
+
+	if err := app.handleSlashCommand("/history missing"); err == nil {
+		t.Error("expected /history on an unknown session to fail")
+	}
+}
+
+func TestCmdJobsJSON(t *testing.T) {
+	app := createInteractiveTestApp(t)
+	app.jsonOutput = true
+
+	start := time.Now().Add(-2 * time.Second)
+	app.bgJobs[1] = &BackgroundJob{ID: 1, Command: "sleep 60", Session: "local", StartTime: start, Status: "running"}
+	app.bgJobs[2] = &BackgroundJob{ID: 2, Command: "false", Session: "local", StartTime: start, EndTime: start.Add(time.Second), Status: "completed", ExitCode: 1}
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err := app.handleSlashCommand("/jobs")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	if err != nil {
+		t.Fatalf("/jobs failed: %v", err)
+	}
+
+	var jobs []map[string]interface{}
+	var buf bytes.Buffer
+	io.Copy(&buf, r)
+	if err := json.Unmarshal(buf.Bytes(), &jobs); err != nil {
+		t.Fatalf("/jobs output is not a JSON array: %v\n%s", err, buf.String())
+	}
+	if len(jobs) != 2 {
+		t.Fatalf("expected 2 jobs, got %d", len(jobs))
+	}
+
+	running := jobs[0]
+	for _, field := range []string{"id", "command", "session", "status", "exit_code", "start_time", "end_time", "duration_seconds"} {
+		if _, ok := running[field]; !ok {
+			t.Errorf("running job is missing field %q", field)
+		}
+	}
+	if running["id"] != float64(1) || running["command"] != "sleep 60" || running["status"] != "running" {
+		t.Errorf("unexpected running job %v", running)
+	}
+	if running["exit_code"] != nil || running["end_time"] != nil {
+		t.Errorf("running job should have null exit_code and end_time, got %v", running)
+	}
+	if d, _ := running["duration_seconds"].(float64); d < 2 {
+		t.Errorf("expected elapsed duration of at least 2s, got %v", running["duration_seconds"])
+	}
+
+	done := jobs[1]
+	if done["exit_code"] != float64(1) || done["duration_seconds"] != float64(1) {
+		t.Errorf("unexpected completed job %v", done)
+	}
+}
+
+func TestReadPasswordFromNonTerminal(t *testing.T) {
+	tests := []struct {
+		input string
+		want  string
+	}{
+		{"secret\n", "secret"},
+		{"secret\r\n", "secret"},
+		{"pass word \n", "pass word "},
+		{"no-newline", "no-newline"},
+		{"\n", ""},
+		{"", ""},
+		{"first\nsecond\n", "first"},
+	}
+	for _, tt := range tests {
+		// -1 is never a terminal, so the password is read as a line
+		got, err := readPasswordFrom(-1, strings.NewReader(tt.input))
+		if err != nil {
+			t.Errorf("readPasswordFrom(%q) failed: %v", tt.input, err)
+			continue
+		}
+		if got != tt.want {
+			t.Errorf("readPasswordFrom(%q) = %q, want %q", tt.input, got, tt.want)
+		}
+	}
+}
+
+func TestHandleSlashCommandRename(t *testing.T) {
+	configPath := filepath.Join(t.TempDir(), "config.toml")
+	app := createInteractiveTestApp(t)
+	app.configPath = configPath
+
+	if err := app.handleSlashCommand("/rename testserver prod"); err != nil {
+		t.Fatalf("/rename failed: %v", err)
+	}
+	if app.sessions.HasSession("testserver") || !app.sessions.HasSession("prod") {
+		t.Error("session should be renamed to prod")
+	}
+
+	saved, err := config.Load(configPath)
+	if err != nil {
+		t.Fatalf("Load saved config failed: %v", err)
+	}
+	if _, ok := saved.Sessions["prod"]; !ok {
+		t.Error("saved config should have the renamed session")
+	}
+	if _, ok := saved.Sessions["testserver"]; ok {
+		t.Error("saved config should not keep the old name")
+	}
+
+	if err := app.handleSlashCommand("/rename prod"); err == nil {
+		t.Error("expected usage error for /rename with one argument")
+	}
+}
+
+func TestHandleSlashCommandRenameDefaultSession(t *testing.T) {
+	configPath := filepath.Join(t.TempDir(), "config.toml")
+	app := createInteractiveTestApp(t)
+	app.configPath = configPath
+	app.config.Settings.DefaultSession = "testserver"
+
+	if err := app.handleSlashCommand("/rename testserver prod"); err != nil {
+		t.Fatalf("/rename failed: %v", err)
+	}
+
+	saved, err := config.Load(configPath)
+	if err != nil {
+		t.Fatalf("Load saved config failed: %v", err)
+	}
+	if saved.Settings.DefaultSession != "prod" {
+		t.Errorf("expected default_session to follow the rename, got %q", saved.Settings.DefaultSession)
+	}
+}
+
+func TestHandleSlashCommandRemoveDefaultSession(t *testing.T) {
+	configPath := filepath.Join(t.TempDir(), "config.toml")
+	app := createInteractiveTestApp(t)
+	app.configPath = configPath
+	app.config.Settings.DefaultSession = "testserver"
+
+	if err := app.handleSlashCommand("/remove testserver --save"); err != nil {
+		t.Fatalf("/remove failed: %v", err)
+	}
+
+	saved, err := config.Load(configPath)
+	if err != nil {
+		t.Fatalf("Load saved config failed: %v", err)
+	}
+	if saved.Settings.DefaultSession != "local" {
+		t.Errorf("expected default_session to fall back to local, got %q", saved.Settings.DefaultSession)
+	}
+	if _, ok := saved.Sessions["testserver"]; ok {
+		t.Error("saved config should not keep the removed session")
+	}
+}
@@ -0,0 +1,88 @@
+package cli
+
+import (
+	"context"
+	"fmt"
+	"os"
+	"os/signal"
+	"strconv"
+	"syscall"
+
+	"github.com/scottgl9/thop/internal/session"
+)
+
+// journalUsage is the error shown for malformed /journal arguments
+const journalUsage = "usage: /journal <unit> [-n N] [-f] [-m]\n  -f follows new entries until Ctrl+C; -m shows only the messages"
+
+// parseJournalArgs parses the arguments of /journal into the unit and the
+// journal options
+func parseJournalArgs(args []string) (string, session.JournalOptions, error) {
+	unit := ""
+	opts := session.JournalOptions{Lines: session.DefaultTailLines}
+	for i := 0; i < len(args); i++ {
+		switch args[i] {
+		case "-f":
+			opts.Follow = true
+		case "-m":
+			opts.MessageOnly = true
+		case "-n":
+			if i+1 >= len(args) {
+				return "", opts, fmt.Errorf(journalUsage)
+			}
+			i++
+			n, err := strconv.Atoi(args[i])
+			if err != nil || n < 0 {
+				return "", opts, fmt.Errorf("invalid line count: %s", args[i])
+			}
+			opts.Lines = n
+		default:
+			if unit != "" {
+				return "", opts, fmt.Errorf(journalUsage)
+			}
+			unit = args[i]
+		}
+	}
+	if unit == "" {
+		return "", opts, fmt.Errorf(journalUsage)
+	}
+	return unit, opts, nil
+}
+
+// cmdJournal handles the /journal command, showing a systemd unit's recent
+// journal entries on the current session and, with -f, following them until
+// Ctrl+C
+func (a *App) cmdJournal(args []string) error {
+	unit, opts, err := parseJournalArgs(args)
+	if err != nil {
+		return err
+	}
+
+	if !opts.Follow {
+		output, err := a.sessions.Journal("", unit, opts)
+		if err != nil {
+			return err
+		}
+		if output != "" {
+			a.print(withTrailingNewline(output))
+		}
+		return nil
+	}
+
+	ctx, cancel := context.WithCancel(context.Background())
+	defer cancel()
+
+	// Ctrl+C stops following rather than exiting thop
+	sigChan := make(chan os.Signal, 1)
+	signal.Notify(sigChan, syscall.SIGINT)
+	defer signal.Stop(sigChan)
+	go func() {
+		select {
+		case <-sigChan:
+			cancel()
+		case <-ctx.Done():
+		}
+	}()
+
+	out := a.terminal()
+	return a.sessions.FollowJournal(ctx, "", unit, opts, out.Stdout(), out.Stderr())
+}
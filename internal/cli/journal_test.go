@@ -0,0 +1,36 @@
+package cli
+
+import (
+	"testing"
+
+	"github.com/scottgl9/thop/internal/session"
+)
+
+func TestParseJournalArgs(t *testing.T) {
+	tests := []struct {
+		args     []string
+		wantUnit string
+		wantOpts session.JournalOptions
+	}{
+		{[]string{"nginx"}, "nginx", session.JournalOptions{Lines: session.DefaultTailLines}},
+		{[]string{"nginx", "-n", "50"}, "nginx", session.JournalOptions{Lines: 50}},
+		{[]string{"-f", "nginx"}, "nginx", session.JournalOptions{Lines: session.DefaultTailLines, Follow: true}},
+		{[]string{"sshd", "-m", "-f", "-n", "0"}, "sshd", session.JournalOptions{Lines: 0, Follow: true, MessageOnly: true}},
+	}
+	for _, tt := range tests {
+		unit, opts, err := parseJournalArgs(tt.args)
+		if err != nil {
+			t.Errorf("parseJournalArgs(%q) failed: %v", tt.args, err)
+			continue
+		}
+		if unit != tt.wantUnit || opts != tt.wantOpts {
+			t.Errorf("parseJournalArgs(%q) = %q, %+v; want %q, %+v", tt.args, unit, opts, tt.wantUnit, tt.wantOpts)
+		}
+	}
+
+	for _, args := range [][]string{nil, {"-f"}, {"a", "b"}, {"nginx", "-n"}, {"nginx", "-n", "x"}, {"nginx", "-n", "-1"}} {
+		if _, _, err := parseJournalArgs(args); err == nil {
+			t.Errorf("parseJournalArgs(%q) should fail", args)
+		}
+	}
+}
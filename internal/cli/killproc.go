@@ -0,0 +1,93 @@
+package cli
+
+import (
+	"fmt"
+	"strconv"
+	"strings"
+
+	"github.com/scottgl9/thop/internal/session"
+)
+
+// killProcUsage is the error shown for malformed /killproc arguments
+const killProcUsage = "usage: /killproc <pid|name> [signal]\n  signal is a name (TERM, KILL, HUP) or number (9); default TERM"
+
+// killTargets returns the PIDs /killproc signals for target: the PID itself
+// if target is a number, otherwise the processes find reports with that
+// name. When several match, confirm is asked first, and no PIDs are returned
+// unless it agrees.
+func killTargets(target string, find func(name string) ([]session.Process, error), confirm func([]session.Process) bool) ([]int, error) {
+	if pid, err := strconv.Atoi(target); err == nil {
+		return []int{pid}, nil
+	}
+
+	matches, err := find(target)
+	if err != nil {
+		return nil, err
+	}
+	if len(matches) == 0 {
+		return nil, fmt.Errorf("no process named %s", target)
+	}
+	if len(matches) > 1 && !confirm(matches) {
+		return nil, nil
+	}
+
+	pids := make([]int, len(matches))
+	for i, p := range matches {
+		pids[i] = p.PID
+	}
+	return pids, nil
+}
+
+// confirmKill lists the processes matching a /killproc name and asks whether
+// to signal all of them
+func (a *App) confirmKill(processes []session.Process) bool {
+	a.printf("%d processes match:\n", len(processes))
+	for _, p := range processes {
+		a.printf("  %7d %-12s %s\n", p.PID, p.User, p.Command)
+	}
+	a.print("Signal all of them? (yes/no): ")
+
+	var answer string
+	if _, err := fmt.Scanln(&answer); err != nil {
+		a.println()
+		return false
+	}
+	answer = strings.ToLower(strings.TrimSpace(answer))
+	return answer == "yes" || answer == "y"
+}
+
+// cmdKillProc handles the /killproc command, signaling a process on the
+// current session by PID or by name
+func (a *App) cmdKillProc(args []string) error {
+	if len(args) < 1 || len(args) > 2 {
+		return fmt.Errorf(killProcUsage)
+	}
+	signal := ""
+	if len(args) == 2 {
+		signal = args[1]
+	}
+	signal, err := session.NormalizeSignal(signal)
+	if err != nil {
+		return err
+	}
+
+	find := func(name string) ([]session.Process, error) {
+		return a.sessions.FindProcessesByName("", name)
+	}
+	pids, err := killTargets(args[0], find, a.confirmKill)
+	if err != nil {
+		return err
+	}
+	if len(pids) == 0 {
+		a.println("No processes signaled")
+		return nil
+	}
+
+	for _, pid := range pids {
+		if err := a.sessions.KillProcess("", pid, signal); err != nil {
+			return err
+		}
+		a.printf("Sent %s to %d\n", signal, pid)
+	}
+	return nil
+}
@@ -0,0 +1,73 @@
+package cli
+
+import (
+	"fmt"
+	"reflect"
+	"testing"
+
+	"github.com/scottgl9/thop/internal/session"
+)
+
+func TestKillTargets(t *testing.T) {
+	processes := map[string][]session.Process{
+		"worker": {{PID: 42, Command: "/usr/bin/worker --queue default"}},
+		"nginx": {
+			{PID: 100, Command: "nginx: master process /usr/sbin/nginx"},
+			{PID: 101, Command: "nginx: worker process"},
+		},
+	}
+	find := func(name string) ([]session.Process, error) {
+		return processes[name], nil
+	}
+
+	tests := []struct {
+		name      string
+		target    string
+		confirm   bool
+		want      []int
+		wantErr   bool
+		wantAsked bool
+	}{
+		{name: "pid", target: "1234", want: []int{1234}},
+		{name: "single match", target: "worker", want: []int{42}},
+		{name: "multiple matches confirmed", target: "nginx", confirm: true, want: []int{100, 101}, wantAsked: true},
+		{name: "multiple matches declined", target: "nginx", confirm: false, want: nil, wantAsked: true},
+		{name: "no match", target: "missing", wantErr: true},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			asked := false
+			confirm := func([]session.Process) bool {
+				asked = true
+				return tt.confirm
+			}
+			got, err := killTargets(tt.target, find, confirm)
+			if (err != nil) != tt.wantErr {
+				t.Fatalf("killTargets(%q) error = %v, wantErr %v", tt.target, err, tt.wantErr)
+			}
+			if !reflect.DeepEqual(got, tt.want) {
+				t.Errorf("killTargets(%q) = %v, want %v", tt.target, got, tt.want)
+			}
+			if asked != tt.wantAsked {
+				t.Errorf("killTargets(%q) asked for confirmation = %v, want %v", tt.target, asked, tt.wantAsked)
+			}
+		})
+	}
+
+	failing := func(string) ([]session.Process, error) {
+		return nil, fmt.Errorf("ps failed")
+	}
+	if _, err := killTargets("nginx", failing, nil); err == nil {
+		t.Error("expected the lookup error to be returned")
+	}
+}
+
+func TestHandleSlashCommandKillProcUsage(t *testing.T) {
+	app := createInteractiveTestApp(t)
+
+	for _, cmd := range []string{"/killproc", "/killproc 1 TERM extra", "/killproc 1 NOT!A!SIGNAL"} {
+		if err := app.handleSlashCommand(cmd); err == nil {
+			t.Errorf("%s: expected an error", cmd)
+		}
+	}
+}
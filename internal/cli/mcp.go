@@ -10,7 +10,7 @@ func (a *App) runMCP() error {
 	logger.Info("Starting MCP server mode")
 
 	// Create MCP server
-	server := mcp.NewServer(a.config, a.sessions, a.state)
+	server := mcp.NewServer(a.config, a.configPath, a.sessions, a.state)
 
 	// Run the server (blocks until stopped)
 	return server.Run()
@@ -0,0 +1,122 @@
+package cli
+
+import (
+	"fmt"
+	"io"
+	"os"
+	"strings"
+	"sync"
+)
+
+// terminalOutput serializes terminal writes from the interactive loop and
+// from background jobs, so that a message from a job never lands in the
+// middle of a line written by the loop
+type terminalOutput struct {
+	mu           sync.Mutex
+	stdout       io.Writer // nil means os.Stdout at the time of the write
+	stderr       io.Writer // nil means os.Stderr at the time of the write
+	redraw       func()    // Redraws the prompt after an asynchronous message; may be nil
+	notifyPrefix string    // Written before an asynchronous message, e.g. to leave the prompt line
+}
+
+// lockedWriter writes to w while holding the shared terminal lock
+type lockedWriter struct {
+	mu *sync.Mutex
+	w  func() io.Writer
+}
+
+func (l lockedWriter) Write(p []byte) (int, error) {
+	l.mu.Lock()
+	defer l.mu.Unlock()
+	return l.w().Write(p)
+}
+
+func (o *terminalOutput) out() io.Writer {
+	if o.stdout != nil {
+		return o.stdout
+	}
+	return os.Stdout
+}
+
+func (o *terminalOutput) errOut() io.Writer {
+	if o.stderr != nil {
+		return o.stderr
+	}
+	return os.Stderr
+}
+
+// Stdout returns a writer to standard output that holds the terminal lock per write
+func (o *terminalOutput) Stdout() io.Writer {
+	return lockedWriter{mu: &o.mu, w: o.out}
+}
+
+// Stderr returns a writer to standard error that holds the terminal lock per write
+func (o *terminalOutput) Stderr() io.Writer {
+	return lockedWriter{mu: &o.mu, w: o.errOut}
+}
+
+// PrintResult writes command output, ending each stream with a newline,
+// without letting asynchronous messages interleave with it
+func (o *terminalOutput) PrintResult(stdout, stderr string) {
+	o.mu.Lock()
+	defer o.mu.Unlock()
+
+	if stdout != "" {
+		fmt.Fprint(o.out(), withTrailingNewline(stdout))
+	}
+	if stderr != "" {
+		fmt.Fprint(o.errOut(), withTrailingNewline(stderr))
+	}
+}
+
+// Notify writes an asynchronous message (such as a background job finishing)
+// as whole lines and then redraws the prompt
+func (o *terminalOutput) Notify(msg string) {
+	o.mu.Lock()
+	defer o.mu.Unlock()
+
+	fmt.Fprint(o.out(), o.notifyPrefix+withTrailingNewline(msg))
+	if o.redraw != nil {
+		o.redraw()
+	}
+}
+
+func withTrailingNewline(s string) string {
+	if strings.HasSuffix(s, "\n") {
+		return s
+	}
+	return s + "\n"
+}
+
+// terminal returns the App's synchronized terminal output
+func (a *App) terminal() *terminalOutput {
+	a.termMu.Lock()
+	defer a.termMu.Unlock()
+	if a.term == nil {
+		a.term = &terminalOutput{}
+	}
+	return a.term
+}
+
+// setTerminal replaces the App's terminal output, e.g. when readline starts
+func (a *App) setTerminal(term *terminalOutput) {
+	a.termMu.Lock()
+	defer a.termMu.Unlock()
+	a.term = term
+}
+
+// print writes to standard output through the shared terminal writer, so
+// slash-command output doesn't interleave with background job messages
+func (a *App) print(args ...interface{}) {
+	fmt.Fprint(a.terminal().Stdout(), args...)
+}
+
+// println is print with a trailing newline
+func (a *App) println(args ...interface{}) {
+	fmt.Fprintln(a.terminal().Stdout(), args...)
+}
+
+// printf is print with a format string
+func (a *App) printf(format string, args ...interface{}) {
+	fmt.Fprintf(a.terminal().Stdout(), format, args...)
+}
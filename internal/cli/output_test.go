@@ -0,0 +1,89 @@
+package cli
+
+import (
+	"bytes"
+	"fmt"
+	"runtime"
+	"strings"
+	"sync"
+	"sync/atomic"
+	"testing"
+)
+
+// trickleWriter writes one byte at a time, yielding in between, and records
+// whether two writes were ever in progress at once
+type trickleWriter struct {
+	buf     bytes.Buffer
+	active  int32
+	overlap int32
+}
+
+func (w *trickleWriter) Write(p []byte) (int, error) {
+	if atomic.AddInt32(&w.active, 1) > 1 {
+		atomic.StoreInt32(&w.overlap, 1)
+	}
+	defer atomic.AddInt32(&w.active, -1)
+
+	for _, b := range p {
+		w.buf.WriteByte(b)
+		runtime.Gosched()
+	}
+	return len(p), nil
+}
+
+func TestTerminalOutputDoesNotInterleaveLines(t *testing.T) {
+	w := &trickleWriter{}
+	redraws := 0
+	term := &terminalOutput{stdout: w, stderr: w, redraw: func() { redraws++ }}
+
+	const writers = 20
+	var wg sync.WaitGroup
+	for i := 0; i < writers; i++ {
+		wg.Add(2)
+		go func(i int) {
+			defer wg.Done()
+			term.Notify(fmt.Sprintf("[%d] Done (1ms): background job %d", i, i))
+		}(i)
+		go func(i int) {
+			defer wg.Done()
+			term.PrintResult(fmt.Sprintf("prompt output line %d", i), "")
+		}(i)
+	}
+	wg.Wait()
+
+	if atomic.LoadInt32(&w.overlap) != 0 {
+		t.Error("writes overlapped")
+	}
+	if redraws != writers {
+		t.Errorf("expected the prompt to be redrawn after each of %d messages, got %d", writers, redraws)
+	}
+
+	lines := strings.Split(strings.TrimSuffix(w.buf.String(), "\n"), "\n")
+	if len(lines) != 2*writers {
+		t.Fatalf("expected %d lines, got %d:\n%s", 2*writers, len(lines), w.buf.String())
+	}
+	for _, line := range lines {
+		var n int
+		if _, err := fmt.Sscanf(line, "prompt output line %d", &n); err == nil {
+			continue
+		}
+		var id, id2 int
+		if _, err := fmt.Sscanf(line, "[%d] Done (1ms): background job %d", &id, &id2); err == nil && id == id2 {
+			continue
+		}
+		t.Errorf("garbled line %q", line)
+	}
+}
+
+func TestSlashCommandOutputUsesTerminal(t *testing.T) {
+	app := createInteractiveTestApp(t)
+	w := &trickleWriter{}
+	app.setTerminal(&terminalOutput{stdout: w, stderr: w})
+
+	if err := app.handleSlashCommand("/help"); err != nil {
+		t.Fatalf("/help failed: %v", err)
+	}
+	if !strings.Contains(w.buf.String(), "Available commands:") {
+		t.Errorf("expected /help output on the terminal writer, got %q", w.buf.String())
+	}
+}
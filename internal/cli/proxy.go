@@ -2,7 +2,9 @@ package cli
 
 import (
 	"bufio"
+	"context"
 	"fmt"
+	"io"
 	"os"
 	"strings"
 
@@ -36,7 +38,24 @@ func (a *App) runProxy() error {
 
 // executeProxyCommand executes a single command and returns the result
 func (a *App) executeProxyCommand(cmd string) *ProxyResult {
-	result, err := a.sessions.Execute(cmd)
+	a.echoCommand(cmd)
+
+	// The "!!" restriction bypass is interactive-only, so here the prefix is
+	// dropped and the command checked like any other
+	if rest, ok := strings.CutPrefix(cmd, restrictionBypassPrefix); ok {
+		cmd = strings.TrimSpace(rest)
+	}
+
+	if !a.rawCommand {
+		exitCode, err := a.executeStreaming(cmd)
+		if err != nil {
+			a.outputError(err)
+			return &ProxyResult{ExitCode: a.errorToExitCode(err)}
+		}
+		return &ProxyResult{ExitCode: exitCode}
+	}
+
+	result, err := a.sessions.ExecuteVerbatim(context.Background(), "", cmd)
 	if err != nil {
 		a.outputError(err)
 		return &ProxyResult{ExitCode: a.errorToExitCode(err)}
@@ -60,6 +79,50 @@ func (a *App) executeProxyCommand(cmd string) *ProxyResult {
 	return &ProxyResult{ExitCode: result.ExitCode}
 }
 
+// executeStreaming runs cmd on the active session, passing its output through
+// to stdout and stderr as it arrives so agents see progress of long-running
+// commands. Output not ending in a newline gets one, as with buffered output.
+// With an output filter, stdout is collected and filtered once the command ends.
+func (a *App) executeStreaming(cmd string) (int, error) {
+	stdout := &newlineWriter{w: os.Stdout}
+	stderr := &newlineWriter{w: os.Stderr}
+	filter := a.outputFilter()
+	if filter == "" {
+		exitCode, err := a.sessions.ExecuteStreaming(context.Background(), cmd, stdout, stderr)
+		stdout.finish()
+		stderr.finish()
+		return exitCode, err
+	}
+
+	var raw strings.Builder
+	exitCode, err := a.sessions.ExecuteStreaming(context.Background(), cmd, &raw, stderr)
+	_, _ = io.WriteString(stdout, a.filterOutput(filter, raw.String()))
+	stdout.finish()
+	stderr.finish()
+	return exitCode, err
+}
+
+// newlineWriter passes writes through and remembers whether the output so far
+// ends in a newline
+type newlineWriter struct {
+	w    io.Writer
+	last byte
+}
+
+func (n *newlineWriter) Write(p []byte) (int, error) {
+	if len(p) > 0 {
+		n.last = p[len(p)-1]
+	}
+	return n.w.Write(p)
+}
+
+// finish terminates output that doesn't end in a newline
+func (n *newlineWriter) finish() {
+	if n.last != 0 && n.last != '\n' {
+		_, _ = n.w.Write([]byte("\n"))
+	}
+}
+
 // runProxyLoop reads commands from stdin in a loop
 func (a *App) runProxyLoop() error {
 	reader := bufio.NewReader(os.Stdin)
@@ -80,41 +143,35 @@ func (a *App) runProxyLoop() error {
 		}
 
 		// Execute command on active session
-		result, err := a.sessions.Execute(input)
+		a.echoCommand(input)
+		exitCode, err := a.executeStreaming(input)
 		if err != nil {
 			a.outputError(err)
 			// In loop proxy mode, continue even on error
 			continue
 		}
 
-		// Output results
-		if result.Stdout != "" {
-			fmt.Print(result.Stdout)
-			// Ensure newline at end
-			if !strings.HasSuffix(result.Stdout, "\n") {
-				fmt.Println()
-			}
-		}
-
-		if result.Stderr != "" {
-			fmt.Fprint(os.Stderr, result.Stderr)
-			if !strings.HasSuffix(result.Stderr, "\n") {
-				fmt.Fprintln(os.Stderr)
-			}
-		}
-
 		// Show exit code in verbose mode
-		if result.ExitCode != 0 && a.verbose {
-			fmt.Fprintf(os.Stderr, "[exit code: %d]\n", result.ExitCode)
+		if exitCode != 0 && a.verbose {
+			fmt.Fprintf(os.Stderr, "[exit code: %d]\n", exitCode)
 		}
 	}
 }
 
+// echoCommand prints cmd to stderr, prefixed with the session it runs on,
+// when command echo is enabled
+func (a *App) echoCommand(cmd string) {
+	if !a.echoCommands {
+		return
+	}
+	fmt.Fprintf(os.Stderr, "+ [%s] %s\n", a.sessions.GetActiveSessionName(), cmd)
+}
+
 // errorToExitCode converts an error to an appropriate exit code
 func (a *App) errorToExitCode(err error) int {
 	if sessionErr, ok := err.(*session.Error); ok {
 		switch sessionErr.Code {
-		case session.ErrAuthPasswordRequired, session.ErrAuthFailed:
+		case session.ErrAuthPasswordRequired, session.ErrAuthPassphraseRequired, session.ErrAuthFailed:
 			return ExitAuthError
 		case session.ErrHostKeyVerification, session.ErrHostKeyChanged:
 			return ExitHostKeyError
@@ -6,6 +6,7 @@ import (
 	"os"
 	"strings"
 	"testing"
+	"time"
 
 	"github.com/scottgl9/thop/internal/config"
 	"github.com/scottgl9/thop/internal/session"
@@ -409,3 +410,135 @@ func TestExitCodeConstants(t *testing.T) {
 		t.Errorf("ExitHostKeyError should be 3, got %d", ExitHostKeyError)
 	}
 }
+
+func TestProxyModeIgnoresRestrictionBypassPrefix(t *testing.T) {
+	app := createProxyTestApp(t)
+	app.sessions.SetRestrictedMode(true)
+	app.config.Settings.AllowRestrictionBypass = true
+
+	target := t.TempDir() + "/victim"
+	if err := os.WriteFile(target, []byte("x"), 0644); err != nil {
+		t.Fatalf("failed to create file: %v", err)
+	}
+
+	// The prefix has no meaning outside interactive mode, so the command is
+	// blocked just as it is without the prefix
+	for _, cmd := range []string{"!! unlink " + target, "unlink " + target} {
+		oldStderr := os.Stderr
+		r, w, _ := os.Pipe()
+		os.Stderr = w
+
+		result := app.executeProxyCommand(cmd)
+
+		w.Close()
+		os.Stderr = oldStderr
+		var buf bytes.Buffer
+		io.Copy(&buf, r)
+
+		if result.ExitCode != ExitGeneralError {
+			t.Errorf("expected %q to be rejected with exit code %d, got %d", cmd, ExitGeneralError, result.ExitCode)
+		}
+		if !strings.Contains(buf.String(), "'unlink' is not allowed in restricted mode") {
+			t.Errorf("expected a restriction error for %q, got %q", cmd, buf.String())
+		}
+	}
+
+	if _, err := os.Stat(target); err != nil {
+		t.Errorf("file should not have been removed: %v", err)
+	}
+}
+
+func TestProxyModeEcho(t *testing.T) {
+	run := func(echo bool) string {
+		app := createProxyTestApp(t)
+		app.echoCommands = echo
+
+		// Send stdout and stderr through one pipe so their order is preserved
+		oldStdout, oldStderr := os.Stdout, os.Stderr
+		r, w, _ := os.Pipe()
+		os.Stdout = w
+		os.Stderr = w
+
+		result := app.executeProxyCommand("echo marker")
+
+		w.Close()
+		os.Stdout = oldStdout
+		os.Stderr = oldStderr
+
+		if result.ExitCode != 0 {
+			t.Fatalf("expected exit code 0, got %d", result.ExitCode)
+		}
+		var buf bytes.Buffer
+		io.Copy(&buf, r)
+		return buf.String()
+	}
+
+	if got, want := run(true), "+ [local] echo marker\nmarker\n"; got != want {
+		t.Errorf("with echo enabled, expected %q, got %q", want, got)
+	}
+	if got, want := run(false), "marker\n"; got != want {
+		t.Errorf("with echo disabled, expected %q, got %q", want, got)
+	}
+}
+
+func TestProxyModeBackgroundJobCompletionIsSilent(t *testing.T) {
+	app := createProxyTestApp(t)
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err := app.cmdBg("echo hidden")
+	if err == nil {
+		deadline := time.Now().Add(5 * time.Second)
+		for time.Now().Before(deadline) {
+			app.bgJobsMu.RLock()
+			status := app.bgJobs[1].Status
+			app.bgJobsMu.RUnlock()
+			if status != "running" {
+				break
+			}
+			time.Sleep(10 * time.Millisecond)
+		}
+	}
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	if err != nil {
+		t.Fatalf("cmdBg failed: %v", err)
+	}
+	app.bgJobsMu.RLock()
+	status := app.bgJobs[1].Status
+	app.bgJobsMu.RUnlock()
+	if status != "completed" {
+		t.Fatalf("expected job to complete, got status %q", status)
+	}
+
+	var buf bytes.Buffer
+	io.Copy(&buf, r)
+	if strings.Contains(buf.String(), "Done") {
+		t.Errorf("background completion should not write to stdout in proxy mode, got %q", buf.String())
+	}
+}
+
+func TestNewlineWriter(t *testing.T) {
+	for _, tt := range []struct {
+		writes []string
+		want   string
+	}{
+		{[]string{"hello"}, "hello\n"},
+		{[]string{"a\n", "b\n"}, "a\nb\n"},
+		{nil, ""},
+	} {
+		var buf bytes.Buffer
+		w := &newlineWriter{w: &buf}
+		for _, s := range tt.writes {
+			w.Write([]byte(s))
+		}
+		w.finish()
+		if buf.String() != tt.want {
+			t.Errorf("writes %q: expected %q, got %q", tt.writes, tt.want, buf.String())
+		}
+	}
+}
@@ -0,0 +1,419 @@
+package cli
+
+import (
+	"context"
+	"fmt"
+	"os"
+	"regexp"
+	"sort"
+	"strings"
+	"sync"
+	"time"
+
+	"github.com/scottgl9/thop/internal/session"
+	"golang.org/x/term"
+)
+
+// tuiRefreshInterval is how often the dashboard redraws on its own, so
+// connection states and job timers stay current
+const tuiRefreshInterval = time.Second
+
+// tuiOutputLines is how many lines of command output the dashboard keeps
+const tuiOutputLines = 500
+
+// tuiHelp is the dashboard's key and command summary
+const tuiHelp = "Tab: next session  Enter: run  /switch <session>  /bg <cmd>  Ctrl+C: interrupt  /quit"
+
+// ansiEscape matches terminal escape sequences, which the dashboard strips
+// from command output so it can measure and truncate lines
+var ansiEscape = regexp.MustCompile(`\x1b(\[[0-9;?]*[ -/]*[@-~]|\][^\x07]*\x07|[@-_])`)
+
+// dashboardSession is a session as shown in the dashboard's sessions pane
+type dashboardSession struct {
+	Name   string
+	Target string // "local" or user@host[:port]
+	Status string // "connected" or "disconnected"
+	CWD    string
+	Active bool
+}
+
+// dashboardJob is a background job as shown in the dashboard's jobs pane
+type dashboardJob struct {
+	ID      int
+	Session string
+	Status  string
+	Command string
+}
+
+// dashboardModel is everything the dashboard draws, taken from the session
+// manager and job list at one point in time
+type dashboardModel struct {
+	Sessions []dashboardSession
+	Jobs     []dashboardJob
+	Output   []string
+	Prompt   string
+	Input    string
+}
+
+// newDashboardModel maps the session and job state to what the dashboard
+// shows: sessions sorted by name, jobs by ID with their status as of now, and
+// a prompt naming the active session
+func newDashboardModel(sessions []session.SessionInfo, jobs []*BackgroundJob, output []string, input string, now time.Time) dashboardModel {
+	model := dashboardModel{Output: output, Input: input, Prompt: "> "}
+
+	sessions = append([]session.SessionInfo(nil), sessions...)
+	sort.Slice(sessions, func(i, j int) bool { return sessions[i].Name < sessions[j].Name })
+	for _, s := range sessions {
+		entry := dashboardSession{
+			Name:   s.Name,
+			Target: "local",
+			Status: "disconnected",
+			CWD:    s.CWD,
+			Active: s.Active,
+		}
+		if s.Connected {
+			entry.Status = "connected"
+		}
+		if s.Type == "ssh" {
+			entry.Target = s.Host
+			if s.Port != 0 && s.Port != 22 {
+				entry.Target = fmt.Sprintf("%s:%d", s.Host, s.Port)
+			}
+			if s.User != "" {
+				entry.Target = s.User + "@" + entry.Target
+			}
+		}
+		if s.Active {
+			model.Prompt = s.Name + "> "
+		}
+		model.Sessions = append(model.Sessions, entry)
+	}
+
+	jobs = append([]*BackgroundJob(nil), jobs...)
+	sort.Slice(jobs, func(i, j int) bool { return jobs[i].ID < jobs[j].ID })
+	for _, job := range jobs {
+		model.Jobs = append(model.Jobs, dashboardJob{
+			ID:      job.ID,
+			Session: job.Session,
+			Status:  jobStatus(job, now),
+			Command: job.Command,
+		})
+	}
+	return model
+}
+
+// renderDashboard lays the model out as height lines of at most width
+// characters: sessions, then jobs, then as much recent output as fits, with
+// the input line at the bottom
+func renderDashboard(model dashboardModel, width, height int) []string {
+	var lines []string
+	lines = append(lines, "thop dashboard  "+tuiHelp, "")
+
+	lines = append(lines, "Sessions:")
+	for _, s := range model.Sessions {
+		marker := " "
+		if s.Active {
+			marker = "*"
+		}
+		lines = append(lines, fmt.Sprintf(" %s %-12s %-28s %-12s %s", marker, s.Name, s.Target, s.Status, s.CWD))
+	}
+
+	lines = append(lines, "", "Jobs:")
+	if len(model.Jobs) == 0 {
+		lines = append(lines, "   (none)")
+	}
+	for _, job := range model.Jobs {
+		lines = append(lines, fmt.Sprintf("   [%d] %-12s %-28s %s", job.ID, job.Session, job.Status, job.Command))
+	}
+	lines = append(lines, "", "Output:")
+
+	// The rest of the screen, less the input line, shows the latest output
+	room := height - len(lines) - 1
+	output := model.Output
+	if room <= 0 {
+		output = nil
+	} else if len(output) > room {
+		output = output[len(output)-room:]
+	}
+	lines = append(lines, output...)
+	for len(lines) < height-1 {
+		lines = append(lines, "")
+	}
+	lines = append(lines, model.Prompt+model.Input)
+
+	// A screen too short for the panes keeps the input line visible
+	if len(lines) > height {
+		lines = append(lines[:height-1], lines[len(lines)-1])
+	}
+	for i, line := range lines {
+		if r := []rune(line); len(r) > width {
+			lines[i] = string(r[:width])
+		}
+	}
+	return lines
+}
+
+// dashboard is the state of a running --tui session
+type dashboard struct {
+	app *App
+
+	mu      sync.Mutex
+	output  []string
+	input   []rune
+	cancel  context.CancelFunc // Interrupts the running command (nil if none)
+	updated chan struct{}      // Signaled when output arrives, to redraw
+}
+
+// runTUI runs the full-screen dashboard until the user quits
+func (a *App) runTUI() error {
+	fd := int(os.Stdin.Fd())
+	if !term.IsTerminal(fd) || !term.IsTerminal(int(os.Stdout.Fd())) {
+		return fmt.Errorf("--tui requires an interactive terminal")
+	}
+
+	state, err := term.MakeRaw(fd)
+	if err != nil {
+		return fmt.Errorf("failed to set up terminal: %w", err)
+	}
+	defer func() { _ = term.Restore(fd, state) }()
+
+	// Draw on the alternate screen so the shell's scrollback is left alone
+	a.print("\033[?1049h")
+	defer a.print("\033[?1049l")
+
+	d := &dashboard{app: a, updated: make(chan struct{}, 1)}
+	keys := make(chan []byte)
+	go func() {
+		buf := make([]byte, 64)
+		for {
+			n, err := os.Stdin.Read(buf)
+			if err != nil {
+				close(keys)
+				return
+			}
+			keys <- append([]byte(nil), buf[:n]...)
+		}
+	}()
+
+	ticker := time.NewTicker(tuiRefreshInterval)
+	defer ticker.Stop()
+	for {
+		d.draw(fd)
+		select {
+		case key, ok := <-keys:
+			if !ok || d.handleKeys(key) {
+				d.interrupt()
+				return nil
+			}
+		case <-d.updated:
+		case <-ticker.C:
+		}
+	}
+}
+
+// draw renders the current state to the terminal
+func (d *dashboard) draw(fd int) {
+	width, height, err := term.GetSize(fd)
+	if err != nil || width <= 0 || height <= 0 {
+		width, height = 80, 24
+	}
+
+	d.app.bgJobsMu.RLock()
+	jobs := make([]*BackgroundJob, 0, len(d.app.bgJobs))
+	for _, job := range d.app.bgJobs {
+		copied := *job
+		jobs = append(jobs, &copied)
+	}
+	d.app.bgJobsMu.RUnlock()
+
+	d.mu.Lock()
+	model := newDashboardModel(d.app.sessions.ListSessions(), jobs, d.output, string(d.input), time.Now())
+	d.mu.Unlock()
+
+	var screen strings.Builder
+	screen.WriteString("\033[H")
+	for i, line := range renderDashboard(model, width, height) {
+		if i > 0 {
+			screen.WriteString("\r\n")
+		}
+		screen.WriteString(line)
+		screen.WriteString("\033[K")
+	}
+	d.app.print(screen.String())
+}
+
+// handleKeys applies a chunk of keyboard input and reports whether the user
+// asked to quit
+func (d *dashboard) handleKeys(keys []byte) bool {
+	// Arrow and function keys arrive as escape sequences; ignore them
+	if len(keys) > 1 && keys[0] == 0x1b {
+		return false
+	}
+
+	for _, r := range string(keys) {
+		switch r {
+		case 3: // Ctrl+C interrupts the running command, or quits when idle
+			if !d.interrupt() {
+				return true
+			}
+		case 4: // Ctrl+D on an empty line quits
+			d.mu.Lock()
+			empty := len(d.input) == 0
+			d.mu.Unlock()
+			if empty {
+				return true
+			}
+		case '\t':
+			d.nextSession()
+		case '\r', '\n':
+			d.mu.Lock()
+			line := strings.TrimSpace(string(d.input))
+			d.input = nil
+			d.mu.Unlock()
+			if d.submit(line) {
+				return true
+			}
+		case 127, 8:
+			d.mu.Lock()
+			if len(d.input) > 0 {
+				d.input = d.input[:len(d.input)-1]
+			}
+			d.mu.Unlock()
+		default:
+			if r >= ' ' {
+				d.mu.Lock()
+				d.input = append(d.input, r)
+				d.mu.Unlock()
+			}
+		}
+	}
+	return false
+}
+
+// submit handles an entered line and reports whether it asked to quit
+func (d *dashboard) submit(line string) bool {
+	if line == "" {
+		return false
+	}
+	fields := strings.Fields(line)
+	switch fields[0] {
+	case "/quit", "/q", "/exit":
+		return true
+	case "/switch", "/sw":
+		if len(fields) != 2 {
+			d.appendOutput("usage: /switch <session>")
+		} else {
+			d.switchSession(fields[1])
+		}
+	case "/bg":
+		command := strings.TrimSpace(strings.TrimPrefix(line, fields[0]))
+		if command == "" {
+			d.appendOutput("usage: /bg <command>")
+		} else {
+			job := d.app.startBackgroundJob(command)
+			d.appendOutput(fmt.Sprintf("[%d] Started in background: %s", job.ID, command))
+		}
+	default:
+		if strings.HasPrefix(fields[0], "/") {
+			d.appendOutput(fmt.Sprintf("unknown dashboard command: %s (%s)", fields[0], tuiHelp))
+			return false
+		}
+		d.run(line)
+	}
+	return false
+}
+
+// run executes line on the active session in the background, appending its
+// output as it finishes
+func (d *dashboard) run(line string) {
+	d.mu.Lock()
+	if d.cancel != nil {
+		d.mu.Unlock()
+		d.appendOutput("A command is already running (Ctrl+C interrupts it)")
+		return
+	}
+	ctx, cancel := context.WithCancel(context.Background())
+	d.cancel = cancel
+	d.mu.Unlock()
+
+	d.appendOutput(d.app.sessions.GetActiveSessionName() + "> " + line)
+	go func() {
+		defer cancel()
+		result, err := d.app.sessions.ExecuteWithContext(ctx, line)
+
+		d.mu.Lock()
+		d.cancel = nil
+		d.mu.Unlock()
+
+		if err != nil {
+			d.appendOutput("Error: " + err.Error())
+			return
+		}
+		d.appendOutput(result.Stdout, result.Stderr)
+		if result.ExitCode != 0 {
+			d.appendOutput(fmt.Sprintf("(exit %d)", result.ExitCode))
+		}
+	}()
+}
+
+// interrupt cancels the running command, reporting whether there was one
+func (d *dashboard) interrupt() bool {
+	d.mu.Lock()
+	defer d.mu.Unlock()
+	if d.cancel == nil {
+		return false
+	}
+	d.cancel()
+	return true
+}
+
+// nextSession makes the session after the active one, by name, active
+func (d *dashboard) nextSession() {
+	names := d.app.sessions.SessionNames()
+	if len(names) == 0 {
+		return
+	}
+	sort.Strings(names)
+	active := d.app.sessions.GetActiveSessionName()
+	next := names[0]
+	for i, name := range names {
+		if name == active {
+			next = names[(i+1)%len(names)]
+			break
+		}
+	}
+	d.switchSession(next)
+}
+
+// switchSession makes name the active session
+func (d *dashboard) switchSession(name string) {
+	if err := d.app.sessions.SetActiveSession(name); err != nil {
+		d.appendOutput("Error: " + err.Error())
+		return
+	}
+	d.appendOutput("Switched to " + name)
+}
+
+// appendOutput adds text to the output pane, one entry per line with escape
+// sequences removed, keeping the last tuiOutputLines lines
+func (d *dashboard) appendOutput(texts ...string) {
+	d.mu.Lock()
+	for _, text := range texts {
+		text = strings.TrimRight(ansiEscape.ReplaceAllString(text, ""), "\n")
+		if text == "" {
+			continue
+		}
+		for _, line := range strings.Split(text, "\n") {
+			d.output = append(d.output, strings.ReplaceAll(strings.TrimSuffix(line, "\r"), "\t", "    "))
+		}
+	}
+	if len(d.output) > tuiOutputLines {
+		d.output = append([]string(nil), d.output[len(d.output)-tuiOutputLines:]...)
+	}
+	d.mu.Unlock()
+
+	select {
+	case d.updated <- struct{}{}:
+	default:
+	}
+}
@@ -0,0 +1,100 @@
+package cli
+
+import (
+	"reflect"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/scottgl9/thop/internal/session"
+)
+
+func TestNewDashboardModel(t *testing.T) {
+	now := time.Date(2024, 1, 1, 12, 0, 0, 0, time.UTC)
+	sessions := []session.SessionInfo{
+		{Name: "prod", Type: "ssh", Host: "prod.example.com", User: "deploy", Port: 2222, CWD: "/srv"},
+		{Name: "local", Type: "local", Connected: true, Active: true, CWD: "/home/user"},
+		{Name: "dev", Type: "ssh", Host: "dev.example.com", Port: 22, Connected: true, CWD: "~"},
+	}
+	jobs := []*BackgroundJob{
+		{ID: 2, Command: "make test", Session: "dev", Status: "running", StartTime: now.Add(-5 * time.Second)},
+		{ID: 1, Command: "sleep 1", Session: "local", Status: "completed", StartTime: now.Add(-2 * time.Second), EndTime: now.Add(-time.Second)},
+	}
+
+	model := newDashboardModel(sessions, jobs, []string{"hello"}, "ls", now)
+
+	wantSessions := []dashboardSession{
+		{Name: "dev", Target: "dev.example.com", Status: "connected", CWD: "~"},
+		{Name: "local", Target: "local", Status: "connected", CWD: "/home/user", Active: true},
+		{Name: "prod", Target: "deploy@prod.example.com:2222", Status: "disconnected", CWD: "/srv"},
+	}
+	if !reflect.DeepEqual(model.Sessions, wantSessions) {
+		t.Errorf("sessions =\n%+v\nwant\n%+v", model.Sessions, wantSessions)
+	}
+
+	wantJobs := []dashboardJob{
+		{ID: 1, Session: "local", Status: "completed (exit 0, 1s)", Command: "sleep 1"},
+		{ID: 2, Session: "dev", Status: "running (5s)", Command: "make test"},
+	}
+	if !reflect.DeepEqual(model.Jobs, wantJobs) {
+		t.Errorf("jobs =\n%+v\nwant\n%+v", model.Jobs, wantJobs)
+	}
+
+	if model.Prompt != "local> " || model.Input != "ls" {
+		t.Errorf("expected prompt %q with input %q, got %q, %q", "local> ", "ls", model.Prompt, model.Input)
+	}
+	if !reflect.DeepEqual(model.Output, []string{"hello"}) {
+		t.Errorf("expected output to pass through, got %v", model.Output)
+	}
+	if sessions[0].Name != "prod" || jobs[0].ID != 2 {
+		t.Error("newDashboardModel should not reorder the caller's slices")
+	}
+}
+
+func TestRenderDashboardFitsScreen(t *testing.T) {
+	model := dashboardModel{
+		Sessions: []dashboardSession{{Name: "local", Target: "local", Status: "connected", Active: true}},
+		Output:   []string{"one", "two", "three", strings.Repeat("x", 200)},
+		Prompt:   "local> ",
+		Input:    "pwd",
+	}
+
+	lines := renderDashboard(model, 80, 12)
+	if len(lines) != 12 {
+		t.Fatalf("expected 12 lines, got %d", len(lines))
+	}
+	for _, line := range lines {
+		if len([]rune(line)) > 80 {
+			t.Errorf("line wider than the screen: %q", line)
+		}
+	}
+	if lines[11] != "local> pwd" {
+		t.Errorf("expected the input line last, got %q", lines[11])
+	}
+	if lines[10] != strings.Repeat("x", 80) {
+		t.Errorf("expected the latest output just above the input line, got %q", lines[10])
+	}
+
+	// Too short for every pane, the input line still shows
+	lines = renderDashboard(model, 80, 3)
+	if len(lines) != 3 || lines[2] != "local> pwd" {
+		t.Errorf("expected 3 lines ending with the input line, got %q", lines)
+	}
+}
+
+func TestDashboardAppendOutput(t *testing.T) {
+	d := &dashboard{updated: make(chan struct{}, 1)}
+	d.appendOutput("\x1b[32mgreen\x1b[0m\nsecond\r\n", "", "err\n")
+
+	want := []string{"green", "second", "err"}
+	if !reflect.DeepEqual(d.output, want) {
+		t.Errorf("output = %q, want %q", d.output, want)
+	}
+
+	for i := 0; i < tuiOutputLines+10; i++ {
+		d.appendOutput("line")
+	}
+	if len(d.output) != tuiOutputLines {
+		t.Errorf("expected output capped at %d lines, got %d", tuiOutputLines, len(d.output))
+	}
+}
@@ -12,34 +12,79 @@ import (
 type Config struct {
 	Settings Settings           `toml:"settings"`
 	Sessions map[string]Session `toml:"sessions"`
+
+	automated bool // Running in proxy/MCP mode; selects automated_timeout
+
+	// With settings.include: the config the included files give on their own
+	// and the main file's parsed contents, so Save writes only what belongs
+	// in the main file
+	includeBase map[string]interface{}
+	mainTree    map[string]interface{}
 }
 
 // Settings contains global settings
 type Settings struct {
-	DefaultSession    string `toml:"default_session"`
-	CommandTimeout    int    `toml:"command_timeout"`
-	ReconnectAttempts int    `toml:"reconnect_attempts"`
-	ReconnectBackoff  int    `toml:"reconnect_backoff_base"`
-	LogLevel          string `toml:"log_level"`
-	StateFile         string `toml:"state_file"`
+	DefaultSession         string   `toml:"default_session"`
+	CommandTimeout         int      `toml:"command_timeout"`
+	ReconnectAttempts      int      `toml:"reconnect_attempts"`
+	ReconnectBackoff       int      `toml:"reconnect_backoff_base"`
+	LogLevel               string   `toml:"log_level"`
+	StateFile              string   `toml:"state_file"`
+	AutoConnect            bool     `toml:"auto_connect"`                       // Connect SSH sessions on demand when executing on them
+	InteractiveTimeout     int      `toml:"interactive_timeout,omitempty"`      // Default command timeout in interactive mode (overrides command_timeout)
+	AutomatedTimeout       int      `toml:"automated_timeout,omitempty"`        // Default command timeout in proxy/MCP mode (overrides command_timeout)
+	RestrictionAuditFile   string   `toml:"restriction_audit_file,omitempty"`   // JSONL file recording every command blocked by restrictions, bypassed, or run raw
+	MaxSessions            int      `toml:"max_sessions,omitempty"`             // Maximum number of sessions (0 uses the default of 100)
+	AllowRestrictionBypass bool     `toml:"allow_restriction_bypass,omitempty"` // Allow the interactive "!!" prefix to run restricted commands
+	KeepaliveInterval      int      `toml:"keepalive_interval"`                 // Seconds between SSH keepalive requests (0 disables)
+	MaxConnections         int      `toml:"max_connections,omitempty"`          // Maximum simultaneously connected SSH sessions (0 uses the default of 10)
+	QuietJobs              bool     `toml:"quiet_jobs,omitempty"`               // Don't announce background job completion; check /jobs instead
+	Locale                 string   `toml:"locale,omitempty"`                   // LC_ALL/LANG for executed commands (default C.UTF-8; "none" keeps the inherited locale)
+	MCPAutoReconnect       bool     `toml:"mcp_auto_reconnect"`                 // Reconnect and retry once when an MCP execute hits a dropped connection
+	ImportSSHConfig        bool     `toml:"import_ssh_config,omitempty"`        // Add a session for every Host in ~/.ssh/config that isn't configured
+	Include                []string `toml:"include,omitempty"`                  // Config files merged in before this one, later files overriding earlier ones
 }
 
 // Session represents a session configuration
 type Session struct {
-	Type            string   `toml:"type"` // "local" or "ssh"
-	Shell           string   `toml:"shell,omitempty"`
-	Host            string   `toml:"host,omitempty"`
-	User            string   `toml:"user,omitempty"`
-	Port            int      `toml:"port,omitempty"`
-	IdentityFile    string   `toml:"identity_file,omitempty"`
-	JumpHost        string   `toml:"jump_host,omitempty"`
-	AgentForwarding bool     `toml:"agent_forwarding,omitempty"`
-	PasswordEnv     string   `toml:"password_env,omitempty"`  // Environment variable containing password
-	PasswordFile    string   `toml:"password_file,omitempty"` // File containing password (must be 0600)
-	StartupCommands []string `toml:"startup_commands,omitempty"`
-	CommandTimeout  int      `toml:"command_timeout,omitempty"` // Command timeout in seconds (overrides global default)
+	Type             string            `toml:"type"` // "local" or "ssh"
+	Shell            string            `toml:"shell,omitempty"`
+	Host             string            `toml:"host,omitempty"`
+	User             string            `toml:"user,omitempty"`
+	Port             int               `toml:"port,omitempty"`
+	IdentityFile     string            `toml:"identity_file,omitempty"`
+	JumpHost         string            `toml:"jump_host,omitempty"`
+	AgentForwarding  bool              `toml:"agent_forwarding,omitempty"`
+	PasswordEnv      string            `toml:"password_env,omitempty"`  // Environment variable containing password
+	PasswordFile     string            `toml:"password_file,omitempty"` // File containing password (must be 0600)
+	StartupCommands  []string          `toml:"startup_commands,omitempty"`
+	CommandTimeout   int               `toml:"command_timeout,omitempty"`   // Command timeout in seconds (overrides global default)
+	InteractiveShell bool              `toml:"interactive_shell,omitempty"` // Local only: run commands via "$SHELL -ic" to load rc files
+	PersistentShell  bool              `toml:"persistent_shell,omitempty"`  // Local only: run commands in one long-lived shell so shell state carries over
+	Restricted       bool              `toml:"restricted,omitempty"`        // Enforce restricted mode for this session only
+	RunAs            string            `toml:"run_as,omitempty"`            // Run commands as this user via "sudo -u <user> -- sh -c"
+	Description      string            `toml:"description,omitempty"`       // Free-form note shown in status output
+	Locale           string            `toml:"locale,omitempty"`            // Overrides settings.locale for this session ("none" disables)
+	Env              map[string]string `toml:"env,omitempty"`               // Environment variables set on the session
+	EnvFile          string            `toml:"env_file,omitempty"`          // Dotenv-style file loaded on connect (env entries win on conflicts)
+	OutputFilter     string            `toml:"output_filter,omitempty"`     // Local command that stdout is piped through before display
+	Imported         bool              `toml:"-"`                           // Created from ~/.ssh/config by ImportSSHConfig; never saved
+	Included         bool              `toml:"-"`                           // Only defined in a file from settings.include; never saved to the main file
 }
 
+// DefaultMaxSessions is the session cap used when max_sessions is not set
+const DefaultMaxSessions = 100
+
+// DefaultMaxConnections is the connected SSH session cap used when max_connections is not set
+const DefaultMaxConnections = 10
+
+// DefaultLocale is the locale commands run under when locale is not set, so
+// their output is English and UTF-8 regardless of the user's environment
+const DefaultLocale = "C.UTF-8"
+
+// LocaleNone disables setting the locale, leaving it inherited
+const LocaleNone = "none"
+
 // DefaultConfig returns a default configuration
 func DefaultConfig() *Config {
 	return &Config{
@@ -48,6 +93,8 @@ func DefaultConfig() *Config {
 			CommandTimeout:    300,
 			ReconnectAttempts: 5,
 			ReconnectBackoff:  2,
+			KeepaliveInterval: 30,
+			MCPAutoReconnect:  true,
 			LogLevel:          "info",
 			StateFile:         defaultStateFile(),
 		},
@@ -87,6 +134,13 @@ func Load(path string) (*Config, error) {
 		return nil, fmt.Errorf("failed to parse config file: %w", err)
 	}
 
+	// Merge in the files listed in settings.include
+	if len(cfg.Settings.Include) > 0 {
+		if err := loadIncludes(cfg, path); err != nil {
+			return nil, err
+		}
+	}
+
 	// Ensure local session exists
 	if _, ok := cfg.Sessions["local"]; !ok {
 		cfg.Sessions["local"] = Session{
@@ -95,6 +149,10 @@ func Load(path string) (*Config, error) {
 		}
 	}
 
+	if err := cfg.Validate(); err != nil {
+		return nil, fmt.Errorf("%s: %w", path, err)
+	}
+
 	// Apply environment overrides
 	cfg.applyEnvOverrides()
 
@@ -152,12 +210,95 @@ func (c *Config) GetTimeout(sessionName string) int {
 	if session, ok := c.Sessions[sessionName]; ok && session.CommandTimeout > 0 {
 		return session.CommandTimeout
 	}
+	return c.DefaultTimeout()
+}
+
+// SetAutomated records whether thop runs in an automated mode (proxy or MCP)
+// rather than interactively, which selects the mode-specific default timeout
+func (c *Config) SetAutomated(automated bool) {
+	c.automated = automated
+}
+
+// DefaultTimeout returns the global command timeout in seconds for the run
+// mode: interactive_timeout or automated_timeout if set, else command_timeout
+func (c *Config) DefaultTimeout() int {
+	if c.automated && c.Settings.AutomatedTimeout > 0 {
+		return c.Settings.AutomatedTimeout
+	}
+	if !c.automated && c.Settings.InteractiveTimeout > 0 {
+		return c.Settings.InteractiveTimeout
+	}
 	if c.Settings.CommandTimeout > 0 {
 		return c.Settings.CommandTimeout
 	}
 	return 300 // Default 5 minutes
 }
 
+// SessionLocale returns the locale to export as LC_ALL and LANG for commands
+// run on a session: its own locale if set, else the global setting, else
+// DefaultLocale. It returns "" if the locale should be left as inherited.
+func (c *Config) SessionLocale(session Session) string {
+	locale := session.Locale
+	if locale == "" {
+		locale = c.Settings.Locale
+	}
+	if locale == "" {
+		locale = DefaultLocale
+	}
+	if locale == LocaleNone {
+		return ""
+	}
+	return locale
+}
+
+// SessionLimit returns the maximum number of sessions allowed
+func (c *Config) SessionLimit() int {
+	if c.Settings.MaxSessions > 0 {
+		return c.Settings.MaxSessions
+	}
+	return DefaultMaxSessions
+}
+
+// ConnectionLimit returns the maximum number of simultaneously connected SSH sessions
+func (c *Config) ConnectionLimit() int {
+	if c.Settings.MaxConnections > 0 {
+		return c.Settings.MaxConnections
+	}
+	return DefaultMaxConnections
+}
+
+// Effective returns a copy of the config with the defaults its settings
+// fall back to filled in, as thop uses them in the current run mode: the
+// session and connection limits, the mode's default timeout, the locale, and
+// each session's command timeout and locale ("none" if left as inherited)
+func (c *Config) Effective() *Config {
+	effective := *c
+	effective.Settings.MaxSessions = c.SessionLimit()
+	effective.Settings.MaxConnections = c.ConnectionLimit()
+	if c.automated {
+		effective.Settings.AutomatedTimeout = c.DefaultTimeout()
+	} else {
+		effective.Settings.InteractiveTimeout = c.DefaultTimeout()
+	}
+	if effective.Settings.Locale == "" {
+		effective.Settings.Locale = DefaultLocale
+	}
+
+	effective.Sessions = make(map[string]Session, len(c.Sessions))
+	for name, session := range c.Sessions {
+		if session.Type == "" {
+			session.Type = "local"
+		}
+		session.CommandTimeout = c.GetTimeout(name)
+		session.Locale = c.SessionLocale(session)
+		if session.Locale == "" {
+			session.Locale = LocaleNone
+		}
+		effective.Sessions[name] = session
+	}
+	return &effective
+}
+
 // AddSession adds a new session to the config
 func (c *Config) AddSession(name string, session Session) error {
 	if _, exists := c.Sessions[name]; exists {
@@ -167,7 +308,9 @@ func (c *Config) AddSession(name string, session Session) error {
 	return nil
 }
 
-// Save saves the configuration to the specified path
+// Save saves the configuration to the specified path. The file is written to
+// a temporary file in the same directory and renamed into place, so a crash
+// or full disk never leaves a truncated config behind.
 func (c *Config) Save(path string) error {
 	if path == "" {
 		path = DefaultConfigPath()
@@ -179,20 +322,69 @@ func (c *Config) Save(path string) error {
 		return fmt.Errorf("failed to create config directory: %w", err)
 	}
 
-	// Marshal to TOML
-	data, err := toml.Marshal(c)
+	// Marshal to TOML, leaving out sessions imported from ~/.ssh/config or
+	// defined in included files
+	saved := *c
+	saved.Sessions = make(map[string]Session, len(c.Sessions))
+	for name, session := range c.Sessions {
+		if !session.Imported && !session.Included {
+			saved.Sessions[name] = session
+		}
+	}
+	var data []byte
+	var err error
+	if c.includeBase != nil {
+		data, err = c.marshalOverIncludes(&saved)
+	} else {
+		data, err = toml.Marshal(&saved)
+	}
 	if err != nil {
 		return fmt.Errorf("failed to marshal config: %w", err)
 	}
 
-	// Write to file
-	if err := os.WriteFile(path, data, 0600); err != nil {
+	if err := writeFileAtomic(path, data, SecureFileMode); err != nil {
 		return fmt.Errorf("failed to write config file: %w", err)
 	}
 
 	return nil
 }
 
+// writeFileAtomic replaces path with data by writing a temporary file next to
+// it and renaming it over the original
+func writeFileAtomic(path string, data []byte, mode os.FileMode) error {
+	tmp, err := os.CreateTemp(filepath.Dir(path), "."+filepath.Base(path)+".*.tmp")
+	if err != nil {
+		return err
+	}
+	tmpPath := tmp.Name()
+	cleanup := func() {
+		_ = tmp.Close()
+		_ = os.Remove(tmpPath)
+	}
+
+	if _, err := tmp.Write(data); err != nil {
+		cleanup()
+		return err
+	}
+	if err := tmp.Chmod(mode); err != nil {
+		cleanup()
+		return err
+	}
+	if err := tmp.Sync(); err != nil {
+		cleanup()
+		return err
+	}
+	if err := tmp.Close(); err != nil {
+		_ = os.Remove(tmpPath)
+		return err
+	}
+	if err := os.Rename(tmpPath, path); err != nil {
+		_ = os.Remove(tmpPath)
+		return err
+	}
+	return nil
+}
+
 func defaultStateFile() string {
 	if val := os.Getenv("THOP_STATE_FILE"); val != "" {
 		return val
@@ -207,6 +399,15 @@ func defaultStateFile() string {
 	return filepath.Join(dataDir, "thop", "state.json")
 }
 
+// DefaultUser returns the login user assumed for SSH sessions that don't name one:
+// $USER, or root if it is unset
+func DefaultUser() string {
+	if user := os.Getenv("USER"); user != "" {
+		return user
+	}
+	return "root"
+}
+
 func getDefaultShell() string {
 	if shell := os.Getenv("SHELL"); shell != "" {
 		return shell
@@ -1,9 +1,14 @@
 package config
 
 import (
+	"errors"
 	"os"
 	"path/filepath"
+	"reflect"
+	"strings"
 	"testing"
+
+	"github.com/pelletier/go-toml/v2"
 )
 
 func TestDefaultConfig(t *testing.T) {
@@ -79,6 +84,7 @@ host = "prod.example.com"
 user = "deploy"
 port = 2222
 identity_file = "~/.ssh/prod_key"
+description = "Production web tier"
 `
 
 	if err := os.WriteFile(configPath, []byte(configContent), 0644); err != nil {
@@ -134,6 +140,14 @@ identity_file = "~/.ssh/prod_key"
 	if prodSession.Port != 2222 {
 		t.Errorf("expected port 2222, got %d", prodSession.Port)
 	}
+
+	if prodSession.Description != "Production web tier" {
+		t.Errorf("expected description 'Production web tier', got '%s'", prodSession.Description)
+	}
+
+	if localSession.Description != "" {
+		t.Errorf("expected empty description for local, got '%s'", localSession.Description)
+	}
 }
 
 func TestLoadInvalidConfig(t *testing.T) {
@@ -236,3 +250,370 @@ func TestDefaultConfigPath(t *testing.T) {
 		t.Errorf("expected '/custom/path/config.toml', got '%s'", path)
 	}
 }
+
+func TestDefaultTimeoutByMode(t *testing.T) {
+	cfg := DefaultConfig()
+	cfg.Settings.CommandTimeout = 300
+	cfg.Settings.InteractiveTimeout = 3600
+	cfg.Settings.AutomatedTimeout = 60
+
+	// Interactive is the default mode
+	if got := cfg.DefaultTimeout(); got != 3600 {
+		t.Errorf("interactive: expected 3600, got %d", got)
+	}
+
+	cfg.SetAutomated(true)
+	if got := cfg.DefaultTimeout(); got != 60 {
+		t.Errorf("automated: expected 60, got %d", got)
+	}
+	if got := cfg.GetTimeout("local"); got != 60 {
+		t.Errorf("automated GetTimeout: expected 60, got %d", got)
+	}
+
+	// Session-specific timeout still wins
+	local := cfg.Sessions["local"]
+	local.CommandTimeout = 10
+	cfg.Sessions["local"] = local
+	if got := cfg.GetTimeout("local"); got != 10 {
+		t.Errorf("session override: expected 10, got %d", got)
+	}
+
+	// Unset mode timeouts fall back to command_timeout, then 300
+	cfg.Settings.AutomatedTimeout = 0
+	if got := cfg.DefaultTimeout(); got != 300 {
+		t.Errorf("fallback: expected 300, got %d", got)
+	}
+	cfg.Settings.CommandTimeout = 0
+	if got := cfg.DefaultTimeout(); got != 300 {
+		t.Errorf("default: expected 300, got %d", got)
+	}
+}
+
+func TestSessionLocale(t *testing.T) {
+	cfg := DefaultConfig()
+
+	if got := cfg.SessionLocale(Session{Type: "local"}); got != DefaultLocale {
+		t.Errorf("default: expected %s, got %q", DefaultLocale, got)
+	}
+
+	cfg.Settings.Locale = "en_US.UTF-8"
+	if got := cfg.SessionLocale(Session{Type: "local"}); got != "en_US.UTF-8" {
+		t.Errorf("global: expected en_US.UTF-8, got %q", got)
+	}
+	if got := cfg.SessionLocale(Session{Type: "ssh", Locale: "C"}); got != "C" {
+		t.Errorf("session override: expected C, got %q", got)
+	}
+	if got := cfg.SessionLocale(Session{Type: "ssh", Locale: LocaleNone}); got != "" {
+		t.Errorf("session none: expected no locale, got %q", got)
+	}
+
+	cfg.Settings.Locale = LocaleNone
+	if got := cfg.SessionLocale(Session{Type: "local"}); got != "" {
+		t.Errorf("global none: expected no locale, got %q", got)
+	}
+}
+
+func TestSessionLimit(t *testing.T) {
+	cfg := DefaultConfig()
+	if got := cfg.SessionLimit(); got != DefaultMaxSessions {
+		t.Errorf("expected default limit %d, got %d", DefaultMaxSessions, got)
+	}
+
+	cfg.Settings.MaxSessions = 5
+	if got := cfg.SessionLimit(); got != 5 {
+		t.Errorf("expected configured limit 5, got %d", got)
+	}
+}
+
+func TestKeepaliveIntervalRoundTrip(t *testing.T) {
+	if got := DefaultConfig().Settings.KeepaliveInterval; got != 30 {
+		t.Errorf("expected default keepalive_interval 30, got %d", got)
+	}
+
+	tmpDir := t.TempDir()
+	path := filepath.Join(tmpDir, "config.toml")
+
+	cfg := DefaultConfig()
+	cfg.Settings.KeepaliveInterval = 15
+	if err := cfg.Save(path); err != nil {
+		t.Fatalf("Save failed: %v", err)
+	}
+
+	loaded, err := Load(path)
+	if err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	if loaded.Settings.KeepaliveInterval != 15 {
+		t.Errorf("expected keepalive_interval 15 after round trip, got %d", loaded.Settings.KeepaliveInterval)
+	}
+
+	// An explicit 0 disables keepalives rather than falling back to the default
+	if err := os.WriteFile(path, []byte("[settings]\nkeepalive_interval = 0\n"), 0644); err != nil {
+		t.Fatalf("failed to write config: %v", err)
+	}
+	loaded, err = Load(path)
+	if err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	if loaded.Settings.KeepaliveInterval != 0 {
+		t.Errorf("expected keepalive_interval 0, got %d", loaded.Settings.KeepaliveInterval)
+	}
+}
+
+func TestConnectionLimit(t *testing.T) {
+	cfg := DefaultConfig()
+	if got := cfg.ConnectionLimit(); got != DefaultMaxConnections {
+		t.Errorf("expected default limit %d, got %d", DefaultMaxConnections, got)
+	}
+
+	cfg.Settings.MaxConnections = 3
+	if got := cfg.ConnectionLimit(); got != 3 {
+		t.Errorf("expected configured limit 3, got %d", got)
+	}
+}
+
+func TestSaveAddedSessionRoundTrip(t *testing.T) {
+	dir := t.TempDir()
+	path := filepath.Join(dir, "config.toml")
+	initial := "[settings]\ndefault_session = \"prod\"\ncommand_timeout = 120\n\n[sessions.prod]\ntype = \"ssh\"\nhost = \"prod.example.com\"\nuser = \"deploy\"\n"
+	if err := os.WriteFile(path, []byte(initial), 0600); err != nil {
+		t.Fatalf("failed to write config: %v", err)
+	}
+
+	cfg, err := Load(path)
+	if err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	if err := cfg.AddSession("staging", Session{Type: "ssh", Host: "staging.example.com", User: "ci", Port: 2222}); err != nil {
+		t.Fatalf("AddSession failed: %v", err)
+	}
+	if err := cfg.Save(path); err != nil {
+		t.Fatalf("Save failed: %v", err)
+	}
+
+	loaded, err := Load(path)
+	if err != nil {
+		t.Fatalf("Load after save failed: %v", err)
+	}
+	staging, ok := loaded.Sessions["staging"]
+	if !ok || staging.Host != "staging.example.com" || staging.User != "ci" || staging.Port != 2222 {
+		t.Errorf("added session not saved: %+v", staging)
+	}
+	if prod, ok := loaded.Sessions["prod"]; !ok || prod.Host != "prod.example.com" {
+		t.Errorf("existing session not preserved: %+v", prod)
+	}
+	if loaded.Settings.DefaultSession != "prod" || loaded.Settings.CommandTimeout != 120 {
+		t.Errorf("settings not preserved: %+v", loaded.Settings)
+	}
+
+	info, err := os.Stat(path)
+	if err != nil {
+		t.Fatalf("Stat failed: %v", err)
+	}
+	if perm := info.Mode().Perm(); perm != SecureFileMode {
+		t.Errorf("config mode = %o, want %o", perm, SecureFileMode)
+	}
+	entries, err := os.ReadDir(dir)
+	if err != nil {
+		t.Fatalf("ReadDir failed: %v", err)
+	}
+	if len(entries) != 1 {
+		t.Errorf("expected only the config file after saving, found %d entries", len(entries))
+	}
+}
+
+func TestValidate(t *testing.T) {
+	cfg := DefaultConfig()
+	cfg.Settings.DefaultSession = "missing"
+	cfg.Sessions["nohost"] = Session{Type: "ssh", User: "deploy"}
+	cfg.Sessions["typo"] = Session{Type: "shh", Host: "typo.example.com"}
+	cfg.Sessions["badport"] = Session{Type: "ssh", Host: "bad.example.com", Port: 70000}
+	cfg.Sessions["prod"] = Session{Type: "ssh", Host: "prod.example.com", User: "deploy"}
+	cfg.Sessions["prod2"] = Session{Type: "ssh", Host: "prod.example.com", User: "deploy", Port: 22}
+	cfg.Sessions["prod-root"] = Session{Type: "ssh", Host: "prod.example.com", User: "root"}
+
+	err := cfg.Validate()
+	var validationErr *ValidationError
+	if !errors.As(err, &validationErr) {
+		t.Fatalf("expected a ValidationError, got %v", err)
+	}
+
+	want := []string{
+		`ssh session "badport" has invalid port 70000`,
+		`ssh session "nohost" has no host`,
+		`sessions "prod" and "prod2" both connect to deploy@prod.example.com:22`,
+		`session "typo" has unknown type "shh" (use "local" or "ssh")`,
+		`default_session "missing" is not a configured session`,
+	}
+	if !reflect.DeepEqual(validationErr.Problems, want) {
+		t.Errorf("Problems =\n%s\nwant\n%s", strings.Join(validationErr.Problems, "\n"), strings.Join(want, "\n"))
+	}
+	for _, problem := range want {
+		if !strings.Contains(err.Error(), problem) {
+			t.Errorf("error message missing %q", problem)
+		}
+	}
+}
+
+func TestValidateValidConfig(t *testing.T) {
+	cfg := DefaultConfig()
+	cfg.Sessions["prod"] = Session{Type: "ssh", Host: "prod.example.com", User: "deploy", Port: 2222}
+	cfg.Sessions["prod-root"] = Session{Type: "ssh", Host: "prod.example.com", User: "root", Port: 2222}
+	if err := cfg.Validate(); err != nil {
+		t.Errorf("expected a valid config, got %v", err)
+	}
+}
+
+func TestLoadReportsAllValidationErrors(t *testing.T) {
+	configPath := filepath.Join(t.TempDir(), "config.toml")
+	content := `[settings]
+default_session = "prdo"
+
+[sessions.prod]
+type = "ssh"
+user = "deploy"
+`
+	if err := os.WriteFile(configPath, []byte(content), 0600); err != nil {
+		t.Fatalf("failed to write test config: %v", err)
+	}
+
+	_, err := Load(configPath)
+	if err == nil {
+		t.Fatal("expected Load to reject an invalid config")
+	}
+	for _, problem := range []string{`"prod" has no host`, `default_session "prdo"`} {
+		if !strings.Contains(err.Error(), problem) {
+			t.Errorf("expected error to mention %q, got %v", problem, err)
+		}
+	}
+}
+
+func TestLoadIncludesOverridePrecedence(t *testing.T) {
+	dir := t.TempDir()
+	files := map[string]string{
+		"team.toml": `
+[settings]
+command_timeout = 100
+log_level = "debug"
+
+[sessions.web]
+type = "ssh"
+host = "web.team.example.com"
+user = "team"
+
+[sessions.db]
+type = "ssh"
+host = "db.team.example.com"
+`,
+		"personal.toml": `
+[settings]
+command_timeout = 200
+
+[sessions.web]
+user = "me"
+port = 2222
+`,
+		"config.toml": `
+[settings]
+include = ["team.toml", "personal.toml"]
+log_level = "warn"
+
+[sessions.web]
+port = 2200
+`,
+	}
+	for name, content := range files {
+		if err := os.WriteFile(filepath.Join(dir, name), []byte(content), 0600); err != nil {
+			t.Fatal(err)
+		}
+	}
+	path := filepath.Join(dir, "config.toml")
+
+	cfg, err := Load(path)
+	if err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+
+	web := cfg.Sessions["web"]
+	if web.Type != "ssh" || web.Host != "web.team.example.com" || web.User != "me" || web.Port != 2200 {
+		t.Errorf("expected team host, personal user and main port, got %+v", web)
+	}
+	if cfg.Settings.CommandTimeout != 200 || cfg.Settings.LogLevel != "warn" {
+		t.Errorf("expected timeout from personal.toml and log level from the main file, got %+v", cfg.Settings)
+	}
+	if db, ok := cfg.Sessions["db"]; !ok || db.Host != "db.team.example.com" || !db.Included {
+		t.Errorf("expected db from team.toml marked as included, got %+v", db)
+	}
+	if web.Included {
+		t.Error("a session also set in the main file should not be marked as included")
+	}
+	if _, ok := cfg.Sessions["local"]; !ok {
+		t.Error("expected the local session to still be added")
+	}
+
+	// Sessions from included files stay there when the main file is saved
+	if err := cfg.Save(path); err != nil {
+		t.Fatalf("Save failed: %v", err)
+	}
+	data, err := os.ReadFile(path)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if strings.Contains(string(data), "db.team.example.com") {
+		t.Errorf("included session written to the main file:\n%s", data)
+	}
+
+	// Nor are settings and session fields that came from them
+	if strings.Contains(string(data), "command_timeout = 200") {
+		t.Errorf("included setting written to the main file:\n%s", data)
+	}
+	var saved map[string]interface{}
+	if err := toml.Unmarshal(data, &saved); err != nil {
+		t.Fatalf("failed to parse saved config: %v", err)
+	}
+	sessions, _ := saved["sessions"].(map[string]interface{})
+	savedWeb, _ := sessions["web"].(map[string]interface{})
+	if _, ok := savedWeb["host"]; ok {
+		t.Errorf("included host written under [sessions.web]:\n%s", data)
+	}
+	if savedWeb["port"] != int64(2200) {
+		t.Errorf("expected the main file's port to be kept, got %v", savedWeb["port"])
+	}
+	if reloaded, err := Load(path); err != nil {
+		t.Errorf("Load after save failed: %v", err)
+	} else if _, ok := reloaded.Sessions["db"]; !ok {
+		t.Error("expected the include list to survive saving")
+	} else if rweb := reloaded.Sessions["web"]; rweb.Host != "web.team.example.com" || rweb.User != "me" || rweb.Port != 2200 {
+		t.Errorf("expected web to reload the same, got %+v", rweb)
+	}
+}
+
+func TestLoadIncludeCycle(t *testing.T) {
+	dir := t.TempDir()
+	if err := os.WriteFile(filepath.Join(dir, "a.toml"), []byte("[settings]\ninclude = [\"b.toml\"]\n"), 0600); err != nil {
+		t.Fatal(err)
+	}
+	if err := os.WriteFile(filepath.Join(dir, "b.toml"), []byte("[settings]\ninclude = [\"a.toml\"]\n"), 0600); err != nil {
+		t.Fatal(err)
+	}
+
+	_, err := Load(filepath.Join(dir, "a.toml"))
+	if err == nil {
+		t.Fatal("expected an include cycle error")
+	}
+	if !strings.Contains(err.Error(), "include cycle") || !strings.Contains(err.Error(), "a.toml -> ") {
+		t.Errorf("expected the error to name the cycle, got: %v", err)
+	}
+}
+
+func TestLoadIncludeMissingFile(t *testing.T) {
+	dir := t.TempDir()
+	path := filepath.Join(dir, "config.toml")
+	if err := os.WriteFile(path, []byte("[settings]\ninclude = [\"missing.toml\"]\n"), 0600); err != nil {
+		t.Fatal(err)
+	}
+
+	if _, err := Load(path); err == nil || !strings.Contains(err.Error(), "missing.toml") {
+		t.Errorf("expected an error naming the missing include, got %v", err)
+	}
+}
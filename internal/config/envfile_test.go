@@ -0,0 +1,85 @@
+package config
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+func TestParseEnvFile(t *testing.T) {
+	input := `# database settings
+DB_HOST=db.internal
+export DB_PORT=5432
+DB_USER = app
+EMPTY=
+BARE=value # trailing comment
+HASH=abc#def
+DOUBLE="hello world"
+ESCAPED="line1\nline2 \"quoted\""
+SINGLE='literal $HOME \n'
+
+`
+	env, err := ParseEnvFile(strings.NewReader(input))
+	if err != nil {
+		t.Fatalf("ParseEnvFile failed: %v", err)
+	}
+
+	want := map[string]string{
+		"DB_HOST": "db.internal",
+		"DB_PORT": "5432",
+		"DB_USER": "app",
+		"EMPTY":   "",
+		"BARE":    "value",
+		"HASH":    "abc#def",
+		"DOUBLE":  "hello world",
+		"ESCAPED": "line1\nline2 \"quoted\"",
+		"SINGLE":  `literal $HOME \n`,
+	}
+	if len(env) != len(want) {
+		t.Errorf("expected %d variables, got %d: %v", len(want), len(env), env)
+	}
+	for k, v := range want {
+		if env[k] != v {
+			t.Errorf("%s = %q, want %q", k, env[k], v)
+		}
+	}
+}
+
+func TestParseEnvFileErrors(t *testing.T) {
+	for _, input := range []string{
+		"NO_EQUALS",
+		"1BAD=x",
+		`OPEN="unterminated`,
+		"OPEN='unterminated",
+	} {
+		if _, err := ParseEnvFile(strings.NewReader(input)); err == nil {
+			t.Errorf("expected an error for %q", input)
+		} else if !strings.Contains(err.Error(), "line 1") {
+			t.Errorf("expected the line number in %q", err)
+		}
+	}
+}
+
+func TestResolveEnvInlineOverridesFile(t *testing.T) {
+	home := t.TempDir()
+	t.Setenv("HOME", home)
+	if err := os.WriteFile(filepath.Join(home, "app.env"), []byte("APP_ENV=staging\nLOG_LEVEL=debug\n"), 0600); err != nil {
+		t.Fatal(err)
+	}
+
+	env, err := ResolveEnv("~/app.env", map[string]string{"APP_ENV": "production", "EXTRA": "1"})
+	if err != nil {
+		t.Fatalf("ResolveEnv failed: %v", err)
+	}
+	if env["APP_ENV"] != "production" {
+		t.Errorf("expected inline env to win, got APP_ENV=%q", env["APP_ENV"])
+	}
+	if env["LOG_LEVEL"] != "debug" || env["EXTRA"] != "1" {
+		t.Errorf("expected file and inline variables to be merged, got %v", env)
+	}
+
+	if _, err := ResolveEnv(filepath.Join(home, "missing.env"), nil); err == nil {
+		t.Error("expected an error for a missing env file")
+	}
+}
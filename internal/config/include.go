@@ -0,0 +1,238 @@
+package config
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"reflect"
+	"strings"
+
+	"github.com/pelletier/go-toml/v2"
+)
+
+// loadIncludes loads the config at path together with the files listed in
+// its settings.include, merged into cfg. Included files are merged in order,
+// each after the files it includes itself, and path goes on top, so later
+// files override earlier ones and the main file overrides all of them.
+// Sessions only defined in included files are marked Included so Save leaves
+// them where they are, and Save writes only what the main file sets itself or
+// what has changed from the included files' values.
+func loadIncludes(cfg *Config, path string) error {
+	abs, err := filepath.Abs(path)
+	if err != nil {
+		return err
+	}
+	mainTree, err := parseTree(abs)
+	if err != nil {
+		return err
+	}
+	base, err := includedTree(mainTree, abs, nil)
+	if err != nil {
+		return err
+	}
+
+	// What the included files give without the main file, in the form Save
+	// compares against
+	baseCfg, err := treeConfig(base)
+	if err != nil {
+		return err
+	}
+	includeBase, err := configTree(baseCfg)
+	if err != nil {
+		return err
+	}
+
+	mergeTables(base, mainTree)
+	merged, err := treeConfig(base)
+	if err != nil {
+		return err
+	}
+	for name, session := range merged.Sessions {
+		if _, ok := cfg.Sessions[name]; !ok {
+			session.Included = true
+			merged.Sessions[name] = session
+		}
+	}
+
+	cfg.Settings = merged.Settings
+	cfg.Sessions = merged.Sessions
+	cfg.includeBase = includeBase
+	cfg.mainTree = mainTree
+	return nil
+}
+
+// loadTree parses the TOML file at path and merges it over the files it
+// includes. stack holds the files being loaded, outermost first, to detect
+// include cycles. The file's own include list is dropped, since only the main
+// file's is kept.
+func loadTree(path string, stack []string) (map[string]interface{}, error) {
+	abs, err := filepath.Abs(path)
+	if err != nil {
+		return nil, err
+	}
+	for i, loading := range stack {
+		if loading == abs {
+			cycle := append(append([]string(nil), stack[i:]...), abs)
+			return nil, fmt.Errorf("config include cycle: %s", strings.Join(cycle, " -> "))
+		}
+	}
+
+	tree, err := parseTree(abs)
+	if err != nil {
+		return nil, err
+	}
+	merged, err := includedTree(tree, abs, stack)
+	if err != nil {
+		return nil, err
+	}
+	if settings, ok := tree["settings"].(map[string]interface{}); ok {
+		delete(settings, "include")
+	}
+	mergeTables(merged, tree)
+	return merged, nil
+}
+
+// includedTree loads and merges, in order, the files listed in the include
+// list of tree, the parsed contents of the file at abs
+func includedTree(tree map[string]interface{}, abs string, stack []string) (map[string]interface{}, error) {
+	includes, err := includePaths(tree, filepath.Dir(abs))
+	if err != nil {
+		return nil, fmt.Errorf("%s: %w", abs, err)
+	}
+
+	merged := make(map[string]interface{})
+	for _, include := range includes {
+		included, err := loadTree(include, append(stack, abs))
+		if err != nil {
+			return nil, err
+		}
+		mergeTables(merged, included)
+	}
+	return merged, nil
+}
+
+// parseTree parses the TOML file at path into a generic tree
+func parseTree(path string) (map[string]interface{}, error) {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return nil, fmt.Errorf("failed to read config file: %w", err)
+	}
+	var tree map[string]interface{}
+	if err := toml.Unmarshal(data, &tree); err != nil {
+		return nil, fmt.Errorf("failed to parse config file %s: %w", path, err)
+	}
+	return tree, nil
+}
+
+// treeConfig decodes a merged tree over the default config
+func treeConfig(tree map[string]interface{}) (*Config, error) {
+	data, err := toml.Marshal(tree)
+	if err != nil {
+		return nil, fmt.Errorf("failed to merge included config files: %w", err)
+	}
+	cfg := DefaultConfig()
+	if err := toml.Unmarshal(data, cfg); err != nil {
+		return nil, fmt.Errorf("failed to parse included config files: %w", err)
+	}
+	return cfg, nil
+}
+
+// configTree encodes a config as a generic tree, as Save would write it
+func configTree(cfg *Config) (map[string]interface{}, error) {
+	data, err := toml.Marshal(cfg)
+	if err != nil {
+		return nil, err
+	}
+	var tree map[string]interface{}
+	if err := toml.Unmarshal(data, &tree); err != nil {
+		return nil, err
+	}
+	return tree, nil
+}
+
+// marshalOverIncludes encodes a config loaded with settings.include for the
+// main file. A value is left out if the main file didn't set it and it is
+// what the included files give, so those files' settings and partial session
+// overrides aren't copied into the main file and later edits to them still
+// take effect.
+func (c *Config) marshalOverIncludes(cfg *Config) ([]byte, error) {
+	tree, err := configTree(cfg)
+	if err != nil {
+		return nil, err
+	}
+	pruneTree(tree, c.includeBase, c.mainTree)
+	return toml.Marshal(tree)
+}
+
+// pruneTree removes from tree the values equal to those in base that main
+// doesn't set, along with tables left empty that main doesn't have
+func pruneTree(tree, base, main map[string]interface{}) {
+	for key, value := range tree {
+		mainValue, inMain := main[key]
+		baseValue, inBase := base[key]
+		if table, ok := value.(map[string]interface{}); ok {
+			if baseTable, ok := baseValue.(map[string]interface{}); ok {
+				mainTable, _ := mainValue.(map[string]interface{})
+				pruneTree(table, baseTable, mainTable)
+				if len(table) == 0 && !inMain {
+					delete(tree, key)
+				}
+			}
+			continue
+		}
+		if !inMain && inBase && reflect.DeepEqual(value, baseValue) {
+			delete(tree, key)
+		}
+	}
+}
+
+// includePaths returns the files listed in a parsed config's settings.include,
+// with ~ expanded and relative paths resolved against dir
+func includePaths(tree map[string]interface{}, dir string) ([]string, error) {
+	settings, ok := tree["settings"].(map[string]interface{})
+	if !ok {
+		return nil, nil
+	}
+	raw, ok := settings["include"]
+	if !ok {
+		return nil, nil
+	}
+	list, ok := raw.([]interface{})
+	if !ok {
+		return nil, fmt.Errorf("settings.include must be a list of file paths")
+	}
+
+	paths := make([]string, 0, len(list))
+	for _, item := range list {
+		path, ok := item.(string)
+		if !ok || path == "" {
+			return nil, fmt.Errorf("settings.include must be a list of file paths")
+		}
+		if strings.HasPrefix(path, "~/") {
+			home, err := os.UserHomeDir()
+			if err != nil {
+				return nil, err
+			}
+			path = filepath.Join(home, path[2:])
+		}
+		if !filepath.IsAbs(path) {
+			path = filepath.Join(dir, path)
+		}
+		paths = append(paths, path)
+	}
+	return paths, nil
+}
+
+// mergeTables merges src into dst. Tables present in both are merged key by
+// key; any other value in src replaces the one in dst.
+func mergeTables(dst, src map[string]interface{}) {
+	for key, value := range src {
+		if srcTable, ok := value.(map[string]interface{}); ok {
+			if dstTable, ok := dst[key].(map[string]interface{}); ok {
+				mergeTables(dstTable, srcTable)
+				continue
+			}
+		}
+		dst[key] = value
+	}
+}
@@ -0,0 +1,42 @@
+package config
+
+import (
+	"fmt"
+	"os"
+	"runtime"
+)
+
+// SecureFileMode is the mode thop's config and state files should have: they
+// can hold hostnames, users and environment values, so only the owner may
+// read them
+const SecureFileMode os.FileMode = 0600
+
+// CheckPermissions reports whether the file at path can be read or written by
+// its group or other users, along with its current mode. A missing file is
+// not insecure. Always false on Windows, where permission bits don't apply.
+func CheckPermissions(path string) (os.FileMode, bool, error) {
+	info, err := os.Stat(path)
+	if err != nil {
+		if os.IsNotExist(err) {
+			return 0, false, nil
+		}
+		return 0, false, err
+	}
+	mode := info.Mode().Perm()
+	if runtime.GOOS == "windows" {
+		return mode, false, nil
+	}
+	return mode, mode&0077 != 0, nil
+}
+
+// FixPermissions restricts the file at path to SecureFileMode. A missing file
+// is left alone.
+func FixPermissions(path string) error {
+	if _, err := os.Stat(path); os.IsNotExist(err) {
+		return nil
+	}
+	if err := os.Chmod(path, SecureFileMode); err != nil {
+		return fmt.Errorf("failed to set permissions on %s: %w", path, err)
+	}
+	return nil
+}
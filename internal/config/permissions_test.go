@@ -0,0 +1,64 @@
+package config
+
+import (
+	"os"
+	"path/filepath"
+	"runtime"
+	"testing"
+)
+
+func TestCheckPermissions(t *testing.T) {
+	if runtime.GOOS == "windows" {
+		t.Skip("permission bits are not enforced on Windows")
+	}
+
+	path := filepath.Join(t.TempDir(), "config.toml")
+	if err := os.WriteFile(path, []byte("[settings]\n"), 0644); err != nil {
+		t.Fatalf("failed to write file: %v", err)
+	}
+	if err := os.Chmod(path, 0644); err != nil {
+		t.Fatalf("failed to chmod file: %v", err)
+	}
+
+	mode, insecure, err := CheckPermissions(path)
+	if err != nil {
+		t.Fatalf("CheckPermissions failed: %v", err)
+	}
+	if !insecure {
+		t.Errorf("expected mode %o to be reported insecure", mode)
+	}
+	if mode != 0644 {
+		t.Errorf("expected mode 644, got %o", mode)
+	}
+
+	if err := FixPermissions(path); err != nil {
+		t.Fatalf("FixPermissions failed: %v", err)
+	}
+
+	info, err := os.Stat(path)
+	if err != nil {
+		t.Fatalf("failed to stat file: %v", err)
+	}
+	if info.Mode().Perm() != 0600 {
+		t.Errorf("expected mode 600 after fix, got %o", info.Mode().Perm())
+	}
+
+	if _, insecure, _ := CheckPermissions(path); insecure {
+		t.Error("expected fixed file to be reported secure")
+	}
+}
+
+func TestCheckPermissionsMissingFile(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "missing.toml")
+
+	_, insecure, err := CheckPermissions(path)
+	if err != nil {
+		t.Errorf("expected no error for missing file, got %v", err)
+	}
+	if insecure {
+		t.Error("expected missing file to be reported secure")
+	}
+	if err := FixPermissions(path); err != nil {
+		t.Errorf("expected no error fixing missing file, got %v", err)
+	}
+}
@@ -0,0 +1,60 @@
+package config
+
+import (
+	"sort"
+	"strconv"
+	"strings"
+
+	"github.com/scottgl9/thop/internal/sshconfig"
+)
+
+// ImportSSHConfig adds an SSH session for every host alias in sshCfg, named
+// after the alias, with its HostName, User, Port, IdentityFile, ProxyJump and
+// ForwardAgent resolved. Explicit config always wins: aliases that already
+// name a session or are used as a session's host are skipped, as are wildcard
+// patterns. Imported sessions are marked so Save leaves them out. It returns
+// the names of the sessions added, sorted.
+func (c *Config) ImportSSHConfig(sshCfg *sshconfig.Config) []string {
+	if sshCfg == nil {
+		return nil
+	}
+	if c.Sessions == nil {
+		c.Sessions = make(map[string]Session)
+	}
+
+	configuredHosts := make(map[string]bool)
+	for _, session := range c.Sessions {
+		if session.Host != "" {
+			configuredHosts[session.Host] = true
+		}
+	}
+
+	var added []string
+	for _, alias := range sshCfg.ListHosts() {
+		if strings.ContainsAny(alias, "*?!") || configuredHosts[alias] {
+			continue
+		}
+		if _, exists := c.Sessions[alias]; exists {
+			continue
+		}
+
+		port, _ := strconv.Atoi(sshCfg.ResolvePort(alias))
+		if port == 22 {
+			port = 0
+		}
+		c.Sessions[alias] = Session{
+			Type:            "ssh",
+			Host:            sshCfg.ResolveHost(alias),
+			User:            sshCfg.ResolveUser(alias),
+			Port:            port,
+			IdentityFile:    sshCfg.ResolveIdentityFile(alias),
+			JumpHost:        sshCfg.ResolveProxyJump(alias),
+			AgentForwarding: sshCfg.ResolveForwardAgent(alias),
+			Description:     "Imported from ~/.ssh/config",
+			Imported:        true,
+		}
+		added = append(added, alias)
+	}
+	sort.Strings(added)
+	return added
+}
@@ -0,0 +1,108 @@
+package config
+
+import (
+	"os"
+	"path/filepath"
+	"reflect"
+	"strings"
+	"testing"
+
+	"github.com/scottgl9/thop/internal/sshconfig"
+)
+
+func loadTestSSHConfig(t *testing.T, content string) *sshconfig.Config {
+	t.Helper()
+	path := filepath.Join(t.TempDir(), "config")
+	if err := os.WriteFile(path, []byte(content), 0600); err != nil {
+		t.Fatal(err)
+	}
+	sshCfg, err := sshconfig.LoadFromFile(path)
+	if err != nil {
+		t.Fatalf("failed to parse ssh config: %v", err)
+	}
+	return sshCfg
+}
+
+func TestImportSSHConfig(t *testing.T) {
+	sshCfg := loadTestSSHConfig(t, `
+Host *
+    ServerAliveInterval 60
+
+Host web
+    HostName web.example.com
+    User deploy
+    Port 2222
+    IdentityFile /keys/web
+    ProxyJump bastion
+    ForwardAgent yes
+
+Host db
+    HostName db.internal
+
+Host prod
+    HostName other.example.com
+
+Host staging
+    User ci
+`)
+
+	cfg := DefaultConfig()
+	cfg.Sessions["prod"] = Session{Type: "ssh", Host: "prod.example.com", User: "admin"}
+	cfg.Sessions["stage"] = Session{Type: "ssh", Host: "staging"}
+
+	added := cfg.ImportSSHConfig(sshCfg)
+	if want := []string{"db", "web"}; !reflect.DeepEqual(added, want) {
+		t.Fatalf("ImportSSHConfig added %v, want %v", added, want)
+	}
+
+	web := cfg.Sessions["web"]
+	want := Session{
+		Type:            "ssh",
+		Host:            "web.example.com",
+		User:            "deploy",
+		Port:            2222,
+		IdentityFile:    "/keys/web",
+		JumpHost:        "bastion",
+		AgentForwarding: true,
+		Description:     "Imported from ~/.ssh/config",
+		Imported:        true,
+	}
+	if !reflect.DeepEqual(web, want) {
+		t.Errorf("imported web session =\n%+v\nwant\n%+v", web, want)
+	}
+	if db := cfg.Sessions["db"]; db.Host != "db.internal" || db.Port != 0 || db.User != "" {
+		t.Errorf("imported db session = %+v", db)
+	}
+
+	// Explicit sessions win over ssh config entries
+	if prod := cfg.Sessions["prod"]; prod.Host != "prod.example.com" || prod.Imported {
+		t.Errorf("explicit prod session was overridden: %+v", prod)
+	}
+	if _, ok := cfg.Sessions["*"]; ok {
+		t.Error("wildcard Host * should not become a session")
+	}
+	if err := cfg.Validate(); err != nil {
+		t.Errorf("config with imported sessions should be valid: %v", err)
+	}
+}
+
+func TestSaveSkipsImportedSessions(t *testing.T) {
+	sshCfg := loadTestSSHConfig(t, "Host web\n    HostName web.example.com\n")
+	cfg := DefaultConfig()
+	cfg.ImportSSHConfig(sshCfg)
+
+	path := filepath.Join(t.TempDir(), "config.toml")
+	if err := cfg.Save(path); err != nil {
+		t.Fatalf("Save failed: %v", err)
+	}
+	data, err := os.ReadFile(path)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if strings.Contains(string(data), "web.example.com") {
+		t.Errorf("imported session was saved:\n%s", data)
+	}
+	if _, ok := cfg.Sessions["web"]; !ok {
+		t.Error("Save should not remove imported sessions from the loaded config")
+	}
+}
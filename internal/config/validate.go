@@ -0,0 +1,76 @@
+package config
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+)
+
+// ValidationError lists every problem Validate found in a config
+type ValidationError struct {
+	Problems []string
+}
+
+// Error returns the problems, one per line
+func (e *ValidationError) Error() string {
+	return "invalid config:\n  - " + strings.Join(e.Problems, "\n  - ")
+}
+
+// Validate checks the config for mistakes that parse fine but would only
+// fail later, such as an SSH session without a host or a default session
+// that doesn't exist. It returns a *ValidationError listing all of them, or
+// nil if there are none.
+func (c *Config) Validate() error {
+	var problems []string
+
+	names := make([]string, 0, len(c.Sessions))
+	for name := range c.Sessions {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+
+	endpoints := make(map[string]string) // user@host:port -> first session using it
+	for _, name := range names {
+		session := c.Sessions[name]
+		switch session.Type {
+		case "", "local":
+			continue
+		case "ssh":
+		default:
+			problems = append(problems, fmt.Sprintf("session %q has unknown type %q (use \"local\" or \"ssh\")", name, session.Type))
+			continue
+		}
+
+		if session.Host == "" {
+			problems = append(problems, fmt.Sprintf("ssh session %q has no host", name))
+		}
+		if session.Port < 0 || session.Port > 65535 {
+			problems = append(problems, fmt.Sprintf("ssh session %q has invalid port %d", name, session.Port))
+		}
+		if session.Host == "" {
+			continue
+		}
+
+		port := session.Port
+		if port == 0 {
+			port = 22
+		}
+		endpoint := fmt.Sprintf("%s@%s:%d", session.User, session.Host, port)
+		if first, ok := endpoints[endpoint]; ok {
+			problems = append(problems, fmt.Sprintf("sessions %q and %q both connect to %s", first, name, strings.TrimPrefix(endpoint, "@")))
+		} else {
+			endpoints[endpoint] = name
+		}
+	}
+
+	if def := c.Settings.DefaultSession; def != "" {
+		if _, ok := c.Sessions[def]; !ok {
+			problems = append(problems, fmt.Sprintf("default_session %q is not a configured session", def))
+		}
+	}
+
+	if len(problems) > 0 {
+		return &ValidationError{Problems: problems}
+	}
+	return nil
+}
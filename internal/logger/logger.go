@@ -73,6 +73,33 @@ type Config struct {
 
 var defaultLogger *Logger
 
+// Sink receives every message logged through the package-level functions,
+// whatever the default logger's level, so it can apply its own filtering
+type Sink func(level Level, msg string)
+
+var (
+	sinkMu sync.RWMutex
+	sink   Sink
+)
+
+// SetSink installs a sink that also receives log messages, such as one
+// forwarding them to an MCP client. A nil sink removes it.
+func SetSink(s Sink) {
+	sinkMu.Lock()
+	defer sinkMu.Unlock()
+	sink = s
+}
+
+// forward passes a message to the sink, if one is installed
+func forward(level Level, format string, args ...interface{}) {
+	sinkMu.RLock()
+	s := sink
+	sinkMu.RUnlock()
+	if s != nil {
+		s(level, fmt.Sprintf(format, args...))
+	}
+}
+
 // Init initializes the default logger
 func Init(cfg Config) error {
 	var err error
@@ -178,6 +205,7 @@ func Debug(format string, args ...interface{}) {
 	if defaultLogger != nil {
 		defaultLogger.Debug(format, args...)
 	}
+	forward(LevelDebug, format, args...)
 }
 
 // Info logs an info message
@@ -185,6 +213,7 @@ func Info(format string, args ...interface{}) {
 	if defaultLogger != nil {
 		defaultLogger.Info(format, args...)
 	}
+	forward(LevelInfo, format, args...)
 }
 
 // Warn logs a warning message
@@ -192,6 +221,7 @@ func Warn(format string, args ...interface{}) {
 	if defaultLogger != nil {
 		defaultLogger.Warn(format, args...)
 	}
+	forward(LevelWarn, format, args...)
 }
 
 // Error logs an error message
@@ -199,6 +229,7 @@ func Error(format string, args ...interface{}) {
 	if defaultLogger != nil {
 		defaultLogger.Error(format, args...)
 	}
+	forward(LevelError, format, args...)
 }
 
 // Close closes the default logger
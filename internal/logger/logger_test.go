@@ -286,3 +286,29 @@ func TestDefaultLogger(t *testing.T) {
 		t.Error("expected message in log file")
 	}
 }
+
+func TestSinkReceivesMessages(t *testing.T) {
+	type entry struct {
+		level Level
+		msg   string
+	}
+	var got []entry
+	SetSink(func(level Level, msg string) {
+		got = append(got, entry{level, msg})
+	})
+	defer SetSink(nil)
+
+	// The sink sees messages even when the default logger is disabled
+	Info("hello %s", "world")
+	Warn("careful")
+
+	if len(got) != 2 || got[0] != (entry{LevelInfo, "hello world"}) || got[1] != (entry{LevelWarn, "careful"}) {
+		t.Errorf("unexpected sink messages: %v", got)
+	}
+
+	SetSink(nil)
+	Error("dropped")
+	if len(got) != 2 {
+		t.Errorf("expected no messages after removing the sink, got %v", got)
+	}
+}
@@ -32,7 +32,10 @@ func (s *Server) handleInitialize(ctx context.Context, params json.RawMessage) (
 				ListChanged: false,
 			},
 			Resources: &ResourcesCapability{
-				Subscribe:   false,
+				Subscribe:   true,
+				ListChanged: false,
+			},
+			Prompts: &PromptsCapability{
 				ListChanged: false,
 			},
 			Logging: &LoggingCapability{},
@@ -104,6 +107,77 @@ func (s *Server) handleToolsList(ctx context.Context, params json.RawMessage) (i
 				Properties: map[string]Property{},
 			},
 		},
+		{
+			Name:        "add_session",
+			Description: "Define a new SSH session",
+			InputSchema: InputSchema{
+				Type: "object",
+				Properties: map[string]Property{
+					"name": {
+						Type:        "string",
+						Description: "Name of the new session",
+					},
+					"host": {
+						Type:        "string",
+						Description: "Hostname or IP address",
+					},
+					"user": {
+						Type:        "string",
+						Description: "SSH user (default: $USER, or root)",
+					},
+					"port": {
+						Type:        "integer",
+						Description: "SSH port",
+						Default:     22,
+					},
+					"identity_file": {
+						Type:        "string",
+						Description: "Private key file to authenticate with",
+					},
+					"persist": {
+						Type:        "boolean",
+						Description: "Also save the session to the config file",
+						Default:     false,
+					},
+				},
+				Required: []string{"name", "host"},
+			},
+		},
+		{
+			Name:        "get_env",
+			Description: "Get the environment variables set on a session",
+			InputSchema: InputSchema{
+				Type: "object",
+				Properties: map[string]Property{
+					"session": {
+						Type:        "string",
+						Description: "Session to read (default: active session)",
+					},
+				},
+			},
+		},
+		{
+			Name:        "set_env",
+			Description: "Set an environment variable for subsequent commands on a session",
+			InputSchema: InputSchema{
+				Type: "object",
+				Properties: map[string]Property{
+					"key": {
+						Type:        "string",
+						Description: "Variable name",
+					},
+					"value": {
+						Type:        "string",
+						Description: "Variable value",
+					},
+					"session": {
+						Type:        "string",
+						Description: "Session to set it on (default: active session)",
+					},
+				},
+				Required: []string{"key", "value"},
+			},
+		},
 
 		// Command execution tool
 		{
@@ -130,10 +204,197 @@ func (s *Server) handleToolsList(ctx context.Context, params json.RawMessage) (i
 						Description: "Optional: run command in background (default: false)",
 						Default:     false,
 					},
+					"raw": {
+						Type:        "boolean",
+						Description: "Optional: run the command verbatim, without cwd/env wrapping; refused in restricted mode (default: false)",
+						Default:     false,
+					},
 				},
 				Required: []string{"command"},
 			},
 		},
+		{
+			Name:        "check_command",
+			Description: "Check whether a command would be blocked by restricted mode without running it",
+			InputSchema: InputSchema{
+				Type: "object",
+				Properties: map[string]Property{
+					"command": {
+						Type:        "string",
+						Description: "Command to check",
+					},
+				},
+				Required: []string{"command"},
+			},
+		},
+		{
+			Name:        "realpath",
+			Description: "Resolve a path to its canonical absolute form, following symlinks",
+			InputSchema: InputSchema{
+				Type: "object",
+				Properties: map[string]Property{
+					"path": {
+						Type:        "string",
+						Description: "Path to resolve, relative to the session's working directory",
+					},
+					"session": {
+						Type:        "string",
+						Description: "Session to resolve the path on (default: active session)",
+					},
+				},
+				Required: []string{"path"},
+			},
+		},
+		{
+			Name:        "list_dir",
+			Description: "List a directory as structured entries (name, is_dir, size, mtime)",
+			InputSchema: InputSchema{
+				Type: "object",
+				Properties: map[string]Property{
+					"path": {
+						Type:        "string",
+						Description: "Directory to list, relative to the session's working directory (default: the working directory)",
+					},
+					"session": {
+						Type:        "string",
+						Description: "Session to list the directory on (default: active session)",
+					},
+				},
+			},
+		},
+		{
+			Name:        "read_file",
+			Description: "Read a text file from a session",
+			InputSchema: InputSchema{
+				Type: "object",
+				Properties: map[string]Property{
+					"path": {
+						Type:        "string",
+						Description: "File to read, relative to the session's working directory",
+					},
+					"session": {
+						Type:        "string",
+						Description: "Session to read the file from (default: active session)",
+					},
+				},
+				Required: []string{"path"},
+			},
+		},
+		{
+			Name:        "tail",
+			Description: "Show the last lines of a file without reading all of it (e.g. a log)",
+			InputSchema: InputSchema{
+				Type: "object",
+				Properties: map[string]Property{
+					"path": {
+						Type:        "string",
+						Description: "File to read, relative to the session's working directory",
+					},
+					"lines": {
+						Type:        "integer",
+						Description: "Number of lines to return",
+						Default:     10,
+					},
+					"session": {
+						Type:        "string",
+						Description: "Session to read the file from (default: active session)",
+					},
+				},
+				Required: []string{"path"},
+			},
+		},
+		{
+			Name:        "write_file",
+			Description: "Write content to a file on a session, creating or replacing it",
+			InputSchema: InputSchema{
+				Type: "object",
+				Properties: map[string]Property{
+					"path": {
+						Type:        "string",
+						Description: "File to write, relative to the session's working directory",
+					},
+					"content": {
+						Type:        "string",
+						Description: "Content to write",
+					},
+					"session": {
+						Type:        "string",
+						Description: "Session to write the file on (default: active session)",
+					},
+				},
+				Required: []string{"path", "content"},
+			},
+		},
+		{
+			Name:        "copy_file",
+			Description: "Copy a file between sessions over SFTP (at least one endpoint must be an SSH session)",
+			InputSchema: InputSchema{
+				Type: "object",
+				Properties: map[string]Property{
+					"source": {
+						Type:        "string",
+						Description: "Source file as session:path (a bare path uses the active session)",
+					},
+					"destination": {
+						Type:        "string",
+						Description: "Destination file as session:path (a bare path uses the active session)",
+					},
+				},
+				Required: []string{"source", "destination"},
+			},
+		},
+		{
+			Name:        "changed_files",
+			Description: "List files under a directory modified after a given time, most recent first",
+			InputSchema: InputSchema{
+				Type: "object",
+				Properties: map[string]Property{
+					"dir": {
+						Type:        "string",
+						Description: "Directory to search, relative to the session's working directory",
+					},
+					"since": {
+						Type:        "string",
+						Description: "Relative time such as 10m, 2h or 3d, or an absolute time (RFC 3339 or 2006-01-02)",
+					},
+					"session": {
+						Type:        "string",
+						Description: "Session to search on (default: active session)",
+					},
+				},
+				Required: []string{"dir", "since"},
+			},
+		},
+		{
+			Name:        "list_processes",
+			Description: "List processes on a session with their user, CPU and memory use, optionally filtered by command line",
+			InputSchema: InputSchema{
+				Type: "object",
+				Properties: map[string]Property{
+					"filter": {
+						Type:        "string",
+						Description: "Only list processes whose command line contains this text",
+					},
+					"session": {
+						Type:        "string",
+						Description: "Session to inspect (default: active session)",
+					},
+				},
+			},
+		},
+		{
+			Name:        "list_ports",
+			Description: "List listening TCP and UDP ports on a session, with the owning process where visible",
+			InputSchema: InputSchema{
+				Type: "object",
+				Properties: map[string]Property{
+					"session": {
+						Type:        "string",
+						Description: "Session to inspect (default: active session)",
+					},
+				},
+			},
+		},
 	}
 
 	return map[string]interface{}{
@@ -154,6 +415,10 @@ func (s *Server) handleToolCall(ctx context.Context, params json.RawMessage) (in
 
 	logger.Debug("Tool call: %s", callParams.Name)
 
+	if callParams.Meta != nil && callParams.Meta.ProgressToken != nil {
+		ctx = withProgressToken(ctx, callParams.Meta.ProgressToken)
+	}
+
 	// Route to appropriate tool handler
 	switch callParams.Name {
 	// Session management
@@ -165,10 +430,36 @@ func (s *Server) handleToolCall(ctx context.Context, params json.RawMessage) (in
 		return s.toolClose(ctx, callParams.Arguments)
 	case "status":
 		return s.toolStatus(ctx, callParams.Arguments)
+	case "add_session":
+		return s.toolAddSession(ctx, callParams.Arguments)
+	case "get_env":
+		return s.toolGetEnv(ctx, callParams.Arguments)
+	case "set_env":
+		return s.toolSetEnv(ctx, callParams.Arguments)
 
 	// Command execution
 	case "execute":
 		return s.toolExecute(ctx, callParams.Arguments)
+	case "check_command":
+		return s.toolCheckCommand(ctx, callParams.Arguments)
+	case "realpath":
+		return s.toolRealpath(ctx, callParams.Arguments)
+	case "list_dir":
+		return s.toolListDir(ctx, callParams.Arguments)
+	case "read_file":
+		return s.toolReadFile(ctx, callParams.Arguments)
+	case "tail":
+		return s.toolTail(ctx, callParams.Arguments)
+	case "write_file":
+		return s.toolWriteFile(ctx, callParams.Arguments)
+	case "copy_file":
+		return s.toolCopyFile(ctx, callParams.Arguments)
+	case "changed_files":
+		return s.toolChangedFiles(ctx, callParams.Arguments)
+	case "list_processes":
+		return s.toolListProcesses(ctx, callParams.Arguments)
+	case "list_ports":
+		return s.toolListPorts(ctx, callParams.Arguments)
 
 	default:
 		return nil, &JSONRPCError{
@@ -181,7 +472,14 @@ func (s *Server) handleToolCall(ctx context.Context, params json.RawMessage) (in
 
 // handleResourcesList handles the resources/list request
 func (s *Server) handleResourcesList(ctx context.Context, params json.RawMessage) (interface{}, error) {
-	resources := []Resource{
+	return map[string]interface{}{
+		"resources": listResources(),
+	}, nil
+}
+
+// listResources returns the resources the server provides
+func listResources() []Resource {
+	return []Resource{
 		{
 			URI:         "session://active",
 			Name:        "Active Session",
@@ -207,10 +505,6 @@ func (s *Server) handleResourcesList(ctx context.Context, params json.RawMessage
 			MimeType:    "application/json",
 		},
 	}
-
-	return map[string]interface{}{
-		"resources": resources,
-	}, nil
 }
 
 // handleResourceRead handles the resources/read request
@@ -272,8 +566,18 @@ func (s *Server) handlePing(ctx context.Context, params json.RawMessage) (interf
 
 // handleCancelled handles cancellation notifications
 func (s *Server) handleCancelled(ctx context.Context, params json.RawMessage) (interface{}, error) {
-	logger.Debug("Received cancellation notification")
-	// TODO: Implement request cancellation
+	var cancelledParams CancelledParams
+	if err := json.Unmarshal(params, &cancelledParams); err != nil || cancelledParams.RequestID == nil {
+		logger.Error("Failed to parse cancellation params: %v", err)
+		return nil, nil
+	}
+
+	if s.cancelRequest(cancelledParams.RequestID) {
+		logger.Debug("Cancelled request %v: %s", cancelledParams.RequestID, cancelledParams.Reason)
+	} else {
+		// The request may already have finished
+		logger.Debug("Cancellation for unknown request %v ignored", cancelledParams.RequestID)
+	}
 	return nil, nil
 }
 
@@ -285,7 +589,7 @@ func (s *Server) handleProgress(ctx context.Context, params json.RawMessage) (in
 		return nil, nil
 	}
 
-	logger.Debug("Progress update: token=%s progress=%f/%f",
+	logger.Debug("Progress update: token=%v progress=%f/%f",
 		progressParams.ProgressToken,
 		progressParams.Progress,
 		progressParams.Total)
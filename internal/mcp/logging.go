@@ -0,0 +1,102 @@
+package mcp
+
+import (
+	"context"
+	"encoding/json"
+	"strings"
+
+	"github.com/scottgl9/thop/internal/logger"
+)
+
+// defaultClientLogLevel is the least severe level forwarded to the client
+// until it sends logging/setLevel
+const defaultClientLogLevel = logger.LevelInfo
+
+// SetLevelParams represents parameters for the logging/setLevel request
+type SetLevelParams struct {
+	Level string `json:"level"`
+}
+
+// mcpLogLevel returns the MCP (syslog-style) name of a log level
+func mcpLogLevel(level logger.Level) string {
+	switch level {
+	case logger.LevelDebug:
+		return "debug"
+	case logger.LevelInfo:
+		return "info"
+	case logger.LevelWarn:
+		return "warning"
+	default:
+		return "error"
+	}
+}
+
+// parseMCPLogLevel maps an MCP log level name onto thop's levels
+func parseMCPLogLevel(name string) (logger.Level, bool) {
+	switch strings.ToLower(name) {
+	case "debug":
+		return logger.LevelDebug, true
+	case "info", "notice":
+		return logger.LevelInfo, true
+	case "warning":
+		return logger.LevelWarn, true
+	case "error", "critical", "alert", "emergency":
+		return logger.LevelError, true
+	default:
+		return 0, false
+	}
+}
+
+// forwardLogs sends thop's own log messages to the client as
+// notifications/message until the returned function is called
+func (s *Server) forwardLogs() (stop func()) {
+	logger.SetSink(s.sendLog)
+	return func() {
+		logger.SetSink(nil)
+	}
+}
+
+// sendLog sends one log message to the client if it is at or above the
+// level the client asked for
+func (s *Server) sendLog(level logger.Level, msg string) {
+	s.logMu.Lock()
+	minLevel := s.logLevel
+	s.logMu.Unlock()
+	if level < minLevel {
+		return
+	}
+
+	// Errors are dropped: logging them would only produce another notification
+	_ = s.sendNotification("notifications/message", LogParams{
+		Level:   mcpLogLevel(level),
+		Logger:  "thop",
+		Message: msg,
+	})
+}
+
+// handleSetLevel handles the logging/setLevel request
+func (s *Server) handleSetLevel(ctx context.Context, params json.RawMessage) (interface{}, error) {
+	var setLevelParams SetLevelParams
+	if err := json.Unmarshal(params, &setLevelParams); err != nil {
+		return nil, &JSONRPCError{
+			Code:    -32602,
+			Message: "Invalid params",
+			Data:    err.Error(),
+		}
+	}
+
+	level, ok := parseMCPLogLevel(setLevelParams.Level)
+	if !ok {
+		return nil, &JSONRPCError{
+			Code:    -32602,
+			Message: "Invalid params",
+			Data:    "Unknown log level: " + setLevelParams.Level,
+		}
+	}
+
+	s.logMu.Lock()
+	s.logLevel = level
+	s.logMu.Unlock()
+
+	return map[string]interface{}{}, nil
+}
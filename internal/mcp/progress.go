@@ -0,0 +1,70 @@
+package mcp
+
+import (
+	"context"
+	"sync"
+	"time"
+
+	"github.com/scottgl9/thop/internal/logger"
+)
+
+// defaultProgressInterval is how often progress is reported while a tool runs
+const defaultProgressInterval = time.Second
+
+// progressTokenKey is the context key holding a request's progress token
+type progressTokenKey struct{}
+
+// withProgressToken returns a context carrying the progress token a client
+// supplied in a request's _meta
+func withProgressToken(ctx context.Context, token interface{}) context.Context {
+	return context.WithValue(ctx, progressTokenKey{}, token)
+}
+
+// progressToken returns the progress token carried by ctx, or nil if the
+// client did not ask for progress
+func progressToken(ctx context.Context) interface{} {
+	return ctx.Value(progressTokenKey{})
+}
+
+// startProgress sends a notifications/progress message for token every
+// progressInterval until the returned stop function is called. Progress is
+// the elapsed time in seconds; total, if non-zero, is the most it can reach
+// (the command timeout). stop waits for the sender to finish, so no
+// notification is written after it returns.
+func (s *Server) startProgress(token interface{}, total float64) (stop func()) {
+	done := make(chan struct{})
+	var wg sync.WaitGroup
+	wg.Add(1)
+
+	go func() {
+		defer wg.Done()
+		start := time.Now()
+		ticker := time.NewTicker(s.progressInterval)
+		defer ticker.Stop()
+
+		for {
+			select {
+			case <-done:
+				return
+			case <-ticker.C:
+				params := ProgressParams{
+					ProgressToken: token,
+					Progress:      time.Since(start).Seconds(),
+					Total:         total,
+				}
+				if err := s.sendNotification("notifications/progress", params); err != nil {
+					logger.Debug("Failed to send progress notification: %v", err)
+					return
+				}
+			}
+		}
+	}()
+
+	var once sync.Once
+	return func() {
+		once.Do(func() {
+			close(done)
+			wg.Wait()
+		})
+	}
+}
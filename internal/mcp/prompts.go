@@ -0,0 +1,146 @@
+package mcp
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"strings"
+)
+
+// promptTemplate is a built-in prompt: the text of the user message it
+// expands to, with {{argument}} placeholders, and values for optional
+// arguments the client leaves out
+type promptTemplate struct {
+	Prompt
+	Template string
+	Defaults map[string]string
+}
+
+// builtinPrompts are the reusable workflows offered through prompts/list
+var builtinPrompts = []promptTemplate{
+	{
+		Prompt: Prompt{
+			Name:        "deploy",
+			Description: "Run a deploy command on a session and check the result",
+			Arguments: []PromptArgument{
+				{Name: "session", Description: "Session to deploy on", Required: true},
+				{Name: "command", Description: "Deploy command to run", Required: true},
+				{Name: "directory", Description: "Directory to run the command in (default: the session's current directory)"},
+			},
+		},
+		Template: "Deploy on the thop session \"{{session}}\":\n" +
+			"1. Call the connect tool with session \"{{session}}\" (skip this for local sessions).\n" +
+			"2. Call the execute tool with session \"{{session}}\" and command `cd {{directory}} && {{command}}`.\n" +
+			"3. If the exit code is not 0, report the error output and stop. Otherwise summarize what was deployed.",
+		Defaults: map[string]string{"directory": "."},
+	},
+	{
+		Prompt: Prompt{
+			Name:        "tail_logs",
+			Description: "Show the end of a log file on a session and summarize problems",
+			Arguments: []PromptArgument{
+				{Name: "session", Description: "Session the log file is on", Required: true},
+				{Name: "path", Description: "Path of the log file", Required: true},
+				{Name: "lines", Description: "Number of lines to read (default: 50)"},
+			},
+		},
+		Template: "Call the tail tool with session \"{{session}}\", path \"{{path}}\" and lines {{lines}}. " +
+			"Summarize any errors or warnings in the output, quoting the relevant lines.",
+		Defaults: map[string]string{"lines": "50"},
+	},
+	{
+		Prompt: Prompt{
+			Name:        "check_health",
+			Description: "Check uptime, disk and memory on a session",
+			Arguments: []PromptArgument{
+				{Name: "session", Description: "Session to check", Required: true},
+			},
+		},
+		Template: "Check the health of the thop session \"{{session}}\":\n" +
+			"1. Call the status tool and connect to \"{{session}}\" if it is not connected.\n" +
+			"2. Call the execute tool with session \"{{session}}\" for each of `uptime`, `df -h` and `free -m`.\n" +
+			"3. Report the load, any filesystem over 90% full, and available memory.",
+	},
+}
+
+// findPrompt returns the built-in prompt with the given name
+func findPrompt(name string) (promptTemplate, bool) {
+	for _, p := range builtinPrompts {
+		if p.Name == name {
+			return p, true
+		}
+	}
+	return promptTemplate{}, false
+}
+
+// expand substitutes args (falling back to the defaults) into the template,
+// failing if a required argument is missing
+func (p promptTemplate) expand(args map[string]interface{}) (string, error) {
+	pairs := make([]string, 0, 2*len(p.Arguments))
+	for _, arg := range p.Arguments {
+		value, ok := p.Defaults[arg.Name]
+		if v, given := args[arg.Name]; given && v != nil && fmt.Sprint(v) != "" {
+			value, ok = fmt.Sprint(v), true
+		}
+		if !ok && arg.Required {
+			return "", fmt.Errorf("missing required argument: %s", arg.Name)
+		}
+		pairs = append(pairs, "{{"+arg.Name+"}}", value)
+	}
+	return strings.NewReplacer(pairs...).Replace(p.Template), nil
+}
+
+// handlePromptsList handles the prompts/list request
+func (s *Server) handlePromptsList(ctx context.Context, params json.RawMessage) (interface{}, error) {
+	prompts := make([]Prompt, 0, len(builtinPrompts))
+	for _, p := range builtinPrompts {
+		prompts = append(prompts, p.Prompt)
+	}
+
+	return map[string]interface{}{
+		"prompts": prompts,
+	}, nil
+}
+
+// handlePromptsGet handles the prompts/get request
+func (s *Server) handlePromptsGet(ctx context.Context, params json.RawMessage) (interface{}, error) {
+	var getParams PromptGetParams
+	if err := json.Unmarshal(params, &getParams); err != nil {
+		return nil, &JSONRPCError{
+			Code:    -32602,
+			Message: "Invalid params",
+			Data:    err.Error(),
+		}
+	}
+
+	prompt, ok := findPrompt(getParams.Name)
+	if !ok {
+		return nil, &JSONRPCError{
+			Code:    -32602,
+			Message: "Unknown prompt",
+			Data:    getParams.Name,
+		}
+	}
+
+	text, err := prompt.expand(getParams.Arguments)
+	if err != nil {
+		return nil, &JSONRPCError{
+			Code:    -32602,
+			Message: "Invalid params",
+			Data:    err.Error(),
+		}
+	}
+
+	return PromptGetResult{
+		Description: prompt.Description,
+		Messages: []PromptMessage{
+			{
+				Role: "user",
+				Content: Content{
+					Type: "text",
+					Text: text,
+				},
+			},
+		},
+	}, nil
+}
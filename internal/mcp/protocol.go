@@ -127,6 +127,12 @@ type Property struct {
 type ToolCallParams struct {
 	Name      string                 `json:"name"`
 	Arguments map[string]interface{} `json:"arguments,omitempty"`
+	Meta      *RequestMeta           `json:"_meta,omitempty"`
+}
+
+// RequestMeta carries the optional _meta field of a request
+type RequestMeta struct {
+	ProgressToken interface{} `json:"progressToken,omitempty"` // Ask for notifications/progress while the request runs
 }
 
 // ToolCallResult represents the result of a tool call
@@ -156,6 +162,17 @@ type ResourceReadParams struct {
 	URI string `json:"uri"`
 }
 
+// ResourceSubscribeParams represents parameters for resources/subscribe and
+// resources/unsubscribe
+type ResourceSubscribeParams struct {
+	URI string `json:"uri"`
+}
+
+// ResourceUpdatedParams represents parameters for resource update notifications
+type ResourceUpdatedParams struct {
+	URI string `json:"uri"`
+}
+
 // ResourceReadResult represents the result of reading a resource
 type ResourceReadResult struct {
 	Contents []ResourceContent `json:"contents"`
@@ -201,11 +218,17 @@ type PromptMessage struct {
 	Content Content `json:"content"`
 }
 
+// CancelledParams represents parameters for cancellation notifications
+type CancelledParams struct {
+	RequestID interface{} `json:"requestId"`
+	Reason    string      `json:"reason,omitempty"`
+}
+
 // ProgressParams represents parameters for progress notifications
 type ProgressParams struct {
-	ProgressToken string  `json:"progressToken"`
-	Progress      float64 `json:"progress"`
-	Total         float64 `json:"total,omitempty"`
+	ProgressToken interface{} `json:"progressToken"` // string or number, as chosen by the requester
+	Progress      float64     `json:"progress"`
+	Total         float64     `json:"total,omitempty"`
 }
 
 // LogParams represents parameters for log notifications
@@ -2,12 +2,14 @@ package mcp
 
 import (
 	"bufio"
+	"bytes"
 	"context"
 	"encoding/json"
 	"fmt"
 	"io"
 	"os"
 	"sync"
+	"time"
 
 	"github.com/scottgl9/thop/internal/config"
 	"github.com/scottgl9/thop/internal/logger"
@@ -20,9 +22,19 @@ const MCPVersion = "2024-11-05"
 
 // Server implements the MCP (Model Context Protocol) server for thop
 type Server struct {
-	config   *config.Config
-	sessions *session.Manager
-	state    *state.Manager
+	config     *config.Config
+	configPath string // File the config was loaded from, for add_session
+	sessions   *session.Manager
+	state      *state.Manager
+
+	// This client's active session; the sessions themselves are shared
+	view *session.View
+
+	// Resource URIs the client subscribed to, and the session event
+	// listener that reports their changes
+	subsMu        sync.Mutex
+	subscriptions map[string]bool
+	unwatch       func()
 
 	// I/O channels for JSON-RPC communication
 	input  io.Reader
@@ -32,33 +44,55 @@ type Server struct {
 	mu       sync.Mutex
 	handlers map[string]HandlerFunc
 
+	// In-flight requests, cancelable by notifications/cancelled
+	inflightMu sync.Mutex
+	inflight   map[string]context.CancelFunc
+
 	// Server state
 	running bool
 	ctx     context.Context
 	cancel  context.CancelFunc
+
+	// How often progress notifications are sent for long-running tool calls
+	progressInterval time.Duration
+
+	// Least severe log level forwarded to the client (set by logging/setLevel)
+	logMu    sync.Mutex
+	logLevel logger.Level
 }
 
 // HandlerFunc is the signature for JSON-RPC method handlers
 type HandlerFunc func(context.Context, json.RawMessage) (interface{}, error)
 
-// NewServer creates a new MCP server instance
-func NewServer(cfg *config.Config, sessions *session.Manager, state *state.Manager) *Server {
+// NewServer creates a new MCP server instance. configPath is the file cfg
+// was loaded from (empty for the default), where add_session saves sessions.
+func NewServer(cfg *config.Config, configPath string, sessions *session.Manager, state *state.Manager) *Server {
 	ctx, cancel := context.WithCancel(context.Background())
 
 	s := &Server{
-		config:   cfg,
-		sessions: sessions,
-		state:    state,
-		input:    os.Stdin,
-		output:   os.Stdout,
-		handlers: make(map[string]HandlerFunc),
-		ctx:      ctx,
-		cancel:   cancel,
+		config:     cfg,
+		configPath: configPath,
+		sessions:   sessions,
+		state:      state,
+		view:       sessions.NewView(),
+		input:      os.Stdin,
+		output:     os.Stdout,
+		handlers:   make(map[string]HandlerFunc),
+		inflight:   make(map[string]context.CancelFunc),
+		ctx:        ctx,
+		cancel:     cancel,
+
+		subscriptions:    make(map[string]bool),
+		progressInterval: defaultProgressInterval,
+		logLevel:         defaultClientLogLevel,
 	}
 
 	// Register handlers
 	s.registerHandlers()
 
+	// Report session changes to subscribed clients
+	s.unwatch = sessions.OnSessionEvent(s.handleSessionEvent)
+
 	return s
 }
 
@@ -77,10 +111,16 @@ func (s *Server) registerHandlers() {
 	s.handlers["tools/call"] = s.handleToolCall
 	s.handlers["resources/list"] = s.handleResourcesList
 	s.handlers["resources/read"] = s.handleResourceRead
+	s.handlers["resources/subscribe"] = s.handleResourcesSubscribe
+	s.handlers["resources/unsubscribe"] = s.handleResourcesUnsubscribe
+	s.handlers["prompts/list"] = s.handlePromptsList
+	s.handlers["prompts/get"] = s.handlePromptsGet
 	s.handlers["ping"] = s.handlePing
+	s.handlers["logging/setLevel"] = s.handleSetLevel
 
 	// Notification handlers
-	s.handlers["cancelled"] = s.handleCancelled // nolint:misspell // MCP protocol standard
+	s.handlers["cancelled"] = s.handleCancelled               // nolint:misspell // MCP protocol standard
+	s.handlers["notifications/cancelled"] = s.handleCancelled // nolint:misspell // MCP protocol standard
 	s.handlers["progress"] = s.handleProgress
 }
 
@@ -91,6 +131,30 @@ func (s *Server) Run() error {
 	defer func() {
 		s.running = false
 		s.cancel()
+		s.unwatch()
+	}()
+	// Forward thop's own log messages to the client
+	defer s.forwardLogs()()
+
+	// Messages are handled one at a time, in the order received, by a single
+	// worker, since sessions aren't safe for concurrent use. This loop keeps
+	// reading meanwhile so a cancellation can reach the call being run.
+	queue := newMessageQueue()
+	worker := make(chan struct{})
+	go func() {
+		defer close(worker)
+		for {
+			line, ok := queue.pop()
+			if !ok {
+				return
+			}
+			s.processMessage(line)
+		}
+	}()
+	// Let queued messages finish and send their responses
+	defer func() {
+		queue.close()
+		<-worker
 	}()
 
 	scanner := bufio.NewScanner(s.input)
@@ -99,12 +163,13 @@ func (s *Server) Run() error {
 		case <-s.ctx.Done():
 			return s.ctx.Err()
 		default:
-			line := scanner.Bytes()
-			if err := s.handleMessage(line); err != nil {
-				logger.Error("Error handling message: %v", err)
-				// Send error response
-				_ = s.sendError(nil, -32603, "Internal error", err.Error())
+			// The scanner reuses its buffer, and the message may outlive this iteration
+			line := append([]byte(nil), scanner.Bytes()...)
+			if isCancellation(line) {
+				s.processMessage(line)
+				continue
 			}
+			queue.push(line)
 		}
 	}
 
@@ -115,14 +180,97 @@ func (s *Server) Run() error {
 	return nil
 }
 
+// processMessage handles one message, answering with an internal error if it fails
+func (s *Server) processMessage(line []byte) {
+	if err := s.handleMessage(line); err != nil {
+		logger.Error("Error handling message: %v", err)
+		// Send error response
+		_ = s.sendError(nil, -32603, "Internal error", err.Error())
+	}
+}
+
+// isCancellation reports whether a raw message is a cancellation
+// notification, which is handled as soon as it's read rather than queued
+// behind the request it cancels
+func isCancellation(data []byte) bool {
+	if isBatch(data) {
+		return false
+	}
+	var msg struct {
+		Method string      `json:"method"`
+		ID     interface{} `json:"id"`
+	}
+	if json.Unmarshal(data, &msg) != nil || msg.ID != nil {
+		return false
+	}
+	return msg.Method == "notifications/cancelled" || msg.Method == "cancelled" // nolint:misspell // MCP protocol standard
+}
+
+// messageQueue is an unbounded FIFO of raw messages from the reader loop to
+// the worker, so reading never waits on a slow call
+type messageQueue struct {
+	mu     sync.Mutex
+	cond   *sync.Cond
+	items  [][]byte
+	closed bool
+}
+
+func newMessageQueue() *messageQueue {
+	q := &messageQueue{}
+	q.cond = sync.NewCond(&q.mu)
+	return q
+}
+
+// push adds a message to the end of the queue
+func (q *messageQueue) push(item []byte) {
+	q.mu.Lock()
+	q.items = append(q.items, item)
+	q.mu.Unlock()
+	q.cond.Signal()
+}
+
+// close marks the end of input; pop drains what's left first
+func (q *messageQueue) close() {
+	q.mu.Lock()
+	q.closed = true
+	q.mu.Unlock()
+	q.cond.Broadcast()
+}
+
+// pop waits for the next message, returning false once the queue is closed
+// and empty
+func (q *messageQueue) pop() ([]byte, bool) {
+	q.mu.Lock()
+	defer q.mu.Unlock()
+	for len(q.items) == 0 && !q.closed {
+		q.cond.Wait()
+	}
+	if len(q.items) == 0 {
+		return nil, false
+	}
+	item := q.items[0]
+	q.items = q.items[1:]
+	return item, true
+}
+
+// isBatch reports whether a raw message is a JSON-RPC batch (a JSON array)
+func isBatch(data []byte) bool {
+	trimmed := bytes.TrimLeft(data, " \t\r\n")
+	return len(trimmed) > 0 && trimmed[0] == '['
+}
+
 // Stop gracefully stops the MCP server
 func (s *Server) Stop() {
 	logger.Info("Stopping MCP server")
 	s.cancel()
 }
 
-// handleMessage processes a single JSON-RPC message
+// handleMessage processes a single JSON-RPC message or batch
 func (s *Server) handleMessage(data []byte) error {
+	if isBatch(data) {
+		return s.handleBatch(data)
+	}
+
 	var msg JSONRPCMessage
 	if err := json.Unmarshal(data, &msg); err != nil {
 		return fmt.Errorf("failed to parse JSON-RPC message: %w", err)
@@ -139,32 +287,125 @@ func (s *Server) handleMessage(data []byte) error {
 	return nil
 }
 
-// handleRequest processes a JSON-RPC request
+// handleBatch processes a JSON-RPC batch. Each element is handled on its
+// own, so an invalid or failing element only produces an error entry, and the
+// responses are sent back together as one array.
+func (s *Server) handleBatch(data []byte) error {
+	var elements []json.RawMessage
+	if err := json.Unmarshal(data, &elements); err != nil {
+		return fmt.Errorf("failed to parse JSON-RPC batch: %w", err)
+	}
+	if len(elements) == 0 {
+		return s.sendError(nil, -32600, "Invalid Request", "Empty batch")
+	}
+
+	responses := make([]*JSONRPCResponse, 0, len(elements))
+	for _, element := range elements {
+		var msg JSONRPCMessage
+		if err := json.Unmarshal(element, &msg); err != nil || msg.Method == "" {
+			rpcErr := &JSONRPCError{Code: -32600, Message: "Invalid Request"}
+			if err != nil {
+				rpcErr.Data = err.Error()
+			}
+			responses = append(responses, &JSONRPCResponse{JSONRPC: "2.0", Error: rpcErr})
+			continue
+		}
+		if response := s.dispatch(&msg); response != nil {
+			responses = append(responses, response)
+		}
+	}
+
+	// A batch of notifications gets no response at all
+	if len(responses) == 0 {
+		return nil
+	}
+	return s.writeMessage(responses)
+}
+
+// handleRequest processes a JSON-RPC request and sends its response
 func (s *Server) handleRequest(msg *JSONRPCMessage) error {
+	if response := s.dispatch(msg); response != nil {
+		return s.writeMessage(response)
+	}
+	return nil
+}
+
+// dispatch runs the handler for a JSON-RPC request and returns the response
+// to send, or nil for a notification that succeeded
+func (s *Server) dispatch(msg *JSONRPCMessage) *JSONRPCResponse {
 	logger.Debug("Handling request: method=%s id=%v", msg.Method, msg.ID)
 
 	handler, ok := s.handlers[msg.Method]
 	if !ok {
-		return s.sendError(msg.ID, -32601, "Method not found", fmt.Sprintf("Unknown method: %s", msg.Method))
+		return &JSONRPCResponse{
+			JSONRPC: "2.0",
+			ID:      msg.ID,
+			Error: &JSONRPCError{
+				Code:    -32601,
+				Message: "Method not found",
+				Data:    fmt.Sprintf("Unknown method: %s", msg.Method),
+			},
+		}
+	}
+
+	// Requests get their own context so notifications/cancelled can abort them
+	ctx := s.ctx
+	if msg.ID != nil {
+		var cancel context.CancelFunc
+		ctx, cancel = context.WithCancel(s.ctx)
+		defer cancel()
+		s.trackRequest(msg.ID, cancel)
+		defer s.untrackRequest(msg.ID)
 	}
 
 	// Execute handler
-	result, err := handler(s.ctx, msg.Params)
+	result, err := handler(ctx, msg.Params)
 	if err != nil {
 		// Check if it's already a JSON-RPC error
-		if rpcErr, ok := err.(*JSONRPCError); ok {
-			return s.sendErrorResponse(msg.ID, rpcErr)
+		rpcErr, ok := err.(*JSONRPCError)
+		if !ok {
+			// Generic error
+			rpcErr = &JSONRPCError{Code: -32603, Message: "Internal error", Data: err.Error()}
 		}
-		// Generic error
-		return s.sendError(msg.ID, -32603, "Internal error", err.Error())
+		return &JSONRPCResponse{JSONRPC: "2.0", ID: msg.ID, Error: rpcErr}
 	}
 
-	// Send successful response (only if it's a request with an ID)
-	if msg.ID != nil {
-		return s.sendResponse(msg.ID, result)
+	// Respond only if it's a request with an ID
+	if msg.ID == nil {
+		return nil
 	}
+	return &JSONRPCResponse{JSONRPC: "2.0", ID: msg.ID, Result: result}
+}
 
-	return nil
+// requestKey returns the map key for a JSON-RPC request ID, which may be a
+// string or a number
+func requestKey(id interface{}) string {
+	return fmt.Sprint(id)
+}
+
+// trackRequest records the cancel function of an in-flight request
+func (s *Server) trackRequest(id interface{}, cancel context.CancelFunc) {
+	s.inflightMu.Lock()
+	defer s.inflightMu.Unlock()
+	s.inflight[requestKey(id)] = cancel
+}
+
+// untrackRequest forgets a request once it has finished
+func (s *Server) untrackRequest(id interface{}) {
+	s.inflightMu.Lock()
+	defer s.inflightMu.Unlock()
+	delete(s.inflight, requestKey(id))
+}
+
+// cancelRequest aborts an in-flight request, reporting whether it was found
+func (s *Server) cancelRequest(id interface{}) bool {
+	s.inflightMu.Lock()
+	defer s.inflightMu.Unlock()
+	cancel, ok := s.inflight[requestKey(id)]
+	if ok {
+		cancel()
+	}
+	return ok
 }
 
 // sendResponse sends a successful JSON-RPC response
@@ -174,23 +415,7 @@ func (s *Server) sendResponse(id interface{}, result interface{}) error {
 		ID:      id,
 		Result:  result,
 	}
-
-	data, err := json.Marshal(response)
-	if err != nil {
-		return fmt.Errorf("failed to marshal response: %w", err)
-	}
-
-	s.mu.Lock()
-	defer s.mu.Unlock()
-
-	if _, err := s.output.Write(data); err != nil {
-		return fmt.Errorf("failed to write response: %w", err)
-	}
-	if _, err := s.output.Write([]byte("\n")); err != nil {
-		return fmt.Errorf("failed to write newline: %w", err)
-	}
-
-	return nil
+	return s.writeMessage(response)
 }
 
 // sendError sends a JSON-RPC error response
@@ -212,23 +437,7 @@ func (s *Server) sendErrorResponse(id interface{}, rpcErr *JSONRPCError) error {
 		ID:      id,
 		Error:   rpcErr,
 	}
-
-	data, err := json.Marshal(response)
-	if err != nil {
-		return fmt.Errorf("failed to marshal error response: %w", err)
-	}
-
-	s.mu.Lock()
-	defer s.mu.Unlock()
-
-	if _, err := s.output.Write(data); err != nil {
-		return fmt.Errorf("failed to write error response: %w", err)
-	}
-	if _, err := s.output.Write([]byte("\n")); err != nil {
-		return fmt.Errorf("failed to write newline: %w", err)
-	}
-
-	return nil
+	return s.writeMessage(response)
 }
 
 // sendNotification sends a JSON-RPC notification (no ID)
@@ -247,16 +456,21 @@ func (s *Server) sendNotification(method string, params interface{}) error {
 		notification.Params = data
 	}
 
-	data, err := json.Marshal(notification)
+	return s.writeMessage(notification)
+}
+
+// writeMessage writes one JSON-RPC message (or batch) as a line of output
+func (s *Server) writeMessage(message interface{}) error {
+	data, err := json.Marshal(message)
 	if err != nil {
-		return fmt.Errorf("failed to marshal notification: %w", err)
+		return fmt.Errorf("failed to marshal message: %w", err)
 	}
 
 	s.mu.Lock()
 	defer s.mu.Unlock()
 
 	if _, err := s.output.Write(data); err != nil {
-		return fmt.Errorf("failed to write notification: %w", err)
+		return fmt.Errorf("failed to write message: %w", err)
 	}
 	if _, err := s.output.Write([]byte("\n")); err != nil {
 		return fmt.Errorf("failed to write newline: %w", err)
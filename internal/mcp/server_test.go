@@ -4,10 +4,17 @@ import (
 	"bytes"
 	"context"
 	"encoding/json"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"reflect"
 	"strings"
 	"testing"
+	"time"
 
 	"github.com/scottgl9/thop/internal/config"
+	"github.com/scottgl9/thop/internal/logger"
 	"github.com/scottgl9/thop/internal/session"
 	"github.com/scottgl9/thop/internal/state"
 )
@@ -33,7 +40,7 @@ func TestMCPServer_Initialize(t *testing.T) {
 	sessionMgr := session.NewManager(cfg, stateMgr)
 
 	// Create MCP server
-	server := NewServer(cfg, sessionMgr, stateMgr)
+	server := NewServer(cfg, "", sessionMgr, stateMgr)
 
 	// Create input/output buffers
 	input := &bytes.Buffer{}
@@ -124,7 +131,7 @@ func TestMCPServer_ToolsList(t *testing.T) {
 	sessionMgr := session.NewManager(cfg, stateMgr)
 
 	// Create MCP server
-	server := NewServer(cfg, sessionMgr, stateMgr)
+	server := NewServer(cfg, "", sessionMgr, stateMgr)
 
 	// Test tools/list
 	ctx := context.Background()
@@ -156,8 +163,10 @@ func TestMCPServer_ToolsList(t *testing.T) {
 	}
 
 	expectedTools := []string{
-		"connect", "switch", "close", "status",
-		"execute",
+		"connect", "switch", "close", "status", "add_session", "get_env", "set_env",
+		"execute", "check_command", "realpath", "list_dir",
+		"read_file", "write_file", "copy_file", "changed_files", "tail",
+		"list_ports", "list_processes",
 	}
 
 	for _, expected := range expectedTools {
@@ -166,9 +175,9 @@ func TestMCPServer_ToolsList(t *testing.T) {
 		}
 	}
 
-	// Ensure we only have these 5 tools
-	if len(tools) != 5 {
-		t.Errorf("Expected exactly 5 tools, got %d", len(tools))
+	// Ensure we only have these tools
+	if len(tools) != 18 {
+		t.Errorf("Expected exactly 18 tools, got %d", len(tools))
 	}
 }
 
@@ -198,7 +207,7 @@ func TestMCPServer_ToolCall_Status(t *testing.T) {
 	sessionMgr := session.NewManager(cfg, stateMgr)
 
 	// Create MCP server
-	server := NewServer(cfg, sessionMgr, stateMgr)
+	server := NewServer(cfg, "", sessionMgr, stateMgr)
 
 	// Test status tool
 	ctx := context.Background()
@@ -261,7 +270,7 @@ func TestMCPServer_ResourcesList(t *testing.T) {
 	sessionMgr := session.NewManager(cfg, stateMgr)
 
 	// Create MCP server
-	server := NewServer(cfg, sessionMgr, stateMgr)
+	server := NewServer(cfg, "", sessionMgr, stateMgr)
 
 	// Test resources/list
 	ctx := context.Background()
@@ -327,7 +336,7 @@ func TestMCPServer_Ping(t *testing.T) {
 	sessionMgr := session.NewManager(cfg, stateMgr)
 
 	// Create MCP server
-	server := NewServer(cfg, sessionMgr, stateMgr)
+	server := NewServer(cfg, "", sessionMgr, stateMgr)
 
 	// Test ping
 	ctx := context.Background()
@@ -392,7 +401,7 @@ func TestMCPServer_JSONRPCParsing(t *testing.T) {
 			sessionMgr := session.NewManager(cfg, stateMgr)
 
 			// Create MCP server
-			server := NewServer(cfg, sessionMgr, stateMgr)
+			server := NewServer(cfg, "", sessionMgr, stateMgr)
 
 			// Create output buffer
 			output := &bytes.Buffer{}
@@ -418,7 +427,7 @@ func createTestServer() *Server {
 			"local": {Type: "local", Shell: "/bin/bash"},
 		},
 	}
-	return NewServer(cfg, session.NewManager(cfg, state.NewManager("/tmp/test-mcp.json")), state.NewManager("/tmp/test-mcp.json"))
+	return NewServer(cfg, "", session.NewManager(cfg, state.NewManager("/tmp/test-mcp.json")), state.NewManager("/tmp/test-mcp.json"))
 }
 
 func TestMCPServer_ToolCall_Execute(t *testing.T) {
@@ -635,3 +644,860 @@ func TestMCPServer_HandleProgress_InvalidParams(t *testing.T) {
 		t.Error(err)
 	}
 }
+
+func TestMCPServer_ToolCall_CheckCommand(t *testing.T) {
+	srv := createTestServer()
+
+	tests := []struct {
+		name         string
+		params       string
+		wantAllowed  bool
+		wantCategory string
+		wantCommand  string
+	}{
+		{"blocked", `{"name":"check_command","arguments":{"command":"sudo rm -rf /"}}`, false, "privilege_escalation", "sudo"},
+		{"allowed", `{"name":"check_command","arguments":{"command":"ls -la"}}`, true, "", ""},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			res, err := srv.handleToolCall(context.Background(), json.RawMessage(tt.params))
+			if err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			tr, ok := res.(ToolCallResult)
+			if !ok || len(tr.Content) != 1 {
+				t.Fatalf("expected single content item, got %#v", res)
+			}
+
+			var check struct {
+				Allowed     bool    `json:"allowed"`
+				Category    *string `json:"category"`
+				Command     *string `json:"command"`
+				MatchedSpan []int   `json:"matched_span"`
+			}
+			if err := json.Unmarshal([]byte(tr.Content[0].Text), &check); err != nil {
+				t.Fatalf("content is not valid JSON: %v", err)
+			}
+
+			if check.Allowed != tt.wantAllowed {
+				t.Errorf("allowed: expected %v, got %v", tt.wantAllowed, check.Allowed)
+			}
+			if tt.wantAllowed {
+				if check.Category != nil || check.Command != nil {
+					t.Errorf("expected null category/command for allowed command, got %v/%v", check.Category, check.Command)
+				}
+				return
+			}
+			if check.Category == nil || *check.Category != tt.wantCategory {
+				t.Errorf("category: expected %q, got %v", tt.wantCategory, check.Category)
+			}
+			if check.Command == nil || *check.Command != tt.wantCommand {
+				t.Errorf("command: expected %q, got %v", tt.wantCommand, check.Command)
+			}
+			if len(check.MatchedSpan) != 2 || check.MatchedSpan[0] != 0 || check.MatchedSpan[1] != 4 {
+				t.Errorf("matched_span: expected [0 4], got %v", check.MatchedSpan)
+			}
+		})
+	}
+
+	// Missing command parameter
+	res, _ := srv.handleToolCall(context.Background(), json.RawMessage(`{"name":"check_command","arguments":{}}`))
+	if tr, ok := res.(ToolCallResult); !ok || !tr.IsError {
+		t.Error("expected error result for missing command")
+	}
+}
+
+func TestMCPServer_ToolCall_Realpath(t *testing.T) {
+	srv := createTestServer()
+
+	tmpDir, err := filepath.EvalSymlinks(t.TempDir())
+	if err != nil {
+		t.Fatal(err)
+	}
+	if err := os.Symlink(tmpDir, filepath.Join(tmpDir, "self")); err != nil {
+		t.Fatal(err)
+	}
+
+	params := fmt.Sprintf(`{"name":"realpath","arguments":{"path":%q}}`, filepath.Join(tmpDir, "self"))
+	res, err := srv.handleToolCall(context.Background(), json.RawMessage(params))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	tr, ok := res.(ToolCallResult)
+	if !ok || tr.IsError || len(tr.Content) != 1 {
+		t.Fatalf("expected single successful content item, got %#v", res)
+	}
+	if tr.Content[0].Text != tmpDir {
+		t.Errorf("expected %s, got %s", tmpDir, tr.Content[0].Text)
+	}
+
+	for _, params := range []string{
+		`{"name":"realpath","arguments":{}}`,
+		`{"name":"realpath","arguments":{"path":"/nonexistent/thop/path"}}`,
+		`{"name":"realpath","arguments":{"path":"/","session":"nonexistent"}}`,
+	} {
+		res, _ := srv.handleToolCall(context.Background(), json.RawMessage(params))
+		if tr, ok := res.(ToolCallResult); !ok || !tr.IsError {
+			t.Errorf("expected error result for %s", params)
+		}
+	}
+}
+
+func TestMCPServer_ToolCall_WriteThenReadFile(t *testing.T) {
+	srv := createTestServer()
+	path := filepath.Join(t.TempDir(), "notes.txt")
+	content := "line one\nline \"two\"\n"
+
+	params, _ := json.Marshal(map[string]interface{}{
+		"name":      "write_file",
+		"arguments": map[string]interface{}{"path": path, "content": content},
+	})
+	res, err := srv.handleToolCall(context.Background(), params)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	tr, ok := res.(ToolCallResult)
+	if !ok || tr.IsError || len(tr.Content) != 1 {
+		t.Fatalf("expected successful write, got %#v", res)
+	}
+	if want := fmt.Sprintf("Wrote %d bytes to %s", len(content), path); tr.Content[0].Text != want {
+		t.Errorf("expected %q, got %q", want, tr.Content[0].Text)
+	}
+
+	params = []byte(fmt.Sprintf(`{"name":"read_file","arguments":{"path":%q,"session":"local"}}`, path))
+	res, err = srv.handleToolCall(context.Background(), params)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	tr, ok = res.(ToolCallResult)
+	if !ok || tr.IsError || len(tr.Content) != 1 {
+		t.Fatalf("expected successful read, got %#v", res)
+	}
+	if tr.Content[0].Text != content {
+		t.Errorf("expected %q, got %q", content, tr.Content[0].Text)
+	}
+
+	for _, params := range []string{
+		`{"name":"read_file","arguments":{}}`,
+		`{"name":"write_file","arguments":{"path":"x"}}`,
+		`{"name":"read_file","arguments":{"path":"/nonexistent/thop/file"}}`,
+		`{"name":"read_file","arguments":{"path":"/etc/hostname","session":"nonexistent"}}`,
+	} {
+		res, _ := srv.handleToolCall(context.Background(), json.RawMessage(params))
+		if tr, ok := res.(ToolCallResult); !ok || !tr.IsError {
+			t.Errorf("expected error result for %s", params)
+		}
+	}
+}
+
+func TestMCPServer_ToolCall_WriteFileRestricted(t *testing.T) {
+	srv := createTestServer()
+	path := filepath.Join(t.TempDir(), "notes.txt")
+	if err := os.WriteFile(path, []byte("keep\n"), 0644); err != nil {
+		t.Fatal(err)
+	}
+	srv.sessions.SetRestrictedMode(true)
+
+	params, _ := json.Marshal(map[string]interface{}{
+		"name":      "write_file",
+		"arguments": map[string]interface{}{"path": path, "content": "overwritten\n"},
+	})
+	res, err := srv.handleToolCall(context.Background(), params)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	tr, ok := res.(ToolCallResult)
+	if !ok || !tr.IsError || len(tr.Content) != 1 {
+		t.Fatalf("expected error result, got %#v", res)
+	}
+	if !strings.Contains(tr.Content[0].Text, "not allowed in restricted mode") {
+		t.Errorf("expected a restriction error, got %q", tr.Content[0].Text)
+	}
+	if data, err := os.ReadFile(path); err != nil || string(data) != "keep\n" {
+		t.Errorf("file should not have been written, got %q, %v", data, err)
+	}
+}
+
+func TestMCPServer_ToolCall_CopyFile(t *testing.T) {
+	srv := createTestServer()
+
+	tests := []struct {
+		name     string
+		params   string
+		wantCode ErrorCode
+	}{
+		{"local to local", `{"name":"copy_file","arguments":{"source":"local:/tmp/a","destination":"/tmp/b"}}`, ErrorInvalidParameter},
+		{"unknown session", `{"name":"copy_file","arguments":{"source":"missing:/tmp/a","destination":"local:/tmp/b"}}`, ErrorSessionNotFound},
+		{"missing destination", `{"name":"copy_file","arguments":{"source":"local:/tmp/a"}}`, ErrorMissingParameter},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			res, err := srv.handleToolCall(context.Background(), json.RawMessage(tt.params))
+			if err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			tr, ok := res.(ToolCallResult)
+			if !ok || !tr.IsError || len(tr.Content) != 1 {
+				t.Fatalf("expected error result, got %#v", res)
+			}
+			if !strings.Contains(tr.Content[0].Text, "["+string(tt.wantCode)+"]") {
+				t.Errorf("expected %s, got %q", tt.wantCode, tr.Content[0].Text)
+			}
+		})
+	}
+}
+
+func TestMCPServer_ToolCall_CopyFileRestricted(t *testing.T) {
+	srv := createTestServer()
+	srv.sessions.SetRestrictedMode(true)
+
+	res, err := srv.handleToolCall(context.Background(),
+		json.RawMessage(`{"name":"copy_file","arguments":{"source":"missing:/tmp/a","destination":"local:/tmp/b"}}`))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	tr, ok := res.(ToolCallResult)
+	if !ok || !tr.IsError || len(tr.Content) != 1 {
+		t.Fatalf("expected error result, got %#v", res)
+	}
+	// Refused before the source session is even looked up
+	if !strings.Contains(tr.Content[0].Text, "not allowed in restricted mode") {
+		t.Errorf("expected a restriction error, got %q", tr.Content[0].Text)
+	}
+}
+
+func TestMCPServer_ToolCall_AddSession(t *testing.T) {
+	t.Setenv("USER", "deploy")
+	tmpDir := t.TempDir()
+	configPath := filepath.Join(tmpDir, "config.toml")
+	defaultPath := filepath.Join(tmpDir, "default.toml")
+	t.Setenv("THOP_CONFIG", defaultPath)
+
+	// As started with --config configPath
+	cfg := &config.Config{
+		Settings: config.Settings{DefaultSession: "local"},
+		Sessions: map[string]config.Session{
+			"local": {Type: "local", Shell: "/bin/bash"},
+		},
+	}
+	stateMgr := state.NewManager(filepath.Join(tmpDir, "state.json"))
+	srv := NewServer(cfg, configPath, session.NewManager(cfg, stateMgr), stateMgr)
+
+	res, err := srv.handleToolCall(context.Background(),
+		json.RawMessage(`{"name":"add_session","arguments":{"name":"web","host":"web.example.com","persist":true}}`))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	tr, ok := res.(ToolCallResult)
+	if !ok || tr.IsError || len(tr.Content) != 1 {
+		t.Fatalf("expected success result, got %#v", res)
+	}
+
+	var info map[string]interface{}
+	if err := json.Unmarshal([]byte(tr.Content[0].Text), &info); err != nil {
+		t.Fatalf("result is not JSON: %v", err)
+	}
+	if info["user"] != "deploy" || info["port"] != float64(22) || info["host"] != "web.example.com" {
+		t.Errorf("unexpected session details: %v", info)
+	}
+	if !srv.sessions.HasSession("web") {
+		t.Error("session was not added to the manager")
+	}
+
+	saved, err := config.Load(configPath)
+	if err != nil {
+		t.Fatalf("failed to load saved config: %v", err)
+	}
+	if sess, ok := saved.GetSession("web"); !ok || sess.Host != "web.example.com" || sess.Port != 22 {
+		t.Errorf("session not persisted correctly: %+v", sess)
+	}
+	if _, err := os.Stat(defaultPath); !os.IsNotExist(err) {
+		t.Errorf("expected the default config to be left alone, got %v", err)
+	}
+
+	for _, params := range []string{
+		`{"name":"add_session","arguments":{"name":"web","host":"other.example.com"}}`,
+		`{"name":"add_session","arguments":{"name":"local","host":"other.example.com"}}`,
+	} {
+		res, _ := srv.handleToolCall(context.Background(), json.RawMessage(params))
+		tr, ok := res.(ToolCallResult)
+		if !ok || !tr.IsError || !strings.Contains(tr.Content[0].Text, "["+string(ErrorSessionAlreadyExists)+"]") {
+			t.Errorf("expected %s for %s, got %#v", ErrorSessionAlreadyExists, params, res)
+		}
+	}
+}
+
+func TestMCPServer_ToolCall_ExecuteSendsProgress(t *testing.T) {
+	srv := createTestServer()
+	srv.progressInterval = 10 * time.Millisecond
+	var output bytes.Buffer
+	srv.SetIO(nil, &output)
+
+	params := `{"name":"execute","arguments":{"command":"sleep 0.2"},"_meta":{"progressToken":"exec-1"}}`
+	res, err := srv.handleToolCall(context.Background(), json.RawMessage(params))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if tr, ok := res.(ToolCallResult); !ok || tr.IsError {
+		t.Fatalf("expected success result, got %#v", res)
+	}
+
+	lines := strings.Split(strings.TrimSpace(output.String()), "\n")
+	if len(lines) == 0 || lines[0] == "" {
+		t.Fatal("expected progress notifications in output")
+	}
+	for _, line := range lines {
+		var msg struct {
+			Method string         `json:"method"`
+			Params ProgressParams `json:"params"`
+		}
+		if err := json.Unmarshal([]byte(line), &msg); err != nil {
+			t.Fatalf("invalid notification %q: %v", line, err)
+		}
+		if msg.Method != "notifications/progress" || msg.Params.ProgressToken != "exec-1" {
+			t.Errorf("unexpected notification: %s", line)
+		}
+	}
+
+	// Without a token, nothing but the result is produced
+	output.Reset()
+	if _, err := srv.handleToolCall(context.Background(), json.RawMessage(`{"name":"execute","arguments":{"command":"sleep 0.05"}}`)); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if output.Len() != 0 {
+		t.Errorf("expected no notifications without a progress token, got %q", output.String())
+	}
+}
+
+func TestMCPServer_CancelledStopsExecute(t *testing.T) {
+	srv := createTestServer()
+	var output bytes.Buffer
+	srv.SetIO(nil, &output)
+
+	done := make(chan struct{})
+	go func() {
+		defer close(done)
+		_ = srv.handleMessage([]byte(`{"jsonrpc":"2.0","id":7,"method":"tools/call","params":{"name":"execute","arguments":{"command":"echo started; sleep 30"}}}`))
+	}()
+
+	// Wait until the call is in flight and has printed something
+	deadline := time.Now().Add(5 * time.Second)
+	for {
+		srv.inflightMu.Lock()
+		_, running := srv.inflight["7"]
+		srv.inflightMu.Unlock()
+		if running {
+			break
+		}
+		if time.Now().After(deadline) {
+			t.Fatal("request never became in flight")
+		}
+		time.Sleep(10 * time.Millisecond)
+	}
+	time.Sleep(200 * time.Millisecond)
+
+	if err := srv.handleMessage([]byte(`{"jsonrpc":"2.0","method":"notifications/cancelled","params":{"requestId":7,"reason":"user stopped it"}}`)); err != nil {
+		t.Fatalf("cancellation failed: %v", err)
+	}
+
+	select {
+	case <-done:
+	case <-time.After(5 * time.Second):
+		t.Fatal("cancellation did not stop the command")
+	}
+
+	var resp struct {
+		Result ToolCallResult `json:"result"`
+	}
+	if err := json.Unmarshal(output.Bytes(), &resp); err != nil {
+		t.Fatalf("invalid response %q: %v", output.String(), err)
+	}
+	if !resp.Result.IsError {
+		t.Error("expected the cancelled call to be marked as an error")
+	}
+	var text strings.Builder
+	for _, c := range resp.Result.Content {
+		text.WriteString(c.Text + "\n")
+	}
+	if !strings.Contains(text.String(), "started") || !strings.Contains(text.String(), "Command cancelled") {
+		t.Errorf("expected partial output marked cancelled, got %q", text.String())
+	}
+
+	if len(srv.inflight) != 0 {
+		t.Errorf("expected no requests in flight, got %d", len(srv.inflight))
+	}
+}
+
+func TestMCPServer_ToolCall_SetAndGetEnv(t *testing.T) {
+	srv := createTestServer()
+	call := func(params string) ToolCallResult {
+		t.Helper()
+		res, err := srv.handleToolCall(context.Background(), json.RawMessage(params))
+		if err != nil {
+			t.Fatalf("unexpected error: %v", err)
+		}
+		tr, ok := res.(ToolCallResult)
+		if !ok || len(tr.Content) == 0 {
+			t.Fatalf("unexpected result %#v", res)
+		}
+		return tr
+	}
+
+	if tr := call(`{"name":"set_env","arguments":{"key":"THOP_TEST_VAR","value":"hello world"}}`); tr.IsError {
+		t.Fatalf("set_env failed: %s", tr.Content[0].Text)
+	}
+
+	tr := call(`{"name":"get_env","arguments":{"session":"local"}}`)
+	if tr.IsError {
+		t.Fatalf("get_env failed: %s", tr.Content[0].Text)
+	}
+	var env map[string]string
+	if err := json.Unmarshal([]byte(tr.Content[0].Text), &env); err != nil {
+		t.Fatalf("get_env did not return a JSON object: %v", err)
+	}
+	if env["THOP_TEST_VAR"] != "hello world" {
+		t.Errorf("expected THOP_TEST_VAR to read back, got %v", env)
+	}
+
+	// Commands on the session see it
+	tr = call(`{"name":"execute","arguments":{"command":"echo $THOP_TEST_VAR"}}`)
+	if strings.TrimSpace(tr.Content[0].Text) != "hello world" {
+		t.Errorf("expected command to see the variable, got %q", tr.Content[0].Text)
+	}
+
+	for params, code := range map[string]ErrorCode{
+		`{"name":"set_env","arguments":{"key":"1BAD","value":"x"}}`:                  ErrorInvalidParameter,
+		`{"name":"set_env","arguments":{"key":"X","value":"x","session":"missing"}}`: ErrorSessionNotFound,
+		`{"name":"get_env","arguments":{"session":"missing"}}`:                       ErrorSessionNotFound,
+	} {
+		tr := call(params)
+		if !tr.IsError || !strings.Contains(tr.Content[0].Text, "["+string(code)+"]") {
+			t.Errorf("expected %s for %s, got %q", code, params, tr.Content[0].Text)
+		}
+	}
+}
+
+func TestMCPServer_ForwardsLogMessages(t *testing.T) {
+	srv := createTestServer()
+	var output bytes.Buffer
+	srv.SetIO(nil, &output)
+
+	stop := srv.forwardLogs()
+	defer stop()
+
+	logger.Info("connected to session %q", "prod")
+
+	var msg struct {
+		Method string    `json:"method"`
+		Params LogParams `json:"params"`
+	}
+	if err := json.Unmarshal(output.Bytes(), &msg); err != nil {
+		t.Fatalf("expected a log notification, got %q: %v", output.String(), err)
+	}
+	if msg.Method != "notifications/message" {
+		t.Errorf("expected notifications/message, got %q", msg.Method)
+	}
+	if msg.Params.Level != "info" || msg.Params.Message != `connected to session "prod"` {
+		t.Errorf("unexpected log params: %+v", msg.Params)
+	}
+
+	// Debug is below the default level
+	output.Reset()
+	logger.Debug("noisy detail")
+	if output.Len() != 0 {
+		t.Errorf("expected debug messages to be filtered, got %q", output.String())
+	}
+
+	// logging/setLevel raises the threshold
+	if _, err := srv.handleSetLevel(context.Background(), json.RawMessage(`{"level":"error"}`)); err != nil {
+		t.Fatalf("setLevel failed: %v", err)
+	}
+	logger.Warn("disk almost full")
+	if output.Len() != 0 {
+		t.Errorf("expected warnings to be filtered at level error, got %q", output.String())
+	}
+	logger.Error("disk full")
+	if !strings.Contains(output.String(), `"level":"error"`) {
+		t.Errorf("expected an error notification, got %q", output.String())
+	}
+
+	if _, err := srv.handleSetLevel(context.Background(), json.RawMessage(`{"level":"loud"}`)); err == nil {
+		t.Error("expected an error for an unknown level")
+	}
+}
+
+func TestMCPServer_Batch(t *testing.T) {
+	srv := createTestServer()
+	var output bytes.Buffer
+	srv.SetIO(nil, &output)
+
+	batch := `[{"jsonrpc":"2.0","method":"ping","id":1},{"jsonrpc":"2.0","method":"tools/list","id":2}]`
+	if err := srv.handleMessage([]byte(batch)); err != nil {
+		t.Fatalf("handleMessage failed: %v", err)
+	}
+
+	var responses []JSONRPCResponse
+	if err := json.Unmarshal(output.Bytes(), &responses); err != nil {
+		t.Fatalf("expected a batch response, got %q: %v", output.String(), err)
+	}
+	if len(responses) != 2 {
+		t.Fatalf("expected 2 responses, got %d", len(responses))
+	}
+	for i, response := range responses {
+		if response.Error != nil {
+			t.Errorf("response %d failed: %v", i, response.Error)
+		}
+		if fmt.Sprint(response.ID) != fmt.Sprint(i+1) {
+			t.Errorf("expected response %d to have ID %d, got %v", i, i+1, response.ID)
+		}
+	}
+
+	// One bad element doesn't fail the rest of the batch
+	output.Reset()
+	batch = `[{"jsonrpc":"2.0","method":"nope","id":1},42,{"jsonrpc":"2.0","method":"ping","id":3}]`
+	if err := srv.handleMessage([]byte(batch)); err != nil {
+		t.Fatalf("handleMessage failed: %v", err)
+	}
+	responses = nil
+	if err := json.Unmarshal(output.Bytes(), &responses); err != nil {
+		t.Fatalf("expected a batch response, got %q: %v", output.String(), err)
+	}
+	if len(responses) != 3 {
+		t.Fatalf("expected 3 responses, got %d", len(responses))
+	}
+	if responses[0].Error == nil || responses[0].Error.Code != -32601 {
+		t.Errorf("expected method not found, got %+v", responses[0].Error)
+	}
+	if responses[1].Error == nil || responses[1].Error.Code != -32600 {
+		t.Errorf("expected invalid request, got %+v", responses[1].Error)
+	}
+	if responses[2].Error != nil {
+		t.Errorf("expected ping to succeed, got %v", responses[2].Error)
+	}
+}
+
+func TestMCPServer_PromptsList(t *testing.T) {
+	srv := createTestServer()
+
+	result, err := srv.handlePromptsList(context.Background(), nil)
+	if err != nil {
+		t.Fatalf("prompts/list failed: %v", err)
+	}
+	prompts, ok := result.(map[string]interface{})["prompts"].([]Prompt)
+	if !ok || len(prompts) == 0 {
+		t.Fatalf("expected at least one prompt, got %v", result)
+	}
+	for _, p := range prompts {
+		if p.Name == "" || p.Description == "" {
+			t.Errorf("prompt missing name or description: %+v", p)
+		}
+	}
+}
+
+func TestMCPServer_PromptsGet(t *testing.T) {
+	srv := createTestServer()
+
+	params := json.RawMessage(`{"name":"tail_logs","arguments":{"session":"prod","path":"/var/log/app.log"}}`)
+	result, err := srv.handlePromptsGet(context.Background(), params)
+	if err != nil {
+		t.Fatalf("prompts/get failed: %v", err)
+	}
+	getResult, ok := result.(PromptGetResult)
+	if !ok || len(getResult.Messages) != 1 {
+		t.Fatalf("expected one message, got %v", result)
+	}
+	text := getResult.Messages[0].Content.Text
+	for _, want := range []string{`session "prod"`, `path "/var/log/app.log"`, "lines 50"} {
+		if !strings.Contains(text, want) {
+			t.Errorf("expected %q in prompt, got %q", want, text)
+		}
+	}
+	if strings.Contains(text, "{{") {
+		t.Errorf("expected all placeholders substituted, got %q", text)
+	}
+
+	// Missing required argument
+	if _, err := srv.handlePromptsGet(context.Background(), json.RawMessage(`{"name":"tail_logs","arguments":{"session":"prod"}}`)); err == nil {
+		t.Error("expected an error for a missing required argument")
+	}
+
+	// Unknown prompt
+	if _, err := srv.handlePromptsGet(context.Background(), json.RawMessage(`{"name":"nope"}`)); err == nil {
+		t.Error("expected an error for an unknown prompt")
+	}
+}
+
+func TestMCPServer_ClientsHaveSeparateActiveSessions(t *testing.T) {
+	cfg := &config.Config{
+		Settings: config.Settings{DefaultSession: "local"},
+		Sessions: map[string]config.Session{
+			"local": {Type: "local", Shell: "/bin/bash"},
+			"build": {Type: "local", Shell: "/bin/sh"},
+		},
+	}
+	stateMgr := state.NewManager(filepath.Join(t.TempDir(), "state.json"))
+	sessionMgr := session.NewManager(cfg, stateMgr)
+
+	// Two clients sharing one session manager
+	first := NewServer(cfg, "", sessionMgr, stateMgr)
+	second := NewServer(cfg, "", sessionMgr, stateMgr)
+
+	res, _ := first.handleToolCall(context.Background(), json.RawMessage(`{"name":"switch","arguments":{"session":"build"}}`))
+	if tr, ok := res.(ToolCallResult); !ok || tr.IsError {
+		t.Fatalf("switch failed: %v", res)
+	}
+
+	if got := first.view.ActiveSessionName(); got != "build" {
+		t.Errorf("expected first client on build, got %q", got)
+	}
+	if got := second.view.ActiveSessionName(); got != "local" {
+		t.Errorf("expected second client to stay on local, got %q", got)
+	}
+
+	// Each client's status marks its own active session
+	for _, tt := range []struct {
+		srv  *Server
+		want string
+	}{
+		{first, "build"},
+		{second, "local"},
+	} {
+		res, _ := tt.srv.handleToolCall(context.Background(), json.RawMessage(`{"name":"status","arguments":{}}`))
+		tr, ok := res.(ToolCallResult)
+		if !ok || tr.IsError {
+			t.Fatalf("status failed: %v", res)
+		}
+		var infos []session.SessionInfo
+		if err := json.Unmarshal([]byte(tr.Content[0].Text), &infos); err != nil {
+			t.Fatalf("failed to parse status: %v", err)
+		}
+		for _, info := range infos {
+			if info.Active != (info.Name == tt.want) {
+				t.Errorf("expected only %q active, got %s active=%v", tt.want, info.Name, info.Active)
+			}
+		}
+	}
+
+	if got := sessionMgr.GetActiveSessionName(); got != "local" {
+		t.Errorf("expected the shared manager to stay on local, got %q", got)
+	}
+}
+
+func TestMCPServer_ResourceSubscription(t *testing.T) {
+	srv := createTestServer()
+	var output bytes.Buffer
+	srv.SetIO(nil, &output)
+	ctx := context.Background()
+
+	if _, err := srv.handleResourcesSubscribe(ctx, json.RawMessage(`{"uri":"session://nope"}`)); err == nil {
+		t.Error("expected an error subscribing to an unknown resource")
+	}
+	if _, err := srv.handleResourcesSubscribe(ctx, json.RawMessage(`{"uri":"session://active"}`)); err != nil {
+		t.Fatalf("subscribe failed: %v", err)
+	}
+
+	res, _ := srv.handleToolCall(ctx, json.RawMessage(`{"name":"switch","arguments":{"session":"local"}}`))
+	if tr, ok := res.(ToolCallResult); !ok || tr.IsError {
+		t.Fatalf("switch failed: %v", res)
+	}
+
+	var msg struct {
+		Method string                `json:"method"`
+		Params ResourceUpdatedParams `json:"params"`
+	}
+	if err := json.Unmarshal(output.Bytes(), &msg); err != nil {
+		t.Fatalf("expected one update notification, got %q: %v", output.String(), err)
+	}
+	if msg.Method != "notifications/resources/updated" || msg.Params.URI != "session://active" {
+		t.Errorf("unexpected notification: %+v", msg)
+	}
+
+	// No notifications after unsubscribing
+	if _, err := srv.handleResourcesUnsubscribe(ctx, json.RawMessage(`{"uri":"session://active"}`)); err != nil {
+		t.Fatalf("unsubscribe failed: %v", err)
+	}
+	output.Reset()
+	srv.handleToolCall(ctx, json.RawMessage(`{"name":"switch","arguments":{"session":"local"}}`))
+	if output.Len() != 0 {
+		t.Errorf("expected no notifications after unsubscribing, got %q", output.String())
+	}
+}
+
+func TestMCPServer_AllSessionsResourceReportsHostAndUser(t *testing.T) {
+	cfg := &config.Config{
+		Settings: config.Settings{DefaultSession: "local"},
+		Sessions: map[string]config.Session{
+			"local": {Type: "local", Shell: "/bin/bash"},
+			"prod":  {Type: "ssh", Host: "prod.example.com", User: "deploy", Port: 2222},
+		},
+	}
+	stateMgr := state.NewManager(filepath.Join(t.TempDir(), "state.json"))
+	srv := NewServer(cfg, "", session.NewManager(cfg, stateMgr), stateMgr)
+
+	content, err := srv.getAllSessionsResource()
+	if err != nil {
+		t.Fatalf("failed to read session://all: %v", err)
+	}
+	var infos []session.SessionInfo
+	if err := json.Unmarshal([]byte(content), &infos); err != nil {
+		t.Fatalf("failed to parse session://all: %v", err)
+	}
+
+	for _, info := range infos {
+		if info.Name != "prod" {
+			continue
+		}
+		if info.Host != "prod.example.com" || info.User != "deploy" || info.Port != 2222 {
+			t.Errorf("expected deploy@prod.example.com:2222, got %s@%s:%d", info.User, info.Host, info.Port)
+		}
+		return
+	}
+	t.Fatal("expected prod in session://all")
+}
+
+func TestMCPServer_ToolCall_ConnectReturnsDetails(t *testing.T) {
+	srv := createTestServer()
+
+	res, _ := srv.handleToolCall(context.Background(), json.RawMessage(`{"name":"connect","arguments":{"session":"local"}}`))
+	tr, ok := res.(ToolCallResult)
+	if !ok || tr.IsError {
+		t.Fatalf("connect failed: %v", res)
+	}
+
+	var info map[string]interface{}
+	if err := json.Unmarshal([]byte(tr.Content[0].Text), &info); err != nil {
+		t.Fatalf("expected JSON connection details, got %q: %v", tr.Content[0].Text, err)
+	}
+	if info["session"] != "local" || info["type"] != "local" || info["connected"] != true {
+		t.Errorf("unexpected connection details: %v", info)
+	}
+	if cwd, _ := info["cwd"].(string); cwd == "" {
+		t.Errorf("expected a cwd, got %v", info["cwd"])
+	}
+
+	// SSH sessions also report the resolved endpoint and auth method
+	sshInfo := connectionInfo(session.NewSSHSession(session.SSHConfig{
+		Name: "prod",
+		Host: "prod.example.com",
+		Port: 2222,
+		User: "deploy",
+	}))
+	if sshInfo["host"] != "prod.example.com" || sshInfo["user"] != "deploy" || sshInfo["port"] != 2222 {
+		t.Errorf("unexpected SSH connection details: %v", sshInfo)
+	}
+	if _, ok := sshInfo["auth_method"]; !ok {
+		t.Error("expected auth_method in SSH connection details")
+	}
+}
+
+func TestExecuteReconnecting(t *testing.T) {
+	dropped := &session.Error{Code: session.ErrSessionDisconnected, Message: "Session prod is not connected", Retryable: true}
+
+	calls := 0
+	execute := func() (*session.ExecuteResult, error) {
+		calls++
+		if calls == 1 {
+			return nil, dropped
+		}
+		return &session.ExecuteResult{Stdout: "ok\n"}, nil
+	}
+	reconnects := 0
+	reconnect := func() error {
+		reconnects++
+		return nil
+	}
+
+	result, reconnected, err := executeReconnecting(context.Background(), execute, reconnect)
+	if err != nil {
+		t.Fatalf("expected the retry to succeed, got %v", err)
+	}
+	if !reconnected || reconnects != 1 || calls != 2 {
+		t.Errorf("expected one reconnect and two attempts, got reconnected=%v reconnects=%d calls=%d", reconnected, reconnects, calls)
+	}
+	if result == nil || result.Stdout != "ok\n" {
+		t.Errorf("expected the retried result, got %+v", result)
+	}
+
+	// Other errors are not retried
+	calls, reconnects = 0, 0
+	failing := func() (*session.ExecuteResult, error) {
+		calls++
+		return nil, &session.Error{Code: session.ErrCommandTimeout, Message: "timed out"}
+	}
+	if _, reconnected, err := executeReconnecting(context.Background(), failing, reconnect); err == nil || reconnected || reconnects != 0 || calls != 1 {
+		t.Errorf("expected no retry for a timeout, got err=%v reconnected=%v reconnects=%d calls=%d", err, reconnected, reconnects, calls)
+	}
+}
+
+func TestMCPServer_RunHandlesCallsInOrder(t *testing.T) {
+	srv := createTestServer()
+	input, writer := io.Pipe()
+	var output bytes.Buffer
+	srv.SetIO(input, &output)
+
+	ran := make(chan error, 1)
+	go func() { ran <- srv.Run() }()
+	send := func(msg string) {
+		t.Helper()
+		if _, err := io.WriteString(writer, msg+"\n"); err != nil {
+			t.Fatalf("failed to write message: %v", err)
+		}
+	}
+
+	// A slow call, then two more pipelined behind it that touch the same
+	// session's environment
+	send(`{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"execute","arguments":{"command":"sleep 30"}}}`)
+	deadline := time.Now().Add(5 * time.Second)
+	for {
+		srv.inflightMu.Lock()
+		_, running := srv.inflight["1"]
+		srv.inflightMu.Unlock()
+		if running {
+			break
+		}
+		if time.Now().After(deadline) {
+			t.Fatal("request never became in flight")
+		}
+		time.Sleep(10 * time.Millisecond)
+	}
+	send(`{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"execute","arguments":{"command":"export ORDER=second"}}}`)
+	send(`{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"execute","arguments":{"command":"echo $ORDER"}}}`)
+
+	// The cancellation is read while the slow call is still running
+	send(`{"jsonrpc":"2.0","method":"notifications/cancelled","params":{"requestId":1}}`)
+	_ = writer.Close()
+
+	select {
+	case err := <-ran:
+		if err != nil {
+			t.Fatalf("Run failed: %v", err)
+		}
+	case <-time.After(10 * time.Second):
+		t.Fatal("Run did not finish; the cancellation never reached the slow call")
+	}
+
+	var ids []int
+	var last ToolCallResult
+	for _, line := range strings.Split(strings.TrimSpace(output.String()), "\n") {
+		var resp struct {
+			ID     int            `json:"id"`
+			Method string         `json:"method"`
+			Result ToolCallResult `json:"result"`
+		}
+		if err := json.Unmarshal([]byte(line), &resp); err != nil {
+			t.Fatalf("invalid response %q: %v", line, err)
+		}
+		if resp.Method != "" {
+			continue // A log or progress notification
+		}
+		ids = append(ids, resp.ID)
+		last = resp.Result
+	}
+	if !reflect.DeepEqual(ids, []int{1, 2, 3}) {
+		t.Fatalf("expected responses 1, 2, 3 in order, got %v", ids)
+	}
+	if len(last.Content) == 0 || !strings.Contains(last.Content[0].Text, "second") {
+		t.Errorf("expected the last call to see ORDER=second, got %+v", last.Content)
+	}
+}
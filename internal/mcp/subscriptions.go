@@ -0,0 +1,103 @@
+package mcp
+
+import (
+	"context"
+	"encoding/json"
+
+	"github.com/scottgl9/thop/internal/logger"
+	"github.com/scottgl9/thop/internal/session"
+)
+
+// handleResourcesSubscribe handles the resources/subscribe request
+func (s *Server) handleResourcesSubscribe(ctx context.Context, params json.RawMessage) (interface{}, error) {
+	uri, err := parseSubscribeParams(params)
+	if err != nil {
+		return nil, err
+	}
+
+	s.subsMu.Lock()
+	s.subscriptions[uri] = true
+	s.subsMu.Unlock()
+
+	logger.Debug("Client subscribed to %s", uri)
+	return map[string]interface{}{}, nil
+}
+
+// handleResourcesUnsubscribe handles the resources/unsubscribe request
+func (s *Server) handleResourcesUnsubscribe(ctx context.Context, params json.RawMessage) (interface{}, error) {
+	uri, err := parseSubscribeParams(params)
+	if err != nil {
+		return nil, err
+	}
+
+	s.subsMu.Lock()
+	delete(s.subscriptions, uri)
+	s.subsMu.Unlock()
+
+	logger.Debug("Client unsubscribed from %s", uri)
+	return map[string]interface{}{}, nil
+}
+
+// parseSubscribeParams returns the URI of a (un)subscribe request, which must
+// name one of the server's resources
+func parseSubscribeParams(params json.RawMessage) (string, error) {
+	var subParams ResourceSubscribeParams
+	if err := json.Unmarshal(params, &subParams); err != nil {
+		return "", &JSONRPCError{
+			Code:    -32602,
+			Message: "Invalid params",
+			Data:    err.Error(),
+		}
+	}
+
+	for _, resource := range listResources() {
+		if resource.URI == subParams.URI {
+			return subParams.URI, nil
+		}
+	}
+	return "", &JSONRPCError{
+		Code:    -32602,
+		Message: "Unknown resource URI",
+		Data:    subParams.URI,
+	}
+}
+
+// handleSessionEvent notifies the client about the resources a session event
+// changed
+func (s *Server) handleSessionEvent(event session.SessionEvent) {
+	switch event.Type {
+	case session.EventActivated:
+		if event.View == nil {
+			// The persisted active session changed; this client's view did not
+			s.notifyResourceUpdated("state://thop")
+			return
+		}
+		if event.View != s.view {
+			// Another client switched
+			return
+		}
+		s.notifyResourceUpdated("session://active")
+		s.notifyResourceUpdated("session://all")
+	case session.EventConnected, session.EventDisconnected:
+		if event.Session == s.view.ActiveSessionName() {
+			s.notifyResourceUpdated("session://active")
+		}
+		s.notifyResourceUpdated("session://all")
+		s.notifyResourceUpdated("state://thop")
+	}
+}
+
+// notifyResourceUpdated sends notifications/resources/updated for uri if the
+// client subscribed to it
+func (s *Server) notifyResourceUpdated(uri string) {
+	s.subsMu.Lock()
+	subscribed := s.subscriptions[uri]
+	s.subsMu.Unlock()
+	if !subscribed {
+		return
+	}
+
+	if err := s.sendNotification("notifications/resources/updated", ResourceUpdatedParams{URI: uri}); err != nil {
+		logger.Debug("Failed to send resource update for %s: %v", uri, err)
+	}
+}
@@ -6,7 +6,11 @@ import (
 	"fmt"
 	"strings"
 	"time"
+	"unicode/utf8"
 
+	"github.com/scottgl9/thop/internal/config"
+	"github.com/scottgl9/thop/internal/logger"
+	"github.com/scottgl9/thop/internal/restriction"
 	"github.com/scottgl9/thop/internal/session"
 )
 
@@ -53,16 +57,46 @@ func (s *Server) toolConnect(ctx context.Context, args map[string]interface{}) (
 		return ConnectionFailedError(sessionName, errStr).ToToolResult(), nil
 	}
 
+	sess, ok := s.sessions.GetSession(sessionName)
+	if !ok || sess == nil {
+		return SessionNotFoundError(sessionName).ToToolResult(), nil
+	}
+
+	// Report where the connection landed so no follow-up status call is needed
+	data, err := json.MarshalIndent(connectionInfo(sess), "", "  ")
+	if err != nil {
+		return NewMCPError(ErrorOperationFailed, err.Error()).ToToolResult(), nil
+	}
+
 	return ToolCallResult{
 		Content: []Content{
 			{
-				Type: "text",
-				Text: fmt.Sprintf("Successfully connected to session '%s'", sessionName),
+				Type:     "text",
+				Text:     string(data),
+				MimeType: "application/json",
 			},
 		},
 	}, nil
 }
 
+// connectionInfo describes a session's connection: its cwd and, for SSH
+// sessions, the resolved host, user, port and auth method
+func connectionInfo(sess session.Session) map[string]interface{} {
+	info := map[string]interface{}{
+		"session":   sess.Name(),
+		"type":      sess.Type(),
+		"connected": sess.IsConnected(),
+		"cwd":       sess.GetCWD(),
+	}
+	if sshSess, ok := sess.(*session.SSHSession); ok {
+		info["host"] = sshSess.Host()
+		info["user"] = sshSess.User()
+		info["port"] = sshSess.Port()
+		info["auth_method"] = sshSess.AuthMethod()
+	}
+	return info
+}
+
 // toolSwitch handles the switch tool
 func (s *Server) toolSwitch(ctx context.Context, args map[string]interface{}) (interface{}, error) {
 	sessionName, ok := args["session"].(string)
@@ -70,7 +104,7 @@ func (s *Server) toolSwitch(ctx context.Context, args map[string]interface{}) (i
 		return MissingParameterError("session").ToToolResult(), nil
 	}
 
-	if err := s.sessions.SetActiveSession(sessionName); err != nil {
+	if err := s.view.SetActiveSession(sessionName); err != nil {
 		errStr := err.Error()
 		if strings.Contains(errStr, "not found") {
 			return SessionNotFoundError(sessionName).ToToolResult(), nil
@@ -135,7 +169,7 @@ func (s *Server) toolClose(ctx context.Context, args map[string]interface{}) (in
 
 // toolStatus handles the status tool
 func (s *Server) toolStatus(ctx context.Context, args map[string]interface{}) (interface{}, error) {
-	sessions := s.sessions.ListSessions()
+	sessions := s.view.ListSessions()
 
 	// Format status as JSON
 	data, err := json.MarshalIndent(sessions, "", "  ")
@@ -156,6 +190,157 @@ func (s *Server) toolStatus(ctx context.Context, args map[string]interface{}) (i
 	}, nil
 }
 
+// toolAddSession handles the add_session tool
+func (s *Server) toolAddSession(ctx context.Context, args map[string]interface{}) (interface{}, error) {
+	name, ok := args["name"].(string)
+	if !ok || name == "" {
+		return MissingParameterError("name").ToToolResult(), nil
+	}
+	host, ok := args["host"].(string)
+	if !ok || host == "" {
+		return MissingParameterError("host").ToToolResult(), nil
+	}
+
+	if s.sessions.HasSession(name) {
+		return NewMCPError(ErrorSessionAlreadyExists, fmt.Sprintf("Session '%s' already exists", name)).
+			WithSession(name).
+			WithSuggestion("Choose a different name, or use connect to use the existing session").
+			ToToolResult(), nil
+	}
+
+	user, _ := args["user"].(string)
+	if user == "" {
+		user = config.DefaultUser()
+	}
+	port := 22
+	if p, ok := args["port"].(float64); ok {
+		if p < 1 || p > 65535 || p != float64(int(p)) {
+			return NewMCPError(ErrorInvalidParameter, fmt.Sprintf("Invalid port: %v", p)).
+				WithSuggestion("Use a port number between 1 and 65535").
+				ToToolResult(), nil
+		}
+		port = int(p)
+	}
+	identityFile, _ := args["identity_file"].(string)
+	persist, _ := args["persist"].(bool)
+
+	sessionCfg := config.Session{
+		Type:         "ssh",
+		Host:         host,
+		User:         user,
+		Port:         port,
+		IdentityFile: identityFile,
+	}
+
+	if err := s.sessions.AddSession(name, sessionCfg); err != nil {
+		return NewMCPError(ErrorOperationFailed, fmt.Sprintf("Failed to add session: %v", err)).
+			WithSession(name).
+			ToToolResult(), nil
+	}
+
+	if persist {
+		if err := s.config.AddSession(name, sessionCfg); err != nil {
+			return NewMCPError(ErrorOperationFailed, fmt.Sprintf("Session added but not saved: %v", err)).
+				WithSession(name).
+				ToToolResult(), nil
+		}
+		if err := s.config.Save(s.configPath); err != nil {
+			return NewMCPError(ErrorOperationFailed, fmt.Sprintf("Session added but failed to save config: %v", err)).
+				WithSession(name).
+				ToToolResult(), nil
+		}
+	}
+
+	info := map[string]interface{}{
+		"name":      name,
+		"type":      "ssh",
+		"host":      host,
+		"user":      user,
+		"port":      port,
+		"persisted": persist,
+	}
+	if identityFile != "" {
+		info["identity_file"] = identityFile
+	}
+
+	data, err := json.MarshalIndent(info, "", "  ")
+	if err != nil {
+		return NewMCPError(ErrorOperationFailed, err.Error()).ToToolResult(), nil
+	}
+
+	return ToolCallResult{
+		Content: []Content{
+			{
+				Type: "text",
+				Text: string(data),
+			},
+		},
+	}, nil
+}
+
+// toolGetEnv handles the get_env tool
+func (s *Server) toolGetEnv(ctx context.Context, args map[string]interface{}) (interface{}, error) {
+	sessionName, _ := args["session"].(string)
+	if sessionName == "" {
+		sessionName = s.view.ActiveSessionName()
+	}
+
+	sess, ok := s.sessions.GetSession(sessionName)
+	if !ok || sess == nil {
+		return SessionNotFoundError(sessionName).ToToolResult(), nil
+	}
+
+	// Same shape as the environment field of session://active
+	data, err := json.MarshalIndent(sess.GetEnv(), "", "  ")
+	if err != nil {
+		return NewMCPError(ErrorOperationFailed, err.Error()).ToToolResult(), nil
+	}
+
+	return ToolCallResult{
+		Content: []Content{
+			{
+				Type: "text",
+				Text: string(data),
+			},
+		},
+	}, nil
+}
+
+// toolSetEnv handles the set_env tool
+func (s *Server) toolSetEnv(ctx context.Context, args map[string]interface{}) (interface{}, error) {
+	key, ok := args["key"].(string)
+	if !ok || key == "" {
+		return MissingParameterError("key").ToToolResult(), nil
+	}
+	value, ok := args["value"].(string)
+	if !ok {
+		return MissingParameterError("value").ToToolResult(), nil
+	}
+	if !config.ValidEnvName(key) {
+		return NewMCPError(ErrorInvalidParameter, fmt.Sprintf("Invalid environment variable name: %q", key)).
+			WithSuggestion("Use letters, digits and underscores, not starting with a digit").
+			ToToolResult(), nil
+	}
+
+	sessionName, _ := args["session"].(string)
+	if sessionName == "" {
+		sessionName = s.view.ActiveSessionName()
+	}
+
+	if err := s.sessions.SetEnvOn(sessionName, key, value); err != nil {
+		return fileToolError(sessionName, err), nil
+	}
+
+	return ToolCallResult{
+		Content: []Content{
+			{
+				Type: "text",
+				Text: fmt.Sprintf("Set %s on session '%s'", key, sessionName),
+			},
+		},
+	}, nil
+}
+
 // toolExecute handles the execute tool
 func (s *Server) toolExecute(ctx context.Context, args map[string]interface{}) (interface{}, error) {
 	command, ok := args["command"].(string)
@@ -180,7 +365,7 @@ func (s *Server) toolExecute(ctx context.Context, args map[string]interface{}) (
 		}
 		sessionName = sess.Name() // Use actual session name
 	} else {
-		sess = s.sessions.GetActiveSession()
+		sess = s.view.ActiveSession()
 		if sess == nil {
 			return NewMCPError(ErrorNoActiveSession, "No active session").
 				WithSuggestion("Use /connect to establish a session or specify a session name").
@@ -204,11 +389,45 @@ func (s *Server) toolExecute(ctx context.Context, args map[string]interface{}) (
 	cmdCtx, cancel := context.WithTimeout(ctx, time.Duration(timeout)*time.Second)
 	defer cancel()
 
-	result, err := sess.ExecuteWithContext(cmdCtx, command)
+	// Keep the client's spinner going while the command runs
+	if token := progressToken(ctx); token != nil {
+		stop := s.startProgress(token, float64(timeout))
+		defer stop()
+	}
+
+	raw, _ := args["raw"].(bool)
+	execute := func() (*session.ExecuteResult, error) {
+		if raw {
+			return s.sessions.ExecuteVerbatim(cmdCtx, sessionName, command)
+		}
+		return sess.ExecuteWithContext(cmdCtx, command)
+	}
+
+	var result *session.ExecuteResult
+	var err error
+	reconnected := false
+	if s.config.Settings.MCPAutoReconnect && sess.Type() == "ssh" {
+		reconnect := func() error {
+			return s.sessions.Connect(sessionName)
+		}
+		result, reconnected, err = executeReconnecting(cmdCtx, execute, reconnect)
+	} else {
+		result, err = execute()
+	}
 	if err != nil {
 		errStr := err.Error()
 
+		if sessionErr, ok := err.(*session.Error); ok && sessionErr.Code == session.ErrCommandRestricted {
+			return NewMCPError(ErrorOperationFailed, sessionErr.Message).
+				WithSession(sessionName).
+				WithSuggestion(sessionErr.Suggestion).
+				ToToolResult(), nil
+		}
+
 		// Check for timeout
+		if sessionErr, ok := err.(*session.Error); ok && sessionErr.Code == session.ErrCommandTimeout {
+			return CommandTimeoutError(sessionName, timeout).ToToolResult(), nil
+		}
 		if strings.Contains(errStr, "context deadline exceeded") || strings.Contains(errStr, "timeout") {
 			return CommandTimeoutError(sessionName, timeout).ToToolResult(), nil
 		}
@@ -240,9 +459,20 @@ func (s *Server) toolExecute(ctx context.Context, args map[string]interface{}) (
 			ToToolResult(), nil
 	}
 
+	// The client cancelled the call: return what the command printed so far
+	cancelled := ctx.Err() == context.Canceled
+
 	// Prepare content
 	content := []Content{}
 
+	// Let the agent know the connection dropped and was re-established
+	if reconnected {
+		content = append(content, Content{
+			Type: "text",
+			Text: fmt.Sprintf("Reconnected to session '%s' after the connection dropped", sessionName),
+		})
+	}
+
 	// Add stdout if present
 	if result.Stdout != "" {
 		content = append(content, Content{
@@ -259,8 +489,13 @@ func (s *Server) toolExecute(ctx context.Context, args map[string]interface{}) (
 		})
 	}
 
-	// Add exit code if non-zero
-	if result.ExitCode != 0 {
+	// Add exit code if non-zero, or note the cancellation
+	if cancelled {
+		content = append(content, Content{
+			Type: "text",
+			Text: "Command cancelled",
+		})
+	} else if result.ExitCode != 0 {
 		content = append(content, Content{
 			Type: "text",
 			Text: fmt.Sprintf("Exit code: %d", result.ExitCode),
@@ -277,17 +512,422 @@ func (s *Server) toolExecute(ctx context.Context, args map[string]interface{}) (
 
 	return ToolCallResult{
 		Content: content,
-		IsError: result.ExitCode != 0,
+		IsError: result.ExitCode != 0 || cancelled,
+	}, nil
+}
+
+// executeReconnecting runs execute and, if it fails because the session's
+// connection dropped, reconnects once and runs it again. It reports whether a
+// reconnect happened.
+func executeReconnecting(ctx context.Context, execute func() (*session.ExecuteResult, error), reconnect func() error) (*session.ExecuteResult, bool, error) {
+	result, err := execute()
+	if err == nil || ctx.Err() != nil || !isDisconnectError(err) {
+		return result, false, err
+	}
+
+	logger.Info("connection dropped during execute, reconnecting: %v", err)
+	if reconnectErr := reconnect(); reconnectErr != nil {
+		return nil, false, reconnectErr
+	}
+
+	result, err = execute()
+	return result, true, err
+}
+
+// isDisconnectError reports whether err means the session's connection is gone
+func isDisconnectError(err error) bool {
+	sessionErr, ok := err.(*session.Error)
+	return ok && (sessionErr.Code == session.ErrSessionDisconnected || sessionErr.Code == session.ErrConnectionFailed)
+}
+
+// toolCheckCommand handles the check_command tool
+func (s *Server) toolCheckCommand(ctx context.Context, args map[string]interface{}) (interface{}, error) {
+	command, ok := args["command"].(string)
+	if !ok {
+		return MissingParameterError("command").ToToolResult(), nil
+	}
+
+	allowed, rule, span := s.sessions.CheckCommand(command)
+
+	check := map[string]interface{}{
+		"allowed":         allowed,
+		"category":        nil,
+		"command":         nil,
+		"matched_span":    nil,
+		"restricted_mode": s.sessions.IsRestrictedMode(),
+	}
+	if rule != nil {
+		check["category"] = string(rule.Category)
+		check["command"] = rule.Command
+		check["description"] = restriction.CategoryDescription(rule.Category)
+	}
+	if span != nil {
+		check["matched_span"] = span
+	}
+
+	data, err := json.MarshalIndent(check, "", "  ")
+	if err != nil {
+		return NewMCPError(ErrorOperationFailed, fmt.Sprintf("Failed to format check result: %v", err)).
+			ToToolResult(), nil
+	}
+
+	return ToolCallResult{
+		Content: []Content{
+			{
+				Type:     "text",
+				Text:     string(data),
+				MimeType: "application/json",
+			},
+		},
+	}, nil
+}
+
+// toolRealpath handles the realpath tool
+func (s *Server) toolRealpath(ctx context.Context, args map[string]interface{}) (interface{}, error) {
+	path, ok := args["path"].(string)
+	if !ok || path == "" {
+		return MissingParameterError("path").ToToolResult(), nil
+	}
+
+	sessionName, _ := args["session"].(string)
+	if sessionName == "" {
+		sessionName = s.view.ActiveSessionName()
+	}
+
+	resolved, err := s.sessions.RealPath(sessionName, path)
+	if err != nil {
+		if sessionErr, ok := err.(*session.Error); ok {
+			switch sessionErr.Code {
+			case session.ErrSessionNotFound:
+				return SessionNotFoundError(sessionName).ToToolResult(), nil
+			case session.ErrSessionDisconnected:
+				return SessionNotConnectedError(sessionName).ToToolResult(), nil
+			}
+		}
+		return NewMCPError(ErrorOperationFailed, err.Error()).
+			WithSession(sessionName).
+			ToToolResult(), nil
+	}
+
+	return ToolCallResult{
+		Content: []Content{
+			{
+				Type: "text",
+				Text: resolved,
+			},
+		},
+	}, nil
+}
+
+// toolListDir handles the list_dir tool
+func (s *Server) toolListDir(ctx context.Context, args map[string]interface{}) (interface{}, error) {
+	path, _ := args["path"].(string)
+	sessionName, _ := args["session"].(string)
+	if sessionName == "" {
+		sessionName = s.view.ActiveSessionName()
+	}
+
+	entries, err := s.sessions.ListDir(sessionName, path)
+	if err != nil {
+		return fileToolError(sessionName, err), nil
+	}
+
+	data, err := json.MarshalIndent(entries, "", "  ")
+	if err != nil {
+		return NewMCPError(ErrorOperationFailed, err.Error()).ToToolResult(), nil
+	}
+
+	return ToolCallResult{
+		Content: []Content{
+			{
+				Type: "text",
+				Text: string(data),
+			},
+		},
+	}, nil
+}
+
+// toolChangedFiles handles the changed_files tool
+func (s *Server) toolChangedFiles(ctx context.Context, args map[string]interface{}) (interface{}, error) {
+	dir, ok := args["dir"].(string)
+	if !ok || dir == "" {
+		return MissingParameterError("dir").ToToolResult(), nil
+	}
+	since, ok := args["since"].(string)
+	if !ok || since == "" {
+		return MissingParameterError("since").ToToolResult(), nil
+	}
+
+	cutoff, err := session.ParseSince(since, time.Now())
+	if err != nil {
+		return NewMCPError(ErrorInvalidParameter, err.Error()).ToToolResult(), nil
+	}
+
+	sessionName, _ := args["session"].(string)
+	if sessionName == "" {
+		sessionName = s.view.ActiveSessionName()
+	}
+
+	changed, err := s.sessions.ChangedFiles(sessionName, dir, cutoff)
+	if err != nil {
+		return fileToolError(sessionName, err), nil
+	}
+	if changed == nil {
+		changed = []session.ChangedFile{}
+	}
+
+	data, err := json.MarshalIndent(changed, "", "  ")
+	if err != nil {
+		return NewMCPError(ErrorOperationFailed, err.Error()).ToToolResult(), nil
+	}
+
+	return ToolCallResult{
+		Content: []Content{
+			{
+				Type: "text",
+				Text: string(data),
+			},
+		},
+	}, nil
+}
+
+// toolListProcesses handles the list_processes tool
+func (s *Server) toolListProcesses(ctx context.Context, args map[string]interface{}) (interface{}, error) {
+	filter, _ := args["filter"].(string)
+	sessionName, _ := args["session"].(string)
+	if sessionName == "" {
+		sessionName = s.view.ActiveSessionName()
+	}
+
+	processes, err := s.sessions.ListProcesses(sessionName, filter)
+	if err != nil {
+		return fileToolError(sessionName, err), nil
+	}
+	if processes == nil {
+		processes = []session.Process{}
+	}
+
+	data, err := json.MarshalIndent(processes, "", "  ")
+	if err != nil {
+		return NewMCPError(ErrorOperationFailed, err.Error()).ToToolResult(), nil
+	}
+
+	return ToolCallResult{
+		Content: []Content{
+			{
+				Type: "text",
+				Text: string(data),
+			},
+		},
+	}, nil
+}
+
+// toolListPorts handles the list_ports tool
+func (s *Server) toolListPorts(ctx context.Context, args map[string]interface{}) (interface{}, error) {
+	sessionName, _ := args["session"].(string)
+	if sessionName == "" {
+		sessionName = s.view.ActiveSessionName()
+	}
+
+	ports, err := s.sessions.ListeningPorts(sessionName)
+	if err != nil {
+		return fileToolError(sessionName, err), nil
+	}
+	if ports == nil {
+		ports = []session.ListeningPort{}
+	}
+
+	data, err := json.MarshalIndent(ports, "", "  ")
+	if err != nil {
+		return NewMCPError(ErrorOperationFailed, err.Error()).ToToolResult(), nil
+	}
+
+	return ToolCallResult{
+		Content: []Content{
+			{
+				Type: "text",
+				Text: string(data),
+			},
+		},
+	}, nil
+}
+
+// toolReadFile handles the read_file tool
+func (s *Server) toolReadFile(ctx context.Context, args map[string]interface{}) (interface{}, error) {
+	path, ok := args["path"].(string)
+	if !ok || path == "" {
+		return MissingParameterError("path").ToToolResult(), nil
+	}
+
+	sessionName, _ := args["session"].(string)
+	if sessionName == "" {
+		sessionName = s.view.ActiveSessionName()
+	}
+
+	data, resolved, err := s.sessions.ReadFile(sessionName, path)
+	if err != nil {
+		return fileToolError(sessionName, err), nil
+	}
+	if !utf8.Valid(data) {
+		return NewMCPError(ErrorOperationFailed, fmt.Sprintf("%s is not a text file (%d bytes)", resolved, len(data))).
+			WithSession(sessionName).
+			WithSuggestion("Use execute with base64 to read binary files").
+			ToToolResult(), nil
+	}
+
+	return ToolCallResult{
+		Content: []Content{
+			{
+				Type: "text",
+				Text: string(data),
+			},
+		},
+	}, nil
+}
+
+// toolTail handles the tail tool
+func (s *Server) toolTail(ctx context.Context, args map[string]interface{}) (interface{}, error) {
+	path, ok := args["path"].(string)
+	if !ok || path == "" {
+		return MissingParameterError("path").ToToolResult(), nil
+	}
+	lines := session.DefaultTailLines
+	if n, ok := args["lines"].(float64); ok {
+		if n < 0 {
+			return NewMCPError(ErrorInvalidParameter, fmt.Sprintf("Invalid line count: %v", n)).ToToolResult(), nil
+		}
+		lines = int(n)
+	}
+
+	sessionName, _ := args["session"].(string)
+	if sessionName == "" {
+		sessionName = s.view.ActiveSessionName()
+	}
+
+	data, resolved, err := s.sessions.Tail(sessionName, path, lines)
+	if err != nil {
+		return fileToolError(sessionName, err), nil
+	}
+	if !utf8.Valid(data) {
+		return NewMCPError(ErrorOperationFailed, fmt.Sprintf("%s is not a text file", resolved)).
+			WithSession(sessionName).
+			ToToolResult(), nil
+	}
+
+	return ToolCallResult{
+		Content: []Content{
+			{
+				Type: "text",
+				Text: string(data),
+			},
+		},
+	}, nil
+}
+
+// toolWriteFile handles the write_file tool
+func (s *Server) toolWriteFile(ctx context.Context, args map[string]interface{}) (interface{}, error) {
+	path, ok := args["path"].(string)
+	if !ok || path == "" {
+		return MissingParameterError("path").ToToolResult(), nil
+	}
+	content, ok := args["content"].(string)
+	if !ok {
+		return MissingParameterError("content").ToToolResult(), nil
+	}
+
+	sessionName, _ := args["session"].(string)
+	if sessionName == "" {
+		sessionName = s.view.ActiveSessionName()
+	}
+
+	if err := s.sessions.CheckFileWrite(sessionName); err != nil {
+		return fileToolError(sessionName, err), nil
+	}
+	resolved, err := s.sessions.WriteFile(sessionName, path, []byte(content), 0)
+	if err != nil {
+		return fileToolError(sessionName, err), nil
+	}
+
+	return ToolCallResult{
+		Content: []Content{
+			{
+				Type: "text",
+				Text: fmt.Sprintf("Wrote %d bytes to %s", len(content), resolved),
+			},
+		},
+	}, nil
+}
+
+// toolCopyFile handles the copy_file tool
+func (s *Server) toolCopyFile(ctx context.Context, args map[string]interface{}) (interface{}, error) {
+	source, ok := args["source"].(string)
+	if !ok || source == "" {
+		return MissingParameterError("source").ToToolResult(), nil
+	}
+	destination, ok := args["destination"].(string)
+	if !ok || destination == "" {
+		return MissingParameterError("destination").ToToolResult(), nil
+	}
+
+	src, dst := s.view.QualifyFileSpec(source), s.view.QualifyFileSpec(destination)
+	dstSession, _ := session.ParseFileSpec(dst)
+	if err := s.sessions.CheckFileWrite(dstSession); err != nil {
+		return fileToolError(dstSession, err), nil
+	}
+
+	copied, err := s.sessions.CopyFile(src, dst)
+	if err != nil {
+		if sessionErr, ok := err.(*session.Error); ok {
+			switch sessionErr.Code {
+			case session.ErrSessionNotFound:
+				return SessionNotFoundError(sessionErr.Session).ToToolResult(), nil
+			case session.ErrCopyUnsupported:
+				return NewMCPError(ErrorInvalidParameter, sessionErr.Message).
+					WithSuggestion(sessionErr.Suggestion).
+					ToToolResult(), nil
+			}
+		}
+		return NewMCPError(ErrorOperationFailed, err.Error()).ToToolResult(), nil
+	}
+
+	return ToolCallResult{
+		Content: []Content{
+			{
+				Type: "text",
+				Text: fmt.Sprintf("Copied %d bytes from %s to %s", copied, source, destination),
+			},
+		},
 	}, nil
 }
 
 // Helper functions
 
+// fileToolError converts an error from an operation on a session's files or
+// environment into a tool result
+func fileToolError(sessionName string, err error) ToolCallResult {
+	if sessionErr, ok := err.(*session.Error); ok {
+		switch sessionErr.Code {
+		case session.ErrSessionNotFound:
+			return SessionNotFoundError(sessionName).ToToolResult()
+		case session.ErrSessionDisconnected:
+			return SessionNotConnectedError(sessionName).ToToolResult()
+		case session.ErrCommandRestricted:
+			return NewMCPError(ErrorOperationFailed, sessionErr.Message).
+				WithSession(sessionName).
+				WithSuggestion(sessionErr.Suggestion).
+				ToToolResult()
+		}
+	}
+	return NewMCPError(ErrorOperationFailed, err.Error()).
+		WithSession(sessionName).
+		ToToolResult()
+}
+
 // Resource helper functions
 
 // getActiveSessionResource returns the active session as a JSON resource
 func (s *Server) getActiveSessionResource() (string, error) {
-	sess := s.sessions.GetActiveSession()
+	sess := s.view.ActiveSession()
 	if sess == nil {
 		return "", fmt.Errorf("no active session")
 	}
@@ -301,6 +941,10 @@ func (s *Server) getActiveSessionResource() (string, error) {
 		"environment": sess.GetEnv(),
 	}
 
+	if sshSess, ok := sess.(*session.SSHSession); ok {
+		info["auth_method"] = sshSess.AuthMethod()
+	}
+
 	data, err := json.MarshalIndent(info, "", "  ")
 	if err != nil {
 		return "", err
@@ -311,7 +955,7 @@ func (s *Server) getActiveSessionResource() (string, error) {
 
 // getAllSessionsResource returns all sessions as a JSON resource
 func (s *Server) getAllSessionsResource() (string, error) {
-	sessions := s.sessions.ListSessions()
+	sessions := s.view.ListSessions()
 	data, err := json.MarshalIndent(sessions, "", "  ")
 	if err != nil {
 		return "", err
@@ -14,20 +14,66 @@ const (
 	CategoryDestructiveFile     Category = "destructive_file"
 	CategorySystemModification  Category = "system_modification"
 	CategoryPrivilegeEscalation Category = "privilege_escalation"
+	CategoryNetworkAccess       Category = "network_access"
+)
+
+// Mode controls what happens when a command matches a rule
+type Mode string
+
+const (
+	// ModeEnforce blocks matched commands
+	ModeEnforce Mode = "enforce"
+	// ModeWarnOnly logs matched commands but still allows them to run
+	ModeWarnOnly Mode = "warn"
 )
 
 // Rule defines a restriction rule
 type Rule struct {
 	Pattern     *regexp.Regexp
+	ArgPattern  *regexp.Regexp // Optional: must also match the arguments following Pattern
 	Category    Category
 	Description string
 	Command     string // Original command name for error messages
 }
 
+// Matches reports whether the rule applies to cmd. When ArgPattern is set it
+// must match the arguments following a Pattern match, up to the next |, ; or &.
+func (r *Rule) Matches(cmd string) bool {
+	return r.Span(cmd) != nil
+}
+
+// Span returns the byte range [start, end) in cmd of the segment that
+// triggered the rule, without surrounding separators or whitespace, or nil
+// if the rule does not match
+func (r *Rule) Span(cmd string) []int {
+	for _, loc := range r.Pattern.FindAllStringIndex(cmd, -1) {
+		if r.ArgPattern != nil {
+			args := cmd[loc[1]:]
+			if end := strings.IndexAny(args, "|;&"); end >= 0 {
+				args = args[:end]
+			}
+			if !r.ArgPattern.MatchString(args) {
+				continue
+			}
+		}
+
+		start, end := loc[0], loc[1]
+		for start < end && strings.ContainsRune("|;& \t\n", rune(cmd[start])) {
+			start++
+		}
+		for end > start && strings.ContainsRune(" \t\n", rune(cmd[end-1])) {
+			end--
+		}
+		return []int{start, end}
+	}
+	return nil
+}
+
 // Checker validates commands against restriction rules
 type Checker struct {
 	rules   []Rule
 	enabled bool
+	mode    Mode
 }
 
 // NewChecker creates a new restriction checker
@@ -35,6 +81,7 @@ func NewChecker() *Checker {
 	return &Checker{
 		rules:   buildDefaultRules(),
 		enabled: false,
+		mode:    ModeEnforce,
 	}
 }
 
@@ -48,6 +95,21 @@ func (c *Checker) IsEnabled() bool {
 	return c.enabled
 }
 
+// SetMode sets the restriction mode (enforce or warn-only)
+func (c *Checker) SetMode(mode Mode) {
+	c.mode = mode
+}
+
+// Mode returns the current restriction mode
+func (c *Checker) Mode() Mode {
+	return c.mode
+}
+
+// IsWarnOnly returns true if matched commands should be logged but not blocked
+func (c *Checker) IsWarnOnly() bool {
+	return c.mode == ModeWarnOnly
+}
+
 // Check validates a command against restriction rules.
 // Returns (allowed bool, rule *Rule) - if not allowed, rule contains the matched rule.
 func (c *Checker) Check(cmd string) (bool, *Rule) {
@@ -55,6 +117,23 @@ func (c *Checker) Check(cmd string) (bool, *Rule) {
 		return true, nil
 	}
 
+	return c.Match(cmd)
+}
+
+// CheckSpan is like Check but also returns the byte range [start, end) of
+// the segment of cmd that matched, so callers can highlight it
+func (c *Checker) CheckSpan(cmd string) (bool, *Rule, []int) {
+	allowed, rule := c.Check(cmd)
+	if rule == nil {
+		return allowed, nil, nil
+	}
+	return allowed, rule, rule.Span(cmd)
+}
+
+// Match validates a command against restriction rules regardless of whether
+// restriction checking is enabled, so callers can ask ahead of time.
+// Returns (allowed bool, rule *Rule) - if not allowed, rule contains the matched rule.
+func (c *Checker) Match(cmd string) (bool, *Rule) {
 	// Normalize the command (trim whitespace)
 	cmd = strings.TrimSpace(cmd)
 	if cmd == "" {
@@ -63,7 +142,7 @@ func (c *Checker) Check(cmd string) (bool, *Rule) {
 
 	// Check against all rules
 	for i := range c.rules {
-		if c.rules[i].Pattern.MatchString(cmd) {
+		if c.rules[i].Matches(cmd) {
 			return false, &c.rules[i]
 		}
 	}
@@ -84,6 +163,9 @@ func buildDefaultRules() []Rule {
 	// System modification commands
 	rules = append(rules, buildSystemModificationRules()...)
 
+	// Outbound network access
+	rules = append(rules, buildNetworkAccessRules()...)
+
 	return rules
 }
 
@@ -121,7 +203,6 @@ func buildDestructiveFileRules() []Rule {
 		name string
 		desc string
 	}{
-		{"rm", "remove files or directories"},
 		{"rmdir", "remove empty directories"},
 		{"shred", "securely delete files"},
 		{"wipe", "securely erase files"},
@@ -142,6 +223,34 @@ func buildDestructiveFileRules() []Rule {
 		})
 	}
 
+	// rm and chmod are only blocked for recursive operations on root-ish paths,
+	// so everyday use like "rm /tmp/scratch/file" is still allowed
+	rmPattern := regexp.MustCompile(`(?:^|[|;&])\s*rm(?:\s|$)`)
+	rmRecursive := `-(?:[a-zA-Z]*[rR][a-zA-Z]*|-recursive)`
+	rules = append(rules, Rule{
+		Pattern:     rmPattern,
+		ArgPattern:  argsInAnyOrder(rmRecursive, `/+(?:(?:bin|boot|etc|home|lib\w*|opt|root|sbin|srv|usr|var)/*)?\*?`),
+		Category:    CategoryDestructiveFile,
+		Description: "recursively remove the root filesystem or a system directory",
+		Command:     "rm -rf /",
+	})
+	rules = append(rules, Rule{
+		Pattern:     rmPattern,
+		ArgPattern:  argsInAnyOrder(rmRecursive, `(?:~|\$HOME|\$\{HOME\})/?\*?`),
+		Category:    CategoryDestructiveFile,
+		Description: "recursively remove the home directory",
+		Command:     "rm -rf ~",
+	})
+	// Destructive rules come before the generic chmod rule in
+	// buildDefaultRules, so this one is reported when both match
+	rules = append(rules, Rule{
+		Pattern:     regexp.MustCompile(`(?:^|[|;&])\s*chmod(?:\s|$)`),
+		ArgPattern:  regexp.MustCompile(`(?:^|\s)-(?:[a-zA-Z]*R[a-zA-Z]*|-recursive)\s(?:.*\s)?0?777\s(?:.*\s)?/\*?(?:\s|$)`),
+		Category:    CategoryDestructiveFile,
+		Description: "make the whole filesystem world-writable",
+		Command:     "chmod -R 777 /",
+	})
+
 	// Special case: truncate with size 0 (destructive)
 	rules = append(rules, Rule{
 		Pattern:     regexp.MustCompile(`(?:^|[|;&])\s*truncate\s+.*-s\s*0`),
@@ -161,6 +270,14 @@ func buildDestructiveFileRules() []Rule {
 	return rules
 }
 
+// argsInAnyOrder builds an argument pattern matching when both the flag and
+// target appear as whole arguments, in either order
+func argsInAnyOrder(flag, target string) *regexp.Regexp {
+	return regexp.MustCompile(
+		`(?:^|\s)` + flag + `\s(?:.*\s)?` + target + `(?:\s|$)` +
+			`|(?:^|\s)` + target + `\s(?:.*\s)?` + flag + `(?:\s|$)`)
+}
+
 // buildSystemModificationRules creates rules for system modification commands
 func buildSystemModificationRules() []Rule {
 	commands := []struct {
@@ -233,6 +350,36 @@ func buildSystemModificationRules() []Rule {
 	return rules
 }
 
+// buildNetworkAccessRules creates rules for commands that make outbound network connections
+func buildNetworkAccessRules() []Rule {
+	commands := []struct {
+		name string
+		desc string
+	}{
+		{"curl", "transfer data from or to a server"},
+		{"wget", "download files from the network"},
+		{"nc", "open arbitrary TCP/UDP connections"},
+		{"ncat", "open arbitrary TCP/UDP connections"},
+		{"ssh", "connect to a remote host"},
+		{"scp", "copy files to or from a remote host"},
+		{"ftp", "transfer files over FTP"},
+		{"telnet", "open a telnet connection"},
+	}
+
+	rules := make([]Rule, 0, len(commands))
+	for _, cmd := range commands {
+		pattern := regexp.MustCompile(`(?:^|[|;&])\s*` + regexp.QuoteMeta(cmd.name) + `(?:\s|$)`)
+		rules = append(rules, Rule{
+			Pattern:     pattern,
+			Category:    CategoryNetworkAccess,
+			Description: cmd.desc,
+			Command:     cmd.name,
+		})
+	}
+
+	return rules
+}
+
 // CategoryDescription returns a human-readable description for a category
 func CategoryDescription(cat Category) string {
 	switch cat {
@@ -242,6 +389,8 @@ func CategoryDescription(cat Category) string {
 		return "System modification"
 	case CategoryPrivilegeEscalation:
 		return "Privilege escalation"
+	case CategoryNetworkAccess:
+		return "Network access"
 	default:
 		return "Restricted operation"
 	}
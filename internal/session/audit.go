@@ -0,0 +1,84 @@
+package session
+
+import (
+	"encoding/json"
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+	"time"
+
+	"github.com/scottgl9/thop/internal/logger"
+	"github.com/scottgl9/thop/internal/restriction"
+)
+
+// Audit actions recorded in the restriction audit log
+const (
+	AuditBlocked  = "blocked"
+	AuditBypassed = "bypassed"
+	AuditRaw      = "raw"
+)
+
+// AuditRecord is a single line in the restriction audit log
+type AuditRecord struct {
+	Timestamp   string `json:"timestamp"`
+	Action      string `json:"action"`
+	Session     string `json:"session"`
+	Command     string `json:"command"`
+	Category    string `json:"category,omitempty"`
+	RuleCommand string `json:"rule_command,omitempty"`
+}
+
+// audit appends a record of a blocked, bypassed or raw command to the
+// restriction audit file, if one is configured. rule is nil for a raw command
+// no rule matches. It is written regardless of the log level.
+func (m *Manager) audit(action, sessionName, cmd string, rule *restriction.Rule) {
+	path := m.config.Settings.RestrictionAuditFile
+	if path == "" {
+		return
+	}
+	if strings.HasPrefix(path, "~/") {
+		if home, err := os.UserHomeDir(); err == nil {
+			path = filepath.Join(home, path[2:])
+		}
+	}
+
+	record := AuditRecord{
+		Timestamp: time.Now().UTC().Format(time.RFC3339Nano),
+		Action:    action,
+		Session:   sessionName,
+		Command:   cmd,
+	}
+	if rule != nil {
+		record.Category = string(rule.Category)
+		record.RuleCommand = rule.Command
+	}
+	data, err := json.Marshal(record)
+	if err != nil {
+		logger.Error("failed to encode audit record: %v", err)
+		return
+	}
+
+	m.auditMu.Lock()
+	defer m.auditMu.Unlock()
+
+	if err := appendLine(path, data); err != nil {
+		logger.Error("failed to write restriction audit log: %v", err)
+	}
+}
+
+// appendLine appends data and a newline to path in a single write
+func appendLine(path string, data []byte) error {
+	if err := os.MkdirAll(filepath.Dir(path), 0700); err != nil {
+		return fmt.Errorf("failed to create audit directory: %w", err)
+	}
+
+	file, err := os.OpenFile(path, os.O_CREATE|os.O_APPEND|os.O_WRONLY, 0600)
+	if err != nil {
+		return fmt.Errorf("failed to open audit file: %w", err)
+	}
+	defer file.Close()
+
+	_, err = file.Write(append(data, '\n'))
+	return err
+}
@@ -0,0 +1,145 @@
+package session
+
+import (
+	"fmt"
+	"io/fs"
+	"path/filepath"
+	"sort"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// ChangedFile is a file reported by ChangedFiles
+type ChangedFile struct {
+	Path    string    `json:"path"`
+	ModTime time.Time `json:"mtime"`
+}
+
+// ParseSince parses the cutoff for ChangedFiles relative to now. It accepts
+// relative times such as "30s", "10m", "2h", "3d" or "1w" (meaning that long
+// ago), and absolute times as RFC 3339, "2006-01-02 15:04" or "2006-01-02"
+// in local time.
+func ParseSince(s string, now time.Time) (time.Time, error) {
+	s = strings.TrimSpace(s)
+	if s == "" {
+		return time.Time{}, fmt.Errorf("empty time")
+	}
+
+	units := map[byte]time.Duration{
+		's': time.Second,
+		'm': time.Minute,
+		'h': time.Hour,
+		'd': 24 * time.Hour,
+		'w': 7 * 24 * time.Hour,
+	}
+	if unit, ok := units[s[len(s)-1]]; ok {
+		if n, err := strconv.Atoi(s[:len(s)-1]); err == nil {
+			if n < 0 {
+				return time.Time{}, fmt.Errorf("invalid time %q: must not be negative", s)
+			}
+			return now.Add(-time.Duration(n) * unit), nil
+		}
+	}
+
+	if t, err := time.Parse(time.RFC3339, s); err == nil {
+		return t, nil
+	}
+	for _, layout := range []string{"2006-01-02 15:04:05", "2006-01-02 15:04", "2006-01-02"} {
+		if t, err := time.ParseInLocation(layout, s, now.Location()); err == nil {
+			return t, nil
+		}
+	}
+	return time.Time{}, fmt.Errorf("invalid time %q: use a relative time like 10m, 2h or 3d, or a date like 2006-01-02", s)
+}
+
+// changedFilesLocal walks dir and returns the regular files modified after since
+func changedFilesLocal(dir string, since time.Time) ([]ChangedFile, error) {
+	var changed []ChangedFile
+	err := filepath.WalkDir(dir, func(path string, d fs.DirEntry, err error) error {
+		if err != nil {
+			if path == dir {
+				return err
+			}
+			// Skip unreadable subtrees rather than failing the whole walk
+			if d != nil && d.IsDir() {
+				return fs.SkipDir
+			}
+			return nil
+		}
+		if !d.Type().IsRegular() {
+			return nil
+		}
+		info, err := d.Info()
+		if err != nil {
+			return nil
+		}
+		if info.ModTime().After(since) {
+			changed = append(changed, ChangedFile{Path: path, ModTime: info.ModTime()})
+		}
+		return nil
+	})
+	if err != nil {
+		return nil, fmt.Errorf("failed to walk %s: %w", dir, err)
+	}
+	return changed, nil
+}
+
+// changedFilesCommand builds the find command listing files under dir
+// modified after since, one "<epoch seconds> <path>" line per file
+func changedFilesCommand(dir string, since time.Time) string {
+	return fmt.Sprintf("find %s -type f -newermt %s -printf '%%T@ %%p\\n'",
+		ShellQuote(dir), ShellQuote(fmt.Sprintf("@%d", since.Unix())))
+}
+
+// parseChangedFiles parses the output of changedFilesCommand
+func parseChangedFiles(output string) []ChangedFile {
+	var changed []ChangedFile
+	for _, line := range strings.Split(output, "\n") {
+		stamp, path, ok := strings.Cut(line, " ")
+		if !ok || path == "" {
+			continue
+		}
+		secs, err := strconv.ParseFloat(stamp, 64)
+		if err != nil {
+			continue
+		}
+		mtime := time.Unix(0, int64(secs*float64(time.Second)))
+		changed = append(changed, ChangedFile{Path: path, ModTime: mtime})
+	}
+	return changed
+}
+
+// ChangedFiles lists the regular files under dir on the named session (the
+// active session if name is empty) that were modified after since, most
+// recent first. Local sessions walk the filesystem; SSH sessions run find.
+func (m *Manager) ChangedFiles(sessionName, dir string, since time.Time) ([]ChangedFile, error) {
+	session, err := m.lookupSession(sessionName)
+	if err != nil {
+		return nil, err
+	}
+
+	var changed []ChangedFile
+	if session.Type() == "local" {
+		if changed, err = changedFilesLocal(FilePath(session, dir), since); err != nil {
+			return nil, err
+		}
+	} else {
+		result, err := m.ExecuteOn(session.Name(), changedFilesCommand(dir, since))
+		if err != nil {
+			return nil, err
+		}
+		if result.ExitCode != 0 && strings.TrimSpace(result.Stdout) == "" {
+			return nil, fmt.Errorf("failed to list changed files in %s: %s", dir, strings.TrimSpace(result.Stderr))
+		}
+		changed = parseChangedFiles(result.Stdout)
+	}
+
+	sort.Slice(changed, func(i, j int) bool {
+		if !changed[i].ModTime.Equal(changed[j].ModTime) {
+			return changed[i].ModTime.After(changed[j].ModTime)
+		}
+		return changed[i].Path < changed[j].Path
+	})
+	return changed, nil
+}
@@ -0,0 +1,105 @@
+package session
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+	"time"
+)
+
+func TestParseSince(t *testing.T) {
+	now := time.Date(2024, 6, 1, 12, 0, 0, 0, time.UTC)
+
+	tests := []struct {
+		input string
+		want  time.Time
+	}{
+		{"30s", now.Add(-30 * time.Second)},
+		{"10m", now.Add(-10 * time.Minute)},
+		{"2h", now.Add(-2 * time.Hour)},
+		{"3d", now.Add(-72 * time.Hour)},
+		{"1w", now.Add(-7 * 24 * time.Hour)},
+		{"0m", now},
+		{"2024-05-31T08:30:00Z", time.Date(2024, 5, 31, 8, 30, 0, 0, time.UTC)},
+		{"2024-05-31 08:30", time.Date(2024, 5, 31, 8, 30, 0, 0, time.UTC)},
+		{"2024-05-31", time.Date(2024, 5, 31, 0, 0, 0, 0, time.UTC)},
+	}
+	for _, tt := range tests {
+		got, err := ParseSince(tt.input, now)
+		if err != nil {
+			t.Errorf("ParseSince(%q) failed: %v", tt.input, err)
+			continue
+		}
+		if !got.Equal(tt.want) {
+			t.Errorf("ParseSince(%q) = %v, want %v", tt.input, got, tt.want)
+		}
+	}
+
+	for _, input := range []string{"", "10", "m", "-5m", "10y", "yesterday"} {
+		if _, err := ParseSince(input, now); err == nil {
+			t.Errorf("expected ParseSince(%q) to fail", input)
+		}
+	}
+}
+
+func TestChangedFilesLocal(t *testing.T) {
+	dir := t.TempDir()
+	now := time.Now()
+	files := map[string]time.Duration{
+		"old.txt":        -3 * time.Hour,
+		"recent.txt":     -5 * time.Minute,
+		"sub/newest.txt": -time.Minute,
+		"sub/stale.log":  -48 * time.Hour,
+	}
+	for name, age := range files {
+		path := filepath.Join(dir, name)
+		if err := os.MkdirAll(filepath.Dir(path), 0755); err != nil {
+			t.Fatal(err)
+		}
+		if err := os.WriteFile(path, []byte(name), 0644); err != nil {
+			t.Fatal(err)
+		}
+		mtime := now.Add(age)
+		if err := os.Chtimes(path, mtime, mtime); err != nil {
+			t.Fatal(err)
+		}
+	}
+
+	mgr, _ := createTestManager(t)
+	local, _ := mgr.GetSession("local")
+	if err := local.SetCWD(dir); err != nil {
+		t.Fatal(err)
+	}
+
+	changed, err := mgr.ChangedFiles("local", ".", now.Add(-time.Hour))
+	if err != nil {
+		t.Fatalf("ChangedFiles failed: %v", err)
+	}
+	want := []string{filepath.Join(dir, "sub/newest.txt"), filepath.Join(dir, "recent.txt")}
+	if len(changed) != len(want) {
+		t.Fatalf("expected %d changed files, got %+v", len(want), changed)
+	}
+	for i, path := range want {
+		if changed[i].Path != path {
+			t.Errorf("changed[%d] = %s, want %s (newest first)", i, changed[i].Path, path)
+		}
+	}
+
+	if _, err := mgr.ChangedFiles("local", filepath.Join(dir, "missing"), now); err == nil {
+		t.Error("expected error for a missing directory")
+	}
+}
+
+func TestParseChangedFiles(t *testing.T) {
+	output := "1717243200.5000000000 /srv/app/config.yml\n1717240000.0000000000 /srv/app/a file.txt\n\ngarbage\n"
+	changed := parseChangedFiles(output)
+	if len(changed) != 2 {
+		t.Fatalf("expected 2 entries, got %+v", changed)
+	}
+	if changed[1].Path != "/srv/app/a file.txt" || changed[1].ModTime.Unix() != 1717240000 {
+		t.Errorf("unexpected entry %+v", changed[1])
+	}
+	if changed[0].ModTime.UnixMilli() != 1717243200500 {
+		t.Errorf("expected fractional mtime to be kept, got %v", changed[0].ModTime)
+	}
+}
@@ -0,0 +1,52 @@
+package session
+
+// EventType identifies what changed in a SessionEvent
+type EventType string
+
+const (
+	EventActivated    EventType = "activated"
+	EventConnected    EventType = "connected"
+	EventDisconnected EventType = "disconnected"
+)
+
+// SessionEvent reports a change of active session or of a session's connection
+type SessionEvent struct {
+	Type    EventType
+	Session string
+	View    *View // For EventActivated, the view that switched; nil when the manager's own active session changed
+}
+
+// OnSessionEvent registers fn to be called after each session event and
+// returns a function that unregisters it. fn runs on the goroutine that made
+// the change, with no Manager locks held, and should not block.
+func (m *Manager) OnSessionEvent(fn func(SessionEvent)) (remove func()) {
+	m.listenersMu.Lock()
+	defer m.listenersMu.Unlock()
+
+	if m.listeners == nil {
+		m.listeners = make(map[int]func(SessionEvent))
+	}
+	id := m.nextListener
+	m.nextListener++
+	m.listeners[id] = fn
+
+	return func() {
+		m.listenersMu.Lock()
+		defer m.listenersMu.Unlock()
+		delete(m.listeners, id)
+	}
+}
+
+// emit calls the registered listeners with event; m.mu must not be held
+func (m *Manager) emit(event SessionEvent) {
+	m.listenersMu.Lock()
+	listeners := make([]func(SessionEvent), 0, len(m.listeners))
+	for _, fn := range m.listeners {
+		listeners = append(listeners, fn)
+	}
+	m.listenersMu.Unlock()
+
+	for _, fn := range listeners {
+		fn(event)
+	}
+}
@@ -0,0 +1,45 @@
+package session
+
+import (
+	"testing"
+)
+
+func TestOnSessionEvent(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	var events []SessionEvent
+	remove := mgr.OnSessionEvent(func(event SessionEvent) {
+		events = append(events, event)
+	})
+
+	if err := mgr.SetActiveSession("testserver"); err != nil {
+		t.Fatalf("SetActiveSession failed: %v", err)
+	}
+	view := mgr.NewView()
+	if err := view.SetActiveSession("local"); err != nil {
+		t.Fatalf("view SetActiveSession failed: %v", err)
+	}
+	_ = mgr.Disconnect("testserver")
+
+	want := []SessionEvent{
+		{Type: EventActivated, Session: "testserver"},
+		{Type: EventActivated, Session: "local", View: view},
+		{Type: EventDisconnected, Session: "testserver"},
+	}
+	if len(events) != len(want) {
+		t.Fatalf("expected %d events, got %+v", len(want), events)
+	}
+	for i := range want {
+		if events[i] != want[i] {
+			t.Errorf("event %d: expected %+v, got %+v", i, want[i], events[i])
+		}
+	}
+
+	remove()
+	if err := mgr.SetActiveSession("local"); err != nil {
+		t.Fatalf("SetActiveSession failed: %v", err)
+	}
+	if len(events) != len(want) {
+		t.Errorf("expected no events after removing the listener, got %+v", events[len(want):])
+	}
+}
@@ -0,0 +1,184 @@
+package session
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+)
+
+// lookupSession returns the named session (the active session if name is
+// empty), or ErrSessionNotFound if there is no such session
+func (m *Manager) lookupSession(sessionName string) (Session, error) {
+	if sessionName == "" {
+		sessionName = m.GetActiveSessionName()
+	}
+	session, ok := m.GetSession(sessionName)
+	if !ok {
+		return nil, &Error{
+			Code:    ErrSessionNotFound,
+			Message: fmt.Sprintf("Session '%s' not found", sessionName),
+			Session: sessionName,
+		}
+	}
+	return session, nil
+}
+
+// connectedSession looks up a session for an operation, such as a file
+// operation, which needs the session to be connected
+func (m *Manager) connectedSession(sessionName string) (Session, error) {
+	session, err := m.lookupSession(sessionName)
+	if err != nil {
+		return nil, err
+	}
+	if !session.IsConnected() {
+		return nil, notConnectedError(session.Name())
+	}
+	return session, nil
+}
+
+// notConnectedError is the error for an operation on a disconnected session
+func notConnectedError(sessionName string) *Error {
+	return &Error{
+		Code:       ErrSessionDisconnected,
+		Message:    fmt.Sprintf("Session '%s' is not connected", sessionName),
+		Session:    sessionName,
+		Suggestion: fmt.Sprintf("Use /connect %s to connect first", sessionName),
+	}
+}
+
+// CheckFileWrite returns ErrCommandRestricted if restricted mode is on
+// globally or for the named session (the active session if name is empty).
+// Writing a file directly would otherwise get around the rules that block
+// commands like "> file".
+func (m *Manager) CheckFileWrite(sessionName string) error {
+	if sessionName == "" {
+		sessionName = m.GetActiveSessionName()
+	}
+	if m.IsRestrictedMode() || m.IsSessionRestricted(sessionName) {
+		return &Error{
+			Code:       ErrCommandRestricted,
+			Message:    "Writing files is not allowed in restricted mode",
+			Session:    sessionName,
+			Suggestion: "Remove --restricted flag to allow file writes",
+		}
+	}
+	return nil
+}
+
+// ReadFile reads a file on the named session (the active session if name is
+// empty), byte for byte. SSH sessions read over SFTP. It returns the contents
+// and the resolved path that was read.
+func (m *Manager) ReadFile(sessionName, path string) ([]byte, string, error) {
+	session, err := m.connectedSession(sessionName)
+	if err != nil {
+		return nil, "", err
+	}
+
+	// Resolve the path the way a shell command on the session would
+	path = FilePath(session, path)
+
+	if sshSession, ok := session.(*SSHSession); ok {
+		data, err := sshSession.ReadFile(path)
+		return data, path, err
+	}
+
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return nil, path, fmt.Errorf("failed to read file: %w", err)
+	}
+	return data, path, nil
+}
+
+// FileMode returns the permission bits of a file on the named session (the
+// active session if name is empty)
+func (m *Manager) FileMode(sessionName, path string) (os.FileMode, error) {
+	session, err := m.connectedSession(sessionName)
+	if err != nil {
+		return 0, err
+	}
+
+	path = FilePath(session, path)
+	if sshSession, ok := session.(*SSHSession); ok {
+		return sshSession.FileMode(path)
+	}
+
+	info, err := os.Stat(path)
+	if err != nil {
+		return 0, fmt.Errorf("failed to stat file: %w", err)
+	}
+	return info.Mode().Perm(), nil
+}
+
+// WriteFile writes data to a file on the named session (the active session if
+// name is empty). SSH sessions write over SFTP. A zero mode keeps the default
+// permissions, which honor the umask. It returns the resolved path written.
+func (m *Manager) WriteFile(sessionName, path string, data []byte, mode os.FileMode) (string, error) {
+	session, err := m.connectedSession(sessionName)
+	if err != nil {
+		return "", err
+	}
+
+	// Resolve the path the way a shell command on the session would
+	path = FilePath(session, path)
+
+	if sshSession, ok := session.(*SSHSession); ok {
+		return path, sshSession.WriteFile(path, data, mode)
+	}
+
+	perm := os.FileMode(0644)
+	if mode != 0 {
+		perm = mode
+	}
+	if err := os.WriteFile(path, data, perm); err != nil {
+		return path, fmt.Errorf("failed to write file: %w", err)
+	}
+	// Apply the exact mode regardless of umask or an existing file's mode
+	if mode != 0 {
+		if err := os.Chmod(path, mode); err != nil {
+			return path, fmt.Errorf("failed to set file mode: %w", err)
+		}
+	}
+	return path, nil
+}
+
+// ReplaceFile writes data to a file on the named session (the active session
+// if name is empty) by writing a temporary file next to it and renaming it
+// into place, so the file is never left half-written. A zero mode uses 0644
+// for local files. It returns the resolved path written.
+func (m *Manager) ReplaceFile(sessionName, path string, data []byte, mode os.FileMode) (string, error) {
+	session, err := m.connectedSession(sessionName)
+	if err != nil {
+		return "", err
+	}
+
+	// Resolve the path the way a shell command on the session would
+	path = FilePath(session, path)
+
+	if sshSession, ok := session.(*SSHSession); ok {
+		return path, sshSession.ReplaceFile(path, data, mode)
+	}
+
+	perm := os.FileMode(0644)
+	if mode != 0 {
+		perm = mode
+	}
+	tmpFile, err := os.CreateTemp(filepath.Dir(path), "."+filepath.Base(path)+".thop-*")
+	if err != nil {
+		return path, fmt.Errorf("failed to create temp file: %w", err)
+	}
+	err = tmpFile.Chmod(perm)
+	if err == nil {
+		_, err = tmpFile.Write(data)
+	}
+	if closeErr := tmpFile.Close(); err == nil {
+		err = closeErr
+	}
+	if err == nil {
+		err = os.Rename(tmpFile.Name(), path)
+	}
+	if err != nil {
+		_ = os.Remove(tmpFile.Name())
+		return path, fmt.Errorf("failed to replace file: %w", err)
+	}
+	return path, nil
+}
@@ -0,0 +1,55 @@
+package session
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+func TestManagerReplaceFileLocal(t *testing.T) {
+	mgr, _ := createTestManager(t)
+	dir := t.TempDir()
+	path := filepath.Join(dir, "notes.txt")
+	if err := os.WriteFile(path, []byte("old\n"), 0600); err != nil {
+		t.Fatal(err)
+	}
+
+	resolved, err := mgr.ReplaceFile("local", path, []byte("new\n"), 0600)
+	if err != nil {
+		t.Fatalf("ReplaceFile failed: %v", err)
+	}
+	if resolved != path {
+		t.Errorf("resolved path = %q, want %q", resolved, path)
+	}
+
+	data, err := os.ReadFile(path)
+	if err != nil {
+		t.Fatalf("failed to read file: %v", err)
+	}
+	if string(data) != "new\n" {
+		t.Errorf("expected replaced contents, got %q", data)
+	}
+	info, err := os.Stat(path)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if info.Mode().Perm() != 0600 {
+		t.Errorf("expected mode 0600, got %o", info.Mode().Perm())
+	}
+
+	// No temp file is left next to the original
+	entries, err := os.ReadDir(dir)
+	if err != nil {
+		t.Fatal(err)
+	}
+	for _, entry := range entries {
+		if strings.Contains(entry.Name(), ".thop-") {
+			t.Errorf("temp file %s left behind", entry.Name())
+		}
+	}
+
+	if _, err := mgr.ReplaceFile("missing", path, nil, 0); err == nil {
+		t.Error("expected an error for an unknown session")
+	}
+}
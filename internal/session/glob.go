@@ -0,0 +1,133 @@
+package session
+
+import (
+	"fmt"
+	"os"
+	"path"
+	"path/filepath"
+	"sort"
+	"strings"
+
+	"github.com/pkg/sftp"
+)
+
+// HasGlob reports whether path contains glob metacharacters (*, ? or [)
+func HasGlob(path string) bool {
+	return strings.ContainsAny(path, "*?[")
+}
+
+// GlobFiles returns the regular files matching a local glob pattern, sorted
+func (s *LocalSession) GlobFiles(pattern string) ([]string, error) {
+	matches, err := filepath.Glob(pattern)
+	if err != nil {
+		return nil, fmt.Errorf("invalid pattern %q: %w", pattern, err)
+	}
+
+	files := make([]string, 0, len(matches))
+	for _, match := range matches {
+		if info, err := os.Stat(match); err == nil && info.Mode().IsRegular() {
+			files = append(files, match)
+		}
+	}
+	return files, nil
+}
+
+// GlobFiles returns the regular files matching a glob pattern on the remote
+// host, expanded over SFTP, sorted
+func (s *SSHSession) GlobFiles(pattern string) ([]string, error) {
+	if !s.IsConnected() {
+		return nil, fmt.Errorf("session is not connected")
+	}
+
+	sftpClient, err := sftp.NewClient(s.client)
+	if err != nil {
+		return nil, fmt.Errorf("failed to create SFTP client: %w", err)
+	}
+	defer sftpClient.Close()
+
+	matches, err := sftpClient.Glob(pattern)
+	if err != nil {
+		return nil, fmt.Errorf("invalid pattern %q: %w", pattern, err)
+	}
+
+	files := make([]string, 0, len(matches))
+	for _, match := range matches {
+		if info, err := sftpClient.Stat(match); err == nil && info.Mode().IsRegular() {
+			files = append(files, match)
+		}
+	}
+	sort.Strings(files)
+	return files, nil
+}
+
+// globDestination returns the path a glob match is copied to: a file of the
+// same name inside the destination directory dir
+func globDestination(dir, match string, remote bool) string {
+	name := filepath.Base(match)
+	if remote {
+		return path.Join(dir, name)
+	}
+	return filepath.Join(dir, name)
+}
+
+// GlobCopy describes one file copied by CopyGlob
+type GlobCopy struct {
+	Source      string
+	Destination string
+	Bytes       int64
+}
+
+// CopyGlob copies every file matching the glob in src's path into the
+// directory named by dst, one CopyFile per match. The glob is expanded on the
+// source session, and relative paths are resolved against each session's cwd.
+// It stops at the first failed copy and returns the copies made before it.
+func (m *Manager) CopyGlob(src, dst string) ([]GlobCopy, error) {
+	srcSess, pattern, err := m.resolveFileSpec(src, "source")
+	if err != nil {
+		return nil, err
+	}
+	dstSess, dir, err := m.resolveFileSpec(dst, "destination")
+	if err != nil {
+		return nil, err
+	}
+	pattern = FilePath(srcSess, pattern)
+	dir = FilePath(dstSess, dir)
+
+	var matches []string
+	switch sess := srcSess.(type) {
+	case *LocalSession:
+		matches, err = sess.GlobFiles(pattern)
+	case *SSHSession:
+		if !sess.IsConnected() {
+			if err := m.Connect(sess.Name()); err != nil {
+				return nil, err
+			}
+		}
+		matches, err = sess.GlobFiles(pattern)
+	default:
+		return nil, fmt.Errorf("session '%s' does not support globs", srcSess.Name())
+	}
+	if err != nil {
+		return nil, err
+	}
+	if len(matches) == 0 {
+		return nil, &Error{
+			Code:       ErrGlobNoMatch,
+			Message:    fmt.Sprintf("No files match %s on session '%s'", pattern, srcSess.Name()),
+			Session:    srcSess.Name(),
+			Suggestion: "Check the pattern; it is expanded on the source session",
+		}
+	}
+
+	_, dstRemote := dstSess.(*SSHSession)
+	copies := make([]GlobCopy, 0, len(matches))
+	for _, match := range matches {
+		target := globDestination(dir, match, dstRemote)
+		copied, err := m.copyResolved(srcSess, match, dstSess, target)
+		if err != nil {
+			return copies, fmt.Errorf("failed to copy %s: %w", match, err)
+		}
+		copies = append(copies, GlobCopy{Source: match, Destination: target, Bytes: copied})
+	}
+	return copies, nil
+}
@@ -0,0 +1,78 @@
+package session
+
+import (
+	"os"
+	"path/filepath"
+	"reflect"
+	"testing"
+)
+
+func TestHasGlob(t *testing.T) {
+	tests := map[string]bool{
+		"/var/log/*.log":  true,
+		"file?.txt":       true,
+		"data[0-9].csv":   true,
+		"/etc/hosts":      false,
+		"./relative/path": false,
+	}
+	for path, want := range tests {
+		if got := HasGlob(path); got != want {
+			t.Errorf("HasGlob(%q) = %v, want %v", path, got, want)
+		}
+	}
+}
+
+func TestLocalGlobFiles(t *testing.T) {
+	dir := t.TempDir()
+	for _, name := range []string{"b.log", "a.log", "notes.txt"} {
+		if err := os.WriteFile(filepath.Join(dir, name), []byte(name), 0644); err != nil {
+			t.Fatal(err)
+		}
+	}
+	// Directories that match are skipped
+	if err := os.Mkdir(filepath.Join(dir, "old.log"), 0755); err != nil {
+		t.Fatal(err)
+	}
+
+	sess := NewLocalSession("local", "/bin/sh")
+	matches, err := sess.GlobFiles(filepath.Join(dir, "*.log"))
+	if err != nil {
+		t.Fatalf("GlobFiles failed: %v", err)
+	}
+	want := []string{filepath.Join(dir, "a.log"), filepath.Join(dir, "b.log")}
+	if !reflect.DeepEqual(matches, want) {
+		t.Errorf("expected %v, got %v", want, matches)
+	}
+
+	matches, err = sess.GlobFiles(filepath.Join(dir, "*.csv"))
+	if err != nil || len(matches) != 0 {
+		t.Errorf("expected no matches, got %v (%v)", matches, err)
+	}
+}
+
+func TestGlobDestination(t *testing.T) {
+	tests := []struct {
+		dir, match string
+		remote     bool
+		want       string
+	}{
+		{"/tmp/", "logs/app.log", true, "/tmp/app.log"},
+		{"/tmp", "/var/log/syslog", true, "/tmp/syslog"},
+		{"backup", "/var/log/app.log", false, filepath.Join("backup", "app.log")},
+	}
+	for _, tt := range tests {
+		if got := globDestination(tt.dir, tt.match, tt.remote); got != tt.want {
+			t.Errorf("globDestination(%q, %q, %v) = %q, want %q", tt.dir, tt.match, tt.remote, got, tt.want)
+		}
+	}
+}
+
+func TestManagerCopyGlobNoMatch(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	_, err := mgr.CopyGlob("local:"+filepath.Join(t.TempDir(), "*.log"), "testserver:/tmp/")
+	sessionErr, ok := err.(*Error)
+	if !ok || sessionErr.Code != ErrGlobNoMatch {
+		t.Errorf("expected %s, got %v", ErrGlobNoMatch, err)
+	}
+}
@@ -0,0 +1,72 @@
+package session
+
+import (
+	"context"
+	"fmt"
+	"io"
+	"strconv"
+	"strings"
+)
+
+// JournalOptions selects the systemd journal entries Journal and
+// FollowJournal show
+type JournalOptions struct {
+	Lines       int  // Number of most recent entries to show
+	Follow      bool // Keep streaming new entries
+	MessageOnly bool // Show only each entry's message, without timestamp, host and process
+}
+
+// journalCommand builds the journalctl command showing unit's entries
+func journalCommand(unit string, opts JournalOptions) string {
+	args := []string{"journalctl", "--no-pager", "-u", ShellQuote(unit), "-n", strconv.Itoa(opts.Lines)}
+	if opts.Follow {
+		args = append(args, "-f")
+	}
+	if opts.MessageOnly {
+		args = append(args, "-o", "cat")
+	}
+	return strings.Join(args, " ")
+}
+
+// Journal returns the most recent systemd journal entries for unit on the
+// named session (the active session if name is empty)
+func (m *Manager) Journal(sessionName, unit string, opts JournalOptions) (string, error) {
+	if sessionName == "" {
+		sessionName = m.GetActiveSessionName()
+	}
+	opts.Follow = false
+
+	result, err := m.ExecuteOn(sessionName, journalCommand(unit, opts))
+	if err != nil {
+		return "", err
+	}
+	if result.ExitCode != 0 {
+		return "", fmt.Errorf("journalctl failed on %s: %s", sessionName, strings.TrimSpace(result.Stderr))
+	}
+	return result.Stdout, nil
+}
+
+// FollowJournal writes the most recent systemd journal entries for unit on
+// the named session (the active session if name is empty) to stdout, then
+// streams new entries until ctx is canceled
+func (m *Manager) FollowJournal(ctx context.Context, sessionName, unit string, opts JournalOptions, stdout, stderr io.Writer) error {
+	session, err := m.connectedSession(sessionName)
+	if err != nil {
+		return err
+	}
+
+	executor, ok := session.(StreamExecutor)
+	if !ok {
+		return fmt.Errorf("session '%s' does not support streaming output", session.Name())
+	}
+
+	opts.Follow = true
+	exitCode, err := executor.ExecuteStream(ctx, journalCommand(unit, opts), stdout, stderr)
+	if err != nil {
+		return err
+	}
+	if exitCode != 0 && exitCode != 130 {
+		return fmt.Errorf("journalctl exited with status %d", exitCode)
+	}
+	return nil
+}
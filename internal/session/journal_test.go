@@ -0,0 +1,25 @@
+package session
+
+import "testing"
+
+func TestJournalCommand(t *testing.T) {
+	tests := []struct {
+		name string
+		unit string
+		opts JournalOptions
+		want string
+	}{
+		{"last lines", "nginx", JournalOptions{Lines: 10}, "journalctl --no-pager -u 'nginx' -n 10"},
+		{"follow", "nginx.service", JournalOptions{Lines: 50, Follow: true}, "journalctl --no-pager -u 'nginx.service' -n 50 -f"},
+		{"messages only", "sshd", JournalOptions{Lines: 5, MessageOnly: true}, "journalctl --no-pager -u 'sshd' -n 5 -o cat"},
+		{"follow messages", "app", JournalOptions{Lines: 0, Follow: true, MessageOnly: true}, "journalctl --no-pager -u 'app' -n 0 -f -o cat"},
+		{"quoted unit", "my app; reboot", JournalOptions{Lines: 10}, "journalctl --no-pager -u 'my app; reboot' -n 10"},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if got := journalCommand(tt.unit, tt.opts); got != tt.want {
+				t.Errorf("journalCommand() = %q, want %q", got, tt.want)
+			}
+		})
+	}
+}
@@ -0,0 +1,95 @@
+package session
+
+import (
+	"fmt"
+	"path"
+	"strconv"
+	"strings"
+)
+
+// DefaultKillSignal is the signal KillProcess sends when none is given
+const DefaultKillSignal = "TERM"
+
+// processName returns the program name of a command line: the base name of
+// its first word, without the trailing colon some daemons add ("sshd:")
+func processName(command string) string {
+	first, _ := cutField(command)
+	return strings.TrimSuffix(path.Base(first), ":")
+}
+
+// FindProcessesByName lists the processes on the named session (the active
+// session if name is empty) whose program name is exactly name, so "nginx"
+// matches /usr/sbin/nginx but not an editor with nginx.conf open
+func (m *Manager) FindProcessesByName(sessionName, name string) ([]Process, error) {
+	processes, err := m.ListProcesses(sessionName, name)
+	if err != nil {
+		return nil, err
+	}
+	var matched []Process
+	for _, p := range processes {
+		if processName(p.Command) == name {
+			matched = append(matched, p)
+		}
+	}
+	return matched, nil
+}
+
+// NormalizeSignal checks a signal given as a name (TERM, SIGTERM, hup) or a
+// number (9) and returns it in the form kill accepts: an upper-case name
+// without the SIG prefix, or the number
+func NormalizeSignal(signal string) (string, error) {
+	if signal == "" {
+		return DefaultKillSignal, nil
+	}
+	if n, err := strconv.Atoi(signal); err == nil {
+		if n < 0 || n > 64 {
+			return "", fmt.Errorf("invalid signal number %d", n)
+		}
+		return signal, nil
+	}
+
+	name := strings.TrimPrefix(strings.ToUpper(signal), "SIG")
+	if name == "" {
+		return "", fmt.Errorf("invalid signal %q", signal)
+	}
+	for _, r := range name {
+		if (r < 'A' || r > 'Z') && (r < '0' || r > '9') && r != '+' && r != '-' {
+			return "", fmt.Errorf("invalid signal %q", signal)
+		}
+	}
+	return name, nil
+}
+
+// killCommand builds the kill command sending signal, as returned by
+// NormalizeSignal, to pid
+func killCommand(pid int, signal string) string {
+	if _, err := strconv.Atoi(signal); err == nil {
+		return fmt.Sprintf("kill -%s %d", signal, pid)
+	}
+	return fmt.Sprintf("kill -s %s %d", signal, pid)
+}
+
+// KillProcess sends signal (DefaultKillSignal if empty) to pid on the named
+// session (the active session if name is empty). The kill command goes
+// through the session's restriction checks like any other command.
+func (m *Manager) KillProcess(sessionName string, pid int, signal string) error {
+	if sessionName == "" {
+		sessionName = m.GetActiveSessionName()
+	}
+	if pid <= 0 {
+		return fmt.Errorf("invalid PID %d", pid)
+	}
+	signal, err := NormalizeSignal(signal)
+	if err != nil {
+		return err
+	}
+
+	result, err := m.ExecuteOn(sessionName, killCommand(pid, signal))
+	if err != nil {
+		return err
+	}
+	if result.ExitCode != 0 {
+		return fmt.Errorf("failed to send %s to %d on %s: %s", signal, pid, sessionName, strings.TrimSpace(result.Stderr))
+	}
+	return nil
+}
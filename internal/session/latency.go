@@ -0,0 +1,68 @@
+package session
+
+import (
+	"context"
+	"fmt"
+	"time"
+)
+
+// latencyProbeCommand is the no-op command timed by MeasureLatency
+const latencyProbeCommand = "true"
+
+// LatencyStats summarizes round-trip times of a series of latency probes
+type LatencyStats struct {
+	Samples int
+	Min     time.Duration
+	Avg     time.Duration
+	Max     time.Duration
+}
+
+// summarizeLatency computes min/avg/max over the given sample durations
+func summarizeLatency(samples []time.Duration) LatencyStats {
+	stats := LatencyStats{Samples: len(samples)}
+	if len(samples) == 0 {
+		return stats
+	}
+
+	var total time.Duration
+	stats.Min = samples[0]
+	for _, d := range samples {
+		total += d
+		if d < stats.Min {
+			stats.Min = d
+		}
+		if d > stats.Max {
+			stats.Max = d
+		}
+	}
+	stats.Avg = total / time.Duration(len(samples))
+	return stats
+}
+
+// MeasureLatency times a no-op command on the named session (the active
+// session if name is empty) over the existing connection, once per sample.
+// Each probe runs verbatim, so only the round trip is measured.
+func (m *Manager) MeasureLatency(ctx context.Context, sessionName string, samples int) (LatencyStats, error) {
+	session, err := m.connectedSession(sessionName)
+	if err != nil {
+		return LatencyStats{}, err
+	}
+	executor, ok := session.(VerbatimExecutor)
+	if !ok {
+		return LatencyStats{}, fmt.Errorf("session '%s' does not support latency probes", session.Name())
+	}
+
+	durations := make([]time.Duration, 0, samples)
+	for i := 0; i < samples; i++ {
+		start := time.Now()
+		result, err := executor.ExecuteVerbatim(ctx, latencyProbeCommand)
+		if err != nil {
+			return LatencyStats{}, err
+		}
+		if result.ExitCode != 0 {
+			return LatencyStats{}, fmt.Errorf("latency probe exited with code %d on session '%s'", result.ExitCode, session.Name())
+		}
+		durations = append(durations, time.Since(start))
+	}
+	return summarizeLatency(durations), nil
+}
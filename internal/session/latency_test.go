@@ -0,0 +1,44 @@
+package session
+
+import (
+	"context"
+	"testing"
+	"time"
+)
+
+func TestSummarizeLatency(t *testing.T) {
+	ms := time.Millisecond
+	tests := []struct {
+		name    string
+		samples []time.Duration
+		want    LatencyStats
+	}{
+		{"none", nil, LatencyStats{}},
+		{"single", []time.Duration{7 * ms}, LatencyStats{Samples: 1, Min: 7 * ms, Avg: 7 * ms, Max: 7 * ms}},
+		{"several", []time.Duration{12 * ms, 10 * ms, 20 * ms, 14 * ms}, LatencyStats{Samples: 4, Min: 10 * ms, Avg: 14 * ms, Max: 20 * ms}},
+		{"fractional average", []time.Duration{1 * ms, 2 * ms}, LatencyStats{Samples: 2, Min: 1 * ms, Avg: 1500 * time.Microsecond, Max: 2 * ms}},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if got := summarizeLatency(tt.samples); got != tt.want {
+				t.Errorf("summarizeLatency(%v) = %+v, want %+v", tt.samples, got, tt.want)
+			}
+		})
+	}
+}
+
+func TestManagerMeasureLatency(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	stats, err := mgr.MeasureLatency(context.Background(), "local", 3)
+	if err != nil {
+		t.Fatalf("MeasureLatency failed: %v", err)
+	}
+	if stats.Samples != 3 || stats.Min <= 0 || stats.Min > stats.Avg || stats.Avg > stats.Max {
+		t.Errorf("inconsistent stats %+v", stats)
+	}
+
+	if _, err := mgr.MeasureLatency(context.Background(), "testserver", 3); err == nil {
+		t.Error("expected error for a disconnected session")
+	}
+}
@@ -0,0 +1,85 @@
+package session
+
+import (
+	"fmt"
+	"os"
+	"sort"
+	"time"
+
+	"github.com/pkg/sftp"
+)
+
+// DirEntry describes one entry of a directory listing
+type DirEntry struct {
+	Name    string    `json:"name"`
+	IsDir   bool      `json:"is_dir"`
+	Size    int64     `json:"size"`
+	ModTime time.Time `json:"mtime"`
+}
+
+// ListDir lists a local directory. Entries are sorted by name.
+func (s *LocalSession) ListDir(path string) ([]DirEntry, error) {
+	entries, err := os.ReadDir(path)
+	if err != nil {
+		return nil, fmt.Errorf("failed to list directory: %w", err)
+	}
+
+	result := make([]DirEntry, 0, len(entries))
+	for _, entry := range entries {
+		info, err := entry.Info()
+		if err != nil {
+			// The entry was removed while listing
+			continue
+		}
+		result = append(result, dirEntryFromInfo(info))
+	}
+	return result, nil
+}
+
+// ListDir lists a remote directory over SFTP. Entries are sorted by name.
+func (s *SSHSession) ListDir(path string) ([]DirEntry, error) {
+	if !s.IsConnected() {
+		return nil, fmt.Errorf("session is not connected")
+	}
+
+	sftpClient, err := sftp.NewClient(s.client)
+	if err != nil {
+		return nil, fmt.Errorf("failed to create SFTP client: %w", err)
+	}
+	defer sftpClient.Close()
+
+	infos, err := sftpClient.ReadDir(path)
+	if err != nil {
+		return nil, fmt.Errorf("failed to list directory: %w", err)
+	}
+
+	result := make([]DirEntry, 0, len(infos))
+	for _, info := range infos {
+		result = append(result, dirEntryFromInfo(info))
+	}
+	sort.Slice(result, func(i, j int) bool { return result[i].Name < result[j].Name })
+	return result, nil
+}
+
+func dirEntryFromInfo(info os.FileInfo) DirEntry {
+	return DirEntry{
+		Name:    info.Name(),
+		IsDir:   info.IsDir(),
+		Size:    info.Size(),
+		ModTime: info.ModTime(),
+	}
+}
+
+// ListDir lists a directory on the named session (the active session if name
+// is empty). Relative paths are resolved against the session's cwd.
+func (m *Manager) ListDir(sessionName, path string) ([]DirEntry, error) {
+	session, err := m.connectedSession(sessionName)
+	if err != nil {
+		return nil, err
+	}
+
+	if path == "" {
+		path = "."
+	}
+	return session.ListDir(FilePath(session, path))
+}
@@ -13,6 +13,7 @@ import (
 	"time"
 
 	"github.com/creack/pty"
+	"github.com/scottgl9/thop/internal/config"
 	"github.com/scottgl9/thop/internal/logger"
 	"golang.org/x/sys/unix"
 	"golang.org/x/term"
@@ -20,13 +21,17 @@ import (
 
 // LocalSession represents a local shell session
 type LocalSession struct {
-	name            string
-	shell           string
-	cwd             string
-	env             map[string]string
-	connected       bool
-	timeout         time.Duration
-	startupCommands []string
+	name             string
+	shell            string
+	cwd              string
+	env              map[string]string
+	connected        bool
+	timeout          time.Duration
+	startupCommands  []string
+	interactiveShell bool             // Run commands via "$SHELL -ic" so rc files (aliases, functions) are loaded
+	locale           string           // Exported as LC_ALL and LANG for commands ("" leaves the inherited locale)
+	persistent       *persistentShell // Long-lived shell commands run in (nil runs each command in a new shell)
+	runAs            string           // User commands run as via sudo -u ("" for the current user)
 }
 
 // NewLocalSession creates a new local session
@@ -54,6 +59,15 @@ func NewLocalSession(name, shell string) *LocalSession {
 	}
 }
 
+// NewPersistentLocalSession creates a local session that runs its commands in
+// one long-lived shell, so shell variables, functions, aliases and set options
+// carry over from one command to the next
+func NewPersistentLocalSession(name, shell string) *LocalSession {
+	s := NewLocalSession(name, shell)
+	s.persistent = newPersistentShell(s.shell)
+	return s
+}
+
 // SetTimeout sets the command timeout
 func (s *LocalSession) SetTimeout(timeout time.Duration) {
 	s.timeout = timeout
@@ -64,6 +78,28 @@ func (s *LocalSession) SetStartupCommands(commands []string) {
 	s.startupCommands = commands
 }
 
+// SetInteractiveShell enables running commands through an interactive shell
+// ("$SHELL -ic") so the user's rc files are sourced. This is slower since the
+// rc files are loaded for every command.
+func (s *LocalSession) SetInteractiveShell(enabled bool) {
+	s.interactiveShell = enabled
+}
+
+// SetLocale sets the locale exported as LC_ALL and LANG for every command, so
+// output does not depend on the user's locale. An empty locale leaves it as
+// inherited.
+func (s *LocalSession) SetLocale(locale string) {
+	s.locale = locale
+}
+
+// shellArgs returns the shell arguments used to run cmdStr
+func (s *LocalSession) shellArgs(cmdStr string) []string {
+	if s.interactiveShell {
+		return []string{"-i", "-c", cmdStr}
+	}
+	return []string{"-c", cmdStr}
+}
+
 // Name returns the session name
 func (s *LocalSession) Name() string {
 	return s.name
@@ -102,9 +138,12 @@ func (s *LocalSession) runStartupCommands() {
 	}
 }
 
-// Disconnect closes the session (no-op for local)
+// Disconnect closes the session, stopping its persistent shell if it has one
 func (s *LocalSession) Disconnect() error {
 	s.connected = false
+	if s.persistent != nil {
+		s.persistent.kill()
+	}
 	return nil
 }
 
@@ -127,51 +166,56 @@ func (s *LocalSession) ExecuteWithContext(ctx context.Context, cmdStr string) (*
 	if trimmedCmd == "cd" || strings.HasPrefix(trimmedCmd, "cd ") {
 		return s.handleCD(cmdStr)
 	}
+	if s.persistent != nil && s.runAs == "" && s.persistent.mu.TryLock() {
+		defer s.persistent.mu.Unlock()
+		return s.executePersistent(ctx, cmdStr)
+	}
+	// Each command runs in a new shell, so track exports in the session env
+	if env, ok := parseEnvAssignments(trimmedCmd, s.lookupEnv); ok {
+		for k, v := range env {
+			s.env[k] = v
+		}
+		return &ExecuteResult{ExitCode: 0}, nil
+	}
 
 	// Create context with timeout if not already set
 	execCtx, cancel := context.WithTimeout(ctx, s.timeout)
 	defer cancel()
 
+	// runCtx is canceled early if the command stops at a password or confirmation prompt
+	runCtx, stop := context.WithCancel(execCtx)
+	defer stop()
+
 	// Create the command with context
-	cmd := exec.CommandContext(execCtx, s.shell, "-c", cmdStr)
+	cmd := exec.CommandContext(runCtx, s.shell, s.shellArgs(s.runAsWrap(cmdStr))...)
 	cmd.Dir = s.cwd
+	killGroupOnCancel(cmd)
+	// Don't block on orphaned children still holding the output pipes once the shell is killed
+	cmd.WaitDelay = time.Second
 
-	// Set environment
-	cmd.Env = os.Environ()
-	for k, v := range s.env {
-		cmd.Env = append(cmd.Env, k+"="+v)
-	}
+	cmd.Env = s.commandEnv()
 
-	// Ensure TERM is set for color support
-	hasTerm := false
-	for _, e := range cmd.Env {
-		if strings.HasPrefix(e, "TERM=") {
-			hasTerm = true
-			break
-		}
-	}
-	if !hasTerm {
-		cmd.Env = append(cmd.Env, "TERM=xterm-256color")
-	}
-
-	// Enable color output for common commands
-	// CLICOLOR=1 enables colors for BSD/macOS commands
-	// CLICOLOR_FORCE=1 forces colors even when not a TTY
-	cmd.Env = append(cmd.Env, "CLICOLOR=1")
-	cmd.Env = append(cmd.Env, "CLICOLOR_FORCE=1")
-	// GCC_COLORS enables colored diagnostics in GCC
-	if !hasEnvPrefix(cmd.Env, "GCC_COLORS=") {
-		cmd.Env = append(cmd.Env, "GCC_COLORS=error=01;31:warning=01;35:note=01;36:caret=01;32:locus=01:quote=01")
-	}
-
-	// Capture output
+	// Capture output, watching for a password or confirmation prompt
 	var stdout, stderr bytes.Buffer
-	cmd.Stdout = &stdout
-	cmd.Stderr = &stderr
+	prompt := newPromptWatcher()
+	cmd.Stdout = prompt.Writer(&stdout)
+	cmd.Stderr = prompt.Writer(&stderr)
+	go func() {
+		select {
+		case <-prompt.Found():
+			stop()
+		case <-runCtx.Done():
+		}
+	}()
 
 	// Run the command
 	err := cmd.Run()
 
+	if err != nil && prompt.Detected() {
+		logger.Warn("local command stopped at an input prompt on %q", s.name)
+		return nil, prompt.Err(s.name, "")
+	}
+
 	result := &ExecuteResult{
 		Stdout:   stdout.String(),
 		Stderr:   stderr.String(),
@@ -182,8 +226,10 @@ func (s *LocalSession) ExecuteWithContext(ctx context.Context, cmdStr string) (*
 		// Check if context was canceled (user interrupt)
 		if ctx.Err() == context.Canceled {
 			logger.Debug("local command interrupted on %q", s.name)
+			// Keep the output produced before the interrupt
 			return &ExecuteResult{
-				Stderr:   "^C\n",
+				Stdout:   result.Stdout,
+				Stderr:   result.Stderr + "^C\n",
 				ExitCode: 130, // Standard exit code for SIGINT
 			}, nil
 		}
@@ -207,10 +253,52 @@ func (s *LocalSession) ExecuteWithContext(ctx context.Context, cmdStr string) (*
 	return result, nil
 }
 
+// commandEnv returns the environment commands run with: thop's own, the
+// session locale and env, and settings that enable colored output
+func (s *LocalSession) commandEnv() []string {
+	env := append(os.Environ(), localeEnv(s.locale)...)
+	for k, v := range s.env {
+		env = append(env, k+"="+v)
+	}
+
+	// Ensure TERM is set for color support
+	hasTerm := false
+	for _, e := range env {
+		if strings.HasPrefix(e, "TERM=") {
+			hasTerm = true
+			break
+		}
+	}
+	if !hasTerm {
+		env = append(env, "TERM=xterm-256color")
+	}
+
+	// Enable color output for common commands
+	// CLICOLOR=1 enables colors for BSD/macOS commands
+	// CLICOLOR_FORCE=1 forces colors even when not a TTY
+	env = append(env, "CLICOLOR=1")
+	env = append(env, "CLICOLOR_FORCE=1")
+	// GCC_COLORS enables colored diagnostics in GCC
+	if !hasEnvPrefix(env, "GCC_COLORS=") {
+		env = append(env, "GCC_COLORS=error=01;31:warning=01;35:note=01;36:caret=01;32:locus=01:quote=01")
+	}
+	return env
+}
+
+// killGroupOnCancel runs cmd in its own process group and makes canceling its
+// context (on timeout or Ctrl+C) kill the whole group rather than just the
+// shell, so pipelines and background children don't outlive the command
+func killGroupOnCancel(cmd *exec.Cmd) {
+	cmd.SysProcAttr = &syscall.SysProcAttr{Setpgid: true}
+	cmd.Cancel = func() error {
+		return syscall.Kill(-cmd.Process.Pid, syscall.SIGKILL)
+	}
+}
+
 // ExecuteInteractive runs a command with PTY support for interactive programs
 func (s *LocalSession) ExecuteInteractive(cmdStr string) (int, error) {
 	// Create the command
-	cmd := exec.Command(s.shell, "-c", cmdStr)
+	cmd := exec.Command(s.shell, s.shellArgs(cmdStr)...)
 	cmd.Dir = s.cwd
 
 	// Set environment
@@ -427,6 +515,147 @@ func (s *LocalSession) handleCD(cmdStr string) (*ExecuteResult, error) {
 	}, nil
 }
 
+// lookupEnv returns the value a command on the session sees for key: the
+// session's own env, then thop's environment
+func (s *LocalSession) lookupEnv(key string) string {
+	if v, ok := s.env[key]; ok {
+		return v
+	}
+	return os.Getenv(key)
+}
+
+// isEnvAssignment reports whether cmd only assigns variables, which local
+// sessions handle themselves to keep them for later commands
+func isEnvAssignment(cmd string) bool {
+	_, ok := parseEnvAssignments(strings.TrimSpace(cmd), func(string) string { return "" })
+	return ok
+}
+
+// parseEnvAssignments parses a command made up only of variable assignments,
+// optionally after "export", as in "export FOO=bar" or "A=1 B='x y'". Values
+// may be quoted, and $VAR and ${VAR} outside single quotes are expanded, from
+// earlier assignments on the line or else with lookup. It returns false for
+// anything else, including commands using command substitution or shell
+// operators, which are left to the shell.
+func parseEnvAssignments(cmdStr string, lookup func(string) string) (map[string]string, bool) {
+	env := make(map[string]string)
+	expand := func(key string) string {
+		if v, ok := env[key]; ok {
+			return v
+		}
+		return lookup(key)
+	}
+
+	var words int
+	var word strings.Builder
+	inWord, inSingle, inDouble := false, false, false
+	endWord := func() bool {
+		defer word.Reset()
+		inWord = false
+		words++
+		if words == 1 && word.String() == "export" {
+			return true
+		}
+		key, value, ok := strings.Cut(word.String(), "=")
+		if !ok || !config.ValidEnvName(key) {
+			return false
+		}
+		env[key] = value
+		return true
+	}
+
+	for i := 0; i < len(cmdStr); i++ {
+		c := cmdStr[i]
+		switch {
+		case inSingle:
+			if c == '\'' {
+				inSingle = false
+			} else {
+				word.WriteByte(c)
+			}
+			continue
+		case c == '"':
+			inDouble = !inDouble
+		case c == '\'' && !inDouble:
+			inSingle = true
+		case c == '\\':
+			if i+1 < len(cmdStr) {
+				i++
+				word.WriteByte(cmdStr[i])
+			}
+		case c == '`':
+			return nil, false
+		case c == '$':
+			name, n := envReference(cmdStr[i+1:])
+			if n < 0 {
+				return nil, false
+			}
+			if n == 0 {
+				word.WriteByte(c)
+			} else {
+				word.WriteString(expand(name))
+				i += n
+			}
+		case inDouble:
+			word.WriteByte(c)
+		case c == ' ' || c == '\t':
+			if inWord && !endWord() {
+				return nil, false
+			}
+			continue
+		case c == '~' && homePosition(word.String(), cmdStr[i+1:]):
+			home, _ := os.UserHomeDir()
+			word.WriteString(home)
+		case c == '#' && !inWord, strings.IndexByte(";|&<>()\n", c) >= 0:
+			return nil, false
+		default:
+			word.WriteByte(c)
+		}
+		inWord = true
+	}
+	if inSingle || inDouble {
+		return nil, false
+	}
+	if inWord && !endWord() {
+		return nil, false
+	}
+	if len(env) == 0 {
+		return nil, false
+	}
+	return env, true
+}
+
+// homePosition reports whether an unquoted ~ is expanded to the home
+// directory in an assignment: at the start of the value or after a colon, and
+// followed by a slash, a colon or the end of the word
+func homePosition(before, after string) bool {
+	if !strings.Contains(before, "=") || !strings.HasSuffix(before, "=") && !strings.HasSuffix(before, ":") {
+		return false
+	}
+	return after == "" || strings.IndexByte("/: \t", after[0]) >= 0
+}
+
+// envReference parses the variable name following a $ in s, returning the
+// name and how many bytes of s it used. It returns 0 if the $ is literal and
+// -1 for expansions it can't evaluate, such as $(...) and $1.
+func envReference(s string) (string, int) {
+	if strings.HasPrefix(s, "{") {
+		end := strings.IndexByte(s, '}')
+		if end < 0 || !config.ValidEnvName(s[1:end]) {
+			return "", -1
+		}
+		return s[1:end], end + 1
+	}
+	n := 0
+	for n < len(s) && (s[n] == '_' || s[n] >= 'A' && s[n] <= 'Z' || s[n] >= 'a' && s[n] <= 'z' || n > 0 && s[n] >= '0' && s[n] <= '9') {
+		n++
+	}
+	if n == 0 && s != "" && strings.IndexByte(" \t\"", s[0]) < 0 {
+		return "", -1
+	}
+	return s[:n], n
+}
+
 // GetCWD returns the current working directory
 func (s *LocalSession) GetCWD() string {
 	return s.cwd
@@ -465,6 +694,10 @@ func (s *LocalSession) SetEnv(key, value string) {
 // SetShell sets the shell to use
 func (s *LocalSession) SetShell(shell string) {
 	s.shell = shell
+	if s.persistent != nil {
+		s.persistent.kill()
+		s.persistent.shell = shell
+	}
 }
 
 // hasEnvPrefix checks if any environment variable starts with the given prefix
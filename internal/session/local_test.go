@@ -1,9 +1,13 @@
 package session
 
 import (
+	"context"
+	"fmt"
 	"os"
 	"path/filepath"
+	"strconv"
 	"strings"
+	"syscall"
 	"testing"
 	"time"
 )
@@ -288,6 +292,80 @@ func TestLocalSessionEnv(t *testing.T) {
 	}
 }
 
+func TestLocalSessionExport(t *testing.T) {
+	session := NewLocalSession("test", "/bin/sh")
+
+	result, err := session.Execute("export FOO=bar")
+	if err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	if result.ExitCode != 0 || result.Stdout != "" || result.Stderr != "" {
+		t.Errorf("expected silent success, got %+v", result)
+	}
+
+	result, err = session.Execute("echo $FOO")
+	if err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	if strings.TrimSpace(result.Stdout) != "bar" {
+		t.Errorf("expected 'bar', got %q", result.Stdout)
+	}
+
+	if _, err := session.Execute(`GREETING="hello $FOO" EMPTY=`); err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	env := session.GetEnv()
+	if env["GREETING"] != "hello bar" {
+		t.Errorf("expected GREETING='hello bar', got %q", env["GREETING"])
+	}
+	if v, ok := env["EMPTY"]; !ok || v != "" {
+		t.Errorf("expected EMPTY to be set to '', got %q (set: %v)", v, ok)
+	}
+}
+
+func TestParseEnvAssignments(t *testing.T) {
+	t.Setenv("HOME", "/home/user")
+	lookup := func(key string) string {
+		return map[string]string{"PATH": "/usr/bin"}[key]
+	}
+
+	tests := []struct {
+		cmd  string
+		want map[string]string
+	}{
+		{"export FOO=bar", map[string]string{"FOO": "bar"}},
+		{"FOO=bar", map[string]string{"FOO": "bar"}},
+		{"export A=1 B=2", map[string]string{"A": "1", "B": "2"}},
+		{"A=1 B=$A", map[string]string{"A": "1", "B": "1"}},
+		{`MSG='it is $HOME'`, map[string]string{"MSG": "it is $HOME"}},
+		{`MSG="a b"`, map[string]string{"MSG": "a b"}},
+		{"PATH=~/bin:${PATH}", map[string]string{"PATH": "/home/user/bin:/usr/bin"}},
+		{"PRICE=5$", map[string]string{"PRICE": "5$"}},
+		{"export", nil},
+		{"export FOO", nil},
+		{"FOO=bar make", nil},
+		{"FOO=$(date)", nil},
+		{"FOO=`date`", nil},
+		{"FOO=$1", nil},
+		{"FOO=bar; echo $FOO", nil},
+		{"FOO=bar && true", nil},
+		{`FOO="unterminated`, nil},
+		{"1FOO=bar", nil},
+		{"echo FOO=bar", nil},
+	}
+	for _, tt := range tests {
+		t.Run(tt.cmd, func(t *testing.T) {
+			got, ok := parseEnvAssignments(tt.cmd, lookup)
+			if ok != (tt.want != nil) {
+				t.Fatalf("parseEnvAssignments(%q) ok = %v, want %v", tt.cmd, ok, tt.want != nil)
+			}
+			if fmt.Sprint(got) != fmt.Sprint(tt.want) && tt.want != nil {
+				t.Errorf("parseEnvAssignments(%q) = %v, want %v", tt.cmd, got, tt.want)
+			}
+		})
+	}
+}
+
 func TestLocalSessionSetShell(t *testing.T) {
 	session := NewLocalSession("test", "/bin/bash")
 
@@ -368,6 +446,80 @@ func TestLocalSessionTimeout(t *testing.T) {
 	}
 }
 
+func TestLocalSessionTimeoutKillsProcessGroup(t *testing.T) {
+	session := NewLocalSession("test", "/bin/sh")
+	session.SetTimeout(time.Second)
+	pidFile := filepath.Join(t.TempDir(), "pid")
+
+	start := time.Now()
+	_, err := session.Execute("sleep 5 & echo $! > " + pidFile + "; wait")
+	if elapsed := time.Since(start); elapsed > 4*time.Second {
+		t.Errorf("timed out command took %s to return", elapsed)
+	}
+	sessionErr, ok := err.(*Error)
+	if !ok || sessionErr.Code != ErrCommandTimeout {
+		t.Fatalf("expected %s error, got %v", ErrCommandTimeout, err)
+	}
+
+	data, err := os.ReadFile(pidFile)
+	if err != nil {
+		t.Fatalf("failed to read child pid: %v", err)
+	}
+	pid, err := strconv.Atoi(strings.TrimSpace(string(data)))
+	if err != nil {
+		t.Fatalf("bad child pid %q: %v", data, err)
+	}
+
+	// The background sleep is in the shell's process group, so it is killed
+	// too; give its new parent a moment to reap it
+	deadline := time.Now().Add(2 * time.Second)
+	for processRunning(pid) {
+		if time.Now().After(deadline) {
+			_ = syscall.Kill(pid, syscall.SIGKILL)
+			t.Fatalf("child process %d survived the timeout", pid)
+		}
+		time.Sleep(20 * time.Millisecond)
+	}
+}
+
+// processRunning reports whether pid exists and isn't a zombie
+func processRunning(pid int) bool {
+	if err := syscall.Kill(pid, 0); err != nil {
+		return false
+	}
+	stat, err := os.ReadFile(fmt.Sprintf("/proc/%d/stat", pid))
+	if err != nil {
+		return !os.IsNotExist(err)
+	}
+	// The state follows the parenthesized command name
+	fields := strings.Fields(string(stat[strings.LastIndex(string(stat), ")")+1:]))
+	return len(fields) == 0 || fields[0] != "Z"
+}
+
+func TestLocalSessionSudoPrompt(t *testing.T) {
+	session := NewLocalSession("test", "/bin/sh")
+	session.SetTimeout(30 * time.Second)
+
+	// Simulate sudo waiting for a password on stderr
+	start := time.Now()
+	_, err := session.Execute("printf '[sudo] password for tester: ' >&2; sleep 20")
+	elapsed := time.Since(start)
+
+	sessionErr, ok := err.(*Error)
+	if !ok {
+		t.Fatalf("expected *Error, got %T: %v", err, err)
+	}
+	if sessionErr.Code != ErrAuthPasswordRequired {
+		t.Errorf("expected code %s, got %s", ErrAuthPasswordRequired, sessionErr.Code)
+	}
+	if !strings.Contains(sessionErr.Suggestion, "sudo -n") {
+		t.Errorf("expected suggestion to mention sudo -n, got %q", sessionErr.Suggestion)
+	}
+	if elapsed > 5*time.Second {
+		t.Errorf("expected prompt to be detected quickly, took %s", elapsed)
+	}
+}
+
 func TestLocalSessionSetTimeout(t *testing.T) {
 	session := NewLocalSession("test", "")
 
@@ -386,3 +538,186 @@ func TestLocalSessionSetTimeout(t *testing.T) {
 		t.Errorf("expected 'fast', got '%s'", result.Stdout)
 	}
 }
+
+func TestLocalSessionShellArgs(t *testing.T) {
+	session := NewLocalSession("test", "/bin/sh")
+
+	args := session.shellArgs("echo hi")
+	if len(args) != 2 || args[0] != "-c" || args[1] != "echo hi" {
+		t.Errorf("expected [-c 'echo hi'], got %q", args)
+	}
+
+	session.SetInteractiveShell(true)
+	args = session.shellArgs("echo hi")
+	if len(args) != 3 || args[0] != "-i" || args[1] != "-c" || args[2] != "echo hi" {
+		t.Errorf("expected [-i -c 'echo hi'], got %q", args)
+	}
+}
+
+func TestLocalSessionInteractiveShell(t *testing.T) {
+	// POSIX sh sources $ENV for interactive shells, which stands in for .bashrc
+	tmpDir := t.TempDir()
+	rcFile := filepath.Join(tmpDir, "shrc")
+	if err := os.WriteFile(rcFile, []byte("thop_greet() { echo hello from rc; }\n"), 0644); err != nil {
+		t.Fatalf("failed to write rc file: %v", err)
+	}
+
+	session := NewLocalSession("test", "/bin/sh")
+	session.SetEnv("ENV", rcFile)
+
+	// Without interactive mode the rc file is not loaded
+	result, err := session.Execute("thop_greet")
+	if err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	if result.ExitCode == 0 {
+		t.Error("expected rc function to be unavailable without interactive shell")
+	}
+
+	session.SetInteractiveShell(true)
+	result, err = session.Execute("thop_greet")
+	if err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	if !strings.Contains(result.Stdout, "hello from rc") {
+		t.Errorf("expected rc function output, got stdout=%q stderr=%q", result.Stdout, result.Stderr)
+	}
+}
+
+func TestLocalSessionLocale(t *testing.T) {
+	t.Setenv("LANG", "de_DE.UTF-8")
+	t.Setenv("LC_ALL", "")
+
+	session := NewLocalSession("test", "/bin/sh")
+	session.SetLocale("C.UTF-8")
+
+	result, err := session.Execute(`echo "$LC_ALL|$LANG"`)
+	if err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	if got := strings.TrimSpace(result.Stdout); got != "C.UTF-8|C.UTF-8" {
+		t.Errorf("expected configured locale in the command environment, got %q", got)
+	}
+
+	// An explicit /env setting still wins
+	session.SetEnv("LANG", "en_GB.UTF-8")
+	result, err = session.Execute(`echo "$LANG"`)
+	if err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	if got := strings.TrimSpace(result.Stdout); got != "en_GB.UTF-8" {
+		t.Errorf("expected session env to override the locale, got %q", got)
+	}
+
+	// Without a locale the inherited one is kept
+	session = NewLocalSession("test", "/bin/sh")
+	result, err = session.Execute(`echo "$LANG"`)
+	if err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	if got := strings.TrimSpace(result.Stdout); got != "de_DE.UTF-8" {
+		t.Errorf("expected inherited locale, got %q", got)
+	}
+}
+
+func TestLocalSessionExecuteVerbatim(t *testing.T) {
+	session := NewLocalSession("test", "/bin/sh")
+	session.SetTimeout(5 * time.Second)
+	session.SetEnv("THOP_VERBATIM_TEST", "set")
+	if err := session.SetCWD(t.TempDir()); err != nil {
+		t.Fatalf("SetCWD failed: %v", err)
+	}
+
+	wd, err := os.Getwd()
+	if err != nil {
+		t.Fatalf("Getwd failed: %v", err)
+	}
+
+	result, err := session.ExecuteVerbatim(context.Background(), `pwd; echo "${THOP_VERBATIM_TEST:-unset}"; exit 3`)
+	if err != nil {
+		t.Fatalf("ExecuteVerbatim failed: %v", err)
+	}
+	// Neither the tracked cwd nor the session environment is applied
+	expected := wd + "\nunset\n"
+	if result.Stdout != expected {
+		t.Errorf("expected %q, got %q", expected, result.Stdout)
+	}
+	if result.ExitCode != 3 {
+		t.Errorf("expected exit code 3, got %d", result.ExitCode)
+	}
+}
+
+func TestLocalSessionExecuteVerbatimTimeout(t *testing.T) {
+	session := NewLocalSession("test", "/bin/sh")
+
+	// The session timeout applies when the caller sets no deadline
+	session.SetTimeout(100 * time.Millisecond)
+	_, err := session.ExecuteVerbatim(context.Background(), "sleep 5")
+	sessionErr, ok := err.(*Error)
+	if !ok || sessionErr.Code != ErrCommandTimeout {
+		t.Fatalf("expected ErrCommandTimeout, got %v", err)
+	}
+	if want := "Command timed out after 100ms"; sessionErr.Message != want {
+		t.Errorf("expected message %q, got %q", want, sessionErr.Message)
+	}
+
+	// A shorter caller deadline is reported as such, not as the session timeout
+	session.SetTimeout(time.Minute)
+	ctx, cancel := context.WithTimeout(context.Background(), 100*time.Millisecond)
+	defer cancel()
+	_, err = session.ExecuteVerbatim(ctx, "sleep 5")
+	sessionErr, ok = err.(*Error)
+	if !ok || sessionErr.Code != ErrCommandTimeout {
+		t.Fatalf("expected ErrCommandTimeout, got %v", err)
+	}
+	if want := "Command timed out (deadline exceeded)"; sessionErr.Message != want {
+		t.Errorf("expected message %q, got %q", want, sessionErr.Message)
+	}
+}
+
+func TestLocalSessionListDir(t *testing.T) {
+	tmpDir := t.TempDir()
+	if err := os.WriteFile(filepath.Join(tmpDir, "b.txt"), []byte("hello"), 0644); err != nil {
+		t.Fatal(err)
+	}
+	if err := os.Mkdir(filepath.Join(tmpDir, "a"), 0755); err != nil {
+		t.Fatal(err)
+	}
+	mtime := time.Date(2024, 1, 2, 3, 4, 5, 0, time.UTC)
+	if err := os.Chtimes(filepath.Join(tmpDir, "b.txt"), mtime, mtime); err != nil {
+		t.Fatal(err)
+	}
+
+	session := NewLocalSession("test", "/bin/sh")
+	entries, err := session.ListDir(tmpDir)
+	if err != nil {
+		t.Fatalf("ListDir failed: %v", err)
+	}
+	if len(entries) != 2 {
+		t.Fatalf("expected 2 entries, got %d: %+v", len(entries), entries)
+	}
+	if entries[0].Name != "a" || !entries[0].IsDir {
+		t.Errorf("expected directory 'a' first, got %+v", entries[0])
+	}
+	if entries[1].Name != "b.txt" || entries[1].IsDir || entries[1].Size != 5 || !entries[1].ModTime.Equal(mtime) {
+		t.Errorf("unexpected file entry %+v", entries[1])
+	}
+
+	// Relative paths are resolved against the session cwd by the manager
+	mgr, _ := createTestManager(t)
+	local, _ := mgr.GetSession("local")
+	if err := local.SetCWD(tmpDir); err != nil {
+		t.Fatal(err)
+	}
+	entries, err = mgr.ListDir("local", "a")
+	if err != nil {
+		t.Fatalf("Manager.ListDir failed: %v", err)
+	}
+	if len(entries) != 0 {
+		t.Errorf("expected empty directory, got %+v", entries)
+	}
+
+	if _, err := session.ListDir(filepath.Join(tmpDir, "missing")); err == nil {
+		t.Error("expected error listing a missing directory")
+	}
+}
@@ -3,7 +3,9 @@ package session
 import (
 	"context"
 	"fmt"
+	"sort"
 	"strconv"
+	"strings"
 	"sync"
 	"time"
 
@@ -25,19 +27,29 @@ type Manager struct {
 	commandTimeout    time.Duration
 	reconnectAttempts int
 	reconnectBackoff  time.Duration
+	tempDirs          map[string][]string // Temp directories created by /mktemp, per session
+	autoConnect       bool                // Connect disconnected SSH sessions on demand in ExecuteOn
+	restrictedSess    map[string]bool     // Sessions with restrictions enforced regardless of the global toggle
+	auditMu           sync.Mutex          // Serializes writes to the restriction audit file
+	recent            []string            // Previously active sessions, most recent last (for /back)
 	mu                sync.RWMutex
+
+	// Session event listeners (see OnSessionEvent)
+	listenersMu  sync.Mutex
+	listeners    map[int]func(SessionEvent)
+	nextListener int
 }
 
+// maxRecentSessions caps the recently-used session stack
+const maxRecentSessions = 10
+
 // NewManager creates a new session manager
 func NewManager(cfg *config.Config, stateMgr *state.Manager) *Manager {
 	// Load SSH config from ~/.ssh/config
 	sshCfg, _ := sshconfig.Load()
 
-	// Calculate command timeout from config (in seconds)
-	timeout := time.Duration(cfg.Settings.CommandTimeout) * time.Second
-	if timeout == 0 {
-		timeout = 300 * time.Second // Default 5 minutes
-	}
+	// Calculate command timeout from config (in seconds), honoring the run mode
+	timeout := time.Duration(cfg.DefaultTimeout()) * time.Second
 
 	// Reconnection settings
 	reconnectAttempts := cfg.Settings.ReconnectAttempts
@@ -60,6 +72,9 @@ func NewManager(cfg *config.Config, stateMgr *state.Manager) *Manager {
 		commandTimeout:    timeout,
 		reconnectAttempts: reconnectAttempts,
 		reconnectBackoff:  reconnectBackoff,
+		tempDirs:          make(map[string][]string),
+		autoConnect:       cfg.Settings.AutoConnect,
+		restrictedSess:    make(map[string]bool),
 	}
 
 	// Initialize sessions from config
@@ -94,6 +109,10 @@ func NewManager(cfg *config.Config, stateMgr *state.Manager) *Manager {
 
 // createSession creates a session from config
 func (m *Manager) createSession(name string, cfg config.Session) Session {
+	if cfg.Restricted {
+		m.restrictedSess[name] = true
+	}
+
 	switch cfg.Type {
 	case "ssh":
 		// Resolve SSH settings from ~/.ssh/config if not specified in thop config
@@ -154,17 +173,23 @@ func (m *Manager) createSession(name string, cfg config.Session) Session {
 		}
 
 		session := NewSSHSession(SSHConfig{
-			Name:            name,
-			Host:            host,
-			Port:            port,
-			User:            user,
-			KeyFile:         keyFile,
-			PasswordEnv:     cfg.PasswordEnv,
-			PasswordFile:    cfg.PasswordFile,
-			JumpHost:        jumpHost,
-			AgentForwarding: agentForwarding,
-			Timeout:         m.commandTimeout,
-			StartupCommands: cfg.StartupCommands,
+			Name:              name,
+			Host:              host,
+			Port:              port,
+			User:              user,
+			KeyFile:           keyFile,
+			PasswordEnv:       cfg.PasswordEnv,
+			PasswordFile:      cfg.PasswordFile,
+			JumpHost:          jumpHost,
+			AgentForwarding:   agentForwarding,
+			Timeout:           m.commandTimeout,
+			StartupCommands:   cfg.StartupCommands,
+			KeepaliveInterval: time.Duration(m.config.Settings.KeepaliveInterval) * time.Second,
+			Locale:            m.config.SessionLocale(cfg),
+			Env:               cfg.Env,
+			EnvFile:           cfg.EnvFile,
+			RunAs:             cfg.RunAs,
+			Shell:             cfg.Shell,
 		})
 		if jumpHost != "" {
 			logger.Debug("created SSH session %q: user=%s host=%s port=%d via jump_host=%s, startup_commands=%d", name, user, host, port, jumpHost, len(cfg.StartupCommands))
@@ -173,12 +198,28 @@ func (m *Manager) createSession(name string, cfg config.Session) Session {
 		}
 		return session
 	default:
-		session := NewLocalSession(name, cfg.Shell)
+		var session *LocalSession
+		if cfg.PersistentShell {
+			session = NewPersistentLocalSession(name, cfg.Shell)
+		} else {
+			session = NewLocalSession(name, cfg.Shell)
+		}
 		session.SetTimeout(m.commandTimeout)
 		if len(cfg.StartupCommands) > 0 {
 			session.SetStartupCommands(cfg.StartupCommands)
 		}
-		logger.Debug("created local session %q: shell=%s, startup_commands=%d", name, cfg.Shell, len(cfg.StartupCommands))
+		session.SetInteractiveShell(cfg.InteractiveShell)
+		session.SetLocale(m.config.SessionLocale(cfg))
+		session.SetRunAs(cfg.RunAs)
+		// Local sessions are connected from the start, so apply the configured env now
+		if env, err := config.ResolveEnv(cfg.EnvFile, cfg.Env); err != nil {
+			logger.Warn("session %q: %v", name, err)
+		} else {
+			for k, v := range env {
+				session.SetEnv(k, v)
+			}
+		}
+		logger.Debug("created local session %q: shell=%s, startup_commands=%d, interactive_shell=%v, persistent_shell=%v", name, cfg.Shell, len(cfg.StartupCommands), cfg.InteractiveShell, cfg.PersistentShell)
 		return session
 	}
 }
@@ -211,9 +252,8 @@ func (m *Manager) GetActiveSessionName() string {
 // SetActiveSession sets the active session
 func (m *Manager) SetActiveSession(name string) error {
 	m.mu.Lock()
-	defer m.mu.Unlock()
-
 	if _, ok := m.sessions[name]; !ok {
+		m.mu.Unlock()
 		logger.Warn("set active session failed: session %q not found", name)
 		return &Error{
 			Code:    ErrSessionNotFound,
@@ -222,6 +262,18 @@ func (m *Manager) SetActiveSession(name string) error {
 		}
 	}
 
+	if name != m.activeSession {
+		m.pushRecent(m.activeSession, name)
+	}
+	m.activateLocked(name)
+	m.mu.Unlock()
+
+	m.emit(SessionEvent{Type: EventActivated, Session: name})
+	return nil
+}
+
+// activateLocked makes name the active session and persists it; m.mu must be held
+func (m *Manager) activateLocked(name string) {
 	logger.Info("switching active session from %q to %q", m.activeSession, name)
 	m.activeSession = name
 
@@ -229,8 +281,69 @@ func (m *Manager) SetActiveSession(name string) error {
 	if m.state != nil {
 		_ = m.state.SetActiveSession(name)
 	}
+}
 
-	return nil
+// pushRecent records prev as the most recently left session. next is dropped
+// from the stack so /back never "returns" to the session already active.
+func (m *Manager) pushRecent(prev, next string) {
+	stack := make([]string, 0, len(m.recent)+1)
+	for _, name := range m.recent {
+		if name != prev && name != next {
+			stack = append(stack, name)
+		}
+	}
+	stack = append(stack, prev)
+	if len(stack) > maxRecentSessions {
+		stack = stack[len(stack)-maxRecentSessions:]
+	}
+	m.recent = stack
+}
+
+// PreviousSession returns the session /back would switch to, if any
+func (m *Manager) PreviousSession() (string, bool) {
+	m.mu.RLock()
+	defer m.mu.RUnlock()
+
+	for i := len(m.recent) - 1; i >= 0; i-- {
+		if _, ok := m.sessions[m.recent[i]]; ok {
+			return m.recent[i], true
+		}
+	}
+	return "", false
+}
+
+// Back pops the recently-used stack and makes that session active, like
+// "cd -" for sessions. Unlike SetActiveSession it does not push the session
+// being left, so repeated calls walk further back.
+func (m *Manager) Back() (string, error) {
+	m.mu.Lock()
+	for len(m.recent) > 0 {
+		name := m.recent[len(m.recent)-1]
+		m.recent = m.recent[:len(m.recent)-1]
+		if _, ok := m.sessions[name]; ok {
+			m.activateLocked(name)
+			m.mu.Unlock()
+
+			m.emit(SessionEvent{Type: EventActivated, Session: name})
+			return name, nil
+		}
+	}
+	m.mu.Unlock()
+	return "", fmt.Errorf("no previous session to go back to")
+}
+
+// RecentSessions returns previously active sessions, most recent first
+func (m *Manager) RecentSessions() []string {
+	m.mu.RLock()
+	defer m.mu.RUnlock()
+
+	recent := make([]string, 0, len(m.recent))
+	for i := len(m.recent) - 1; i >= 0; i-- {
+		if _, ok := m.sessions[m.recent[i]]; ok {
+			recent = append(recent, m.recent[i])
+		}
+	}
+	return recent
 }
 
 // Connect connects a session by name
@@ -248,6 +361,18 @@ func (m *Manager) Connect(name string) error {
 		}
 	}
 
+	// Remember where the session was before it was last closed
+	savedCWD := ""
+	if m.state != nil {
+		if sessionState, ok := m.state.GetSessionState(name); ok {
+			savedCWD = sessionState.CWD
+		}
+	}
+
+	if err := m.checkConnectionLimit(session); err != nil {
+		return err
+	}
+
 	logger.Info("connecting to session %q", name)
 	err := session.Connect()
 	if err != nil {
@@ -255,20 +380,63 @@ func (m *Manager) Connect(name string) error {
 		return err
 	}
 
-	// Update state
-	if m.state != nil {
-		_ = m.state.SetSessionConnected(name, true)
-	}
-
-	// Restore environment from state for SSH sessions
+	// Restore environment and cwd from state for SSH sessions
 	if sshSession, ok := session.(*SSHSession); ok {
 		m.restoreSessionEnv(sshSession)
+		sshSession.restoreCWD(savedCWD)
+	}
+
+	// Update state, including any cwd set by startup commands or restored above
+	if m.state != nil {
+		_ = m.state.SetSessionConnected(name, true)
+		_ = m.state.SetSessionCWD(name, session.GetCWD())
 	}
 
 	logger.Info("connected to session %q", name)
+	m.emit(SessionEvent{Type: EventConnected, Session: name})
 	return nil
 }
 
+// checkConnectionLimit refuses to open another SSH connection once
+// max_connections sessions are connected, naming the ones that could be closed
+func (m *Manager) checkConnectionLimit(session Session) error {
+	if session.Type() != "ssh" || session.IsConnected() {
+		return nil
+	}
+
+	m.mu.RLock()
+	var connected []string
+	for name, s := range m.sessions {
+		if s.Type() == "ssh" && s.IsConnected() {
+			connected = append(connected, name)
+		}
+	}
+	active := m.activeSession
+	m.mu.RUnlock()
+
+	limit := m.config.ConnectionLimit()
+	if len(connected) < limit {
+		return nil
+	}
+
+	// Suggest closing sessions other than the active one
+	sort.Strings(connected)
+	candidates := make([]string, 0, len(connected))
+	for _, name := range connected {
+		if name != active {
+			candidates = append(candidates, name)
+		}
+	}
+
+	logger.Warn("connect refused for session %q: %d of %d connections in use", session.Name(), len(connected), limit)
+	return &Error{
+		Code:       ErrConnectionLimit,
+		Message:    fmt.Sprintf("Cannot connect '%s': %d sessions are already connected (max_connections = %d)", session.Name(), len(connected), limit),
+		Session:    session.Name(),
+		Suggestion: fmt.Sprintf("Close a session first (connected: %s), or raise max_connections in [settings]", strings.Join(candidates, ", ")),
+	}
+}
+
 // Disconnect disconnects a session by name
 func (m *Manager) Disconnect(name string) error {
 	m.mu.Lock()
@@ -298,9 +466,59 @@ func (m *Manager) Disconnect(name string) error {
 		logger.Info("disconnected from session %q", name)
 	}
 
+	m.emit(SessionEvent{Type: EventDisconnected, Session: name})
 	return err
 }
 
+// SetSessionPassword sets the password used to authenticate an SSH session.
+// It is tried after agent and key authentication on the next connect.
+func (m *Manager) SetSessionPassword(name, password string) error {
+	m.mu.RLock()
+	session, ok := m.sessions[name]
+	m.mu.RUnlock()
+
+	if !ok {
+		return &Error{
+			Code:    ErrSessionNotFound,
+			Message: fmt.Sprintf("Session '%s' not found", name),
+			Session: name,
+		}
+	}
+
+	sshSession, ok := session.(*SSHSession)
+	if !ok {
+		return fmt.Errorf("session '%s' is not an SSH session", name)
+	}
+
+	sshSession.SetPassword(password)
+	logger.Debug("password set for session %q", name)
+	return nil
+}
+
+// SetSessionPassphrase sets the private key passphrase for an SSH session
+func (m *Manager) SetSessionPassphrase(name, passphrase string) error {
+	m.mu.RLock()
+	session, ok := m.sessions[name]
+	m.mu.RUnlock()
+
+	if !ok {
+		return &Error{
+			Code:    ErrSessionNotFound,
+			Message: fmt.Sprintf("Session '%s' not found", name),
+			Session: name,
+		}
+	}
+
+	sshSession, ok := session.(*SSHSession)
+	if !ok {
+		return fmt.Errorf("session '%s' is not an SSH session", name)
+	}
+
+	sshSession.SetPassphrase(passphrase)
+	logger.Debug("key passphrase set for session %q", name)
+	return nil
+}
+
 // Execute executes a command on the active session
 func (m *Manager) Execute(cmd string) (*ExecuteResult, error) {
 	return m.ExecuteWithContext(context.Background(), cmd)
@@ -308,16 +526,27 @@ func (m *Manager) Execute(cmd string) (*ExecuteResult, error) {
 
 // ExecuteWithContext executes a command on the active session with cancellation support
 func (m *Manager) ExecuteWithContext(ctx context.Context, cmd string) (*ExecuteResult, error) {
-	// Check for restricted commands first
-	if allowed, rule := m.restriction.Check(cmd); !allowed {
-		logger.Warn("command blocked by restriction: %s (rule: %s)", cmd, rule.Command)
+	return m.executeActive(ctx, cmd, false)
+}
+
+// ExecuteBypassWithContext executes a command on the active session even if
+// restricted mode would block it, recording the bypass in the audit log. It
+// requires allow_restriction_bypass in [settings] and must only be reachable
+// by an interactive operator, never by proxy or MCP clients.
+func (m *Manager) ExecuteBypassWithContext(ctx context.Context, cmd string) (*ExecuteResult, error) {
+	if !m.config.Settings.AllowRestrictionBypass {
 		return nil, &Error{
 			Code:       ErrCommandRestricted,
-			Message:    fmt.Sprintf("%s: '%s' is not allowed in restricted mode", restriction.CategoryDescription(rule.Category), rule.Command),
-			Suggestion: "Remove --restricted flag to allow this command, or use a different approach",
+			Message:    "Restriction bypass is disabled",
+			Session:    m.GetActiveSessionName(),
+			Suggestion: "Set allow_restriction_bypass = true in [settings] to allow the '!!' prefix in interactive mode",
 		}
 	}
+	return m.executeActive(ctx, cmd, true)
+}
 
+// executeActive runs cmd on the active session, checking restrictions unless bypass is set
+func (m *Manager) executeActive(ctx context.Context, cmd string, bypass bool) (*ExecuteResult, error) {
 	session := m.GetActiveSession()
 	if session == nil {
 		logger.Warn("execute failed: no active session")
@@ -327,24 +556,16 @@ func (m *Manager) ExecuteWithContext(ctx context.Context, cmd string) (*ExecuteR
 		}
 	}
 
-	logger.Debug("executing on session %q: %s", session.Name(), cmd)
-	result, err := session.ExecuteWithContext(ctx, cmd)
-
-	// For SSH sessions, try to reconnect on connection errors (but not if context was canceled)
-	if err != nil && session.Type() == "ssh" && ctx.Err() == nil {
-		if sessionErr, ok := err.(*Error); ok && sessionErr.Retryable {
-			if sessionErr.Code == ErrSessionDisconnected || sessionErr.Code == ErrConnectionFailed {
-				logger.Info("connection lost on session %q, attempting reconnect", session.Name())
-				// Attempt reconnection
-				if reconnectErr := m.attemptReconnect(session); reconnectErr == nil {
-					// Retry the command after successful reconnection
-					logger.Debug("retrying command after reconnect: %s", cmd)
-					result, err = session.ExecuteWithContext(ctx, cmd)
-				}
-			}
-		}
+	// Check for restricted commands before running anything
+	if bypass {
+		m.recordBypass(session.Name(), cmd)
+	} else if err := m.checkRestriction(session.Name(), cmd); err != nil {
+		return nil, err
 	}
 
+	logger.Debug("executing on session %q: %s", session.Name(), cmd)
+	result, err := m.executeWithReconnect(ctx, session, cmd)
+
 	// Update cwd in state if successful
 	if err == nil && m.state != nil {
 		_ = m.state.SetSessionCWD(session.Name(), session.GetCWD())
@@ -357,6 +578,87 @@ func (m *Manager) ExecuteWithContext(ctx context.Context, cmd string) (*ExecuteR
 	return result, err
 }
 
+// checkRestriction checks a command against the restriction rules for a session.
+// Rules apply when restricted mode is enabled globally or for that session.
+// In enforce mode a matched command returns ErrCommandRestricted; in warn-only
+// mode the match is logged and nil is returned so the command still runs.
+func (m *Manager) checkRestriction(sessionName, cmd string) error {
+	allowed, rule := m.matchRestriction(sessionName, cmd)
+	if allowed {
+		return nil
+	}
+
+	if m.restriction.IsWarnOnly() {
+		logger.Warn("restriction warning: %s: '%s' would be blocked (command: %s)", restriction.CategoryDescription(rule.Category), rule.Command, cmd)
+		return nil
+	}
+
+	logger.Warn("command blocked by restriction: %s (rule: %s)", cmd, rule.Command)
+	m.audit(AuditBlocked, sessionName, cmd, rule)
+	return &Error{
+		Code:       ErrCommandRestricted,
+		Message:    fmt.Sprintf("%s: '%s' is not allowed in restricted mode", restriction.CategoryDescription(rule.Category), rule.Command),
+		Session:    sessionName,
+		Suggestion: "Remove --restricted flag to allow this command, or use a different approach",
+	}
+}
+
+// matchRestriction checks cmd against the rules in effect for a session:
+// all rules when the session is restricted, otherwise only if restricted mode is on
+func (m *Manager) matchRestriction(sessionName, cmd string) (bool, *restriction.Rule) {
+	if m.IsSessionRestricted(sessionName) {
+		return m.restriction.Match(cmd)
+	}
+	return m.restriction.Check(cmd)
+}
+
+// recordBypass logs and audits a command that runs past a restriction rule
+func (m *Manager) recordBypass(sessionName, cmd string) {
+	allowed, rule := m.matchRestriction(sessionName, cmd)
+	if allowed {
+		return
+	}
+	logger.Warn("restriction bypassed on session %q: %s (rule: %s)", sessionName, cmd, rule.Command)
+	m.audit(AuditBypassed, sessionName, cmd, rule)
+}
+
+// CheckCommand reports whether a command passes the restriction rules,
+// independent of whether restricted mode is currently enabled. When blocked,
+// it also returns the byte range [start, end) of the offending segment.
+func (m *Manager) CheckCommand(cmd string) (bool, *restriction.Rule, []int) {
+	allowed, rule := m.restriction.Match(cmd)
+	if rule == nil {
+		return allowed, nil, nil
+	}
+	return allowed, rule, rule.Span(cmd)
+}
+
+// executeWithReconnect runs cmd on session. If an SSH session turns out to be
+// disconnected, it is reconnected with backoff and the command retried once.
+func (m *Manager) executeWithReconnect(ctx context.Context, session Session, cmd string) (*ExecuteResult, error) {
+	result, err := session.ExecuteWithContext(ctx, cmd)
+
+	// Don't reconnect if the caller canceled
+	if err == nil || session.Type() != "ssh" || ctx.Err() != nil {
+		return result, err
+	}
+	sessionErr, ok := err.(*Error)
+	if !ok || !sessionErr.Retryable {
+		return result, err
+	}
+	if sessionErr.Code != ErrSessionDisconnected && sessionErr.Code != ErrConnectionFailed {
+		return result, err
+	}
+
+	logger.Info("connection lost on session %q, attempting reconnect", session.Name())
+	if reconnectErr := m.attemptReconnect(session); reconnectErr != nil {
+		return nil, reconnectErr
+	}
+
+	logger.Debug("retrying command after reconnect: %s", cmd)
+	return session.ExecuteWithContext(ctx, cmd)
+}
+
 // attemptReconnect attempts to reconnect an SSH session with exponential backoff
 func (m *Manager) attemptReconnect(session Session) error {
 	sshSession, ok := session.(*SSHSession)
@@ -372,11 +674,13 @@ func (m *Manager) attemptReconnect(session Session) error {
 	for attempt := 1; attempt <= m.reconnectAttempts; attempt++ {
 		// Wait before retry (except first attempt)
 		if attempt > 1 {
-			logger.Debug("reconnect attempt %d/%d for session %q, waiting %v", attempt, m.reconnectAttempts, session.Name(), backoff)
+			logger.Debug("waiting %v before next reconnect attempt for session %q", backoff, session.Name())
 			time.Sleep(backoff)
 			backoff *= 2 // Exponential backoff
 		}
 
+		logger.Warn("reconnect attempt %d/%d for session %q", attempt, m.reconnectAttempts, session.Name())
+
 		// Attempt reconnection
 		if err := sshSession.Reconnect(); err != nil {
 			lastErr = err
@@ -419,13 +723,25 @@ func (m *Manager) restoreSessionEnv(session *SSHSession) {
 
 // SetSessionEnv sets and persists an environment variable for the active session
 func (m *Manager) SetSessionEnv(key, value string) error {
-	session := m.GetActiveSession()
-	if session == nil {
+	if m.GetActiveSession() == nil {
 		return &Error{
 			Code:    ErrSessionNotFound,
 			Message: "No active session",
 		}
 	}
+	return m.SetEnvOn(m.GetActiveSessionName(), key, value)
+}
+
+// SetEnvOn sets and persists an environment variable for a specific session.
+// SSH sessions must be connected.
+func (m *Manager) SetEnvOn(sessionName, key, value string) error {
+	session, err := m.connectedSession(sessionName)
+	if err != nil {
+		return err
+	}
+	if !config.ValidEnvName(key) {
+		return fmt.Errorf("invalid environment variable name: %q", key)
+	}
 
 	session.SetEnv(key, value)
 
@@ -440,16 +756,6 @@ func (m *Manager) SetSessionEnv(key, value string) error {
 
 // ExecuteOn executes a command on a specific session
 func (m *Manager) ExecuteOn(sessionName, cmd string) (*ExecuteResult, error) {
-	// Check for restricted commands first
-	if allowed, rule := m.restriction.Check(cmd); !allowed {
-		logger.Warn("command blocked by restriction: %s (rule: %s)", cmd, rule.Command)
-		return nil, &Error{
-			Code:       ErrCommandRestricted,
-			Message:    fmt.Sprintf("%s: '%s' is not allowed in restricted mode", restriction.CategoryDescription(rule.Category), rule.Command),
-			Suggestion: "Remove --restricted flag to allow this command, or use a different approach",
-		}
-	}
-
 	session, ok := m.GetSession(sessionName)
 	if !ok {
 		return nil, &Error{
@@ -459,21 +765,36 @@ func (m *Manager) ExecuteOn(sessionName, cmd string) (*ExecuteResult, error) {
 		}
 	}
 
-	return session.Execute(cmd)
-}
+	// Check for restricted commands before connecting or running anything
+	if err := m.checkRestriction(sessionName, cmd); err != nil {
+		return nil, err
+	}
 
-// ExecuteInteractive executes a command on the active session with PTY support
-func (m *Manager) ExecuteInteractive(cmd string) (int, error) {
-	// Check for restricted commands first
-	if allowed, rule := m.restriction.Check(cmd); !allowed {
-		logger.Warn("command blocked by restriction: %s (rule: %s)", cmd, rule.Command)
-		return 1, &Error{
-			Code:       ErrCommandRestricted,
-			Message:    fmt.Sprintf("%s: '%s' is not allowed in restricted mode", restriction.CategoryDescription(rule.Category), rule.Command),
-			Suggestion: "Remove --restricted flag to allow this command, or use a different approach",
+	if session.Type() == "ssh" && !session.IsConnected() {
+		if !m.autoConnect {
+			return nil, &Error{
+				Code:       ErrSessionDisconnected,
+				Message:    fmt.Sprintf("Session '%s' is not connected", sessionName),
+				Session:    sessionName,
+				Suggestion: fmt.Sprintf("Use /connect %s first, or set auto_connect = true in [settings]", sessionName),
+			}
+		}
+		logger.Info("auto-connecting session %q", sessionName)
+		if err := m.Connect(sessionName); err != nil {
+			return nil, err
 		}
 	}
 
+	return m.executeWithReconnect(context.Background(), session, cmd)
+}
+
+// SetAutoConnect sets whether ExecuteOn connects disconnected SSH sessions on demand
+func (m *Manager) SetAutoConnect(enabled bool) {
+	m.autoConnect = enabled
+}
+
+// ExecuteInteractive executes a command on the active session with PTY support
+func (m *Manager) ExecuteInteractive(cmd string) (int, error) {
 	session := m.GetActiveSession()
 	if session == nil {
 		logger.Warn("execute interactive failed: no active session")
@@ -483,6 +804,11 @@ func (m *Manager) ExecuteInteractive(cmd string) (int, error) {
 		}
 	}
 
+	// Check for restricted commands before running anything
+	if err := m.checkRestriction(session.Name(), cmd); err != nil {
+		return 1, err
+	}
+
 	logger.Debug("executing interactive on session %q: %s", session.Name(), cmd)
 	return session.ExecuteInteractive(cmd)
 }
@@ -501,10 +827,15 @@ func (m *Manager) ListSessions() []SessionInfo {
 			CWD:       session.GetCWD(),
 			Active:    name == m.activeSession,
 		}
+		if cfg, ok := m.config.Sessions[name]; ok {
+			info.Description = cfg.Description
+		}
 
 		if sshSession, ok := session.(*SSHSession); ok {
 			info.Host = sshSession.Host()
 			info.User = sshSession.User()
+			info.Port = sshSession.Port()
+			info.AuthMethod = sshSession.AuthMethod()
 		}
 
 		sessions = append(sessions, info)
@@ -515,13 +846,16 @@ func (m *Manager) ListSessions() []SessionInfo {
 
 // SessionInfo contains information about a session
 type SessionInfo struct {
-	Name      string
-	Type      string
-	Connected bool
-	CWD       string
-	Active    bool
-	Host      string
-	User      string
+	Name        string
+	Type        string
+	Connected   bool
+	CWD         string
+	Active      bool
+	Host        string
+	User        string
+	Port        int
+	AuthMethod  string
+	Description string
 }
 
 // SessionNames returns all session names
@@ -569,12 +903,72 @@ func (m *Manager) AddSession(name string, cfg config.Session) error {
 	if _, exists := m.sessions[name]; exists {
 		return fmt.Errorf("session '%s' already exists", name)
 	}
+	if limit := m.config.SessionLimit(); len(m.sessions) >= limit {
+		return fmt.Errorf("cannot add session '%s': session limit of %d reached (raise max_sessions in [settings] to allow more)", name, limit)
+	}
 
 	m.sessions[name] = m.createSession(name, cfg)
 	logger.Info("added new session %q", name)
 	return nil
 }
 
+// RemoveSession disconnects a session if it is connected and forgets it,
+// along with its configuration entry and saved state. A default_session
+// naming it falls back to local. The local session and the active session
+// can't be removed.
+func (m *Manager) RemoveSession(name string) error {
+	m.mu.RLock()
+	session, ok := m.sessions[name]
+	active := m.activeSession
+	m.mu.RUnlock()
+
+	if !ok {
+		return &Error{
+			Code:    ErrSessionNotFound,
+			Message: fmt.Sprintf("Session '%s' not found", name),
+			Session: name,
+		}
+	}
+	if name == "local" {
+		return fmt.Errorf("the local session can't be removed")
+	}
+	if name == active {
+		return fmt.Errorf("session '%s' is active; switch to another session first", name)
+	}
+
+	if session.IsConnected() {
+		if err := m.Disconnect(name); err != nil {
+			logger.Warn("error disconnecting %q before removing it: %v", name, err)
+		}
+	}
+
+	m.mu.Lock()
+	delete(m.sessions, name)
+	delete(m.tempDirs, name)
+	delete(m.restrictedSess, name)
+	delete(m.config.Sessions, name)
+	if m.config.Settings.DefaultSession == name {
+		m.config.Settings.DefaultSession = "local"
+	}
+	recent := m.recent[:0]
+	for _, r := range m.recent {
+		if r != name {
+			recent = append(recent, r)
+		}
+	}
+	m.recent = recent
+	m.mu.Unlock()
+
+	if m.state != nil {
+		if err := m.state.RemoveSession(name); err != nil {
+			logger.Warn("failed to save state after removing %q: %v", name, err)
+		}
+	}
+
+	logger.Info("removed session %q", name)
+	return nil
+}
+
 // GetConfig returns the current configuration
 func (m *Manager) GetConfig() *config.Config {
 	return m.config
@@ -588,6 +982,29 @@ func (m *Manager) SetRestrictedMode(enabled bool) {
 	}
 }
 
+// SetRestrictionWarnOnly switches restriction checking between enforce and warn-only mode
+func (m *Manager) SetRestrictionWarnOnly(warnOnly bool) {
+	if warnOnly {
+		m.restriction.SetMode(restriction.ModeWarnOnly)
+		logger.Info("restriction warn-only mode enabled - dangerous commands will be logged but not blocked")
+	} else {
+		m.restriction.SetMode(restriction.ModeEnforce)
+	}
+}
+
+// IsRestrictionWarnOnly returns whether restrictions only log instead of blocking
+func (m *Manager) IsRestrictionWarnOnly() bool {
+	return m.restriction.IsWarnOnly()
+}
+
+// IsSessionRestricted returns true if restrictions are enforced for the named
+// session by its own config, independent of the global restricted mode
+func (m *Manager) IsSessionRestricted(name string) bool {
+	m.mu.RLock()
+	defer m.mu.RUnlock()
+	return m.restrictedSess[name]
+}
+
 // IsRestrictedMode returns whether restricted mode is enabled
 func (m *Manager) IsRestrictedMode() bool {
 	return m.restriction.IsEnabled()
@@ -1,6 +1,9 @@
 package session
 
 import (
+	"bytes"
+	"context"
+	"encoding/json"
 	"os"
 	"path/filepath"
 	"strings"
@@ -8,6 +11,7 @@ import (
 
 	"github.com/scottgl9/thop/internal/config"
 	"github.com/scottgl9/thop/internal/state"
+	"golang.org/x/crypto/ssh"
 )
 
 func createTestManager(t *testing.T) (*Manager, string) {
@@ -216,6 +220,10 @@ func TestListSessions(t *testing.T) {
 	if sshInfo.User != "testuser" {
 		t.Errorf("expected user 'testuser', got '%s'", sshInfo.User)
 	}
+
+	if sshInfo.Port != 22 {
+		t.Errorf("expected port 22, got %d", sshInfo.Port)
+	}
 }
 
 func TestSessionNames(t *testing.T) {
@@ -339,6 +347,7 @@ Host myalias
     User deploy
     Port 2222
     IdentityFile ~/.ssh/mykey
+    ForwardAgent yes
 
 Host prod
     HostName prod.example.com
@@ -419,6 +428,9 @@ Host prod
 	if sshSession.Port() != 2222 {
 		t.Errorf("expected port 2222, got %d", sshSession.Port())
 	}
+	if !sshSession.AgentForwarding() {
+		t.Error("expected agent forwarding from ForwardAgent in SSH config")
+	}
 
 	// Verify the explicit session uses explicit values (not SSH config)
 	explicitSession, ok := mgr.GetSession("explicit")
@@ -488,3 +500,516 @@ func TestSSHConfigWithNoSSHConfig(t *testing.T) {
 		t.Error("expected HasSSHConfigHost to return false without SSH config")
 	}
 }
+
+func TestRestrictionWarnOnly(t *testing.T) {
+	mgr, tmpDir := createTestManager(t)
+	mgr.SetRestrictedMode(true)
+
+	target := filepath.Join(tmpDir, "victim.txt")
+	if err := os.WriteFile(target, []byte("data"), 0644); err != nil {
+		t.Fatal(err)
+	}
+
+	// Enforce mode blocks the command
+	_, err := mgr.Execute("unlink " + target)
+	if err == nil {
+		t.Fatal("expected unlink to be blocked in enforce mode")
+	}
+	if sessionErr, ok := err.(*Error); !ok || sessionErr.Code != ErrCommandRestricted {
+		t.Errorf("expected ErrCommandRestricted, got %v", err)
+	}
+	if _, statErr := os.Stat(target); statErr != nil {
+		t.Error("file should still exist after blocked command")
+	}
+
+	// Warn-only mode logs but still runs the command
+	mgr.SetRestrictionWarnOnly(true)
+	if !mgr.IsRestrictionWarnOnly() {
+		t.Error("expected warn-only mode to be enabled")
+	}
+	result, err := mgr.Execute("unlink " + target)
+	if err != nil {
+		t.Fatalf("expected unlink to run in warn-only mode, got %v", err)
+	}
+	if result.ExitCode != 0 {
+		t.Errorf("expected exit code 0, got %d", result.ExitCode)
+	}
+	if _, statErr := os.Stat(target); !os.IsNotExist(statErr) {
+		t.Error("file should have been removed in warn-only mode")
+	}
+}
+
+func TestExecuteOnDisconnectedSSH(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	// Without auto-connect, a structured error tells the caller to connect
+	_, err := mgr.ExecuteOn("testserver", "echo test")
+	sessionErr, ok := err.(*Error)
+	if !ok {
+		t.Fatalf("expected *Error, got %T: %v", err, err)
+	}
+	if sessionErr.Code != ErrSessionDisconnected {
+		t.Errorf("expected code %s, got %s", ErrSessionDisconnected, sessionErr.Code)
+	}
+	if sessionErr.Session != "testserver" {
+		t.Errorf("expected session 'testserver', got %q", sessionErr.Session)
+	}
+	if !strings.Contains(sessionErr.Suggestion, "/connect testserver") {
+		t.Errorf("expected suggestion to mention /connect, got %q", sessionErr.Suggestion)
+	}
+}
+
+func TestExecuteOnAutoConnect(t *testing.T) {
+	tmpDir := t.TempDir()
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+
+	keyPath, pub := writeTestKey(t, tmpDir)
+	port := startTestSSHServer(t, &ssh.ServerConfig{
+		PublicKeyCallback: func(conn ssh.ConnMetadata, key ssh.PublicKey) (*ssh.Permissions, error) {
+			if bytes.Equal(key.Marshal(), pub.Marshal()) {
+				return nil, nil
+			}
+			return nil, ssh.ErrNoAuth
+		},
+	})
+
+	mgr, _ := createTestManager(t)
+	mgr.SetAutoConnect(true)
+
+	// Swap in a session pointing at the in-process test server
+	sshSession := NewSSHSession(SSHConfig{
+		Name:                  "testserver",
+		Host:                  "127.0.0.1",
+		Port:                  port,
+		User:                  "testuser",
+		KeyFile:               keyPath,
+		InsecureIgnoreHostKey: true,
+	})
+	mgr.sessions["testserver"] = sshSession
+	defer sshSession.Disconnect()
+
+	// The test server rejects exec channels, so the command itself fails,
+	// but the session must have been connected on demand
+	_, err := mgr.ExecuteOn("testserver", "echo test")
+	if sessionErr, ok := err.(*Error); ok && sessionErr.Code == ErrSessionDisconnected {
+		t.Fatalf("expected auto-connect, got disconnected error: %v", err)
+	}
+	if !sshSession.IsConnected() {
+		t.Error("expected session to be connected on demand")
+	}
+}
+
+func TestPerSessionRestriction(t *testing.T) {
+	tmpDir := t.TempDir()
+
+	cfg := &config.Config{
+		Settings: config.Settings{
+			DefaultSession: "local",
+		},
+		Sessions: map[string]config.Session{
+			"local": {
+				Type:  "local",
+				Shell: "/bin/sh",
+			},
+			"prod": {
+				Type:       "local",
+				Shell:      "/bin/sh",
+				Restricted: true,
+			},
+		},
+	}
+	mgr := NewManager(cfg, state.NewManager(filepath.Join(tmpDir, "state.json")))
+
+	if mgr.IsRestrictedMode() {
+		t.Error("expected global restricted mode to be off")
+	}
+	if !mgr.IsSessionRestricted("prod") || mgr.IsSessionRestricted("local") {
+		t.Error("expected only prod to be restricted")
+	}
+
+	target := filepath.Join(tmpDir, "x")
+	if err := os.WriteFile(target, []byte("data"), 0644); err != nil {
+		t.Fatal(err)
+	}
+
+	// Blocked on the restricted session
+	_, err := mgr.ExecuteOn("prod", "unlink "+target)
+	sessionErr, ok := err.(*Error)
+	if !ok || sessionErr.Code != ErrCommandRestricted {
+		t.Fatalf("expected ErrCommandRestricted on prod, got %v", err)
+	}
+	if sessionErr.Session != "prod" {
+		t.Errorf("expected error session 'prod', got %q", sessionErr.Session)
+	}
+	if _, statErr := os.Stat(target); statErr != nil {
+		t.Error("file should still exist after blocked command")
+	}
+
+	// Allowed on the unrestricted session
+	result, err := mgr.ExecuteOn("local", "unlink "+target)
+	if err != nil {
+		t.Fatalf("expected command to run on local, got %v", err)
+	}
+	if result.ExitCode != 0 {
+		t.Errorf("expected exit code 0, got %d", result.ExitCode)
+	}
+
+	// The active session's flag applies to Execute as well
+	if err := mgr.SetActiveSession("prod"); err != nil {
+		t.Fatal(err)
+	}
+	if _, err := mgr.Execute("sudo true"); err == nil {
+		t.Error("expected sudo to be blocked on active restricted session")
+	}
+
+	// Sessions added at runtime honor the flag too
+	if err := mgr.AddSession("staging", config.Session{Type: "local", Restricted: true}); err != nil {
+		t.Fatal(err)
+	}
+	if !mgr.IsSessionRestricted("staging") {
+		t.Error("expected added session to be restricted")
+	}
+}
+
+func TestRestrictionAuditFile(t *testing.T) {
+	mgr, tmpDir := createTestManager(t)
+	auditPath := filepath.Join(tmpDir, "audit", "blocked.jsonl")
+	mgr.config.Settings.RestrictionAuditFile = auditPath
+
+	mgr.SetRestrictedMode(true)
+	blocked := []string{"sudo ls", "shutdown -h now"}
+	for _, cmd := range blocked {
+		if _, err := mgr.Execute(cmd); err == nil {
+			t.Fatalf("expected %q to be blocked", cmd)
+		}
+	}
+
+	// Allowed commands are not recorded
+	if _, err := mgr.Execute("echo ok"); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	data, err := os.ReadFile(auditPath)
+	if err != nil {
+		t.Fatalf("failed to read audit file: %v", err)
+	}
+	lines := strings.Split(strings.TrimSpace(string(data)), "\n")
+	if len(lines) != len(blocked) {
+		t.Fatalf("expected %d audit records, got %d:\n%s", len(blocked), len(lines), data)
+	}
+
+	for i, line := range lines {
+		var record AuditRecord
+		if err := json.Unmarshal([]byte(line), &record); err != nil {
+			t.Fatalf("invalid audit record %q: %v", line, err)
+		}
+		if record.Command != blocked[i] {
+			t.Errorf("expected command %q, got %q", blocked[i], record.Command)
+		}
+		if record.Session != "local" {
+			t.Errorf("expected session 'local', got %q", record.Session)
+		}
+		if record.Action != AuditBlocked {
+			t.Errorf("expected action %q, got %q", AuditBlocked, record.Action)
+		}
+		if record.Timestamp == "" || record.Category == "" || record.RuleCommand == "" {
+			t.Errorf("expected populated record, got %+v", record)
+		}
+	}
+}
+
+func TestRestrictionAuditFileExpandsHome(t *testing.T) {
+	mgr, tmpDir := createTestManager(t)
+	home := filepath.Join(tmpDir, "home")
+	t.Setenv("HOME", home)
+	t.Chdir(tmpDir)
+	mgr.config.Settings.RestrictionAuditFile = "~/audit/blocked.jsonl"
+
+	mgr.SetRestrictedMode(true)
+	if _, err := mgr.Execute("sudo ls"); err == nil {
+		t.Fatal("expected command to be blocked")
+	}
+
+	if _, err := os.Stat(filepath.Join(home, "audit", "blocked.jsonl")); err != nil {
+		t.Errorf("expected audit file under home: %v", err)
+	}
+	if _, err := os.Stat(filepath.Join(tmpDir, "~")); !os.IsNotExist(err) {
+		t.Errorf("expected no literal ~ directory, stat returned %v", err)
+	}
+}
+
+func TestExecuteBypass(t *testing.T) {
+	mgr, tmpDir := createTestManager(t)
+	auditPath := filepath.Join(tmpDir, "audit.jsonl")
+	mgr.config.Settings.RestrictionAuditFile = auditPath
+	mgr.SetRestrictedMode(true)
+
+	target := filepath.Join(tmpDir, "victim")
+	if err := os.WriteFile(target, []byte("x"), 0644); err != nil {
+		t.Fatalf("failed to create file: %v", err)
+	}
+	cmd := "unlink " + target
+
+	// Disabled by default
+	_, err := mgr.ExecuteBypassWithContext(context.Background(), cmd)
+	sessionErr, ok := err.(*Error)
+	if !ok || sessionErr.Code != ErrCommandRestricted {
+		t.Fatalf("expected ErrCommandRestricted when bypass is disabled, got %v", err)
+	}
+	if _, err := os.Stat(target); err != nil {
+		t.Fatalf("file should not have been removed: %v", err)
+	}
+
+	mgr.config.Settings.AllowRestrictionBypass = true
+	result, err := mgr.ExecuteBypassWithContext(context.Background(), cmd)
+	if err != nil {
+		t.Fatalf("expected bypass to run the command, got %v", err)
+	}
+	if result.ExitCode != 0 {
+		t.Fatalf("expected exit code 0, got %d: %s", result.ExitCode, result.Stderr)
+	}
+	if _, err := os.Stat(target); !os.IsNotExist(err) {
+		t.Errorf("expected file to be removed, stat returned %v", err)
+	}
+
+	// The normal path still blocks
+	if _, err := mgr.Execute(cmd); err == nil {
+		t.Error("expected restricted command to be blocked without the bypass")
+	}
+
+	data, err := os.ReadFile(auditPath)
+	if err != nil {
+		t.Fatalf("failed to read audit file: %v", err)
+	}
+	lines := strings.Split(strings.TrimSpace(string(data)), "\n")
+	if len(lines) != 2 {
+		t.Fatalf("expected 2 audit records, got %d:\n%s", len(lines), data)
+	}
+	for i, want := range []string{AuditBypassed, AuditBlocked} {
+		var record AuditRecord
+		if err := json.Unmarshal([]byte(lines[i]), &record); err != nil {
+			t.Fatalf("invalid audit record %q: %v", lines[i], err)
+		}
+		if record.Action != want || record.Command != cmd || record.RuleCommand != "unlink" {
+			t.Errorf("record %d: expected %s of %q by unlink, got %+v", i, want, cmd, record)
+		}
+	}
+}
+
+func TestAddSessionLimit(t *testing.T) {
+	mgr, _ := createTestManager(t)
+	mgr.config.Settings.MaxSessions = 4 // local and testserver already exist
+
+	for _, name := range []string{"one", "two"} {
+		if err := mgr.AddSession(name, config.Session{Type: "local"}); err != nil {
+			t.Fatalf("expected %q within the cap to be added, got %v", name, err)
+		}
+	}
+
+	err := mgr.AddSession("three", config.Session{Type: "local"})
+	if err == nil {
+		t.Fatal("expected session beyond the cap to be rejected")
+	}
+	if !strings.Contains(err.Error(), "max_sessions") {
+		t.Errorf("expected error to mention max_sessions, got %v", err)
+	}
+	if mgr.HasSession("three") {
+		t.Error("rejected session should not be added")
+	}
+}
+
+func TestSessionEnvFromConfig(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	envFile := filepath.Join(t.TempDir(), "build.env")
+	if err := os.WriteFile(envFile, []byte("GOFLAGS=-mod=vendor\nCGO_ENABLED=1\n"), 0600); err != nil {
+		t.Fatal(err)
+	}
+
+	err := mgr.AddSession("build", config.Session{
+		Type:    "local",
+		EnvFile: envFile,
+		Env:     map[string]string{"CGO_ENABLED": "0"},
+	})
+	if err != nil {
+		t.Fatalf("AddSession failed: %v", err)
+	}
+
+	result, err := mgr.ExecuteOn("build", `echo "$GOFLAGS $CGO_ENABLED"`)
+	if err != nil {
+		t.Fatalf("ExecuteOn failed: %v", err)
+	}
+	if got := strings.TrimSpace(result.Stdout); got != "-mod=vendor 0" {
+		t.Errorf("expected env file merged with inline env winning, got %q", got)
+	}
+}
+
+func TestSetSessionPassword(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	err := mgr.SetSessionPassword("nonexistent", "secret")
+	if sessionErr, ok := err.(*Error); !ok || sessionErr.Code != ErrSessionNotFound {
+		t.Errorf("expected ErrSessionNotFound, got %v", err)
+	}
+
+	if err := mgr.SetSessionPassword("local", "secret"); err == nil {
+		t.Error("expected error setting a password on a local session")
+	}
+
+	if err := mgr.SetSessionPassword("testserver", "secret"); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	sess, _ := mgr.GetSession("testserver")
+	if !sess.(*SSHSession).HasPassword() {
+		t.Error("expected SSH session to have the password set")
+	}
+}
+
+func TestExecuteVerbatimRefusedWhenRestricted(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	result, err := mgr.ExecuteVerbatim(context.Background(), "", "echo raw")
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if strings.TrimSpace(result.Stdout) != "raw" {
+		t.Errorf("expected 'raw', got %q", result.Stdout)
+	}
+
+	mgr.SetRestrictedMode(true)
+	_, err = mgr.ExecuteVerbatim(context.Background(), "", "echo raw")
+	sessionErr, ok := err.(*Error)
+	if !ok || sessionErr.Code != ErrCommandRestricted {
+		t.Errorf("expected ErrCommandRestricted in restricted mode, got %v", err)
+	}
+}
+
+func TestExecuteVerbatimAudited(t *testing.T) {
+	mgr, tmpDir := createTestManager(t)
+	auditPath := filepath.Join(tmpDir, "audit.jsonl")
+	mgr.config.Settings.RestrictionAuditFile = auditPath
+
+	for _, cmd := range []string{"echo raw", "curl --version >/dev/null 2>&1; true"} {
+		if _, err := mgr.ExecuteVerbatim(context.Background(), "", cmd); err != nil {
+			t.Fatalf("ExecuteVerbatim(%q) failed: %v", cmd, err)
+		}
+	}
+
+	data, err := os.ReadFile(auditPath)
+	if err != nil {
+		t.Fatalf("failed to read audit file: %v", err)
+	}
+	lines := strings.Split(strings.TrimSpace(string(data)), "\n")
+	if len(lines) != 2 {
+		t.Fatalf("expected 2 audit records, got %d:\n%s", len(lines), data)
+	}
+
+	var plain, matched AuditRecord
+	if err := json.Unmarshal([]byte(lines[0]), &plain); err != nil {
+		t.Fatalf("invalid audit record %q: %v", lines[0], err)
+	}
+	if err := json.Unmarshal([]byte(lines[1]), &matched); err != nil {
+		t.Fatalf("invalid audit record %q: %v", lines[1], err)
+	}
+	if plain.Action != AuditRaw || plain.Command != "echo raw" || plain.Session != "local" || plain.RuleCommand != "" {
+		t.Errorf("unexpected record for a plain raw command: %+v", plain)
+	}
+	// A raw command that a rule would block names the rule
+	if matched.Action != AuditRaw || matched.RuleCommand != "curl" {
+		t.Errorf("expected the matching rule to be recorded, got %+v", matched)
+	}
+}
+
+func TestManagerBackWalksRecentSessions(t *testing.T) {
+	mgr, _ := createTestManager(t)
+	for _, name := range []string{"a", "b", "c"} {
+		if err := mgr.AddSession(name, config.Session{Type: "local"}); err != nil {
+			t.Fatalf("AddSession %s failed: %v", name, err)
+		}
+	}
+
+	for _, name := range []string{"a", "b", "c"} {
+		if err := mgr.SetActiveSession(name); err != nil {
+			t.Fatalf("SetActiveSession %s failed: %v", name, err)
+		}
+	}
+
+	recent := mgr.RecentSessions()
+	if strings.Join(recent, ",") != "b,a,local" {
+		t.Errorf("expected recent b,a,local, got %v", recent)
+	}
+
+	for _, want := range []string{"b", "a", "local"} {
+		name, err := mgr.Back()
+		if err != nil {
+			t.Fatalf("Back failed: %v", err)
+		}
+		if name != want || mgr.GetActiveSessionName() != want {
+			t.Errorf("expected back to %s, got %s (active %s)", want, name, mgr.GetActiveSessionName())
+		}
+	}
+
+	if _, err := mgr.Back(); err == nil {
+		t.Error("expected error once the stack is exhausted")
+	}
+
+	// Returning to a session already on the stack moves it rather than duplicating it
+	_ = mgr.SetActiveSession("a")
+	_ = mgr.SetActiveSession("b")
+	_ = mgr.SetActiveSession("a")
+	if recent := mgr.RecentSessions(); strings.Join(recent, ",") != "b,local" {
+		t.Errorf("expected recent b,local, got %v", recent)
+	}
+}
+
+func TestRemoveSession(t *testing.T) {
+	mgr, _ := createTestManager(t)
+	if err := mgr.state.SetSessionCWD("testserver", "/srv"); err != nil {
+		t.Fatalf("SetSessionCWD failed: %v", err)
+	}
+
+	if err := mgr.RemoveSession("testserver"); err != nil {
+		t.Fatalf("RemoveSession failed: %v", err)
+	}
+
+	if mgr.HasSession("testserver") {
+		t.Error("removed session should be gone from the manager")
+	}
+	if _, ok := mgr.config.Sessions["testserver"]; ok {
+		t.Error("removed session should be gone from the config")
+	}
+	if _, ok := mgr.state.GetSessionState("testserver"); ok {
+		t.Error("removed session should be gone from the state")
+	}
+	for _, info := range mgr.ListSessions() {
+		if info.Name == "testserver" {
+			t.Error("removed session should not be listed")
+		}
+	}
+}
+
+func TestRemoveSessionRefusals(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	if err := mgr.RemoveSession("local"); err == nil {
+		t.Error("expected removing local to fail")
+	}
+	if err := mgr.RemoveSession("missing"); err == nil {
+		t.Error("expected removing a missing session to fail")
+	}
+
+	if err := mgr.AddSession("scratch", config.Session{Type: "local", Shell: "/bin/sh"}); err != nil {
+		t.Fatalf("AddSession failed: %v", err)
+	}
+	if err := mgr.SetActiveSession("scratch"); err != nil {
+		t.Fatalf("SetActiveSession failed: %v", err)
+	}
+	err := mgr.RemoveSession("scratch")
+	if err == nil || !strings.Contains(err.Error(), "switch") {
+		t.Errorf("expected removing the active session to ask for a switch first, got %v", err)
+	}
+	if !mgr.HasSession("scratch") || !mgr.HasSession("local") {
+		t.Error("refused removals should leave sessions in place")
+	}
+}
@@ -0,0 +1,92 @@
+package session
+
+import (
+	"fmt"
+	"os"
+	"path"
+	"path/filepath"
+	"strings"
+)
+
+// FilePath returns the path that file operations (SFTP or local file I/O)
+// should use for a path given on a session, so that it refers to the same file
+// a shell command on the session would. Relative paths are resolved against the
+// session's cwd. On SSH sessions a leading ~ is turned into a path relative to
+// the SFTP start directory, which is the remote home directory.
+func FilePath(session Session, p string) string {
+	if session.Type() == "local" {
+		if p == "~" || strings.HasPrefix(p, "~/") {
+			if home, err := os.UserHomeDir(); err == nil {
+				return filepath.Join(home, p[1:])
+			}
+		}
+		if cwd := session.GetCWD(); !filepath.IsAbs(p) && cwd != "" {
+			return filepath.Join(cwd, p)
+		}
+		return p
+	}
+
+	if p == "~" {
+		return "."
+	}
+	if strings.HasPrefix(p, "~/") {
+		return p[2:]
+	}
+	if strings.HasPrefix(p, "/") {
+		return p
+	}
+
+	cwd := session.GetCWD()
+	switch {
+	case cwd == "" || cwd == "~":
+		return p
+	case strings.HasPrefix(cwd, "~/"):
+		return path.Join(cwd[2:], p)
+	default:
+		return path.Join(cwd, p)
+	}
+}
+
+// RealPath resolves path on the named session to its canonical absolute
+// form, following symlinks. Relative paths are resolved against the
+// session's cwd. The path must exist.
+func (m *Manager) RealPath(sessionName, path string) (string, error) {
+	session, err := m.lookupSession(sessionName)
+	if err != nil {
+		return "", err
+	}
+
+	if session.Type() == "local" {
+		target := path
+		if !filepath.IsAbs(target) {
+			target = filepath.Join(session.GetCWD(), target)
+		}
+		resolved, err := filepath.EvalSymlinks(target)
+		if err != nil {
+			if os.IsNotExist(err) {
+				return "", fmt.Errorf("path '%s' does not exist", path)
+			}
+			return "", fmt.Errorf("failed to resolve path '%s': %w", path, err)
+		}
+		return resolved, nil
+	}
+
+	if !session.IsConnected() {
+		return "", notConnectedError(session.Name())
+	}
+
+	// The session runs this in its cwd, so relative paths resolve there
+	quoted := ShellQuote(path)
+	result, err := session.Execute(fmt.Sprintf("test -e %s && readlink -f -- %s", quoted, quoted))
+	if err != nil {
+		return "", err
+	}
+	resolved := strings.TrimSpace(result.Stdout)
+	if result.ExitCode != 0 || resolved == "" {
+		if stderr := strings.TrimSpace(result.Stderr); stderr != "" {
+			return "", fmt.Errorf("failed to resolve path '%s': %s", path, stderr)
+		}
+		return "", fmt.Errorf("path '%s' does not exist", path)
+	}
+	return resolved, nil
+}
@@ -0,0 +1,107 @@
+package session
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+func TestRealPathLocal(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	// Resolve the temp dir itself in case it sits behind a symlink
+	tmpDir, err := filepath.EvalSymlinks(t.TempDir())
+	if err != nil {
+		t.Fatal(err)
+	}
+	target := filepath.Join(tmpDir, "data", "file.txt")
+	if err := os.MkdirAll(filepath.Dir(target), 0755); err != nil {
+		t.Fatal(err)
+	}
+	if err := os.WriteFile(target, []byte("x"), 0644); err != nil {
+		t.Fatal(err)
+	}
+	if err := os.Symlink(filepath.Join(tmpDir, "data"), filepath.Join(tmpDir, "link")); err != nil {
+		t.Fatal(err)
+	}
+
+	sess, _ := mgr.GetSession("local")
+	if err := sess.SetCWD(tmpDir); err != nil {
+		t.Fatal(err)
+	}
+
+	tests := []struct {
+		name string
+		path string
+	}{
+		{"relative", "data/../data/file.txt"},
+		{"symlink", "link/file.txt"},
+		{"absolute symlink", filepath.Join(tmpDir, "link", "file.txt")},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			resolved, err := mgr.RealPath("local", tt.path)
+			if err != nil {
+				t.Fatalf("RealPath failed: %v", err)
+			}
+			if resolved != target {
+				t.Errorf("expected %s, got %s", target, resolved)
+			}
+		})
+	}
+
+	_, err = mgr.RealPath("local", "missing.txt")
+	if err == nil || !strings.Contains(err.Error(), "does not exist") {
+		t.Errorf("expected does-not-exist error, got %v", err)
+	}
+
+	_, err = mgr.RealPath("nonexistent", "file.txt")
+	if sessionErr, ok := err.(*Error); !ok || sessionErr.Code != ErrSessionNotFound {
+		t.Errorf("expected ErrSessionNotFound, got %v", err)
+	}
+
+	_, err = mgr.RealPath("testserver", "file.txt")
+	if sessionErr, ok := err.(*Error); !ok || sessionErr.Code != ErrSessionDisconnected {
+		t.Errorf("expected ErrSessionDisconnected, got %v", err)
+	}
+}
+
+func TestFilePath(t *testing.T) {
+	home := t.TempDir()
+	t.Setenv("HOME", home)
+
+	local := NewLocalSession("local", "/bin/sh")
+	if err := local.SetCWD(home); err != nil {
+		t.Fatal(err)
+	}
+	remote := NewSSHSession(SSHConfig{Name: "remote", Host: "example.com", User: "deploy"})
+
+	tests := []struct {
+		name    string
+		session Session
+		cwd     string
+		path    string
+		want    string
+	}{
+		{"local relative", local, "", "notes.txt", filepath.Join(home, "notes.txt")},
+		{"local absolute", local, "", "/etc/hosts", "/etc/hosts"},
+		{"local home", local, "", "~/notes.txt", filepath.Join(home, "notes.txt")},
+		{"ssh relative", remote, "/srv/app", "logs/app.log", "/srv/app/logs/app.log"},
+		{"ssh parent", remote, "/srv/app", "../shared/x", "/srv/shared/x"},
+		{"ssh absolute", remote, "/srv/app", "/etc/hosts", "/etc/hosts"},
+		{"ssh home", remote, "/srv/app", "~/.bashrc", ".bashrc"},
+		{"ssh home cwd", remote, "~", "notes.txt", "notes.txt"},
+		{"ssh home subdir cwd", remote, "~/src", "main.go", "src/main.go"},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if tt.cwd != "" {
+				_ = tt.session.SetCWD(tt.cwd)
+			}
+			if got := FilePath(tt.session, tt.path); got != tt.want {
+				t.Errorf("FilePath(%q) = %q, want %q", tt.path, got, tt.want)
+			}
+		})
+	}
+}
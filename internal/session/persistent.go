@@ -0,0 +1,312 @@
+package session
+
+import (
+	"bytes"
+	"context"
+	"crypto/rand"
+	"encoding/hex"
+	"fmt"
+	"io"
+	"os"
+	"os/exec"
+	"sort"
+	"strconv"
+	"strings"
+	"sync"
+	"syscall"
+
+	"github.com/scottgl9/thop/internal/logger"
+)
+
+// persistentShell is a long-lived shell that a local session in persistent
+// mode runs its commands in, so shell variables, functions, aliases and set
+// options carry over between commands. Each command is written to the shell's
+// stdin followed by a unique marker printed to stdout, with the exit code, and
+// to stderr, which delimits its output. If the shell exits, as after "exit" or
+// a failure under set -e, a new one is started for the next command.
+type persistentShell struct {
+	shell string
+
+	// mu is held while a command runs; commands that find it busy, such as
+	// one started while a background job is running, use a one-off shell
+	mu sync.Mutex
+
+	procMu sync.Mutex
+	cmd    *exec.Cmd
+	stdin  io.WriteCloser
+	stdout *os.File
+	stderr *os.File
+	done   chan struct{} // Closed once the shell process has exited
+}
+
+// newPersistentShell returns a persistentShell for shell; the process is
+// started by the first command
+func newPersistentShell(shell string) *persistentShell {
+	return &persistentShell{shell: shell}
+}
+
+// ensureStarted starts the shell in dir with env unless it is already running
+func (p *persistentShell) ensureStarted(dir string, env []string) error {
+	p.procMu.Lock()
+	defer p.procMu.Unlock()
+	if p.cmd != nil {
+		select {
+		case <-p.done:
+			p.release()
+		default:
+			return nil
+		}
+	}
+
+	// Output goes through our own pipes rather than cmd's, whose read ends
+	// Wait closes when the shell exits, possibly before the output is read
+	stdoutR, stdoutW, err := os.Pipe()
+	if err != nil {
+		return fmt.Errorf("failed to start persistent shell: %w", err)
+	}
+	stderrR, stderrW, err := os.Pipe()
+	if err != nil {
+		stdoutR.Close()
+		stdoutW.Close()
+		return fmt.Errorf("failed to start persistent shell: %w", err)
+	}
+
+	cmd := exec.Command(p.shell)
+	cmd.Dir = dir
+	cmd.Env = env
+	cmd.Stdout = stdoutW
+	cmd.Stderr = stderrW
+	cmd.SysProcAttr = &syscall.SysProcAttr{Setpgid: true}
+	stdin, err := cmd.StdinPipe()
+	if err == nil {
+		err = cmd.Start()
+	}
+	// The shell holds its own copies of the write ends
+	stdoutW.Close()
+	stderrW.Close()
+	if err != nil {
+		stdoutR.Close()
+		stderrR.Close()
+		return fmt.Errorf("failed to start persistent shell: %w", err)
+	}
+
+	done := make(chan struct{})
+	go func() {
+		_ = cmd.Wait()
+		close(done)
+	}()
+	p.cmd, p.stdin, p.stdout, p.stderr, p.done = cmd, stdin, stdoutR, stderrR, done
+	logger.Debug("started persistent shell %s (pid %d)", p.shell, cmd.Process.Pid)
+	return nil
+}
+
+// release forgets the current shell process, closing our ends of its pipes.
+// The caller must hold procMu.
+func (p *persistentShell) release() {
+	if p.cmd == nil {
+		return
+	}
+	p.stdin.Close()
+	p.stdout.Close()
+	p.stderr.Close()
+	p.cmd = nil
+}
+
+// kill kills the shell and everything it started, unblocking a command
+// waiting on its output. The next command starts a new shell.
+func (p *persistentShell) kill() {
+	p.procMu.Lock()
+	defer p.procMu.Unlock()
+	if p.cmd == nil {
+		return
+	}
+	_ = syscall.Kill(-p.cmd.Process.Pid, syscall.SIGKILL)
+	p.release()
+}
+
+// run runs cmdStr in the shell after the setup lines, copying its output to
+// stdout and stderr as it arrives, and returns its exit code. The command's
+// stdin is /dev/null so it can't read the lines meant for the shell. If ctx is
+// done first, the shell is killed and ctx's error returned. The caller must
+// hold p.mu and have called ensureStarted.
+func (p *persistentShell) run(ctx context.Context, setup, cmdStr string, stdout, stderr io.Writer) (int, error) {
+	marker, err := newShellMarker()
+	if err != nil {
+		return 1, err
+	}
+
+	p.procMu.Lock()
+	cmd, stdin, outR, errR, done := p.cmd, p.stdin, p.stdout, p.stderr, p.done
+	p.procMu.Unlock()
+	if cmd == nil {
+		return 1, fmt.Errorf("persistent shell is not running")
+	}
+
+	var tail string
+	var outErr error
+	finished := make(chan struct{})
+	go func() {
+		var wg sync.WaitGroup
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			_, _ = copyUntilMarker(errR, stderr, marker)
+		}()
+		tail, outErr = copyUntilMarker(outR, stdout, marker)
+		wg.Wait()
+		close(finished)
+	}()
+
+	script := fmt.Sprintf("%seval %s </dev/null\n__thop_status=$?\nprintf '%%s:%%d\\n' %s \"$__thop_status\"\nprintf '%%s\\n' %s >&2\n",
+		setup, ShellQuote(cmdStr), marker, marker)
+	if _, err := io.WriteString(stdin, script); err != nil {
+		p.kill()
+		<-finished
+		return 1, fmt.Errorf("failed to write to persistent shell: %w", err)
+	}
+
+	select {
+	case <-finished:
+	case <-ctx.Done():
+		p.kill()
+		<-finished
+		return 1, ctx.Err()
+	}
+
+	if outErr != nil {
+		// The shell exited before the command finished, as with "exit 3"
+		<-done
+		p.procMu.Lock()
+		if p.cmd == cmd {
+			p.release()
+		}
+		p.procMu.Unlock()
+		exitCode := cmd.ProcessState.ExitCode()
+		if exitCode < 0 {
+			exitCode = 1
+		}
+		logger.Debug("persistent shell exited with code %d", exitCode)
+		return exitCode, nil
+	}
+
+	exitCode, err := strconv.Atoi(strings.TrimPrefix(tail, ":"))
+	if err != nil {
+		return 1, fmt.Errorf("persistent shell returned an invalid exit status %q", tail)
+	}
+	return exitCode, nil
+}
+
+// newShellMarker returns a random marker that command output won't contain
+func newShellMarker() (string, error) {
+	b := make([]byte, 12)
+	if _, err := rand.Read(b); err != nil {
+		return "", fmt.Errorf("failed to generate output marker: %w", err)
+	}
+	return "__thop_done_" + hex.EncodeToString(b), nil
+}
+
+// copyUntilMarker copies r to w until marker and returns the rest of the line
+// after it. Output is passed on as it arrives, holding back only what could
+// be the start of the marker. It returns an error if r ends first.
+func copyUntilMarker(r io.Reader, w io.Writer, marker string) (string, error) {
+	var pending []byte
+	buf := make([]byte, 32*1024)
+	for {
+		n, err := r.Read(buf)
+		pending = append(pending, buf[:n]...)
+		if idx := bytes.Index(pending, []byte(marker)); idx >= 0 {
+			rest := pending[idx+len(marker):]
+			if end := bytes.IndexByte(rest, '\n'); end >= 0 {
+				_, _ = w.Write(pending[:idx])
+				return string(rest[:end]), nil
+			}
+		} else if keep := len(marker) - 1; len(pending) > keep {
+			_, _ = w.Write(pending[:len(pending)-keep])
+			pending = pending[len(pending)-keep:]
+		}
+		if err != nil {
+			_, _ = w.Write(pending)
+			return "", err
+		}
+	}
+}
+
+// persistentSetup returns the lines run before each command in the
+// persistent shell, putting it in the session's cwd and exporting the
+// session's env, which may have changed since the shell started
+func (s *LocalSession) persistentSetup() string {
+	var setup strings.Builder
+	fmt.Fprintf(&setup, "cd %s 2>/dev/null\n", ShellQuote(s.cwd))
+	keys := make([]string, 0, len(s.env))
+	for k := range s.env {
+		keys = append(keys, k)
+	}
+	sort.Strings(keys)
+	for _, k := range keys {
+		fmt.Fprintf(&setup, "export %s=%s\n", k, ShellQuote(s.env[k]))
+	}
+	return setup.String()
+}
+
+// runPersistent runs cmdStr in the session's persistent shell, starting it
+// if needed. The caller must hold s.persistent.mu.
+func (s *LocalSession) runPersistent(ctx context.Context, cmdStr string, stdout, stderr io.Writer) (int, error) {
+	if err := s.persistent.ensureStarted(s.cwd, s.commandEnv()); err != nil {
+		return 1, err
+	}
+	return s.persistent.run(ctx, s.persistentSetup(), cmdStr, stdout, stderr)
+}
+
+// executePersistent runs cmdStr in the session's persistent shell with the
+// session's timeout, like ExecuteWithContext does in a new shell. The caller
+// must hold s.persistent.mu.
+func (s *LocalSession) executePersistent(ctx context.Context, cmdStr string) (*ExecuteResult, error) {
+	execCtx, cancel := context.WithTimeout(ctx, s.timeout)
+	defer cancel()
+
+	// runCtx is canceled early if the command stops at a password or confirmation prompt
+	runCtx, stop := context.WithCancel(execCtx)
+	defer stop()
+
+	var stdout, stderr bytes.Buffer
+	prompt := newPromptWatcher()
+	go func() {
+		select {
+		case <-prompt.Found():
+			stop()
+		case <-runCtx.Done():
+		}
+	}()
+
+	exitCode, err := s.runPersistent(runCtx, cmdStr, prompt.Writer(&stdout), prompt.Writer(&stderr))
+	if err != nil && prompt.Detected() {
+		logger.Warn("local command stopped at an input prompt on %q", s.name)
+		return nil, prompt.Err(s.name, "")
+	}
+	if err != nil {
+		if ctx.Err() == context.Canceled {
+			logger.Debug("local command interrupted on %q", s.name)
+			return &ExecuteResult{
+				Stdout:   stdout.String(),
+				Stderr:   stderr.String() + "^C\n",
+				ExitCode: 130,
+			}, nil
+		}
+		if execCtx.Err() == context.DeadlineExceeded {
+			logger.Warn("local command timed out after %s on %q", s.timeout, s.name)
+			return nil, &Error{
+				Code:      ErrCommandTimeout,
+				Message:   "Command timed out after " + s.timeout.String(),
+				Session:   s.name,
+				Retryable: true,
+			}
+		}
+		return nil, err
+	}
+
+	return &ExecuteResult{
+		Stdout:   stdout.String(),
+		Stderr:   stderr.String(),
+		ExitCode: exitCode,
+	}, nil
+}
@@ -0,0 +1,140 @@
+package session
+
+import (
+	"bytes"
+	"context"
+	"strings"
+	"testing"
+	"time"
+)
+
+func TestPersistentLocalSessionKeepsShellState(t *testing.T) {
+	session := NewPersistentLocalSession("test", "/bin/sh")
+	defer session.Disconnect()
+
+	for _, cmd := range []string{"greeting=hello", "greet() { echo \"$greeting $1\"; }"} {
+		result, err := session.Execute(cmd)
+		if err != nil {
+			t.Fatalf("Execute(%q) failed: %v", cmd, err)
+		}
+		if result.ExitCode != 0 {
+			t.Fatalf("Execute(%q) exited %d: %s", cmd, result.ExitCode, result.Stderr)
+		}
+	}
+
+	result, err := session.Execute("greet world")
+	if err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	if result.Stdout != "hello world\n" {
+		t.Errorf("expected shell variable and function to persist, got %q", result.Stdout)
+	}
+	if _, ok := session.GetEnv()["greeting"]; ok {
+		t.Error("shell variables should stay in the shell, not the session env")
+	}
+}
+
+func TestPersistentLocalSessionOutput(t *testing.T) {
+	session := NewPersistentLocalSession("test", "/bin/sh")
+	defer session.Disconnect()
+
+	result, err := session.Execute("printf out; printf err >&2; exit_code() { return 7; }; exit_code")
+	if err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	if result.Stdout != "out" || result.Stderr != "err" || result.ExitCode != 7 {
+		t.Errorf("expected stdout %q, stderr %q, exit 7; got %q, %q, %d", "out", "err", result.Stdout, result.Stderr, result.ExitCode)
+	}
+
+	dir := t.TempDir()
+	if _, err := session.Execute("cd " + dir); err != nil {
+		t.Fatalf("cd failed: %v", err)
+	}
+	session.SetEnv("THOP_TEST", "value")
+	result, err = session.Execute("pwd; echo $THOP_TEST")
+	if err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	if want := dir + "\nvalue\n"; result.Stdout != want {
+		t.Errorf("expected %q, got %q", want, result.Stdout)
+	}
+}
+
+func TestPersistentLocalSessionRespawns(t *testing.T) {
+	session := NewPersistentLocalSession("test", "/bin/sh")
+	defer session.Disconnect()
+
+	if _, err := session.Execute("lost=yes"); err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	result, err := session.Execute("exit 3")
+	if err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	if result.ExitCode != 3 {
+		t.Errorf("expected the shell's exit code 3, got %d", result.ExitCode)
+	}
+
+	result, err = session.Execute("echo \"lost=${lost:-no}\"")
+	if err != nil {
+		t.Fatalf("Execute after the shell exited failed: %v", err)
+	}
+	if result.Stdout != "lost=no\n" {
+		t.Errorf("expected a fresh shell, got %q", result.Stdout)
+	}
+}
+
+func TestPersistentLocalSessionTimeout(t *testing.T) {
+	session := NewPersistentLocalSession("test", "/bin/sh")
+	defer session.Disconnect()
+	session.SetTimeout(200 * time.Millisecond)
+
+	_, err := session.Execute("sleep 5")
+	if sessionErr, ok := err.(*Error); !ok || sessionErr.Code != ErrCommandTimeout {
+		t.Fatalf("expected %s, got %v", ErrCommandTimeout, err)
+	}
+
+	session.SetTimeout(5 * time.Second)
+	result, err := session.Execute("echo ok")
+	if err != nil || strings.TrimSpace(result.Stdout) != "ok" {
+		t.Errorf("expected the shell to be restarted after a timeout, got %+v, %v", result, err)
+	}
+}
+
+func TestCopyUntilMarker(t *testing.T) {
+	marker := "__thop_done_test"
+	input := "line one\npartial" + marker + ":0\n"
+	var out bytes.Buffer
+	tail, err := copyUntilMarker(strings.NewReader(input), &out, marker)
+	if err != nil {
+		t.Fatalf("copyUntilMarker failed: %v", err)
+	}
+	if out.String() != "line one\npartial" || tail != ":0" {
+		t.Errorf("got output %q, tail %q", out.String(), tail)
+	}
+
+	out.Reset()
+	if _, err := copyUntilMarker(strings.NewReader("no marker"), &out, marker); err == nil {
+		t.Error("expected an error when the input ends before the marker")
+	}
+	if out.String() != "no marker" {
+		t.Errorf("expected output before EOF to be kept, got %q", out.String())
+	}
+}
+
+func TestPersistentLocalSessionStream(t *testing.T) {
+	session := NewPersistentLocalSession("test", "/bin/sh")
+	defer session.Disconnect()
+
+	if _, err := session.Execute("count=3"); err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	var stdout, stderr bytes.Buffer
+	exitCode, err := session.ExecuteStream(context.Background(), "echo $count", &stdout, &stderr)
+	if err != nil || exitCode != 0 {
+		t.Fatalf("ExecuteStream = %d, %v", exitCode, err)
+	}
+	if stdout.String() != "3\n" {
+		t.Errorf("expected streamed command to see the shell variable, got %q", stdout.String())
+	}
+}
@@ -0,0 +1,156 @@
+package session
+
+import (
+	"fmt"
+	"regexp"
+	"sort"
+	"strconv"
+	"strings"
+)
+
+// ListeningPort is a listening socket reported by ListeningPorts
+type ListeningPort struct {
+	Proto     string `json:"proto"`
+	LocalAddr string `json:"local_addr"`
+	Port      int    `json:"port"`
+	PID       int    `json:"pid,omitempty"`
+	Program   string `json:"program,omitempty"`
+}
+
+// listeningPortsCommand lists listening TCP and UDP sockets with ss, or with
+// netstat on hosts without it
+const listeningPortsCommand = "if command -v ss >/dev/null 2>&1; then ss -tulnp; " +
+	"elif command -v netstat >/dev/null 2>&1; then netstat -tulnp; " +
+	"else echo 'neither ss nor netstat is installed' >&2; exit 127; fi"
+
+// ssProcessPattern matches the first process in an ss users:(...) column
+var ssProcessPattern = regexp.MustCompile(`\("([^"]*)",pid=(\d+)`)
+
+// parseListeningPorts parses the output of ss -tlnp, ss -tulnp or
+// netstat -tulnp, telling them apart by the header line. Lines that are not
+// sockets with a numeric port are skipped.
+func parseListeningPorts(output string) []ListeningPort {
+	var ports []ListeningPort
+	format := ""
+	for _, line := range strings.Split(output, "\n") {
+		fields := strings.Fields(line)
+		if len(fields) == 0 {
+			continue
+		}
+		switch fields[0] {
+		case "Netid", "State", "Proto":
+			format = fields[0]
+			continue
+		}
+
+		var port ListeningPort
+		var ok bool
+		switch format {
+		case "Netid":
+			port, ok = parseSSLine("", fields)
+		case "State":
+			port, ok = parseSSLine("tcp", fields)
+		case "Proto":
+			port, ok = parseNetstatLine(fields)
+		}
+		if ok {
+			ports = append(ports, port)
+		}
+	}
+
+	sort.SliceStable(ports, func(i, j int) bool {
+		if ports[i].Port != ports[j].Port {
+			return ports[i].Port < ports[j].Port
+		}
+		return ports[i].Proto < ports[j].Proto
+	})
+	return ports
+}
+
+// parseSSLine parses one ss socket line. proto is the protocol for output
+// without a Netid column (ss -tlnp); otherwise it is read from the line.
+func parseSSLine(proto string, fields []string) (ListeningPort, bool) {
+	if proto == "" {
+		if len(fields) == 0 {
+			return ListeningPort{}, false
+		}
+		proto, fields = fields[0], fields[1:]
+	}
+	// State Recv-Q Send-Q Local Peer [Process]
+	if len(fields) < 5 {
+		return ListeningPort{}, false
+	}
+	port, ok := splitListenAddr(proto, fields[3])
+	if !ok {
+		return ListeningPort{}, false
+	}
+	if len(fields) > 5 {
+		if m := ssProcessPattern.FindStringSubmatch(strings.Join(fields[5:], " ")); m != nil {
+			port.Program = m[1]
+			port.PID, _ = strconv.Atoi(m[2])
+		}
+	}
+	return port, true
+}
+
+// parseNetstatLine parses one netstat -tulnp socket line. UDP lines have no
+// State column, and the PID/Program name column is "-" for processes the
+// user can't see.
+func parseNetstatLine(fields []string) (ListeningPort, bool) {
+	// Proto Recv-Q Send-Q Local Foreign [State] PID/Program
+	if len(fields) < 5 {
+		return ListeningPort{}, false
+	}
+	proto := strings.TrimSuffix(fields[0], "6")
+	if proto != "tcp" && proto != "udp" {
+		return ListeningPort{}, false
+	}
+	port, ok := splitListenAddr(proto, fields[3])
+	if !ok {
+		return ListeningPort{}, false
+	}
+
+	rest := fields[5:]
+	if len(rest) > 0 && strings.ToUpper(rest[0]) == rest[0] && !strings.Contains(rest[0], "/") && rest[0] != "-" {
+		rest = rest[1:]
+	}
+	if pid, program, found := strings.Cut(strings.Join(rest, " "), "/"); found {
+		port.PID, _ = strconv.Atoi(pid)
+		port.Program = program
+	}
+	return port, true
+}
+
+// splitListenAddr splits a local address such as 0.0.0.0:22, [::]:80 or
+// :::443 into address and port
+func splitListenAddr(proto, addr string) (ListeningPort, bool) {
+	idx := strings.LastIndex(addr, ":")
+	if idx < 0 {
+		return ListeningPort{}, false
+	}
+	port, err := strconv.Atoi(addr[idx+1:])
+	if err != nil {
+		return ListeningPort{}, false
+	}
+	host := strings.TrimSuffix(strings.TrimPrefix(addr[:idx], "["), "]")
+	return ListeningPort{Proto: proto, LocalAddr: host, Port: port}, true
+}
+
+// ListeningPorts lists the listening TCP and UDP sockets on the named session
+// (the active session if name is empty), ordered by port. It runs ss, or
+// netstat where ss is missing. Process details are only reported for
+// processes the session's user can see.
+func (m *Manager) ListeningPorts(sessionName string) ([]ListeningPort, error) {
+	if sessionName == "" {
+		sessionName = m.GetActiveSessionName()
+	}
+
+	result, err := m.ExecuteOn(sessionName, listeningPortsCommand)
+	if err != nil {
+		return nil, err
+	}
+	if result.ExitCode != 0 && strings.TrimSpace(result.Stdout) == "" {
+		return nil, fmt.Errorf("failed to list listening ports on %s: %s", sessionName, strings.TrimSpace(result.Stderr))
+	}
+	return parseListeningPorts(result.Stdout), nil
+}
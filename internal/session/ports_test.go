@@ -0,0 +1,61 @@
+package session
+
+import (
+	"reflect"
+	"testing"
+)
+
+func TestParseListeningPortsSS(t *testing.T) {
+	output := `State  Recv-Q Send-Q Local Address:Port  Peer Address:Port Process
+LISTEN 0      4096   127.0.0.53%lo:53        0.0.0.0:*
+LISTEN 0      128          0.0.0.0:22        0.0.0.0:*     users:(("sshd",pid=812,fd=3))
+LISTEN 0      511                *:8080            *:*     users:(("node",pid=2301,fd=21),("node",pid=2302,fd=21))
+LISTEN 0      128             [::]:22           [::]:*     users:(("sshd",pid=812,fd=4))
+`
+	want := []ListeningPort{
+		{Proto: "tcp", LocalAddr: "0.0.0.0", Port: 22, PID: 812, Program: "sshd"},
+		{Proto: "tcp", LocalAddr: "::", Port: 22, PID: 812, Program: "sshd"},
+		{Proto: "tcp", LocalAddr: "127.0.0.53%lo", Port: 53},
+		{Proto: "tcp", LocalAddr: "*", Port: 8080, PID: 2301, Program: "node"},
+	}
+	if got := parseListeningPorts(output); !reflect.DeepEqual(got, want) {
+		t.Errorf("parseListeningPorts() =\n%+v\nwant\n%+v", got, want)
+	}
+}
+
+func TestParseListeningPortsSSNetid(t *testing.T) {
+	output := `Netid State  Recv-Q Send-Q Local Address:Port Peer Address:Port Process
+udp   UNCONN 0      0            0.0.0.0:68        0.0.0.0:*     users:(("dhclient",pid=540,fd=6))
+tcp   LISTEN 0      511          0.0.0.0:80        0.0.0.0:*     users:(("nginx",pid=900,fd=6))
+`
+	want := []ListeningPort{
+		{Proto: "udp", LocalAddr: "0.0.0.0", Port: 68, PID: 540, Program: "dhclient"},
+		{Proto: "tcp", LocalAddr: "0.0.0.0", Port: 80, PID: 900, Program: "nginx"},
+	}
+	if got := parseListeningPorts(output); !reflect.DeepEqual(got, want) {
+		t.Errorf("parseListeningPorts() =\n%+v\nwant\n%+v", got, want)
+	}
+}
+
+func TestParseListeningPortsNetstat(t *testing.T) {
+	output := `Active Internet connections (only servers)
+Proto Recv-Q Send-Q Local Address           Foreign Address         State       PID/Program name
+tcp        0      0 0.0.0.0:22              0.0.0.0:*               LISTEN      812/sshd
+tcp6       0      0 :::443                  :::*                    LISTEN      -
+udp        0      0 0.0.0.0:68              0.0.0.0:*                           540/dhclient
+`
+	want := []ListeningPort{
+		{Proto: "tcp", LocalAddr: "0.0.0.0", Port: 22, PID: 812, Program: "sshd"},
+		{Proto: "udp", LocalAddr: "0.0.0.0", Port: 68, PID: 540, Program: "dhclient"},
+		{Proto: "tcp", LocalAddr: "::", Port: 443},
+	}
+	if got := parseListeningPorts(output); !reflect.DeepEqual(got, want) {
+		t.Errorf("parseListeningPorts() =\n%+v\nwant\n%+v", got, want)
+	}
+}
+
+func TestParseListeningPortsEmpty(t *testing.T) {
+	if got := parseListeningPorts("State Recv-Q Send-Q Local Address:Port Peer Address:Port Process\n"); len(got) != 0 {
+		t.Errorf("expected no ports, got %+v", got)
+	}
+}
@@ -0,0 +1,95 @@
+package session
+
+import (
+	"fmt"
+	"strconv"
+	"strings"
+)
+
+// Process is a process reported by ListProcesses
+type Process struct {
+	PID     int     `json:"pid"`
+	User    string  `json:"user"`
+	CPU     float64 `json:"cpu"`
+	Mem     float64 `json:"mem"`
+	Command string  `json:"command"`
+}
+
+// listProcessesCommand lists every process without a header, one
+// "pid user %cpu %mem args" line each. args is last since it contains spaces.
+const listProcessesCommand = "ps -eo pid=,user=,pcpu=,pmem=,args="
+
+// parseProcesses parses the output of listProcessesCommand, keeping the
+// spacing within each command line. Malformed lines and the ps process itself
+// are skipped.
+func parseProcesses(output string) []Process {
+	var processes []Process
+	for _, line := range strings.Split(output, "\n") {
+		pid, rest := cutField(line)
+		user, rest := cutField(rest)
+		cpu, rest := cutField(rest)
+		mem, rest := cutField(rest)
+		command := strings.TrimSpace(rest)
+		if command == "" || strings.Contains(command, listProcessesCommand) {
+			continue
+		}
+
+		var p Process
+		var err error
+		if p.PID, err = strconv.Atoi(pid); err != nil {
+			continue
+		}
+		if p.CPU, err = strconv.ParseFloat(cpu, 64); err != nil {
+			continue
+		}
+		if p.Mem, err = strconv.ParseFloat(mem, 64); err != nil {
+			continue
+		}
+		p.User = user
+		p.Command = command
+		processes = append(processes, p)
+	}
+	return processes
+}
+
+// cutField returns the first whitespace-separated field of s and what
+// follows it
+func cutField(s string) (field, rest string) {
+	s = strings.TrimLeft(s, " \t")
+	if idx := strings.IndexAny(s, " \t"); idx >= 0 {
+		return s[:idx], s[idx:]
+	}
+	return s, ""
+}
+
+// filterProcesses returns the processes whose command line contains filter
+func filterProcesses(processes []Process, filter string) []Process {
+	if filter == "" {
+		return processes
+	}
+	var matched []Process
+	for _, p := range processes {
+		if strings.Contains(p.Command, filter) {
+			matched = append(matched, p)
+		}
+	}
+	return matched
+}
+
+// ListProcesses lists the processes on the named session (the active session
+// if name is empty) whose command line contains filter, or all of them if
+// filter is empty
+func (m *Manager) ListProcesses(sessionName, filter string) ([]Process, error) {
+	if sessionName == "" {
+		sessionName = m.GetActiveSessionName()
+	}
+
+	result, err := m.ExecuteOn(sessionName, listProcessesCommand)
+	if err != nil {
+		return nil, err
+	}
+	if result.ExitCode != 0 && strings.TrimSpace(result.Stdout) == "" {
+		return nil, fmt.Errorf("failed to list processes on %s: %s", sessionName, strings.TrimSpace(result.Stderr))
+	}
+	return filterProcesses(parseProcesses(result.Stdout), filter), nil
+}
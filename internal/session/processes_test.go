@@ -0,0 +1,148 @@
+package session
+
+import (
+	"fmt"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"reflect"
+	"testing"
+	"time"
+)
+
+const samplePSOutput = `    1 root      0.0  0.1 /sbin/init splash
+  812 root      0.0  0.0 sshd: /usr/sbin/sshd -D [listener] 0 of 10-100 startups
+ 2301 www-data 12.5  3.4 node  /srv/app/server.js   --port 8080
+ 4410 deploy    0.0  0.0 ps -eo pid=,user=,pcpu=,pmem=,args=
+ bogus line
+`
+
+func TestParseProcesses(t *testing.T) {
+	want := []Process{
+		{PID: 1, User: "root", CPU: 0, Mem: 0.1, Command: "/sbin/init splash"},
+		{PID: 812, User: "root", CPU: 0, Mem: 0, Command: "sshd: /usr/sbin/sshd -D [listener] 0 of 10-100 startups"},
+		{PID: 2301, User: "www-data", CPU: 12.5, Mem: 3.4, Command: "node  /srv/app/server.js   --port 8080"},
+	}
+	if got := parseProcesses(samplePSOutput); !reflect.DeepEqual(got, want) {
+		t.Errorf("parseProcesses() =\n%+v\nwant\n%+v", got, want)
+	}
+}
+
+func TestFilterProcesses(t *testing.T) {
+	processes := parseProcesses(samplePSOutput)
+
+	matched := filterProcesses(processes, "sshd -D")
+	if len(matched) != 1 || matched[0].PID != 812 {
+		t.Errorf("expected only sshd to match, got %+v", matched)
+	}
+	if got := filterProcesses(processes, "nomatch"); len(got) != 0 {
+		t.Errorf("expected no matches, got %+v", got)
+	}
+	if got := filterProcesses(processes, ""); len(got) != len(processes) {
+		t.Errorf("expected an empty filter to keep all %d processes, got %d", len(processes), len(got))
+	}
+}
+
+func TestManagerListProcessesLocal(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	processes, err := mgr.ListProcesses("local", "")
+	if err != nil {
+		t.Fatalf("ListProcesses failed: %v", err)
+	}
+	if len(processes) == 0 {
+		t.Fatal("expected at least one process")
+	}
+	for _, p := range processes {
+		if p.PID <= 0 || p.Command == "" {
+			t.Errorf("malformed process entry: %+v", p)
+		}
+	}
+}
+
+func TestProcessName(t *testing.T) {
+	tests := map[string]string{
+		"/usr/sbin/nginx -g daemon off;": "nginx",
+		"sshd: /usr/sbin/sshd -D":        "sshd",
+		"python3 app.py":                 "python3",
+	}
+	for command, want := range tests {
+		if got := processName(command); got != want {
+			t.Errorf("processName(%q) = %q, want %q", command, got, want)
+		}
+	}
+}
+
+func TestNormalizeSignal(t *testing.T) {
+	tests := []struct {
+		signal  string
+		want    string
+		wantErr bool
+	}{
+		{"", "TERM", false},
+		{"TERM", "TERM", false},
+		{"sigkill", "KILL", false},
+		{"hup", "HUP", false},
+		{"9", "9", false},
+		{"RTMIN+1", "RTMIN+1", false},
+		{"99", "", true},
+		{"SIG", "", true},
+		{"TERM;reboot", "", true},
+	}
+	for _, tt := range tests {
+		got, err := NormalizeSignal(tt.signal)
+		if (err != nil) != tt.wantErr || got != tt.want {
+			t.Errorf("NormalizeSignal(%q) = %q, %v; want %q, error %v", tt.signal, got, err, tt.want, tt.wantErr)
+		}
+	}
+
+	if got := killCommand(42, "9"); got != "kill -9 42" {
+		t.Errorf("killCommand with a number = %q", got)
+	}
+	if got := killCommand(42, "TERM"); got != "kill -s TERM 42" {
+		t.Errorf("killCommand with a name = %q", got)
+	}
+}
+
+func TestManagerKillProcessByName(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	// Run sleep under a name no other process has
+	sleepPath, err := exec.LookPath("sleep")
+	if err != nil {
+		t.Skip("sleep not available")
+	}
+	data, err := os.ReadFile(sleepPath)
+	if err != nil {
+		t.Fatal(err)
+	}
+	name := fmt.Sprintf("thop-sleeper-%d", os.Getpid())
+	binary := filepath.Join(t.TempDir(), name)
+	if err := os.WriteFile(binary, data, 0755); err != nil {
+		t.Fatal(err)
+	}
+	cmd := exec.Command(binary, "30")
+	if err := cmd.Start(); err != nil {
+		t.Fatal(err)
+	}
+	exited := make(chan error, 1)
+	go func() { exited <- cmd.Wait() }()
+	defer cmd.Process.Kill()
+
+	matches, err := mgr.FindProcessesByName("local", name)
+	if err != nil {
+		t.Fatalf("FindProcessesByName failed: %v", err)
+	}
+	if len(matches) != 1 || matches[0].PID != cmd.Process.Pid {
+		t.Fatalf("expected only pid %d to match, got %+v", cmd.Process.Pid, matches)
+	}
+
+	if err := mgr.KillProcess("local", matches[0].PID, ""); err != nil {
+		t.Fatalf("KillProcess failed: %v", err)
+	}
+	select {
+	case <-exited:
+	case <-time.After(5 * time.Second):
+		t.Fatal("process was not terminated")
+	}
+}
@@ -0,0 +1,161 @@
+package session
+
+import (
+	"fmt"
+	"io"
+	"regexp"
+	"strings"
+	"sync"
+	"time"
+)
+
+// sudoPromptPattern matches the password prompt printed by sudo
+var sudoPromptPattern = regexp.MustCompile(`\[sudo\] password for [^:\n]*:`)
+
+// interactivePromptPattern matches common prompts that wait for an answer,
+// such as apt's [Y/n], ssh's (yes/no) host key question, password and
+// passphrase prompts and "Are you sure" confirmations, when they end the
+// output so far
+var interactivePromptPattern = regexp.MustCompile(`(?i)(\[y/n\]|\(y/n\)|\(yes/no[^)\n]*\)\??|password:|passphrase[^:\n]*:|are you sure[^\n]*)[ \t]*$`)
+
+// promptScanTail is how many trailing bytes are kept between writes so a
+// prompt split across writes is still detected
+const promptScanTail = 256
+
+// interactivePromptIdle is how long output must stay quiet after an
+// interactive prompt before the command is taken to be waiting for input.
+// Programs that print something like "[Y/n]" and carry on are left alone.
+var interactivePromptIdle = time.Second
+
+// promptWatcher signals when command output shows that the command is
+// waiting for input nobody can type, such as a sudo password prompt, so a
+// non-interactive command can fail fast instead of hanging until timeout
+type promptWatcher struct {
+	found chan struct{}
+	once  sync.Once
+
+	mu     sync.Mutex
+	writes int    // Bumped on every write, so a quiet-period check can tell output resumed
+	prompt string // The interactive prompt that was detected ("" for sudo)
+	sudo   bool
+}
+
+// newPromptWatcher creates a prompt watcher
+func newPromptWatcher() *promptWatcher {
+	return &promptWatcher{found: make(chan struct{})}
+}
+
+// Found returns a channel that is closed once a prompt has been seen
+func (w *promptWatcher) Found() <-chan struct{} {
+	return w.found
+}
+
+// Detected reports whether a prompt has been seen
+func (w *promptWatcher) Detected() bool {
+	select {
+	case <-w.found:
+		return true
+	default:
+		return false
+	}
+}
+
+// Err returns the error describing the detected prompt
+func (w *promptWatcher) Err(sessionName, host string) *Error {
+	w.mu.Lock()
+	defer w.mu.Unlock()
+
+	if w.sudo {
+		return sudoPromptError(sessionName, host)
+	}
+	return interactivePromptError(sessionName, host, w.prompt)
+}
+
+// detect records what was found and signals it, once
+func (w *promptWatcher) detect(sudo bool, prompt string) {
+	w.once.Do(func() {
+		w.mu.Lock()
+		w.sudo = sudo
+		w.prompt = prompt
+		w.mu.Unlock()
+		close(w.found)
+	})
+}
+
+// Writer returns a writer that passes output on to out while scanning it
+func (w *promptWatcher) Writer(out io.Writer) *promptScanWriter {
+	return &promptScanWriter{watcher: w, out: out}
+}
+
+// promptScanWriter captures one output stream for a promptWatcher
+type promptScanWriter struct {
+	watcher *promptWatcher
+	out     io.Writer
+	tail    []byte
+}
+
+// Write passes p on and checks the recent output for a prompt. A sudo prompt
+// is reported at once; other prompts only if no more output follows within
+// interactivePromptIdle.
+func (sw *promptScanWriter) Write(p []byte) (int, error) {
+	n, err := sw.out.Write(p)
+
+	w := sw.watcher
+	w.mu.Lock()
+	w.writes++
+	writes := w.writes
+	w.mu.Unlock()
+
+	sw.tail = append(sw.tail, p...)
+	if sudoPromptPattern.Match(sw.tail) {
+		w.detect(true, "")
+	} else if interactivePromptPattern.Match(sw.tail) {
+		prompt := lastLine(string(sw.tail))
+		time.AfterFunc(interactivePromptIdle, func() {
+			w.mu.Lock()
+			quiet := w.writes == writes
+			w.mu.Unlock()
+			if quiet {
+				w.detect(false, prompt)
+			}
+		})
+	}
+	if len(sw.tail) > promptScanTail {
+		sw.tail = append(sw.tail[:0], sw.tail[len(sw.tail)-promptScanTail:]...)
+	}
+
+	return n, err
+}
+
+// lastLine returns the last line of s, trimmed
+func lastLine(s string) string {
+	s = strings.TrimRight(s, " \t\r\n")
+	if i := strings.LastIndexAny(s, "\r\n"); i >= 0 {
+		s = s[i+1:]
+	}
+	return strings.TrimSpace(s)
+}
+
+// sudoPromptError returns the error for a command that stopped at a sudo
+// password prompt
+func sudoPromptError(sessionName, host string) *Error {
+	return &Error{
+		Code:       ErrAuthPasswordRequired,
+		Message:    fmt.Sprintf("Command on %s is waiting for a sudo password, which cannot be entered non-interactively", sessionName),
+		Session:    sessionName,
+		Host:       host,
+		Suggestion: "Use 'sudo -n' or configure passwordless sudo (NOPASSWD) for this command, or run it interactively with /shell",
+	}
+}
+
+// interactivePromptError returns the error for a command that stopped at a
+// prompt asking for input
+func interactivePromptError(sessionName, host, prompt string) *Error {
+	return &Error{
+		Code:       ErrInteractivePrompt,
+		Message:    fmt.Sprintf("Command on %s is waiting for interactive input: %q", sessionName, prompt),
+		Session:    sessionName,
+		Host:       host,
+		Suggestion: "Pass a non-interactive flag (such as -y or --yes), feed the answer through a pipe, or run the command with /shell",
+	}
+}
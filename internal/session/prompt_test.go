@@ -0,0 +1,106 @@
+package session
+
+import (
+	"bytes"
+	"strings"
+	"testing"
+	"time"
+)
+
+// withPromptIdle shortens the quiet period before a prompt is reported
+func withPromptIdle(t *testing.T, d time.Duration) {
+	t.Helper()
+	old := interactivePromptIdle
+	interactivePromptIdle = d
+	t.Cleanup(func() { interactivePromptIdle = old })
+}
+
+func TestLocalSessionInteractivePromptAbortsEarly(t *testing.T) {
+	withPromptIdle(t, 200*time.Millisecond)
+	session := NewLocalSession("test", "/bin/sh")
+	session.SetTimeout(30 * time.Second)
+
+	start := time.Now()
+	_, err := session.Execute("echo 'Reading package lists...'; printf 'Do you want to continue? [Y/n] '; sleep 10")
+	if elapsed := time.Since(start); elapsed > 5*time.Second {
+		t.Errorf("prompt took %s to detect, want an early abort", elapsed)
+	}
+
+	sessionErr, ok := err.(*Error)
+	if !ok {
+		t.Fatalf("expected *Error, got %T (%v)", err, err)
+	}
+	if sessionErr.Code != ErrInteractivePrompt {
+		t.Errorf("expected code %s, got %s", ErrInteractivePrompt, sessionErr.Code)
+	}
+	if !strings.Contains(sessionErr.Message, "Do you want to continue? [Y/n]") {
+		t.Errorf("error should include the prompt, got %q", sessionErr.Message)
+	}
+	if strings.Contains(sessionErr.Message, "Reading package lists") {
+		t.Errorf("error should only include the prompt line, got %q", sessionErr.Message)
+	}
+}
+
+func TestLocalSessionPromptFollowedByOutputIsNotAborted(t *testing.T) {
+	withPromptIdle(t, 500*time.Millisecond)
+	session := NewLocalSession("test", "/bin/sh")
+
+	result, err := session.Execute("printf 'Proceed? [Y/n] '; sleep 0.05; echo assumed yes")
+	if err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	if !strings.Contains(result.Stdout, "assumed yes") {
+		t.Errorf("unexpected output %q", result.Stdout)
+	}
+}
+
+func TestPromptWatcherPatterns(t *testing.T) {
+	withPromptIdle(t, 10*time.Millisecond)
+
+	tests := []struct {
+		output string
+		want   bool
+	}{
+		{"Do you want to continue? [Y/n] ", true},
+		{"Overwrite? (y/n)", true},
+		{"Are you sure you want to continue connecting (yes/no/[fingerprint])? ", true},
+		{"Password: ", true},
+		{"Enter passphrase for key '/home/u/.ssh/id_ed25519': ", true},
+		{"Are you sure? ", true},
+		{"[Y/n] shown in a help line\n", false},
+		{"Build finished\n", false},
+	}
+	for _, tt := range tests {
+		w := newPromptWatcher()
+		var out bytes.Buffer
+		_, _ = w.Writer(&out).Write([]byte(tt.output))
+
+		select {
+		case <-w.Found():
+			if !tt.want {
+				t.Errorf("%q should not be detected as a prompt", tt.output)
+			} else if err := w.Err("s", ""); err.Code != ErrInteractivePrompt {
+				t.Errorf("%q: error code = %s", tt.output, err.Code)
+			}
+		case <-time.After(200 * time.Millisecond):
+			if tt.want {
+				t.Errorf("%q should be detected as a prompt", tt.output)
+			}
+		}
+		if out.String() != tt.output {
+			t.Errorf("output should pass through unchanged, got %q", out.String())
+		}
+	}
+}
+
+func TestPromptWatcherSudoTakesPrecedence(t *testing.T) {
+	w := newPromptWatcher()
+	_, _ = w.Writer(&bytes.Buffer{}).Write([]byte("[sudo] password for deploy: "))
+
+	if !w.Detected() {
+		t.Fatal("sudo prompt should be detected immediately")
+	}
+	if err := w.Err("s", "h"); err.Code != ErrAuthPasswordRequired {
+		t.Errorf("expected %s, got %s", ErrAuthPasswordRequired, err.Code)
+	}
+}
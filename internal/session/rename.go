@@ -0,0 +1,85 @@
+package session
+
+import (
+	"fmt"
+	"strings"
+
+	"github.com/scottgl9/thop/internal/logger"
+)
+
+// setName changes the name the session reports
+func (s *LocalSession) setName(name string) {
+	s.name = name
+}
+
+// setName changes the name the session reports
+func (s *SSHSession) setName(name string) {
+	s.name = name
+}
+
+// RenameSession renames a session, keeping its connection, working directory
+// and environment. The active session, /back history, temp directories,
+// configuration entry, default_session setting and saved state follow the new
+// name. The local session can't be renamed, and newName must not already be
+// in use.
+func (m *Manager) RenameSession(oldName, newName string) error {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+
+	sess, ok := m.sessions[oldName]
+	if !ok {
+		return &Error{
+			Code:    ErrSessionNotFound,
+			Message: fmt.Sprintf("Session '%s' not found", oldName),
+			Session: oldName,
+		}
+	}
+	if oldName == "local" {
+		return fmt.Errorf("the local session can't be renamed")
+	}
+	// A colon would make file specs like name:/path ambiguous
+	if newName == "" || strings.ContainsAny(newName, ": \t") {
+		return fmt.Errorf("invalid session name '%s'", newName)
+	}
+	if _, exists := m.sessions[newName]; exists {
+		return fmt.Errorf("session '%s' already exists", newName)
+	}
+
+	if renamer, ok := sess.(interface{ setName(string) }); ok {
+		renamer.setName(newName)
+	}
+	m.sessions[newName] = sess
+	delete(m.sessions, oldName)
+
+	if m.activeSession == oldName {
+		m.activeSession = newName
+	}
+	for i, name := range m.recent {
+		if name == oldName {
+			m.recent[i] = newName
+		}
+	}
+	if dirs, ok := m.tempDirs[oldName]; ok {
+		m.tempDirs[newName] = dirs
+		delete(m.tempDirs, oldName)
+	}
+	if m.restrictedSess[oldName] {
+		m.restrictedSess[newName] = true
+		delete(m.restrictedSess, oldName)
+	}
+	if cfg, ok := m.config.Sessions[oldName]; ok {
+		m.config.Sessions[newName] = cfg
+		delete(m.config.Sessions, oldName)
+	}
+	if m.config.Settings.DefaultSession == oldName {
+		m.config.Settings.DefaultSession = newName
+	}
+	if m.state != nil {
+		if err := m.state.RenameSession(oldName, newName); err != nil {
+			logger.Warn("failed to save state after renaming %q: %v", oldName, err)
+		}
+	}
+
+	logger.Info("renamed session %q to %q", oldName, newName)
+	return nil
+}
@@ -0,0 +1,92 @@
+package session
+
+import (
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/scottgl9/thop/internal/config"
+)
+
+func TestRenameSession(t *testing.T) {
+	mgr, _ := createTestManager(t)
+	scratch := config.Session{Type: "local", Shell: "/bin/sh"}
+	mgr.config.Sessions["scratch"] = scratch
+	if err := mgr.AddSession("scratch", scratch); err != nil {
+		t.Fatalf("AddSession failed: %v", err)
+	}
+	if err := mgr.SetActiveSession("scratch"); err != nil {
+		t.Fatalf("SetActiveSession failed: %v", err)
+	}
+	dir, _ := filepath.EvalSymlinks(t.TempDir())
+	if _, err := mgr.Execute("cd " + dir); err != nil {
+		t.Fatalf("cd failed: %v", err)
+	}
+	before, _ := mgr.GetSession("scratch")
+
+	if err := mgr.RenameSession("scratch", "work"); err != nil {
+		t.Fatalf("RenameSession failed: %v", err)
+	}
+
+	if mgr.HasSession("scratch") {
+		t.Error("old name should no longer exist")
+	}
+	after, ok := mgr.GetSession("work")
+	if !ok {
+		t.Fatal("renamed session not found")
+	}
+	if after != before {
+		t.Error("renaming should keep the same session, not recreate it")
+	}
+	if after.Name() != "work" {
+		t.Errorf("Name() = %q, want work", after.Name())
+	}
+	if !after.IsConnected() || after.GetCWD() != dir {
+		t.Errorf("renamed session lost its state: connected=%v cwd=%q", after.IsConnected(), after.GetCWD())
+	}
+	if got := mgr.GetActiveSessionName(); got != "work" {
+		t.Errorf("active session = %q, want work", got)
+	}
+	if got := mgr.state.GetActiveSession(); got != "work" {
+		t.Errorf("persisted active session = %q, want work", got)
+	}
+	if _, ok := mgr.config.Sessions["work"]; !ok {
+		t.Error("config entry should move to the new name")
+	}
+	if sessionState, ok := mgr.state.GetSessionState("work"); !ok || sessionState.CWD != dir {
+		t.Errorf("state entry should move to the new name, got %+v", sessionState)
+	}
+
+	result, err := mgr.Execute("pwd")
+	if err != nil {
+		t.Fatalf("Execute after rename failed: %v", err)
+	}
+	if got, _ := filepath.EvalSymlinks(strings.TrimSpace(result.Stdout)); got != dir {
+		t.Errorf("pwd after rename = %q, want %q", got, dir)
+	}
+}
+
+func TestRenameSessionRefusals(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	tests := []struct {
+		name     string
+		old, new string
+	}{
+		{"local", "local", "mine"},
+		{"existing name", "testserver", "local"},
+		{"missing session", "nope", "other"},
+		{"empty name", "testserver", ""},
+		{"colon in name", "testserver", "prod:1"},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if err := mgr.RenameSession(tt.old, tt.new); err == nil {
+				t.Errorf("RenameSession(%q, %q) should fail", tt.old, tt.new)
+			}
+		})
+	}
+	if !mgr.HasSession("testserver") || !mgr.HasSession("local") {
+		t.Error("refused renames should leave sessions in place")
+	}
+}
@@ -0,0 +1,89 @@
+package session
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+)
+
+// RunAsSetter is implemented by sessions that can run their commands as
+// another user through sudo
+type RunAsSetter interface {
+	SetRunAs(user string)
+	RunAs() string
+}
+
+// runAsCommand wraps cmdStr so it runs as user via sudo -u, or returns it
+// unchanged if user is empty. sudo resets the environment, so cmdStr must
+// export whatever the command needs itself.
+func runAsCommand(user, cmdStr string) string {
+	if user == "" {
+		return cmdStr
+	}
+	return fmt.Sprintf("sudo -u %s -- sh -c %s", ShellQuote(user), ShellQuote(cmdStr))
+}
+
+// envExports returns export statements for the locale (if set) and env,
+// sorted by name, for prefixing a command string
+func envExports(env map[string]string, locale string) string {
+	var exports strings.Builder
+	if locale != "" {
+		fmt.Fprintf(&exports, "export LC_ALL=%[1]s LANG=%[1]s; ", ShellQuote(locale))
+	}
+	keys := make([]string, 0, len(env))
+	for k := range env {
+		keys = append(keys, k)
+	}
+	sort.Strings(keys)
+	for _, k := range keys {
+		fmt.Fprintf(&exports, "export %s=%s; ", k, ShellQuote(env[k]))
+	}
+	return exports.String()
+}
+
+// SetRunAs sets the user commands run as ("" runs them as the current user).
+// Commands run as another user always start a new shell, even on a session
+// with a persistent shell, since sudo would drop the shell's state anyway.
+func (s *LocalSession) SetRunAs(user string) {
+	s.runAs = user
+}
+
+// RunAs returns the user commands run as ("" for the current user)
+func (s *LocalSession) RunAs() string {
+	return s.runAs
+}
+
+// runAsWrap wraps cmdStr to run as the session's run_as user, exporting the
+// session env inside the sudo shell. The cwd carries over through sudo.
+func (s *LocalSession) runAsWrap(cmdStr string) string {
+	if s.runAs == "" {
+		return cmdStr
+	}
+	return runAsCommand(s.runAs, envExports(s.env, s.locale)+cmdStr)
+}
+
+// SetRunAs sets the user commands run as ("" runs them as the login user)
+func (s *SSHSession) SetRunAs(user string) {
+	s.runAs = user
+}
+
+// RunAs returns the user commands run as ("" for the login user)
+func (s *SSHSession) RunAs() string {
+	return s.runAs
+}
+
+// SetRunAs sets the user commands on the named session run as, via sudo -u
+// ("" runs them as the session's own user). Restriction rules still apply to
+// the command itself, not to the sudo wrapper.
+func (m *Manager) SetRunAs(sessionName, user string) error {
+	session, err := m.lookupSession(sessionName)
+	if err != nil {
+		return err
+	}
+	setter, ok := session.(RunAsSetter)
+	if !ok {
+		return fmt.Errorf("session '%s' does not support run_as", sessionName)
+	}
+	setter.SetRunAs(user)
+	return nil
+}
@@ -0,0 +1,78 @@
+package session
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+func TestRunAsCommand(t *testing.T) {
+	if got := runAsCommand("", "ls -la"); got != "ls -la" {
+		t.Errorf("expected no wrapping without a user, got %q", got)
+	}
+
+	want := `sudo -u 'appuser' -- sh -c 'cd /srv && echo '\''hi'\'''`
+	if got := runAsCommand("appuser", "cd /srv && echo 'hi'"); got != want {
+		t.Errorf("runAsCommand() = %q, want %q", got, want)
+	}
+}
+
+func TestLocalRunAsWrap(t *testing.T) {
+	session := NewLocalSession("test", "/bin/sh")
+	session.SetEnv("B", "two words")
+	session.SetEnv("A", "1")
+
+	if got := session.runAsWrap("make"); got != "make" {
+		t.Errorf("expected no wrapping without run_as, got %q", got)
+	}
+
+	session.SetRunAs("appuser")
+	session.SetLocale("C.UTF-8")
+	want := runAsCommand("appuser", "export LC_ALL='C.UTF-8' LANG='C.UTF-8'; export A='1'; export B='two words'; make")
+	if got := session.runAsWrap("make"); got != want {
+		t.Errorf("runAsWrap() = %q, want %q", got, want)
+	}
+}
+
+func TestLocalSessionRunAs(t *testing.T) {
+	// A stand-in sudo that records the target user and runs the command as is
+	binDir := t.TempDir()
+	userFile := filepath.Join(binDir, "user")
+	script := "#!/bin/sh\necho \"$2\" > " + userFile + "\nshift 3\nexec \"$@\"\n"
+	if err := os.WriteFile(filepath.Join(binDir, "sudo"), []byte(script), 0755); err != nil {
+		t.Fatal(err)
+	}
+	t.Setenv("PATH", binDir+string(os.PathListSeparator)+os.Getenv("PATH"))
+
+	mgr, _ := createTestManager(t)
+	if err := mgr.SetRunAs("local", "appuser"); err != nil {
+		t.Fatalf("SetRunAs failed: %v", err)
+	}
+	if err := mgr.SetRunAs("missing", "appuser"); err == nil {
+		t.Error("expected an error for an unknown session")
+	}
+
+	dir := t.TempDir()
+	if _, err := mgr.ExecuteOn("local", "cd "+dir); err != nil {
+		t.Fatalf("cd failed: %v", err)
+	}
+	if _, err := mgr.ExecuteOn("local", "export GREETING=hello"); err != nil {
+		t.Fatalf("export failed: %v", err)
+	}
+	result, err := mgr.ExecuteOn("local", "pwd; echo $GREETING")
+	if err != nil {
+		t.Fatalf("ExecuteOn failed: %v", err)
+	}
+	if want := dir + "\nhello\n"; result.Stdout != want {
+		t.Errorf("expected cwd and env inside the wrapper, got %q", result.Stdout)
+	}
+
+	user, err := os.ReadFile(userFile)
+	if err != nil {
+		t.Fatalf("command did not go through sudo: %v", err)
+	}
+	if strings.TrimSpace(string(user)) != "appuser" {
+		t.Errorf("expected sudo -u appuser, got %q", user)
+	}
+}
@@ -47,6 +47,9 @@ type Session interface {
 
 	// SetEnv sets an environment variable
 	SetEnv(key, value string)
+
+	// ListDir returns the entries of a directory, sorted by name
+	ListDir(path string) ([]DirEntry, error)
 }
 
 // ExecuteResult contains the result of command execution
@@ -72,18 +75,23 @@ func (e *Error) Error() string {
 
 // Error codes
 const (
-	ErrConnectionFailed     = "CONNECTION_FAILED"
-	ErrConnectionTimeout    = "CONNECTION_TIMEOUT"
-	ErrAuthPasswordRequired = "AUTH_PASSWORD_REQUIRED"
-	ErrAuthKeyRejected      = "AUTH_KEY_REJECTED"
-	ErrAuthFailed           = "AUTH_FAILED"
-	ErrHostKeyVerification  = "HOST_KEY_VERIFICATION_FAILED"
-	ErrHostKeyChanged       = "HOST_KEY_CHANGED"
-	ErrCommandTimeout       = "COMMAND_TIMEOUT"
-	ErrCommandInterrupted   = "COMMAND_INTERRUPTED"
-	ErrCommandRestricted    = "COMMAND_RESTRICTED"
-	ErrSessionNotFound      = "SESSION_NOT_FOUND"
-	ErrSessionDisconnected  = "SESSION_DISCONNECTED"
+	ErrConnectionFailed       = "CONNECTION_FAILED"
+	ErrConnectionTimeout      = "CONNECTION_TIMEOUT"
+	ErrConnectionLimit        = "CONNECTION_LIMIT"
+	ErrAuthPasswordRequired   = "AUTH_PASSWORD_REQUIRED"
+	ErrAuthPassphraseRequired = "AUTH_PASSPHRASE_REQUIRED"
+	ErrAuthKeyRejected        = "AUTH_KEY_REJECTED"
+	ErrAuthFailed             = "AUTH_FAILED"
+	ErrHostKeyVerification    = "HOST_KEY_VERIFICATION_FAILED"
+	ErrHostKeyChanged         = "HOST_KEY_CHANGED"
+	ErrCommandTimeout         = "COMMAND_TIMEOUT"
+	ErrCommandInterrupted     = "COMMAND_INTERRUPTED"
+	ErrCommandRestricted      = "COMMAND_RESTRICTED"
+	ErrInteractivePrompt      = "INTERACTIVE_PROMPT"
+	ErrSessionNotFound        = "SESSION_NOT_FOUND"
+	ErrSessionDisconnected    = "SESSION_DISCONNECTED"
+	ErrCopyUnsupported        = "COPY_UNSUPPORTED"
+	ErrGlobNoMatch            = "GLOB_NO_MATCH"
 )
 
 // NewError creates a new session error
@@ -95,6 +103,20 @@ func NewError(code, message, session string) *Error {
 	}
 }
 
+// ShellQuote quotes s for safe use as a single POSIX shell word
+func ShellQuote(s string) string {
+	return "'" + strings.ReplaceAll(s, "'", `'\''`) + "'"
+}
+
+// localeEnv returns the LC_ALL and LANG environment entries for locale, or nil
+// if locale is empty
+func localeEnv(locale string) []string {
+	if locale == "" {
+		return nil
+	}
+	return []string{"LC_ALL=" + locale, "LANG=" + locale}
+}
+
 // CopyOutput copies data from reader to writer
 func CopyOutput(dst io.Writer, src io.Reader) error {
 	_, err := io.Copy(dst, src)
@@ -0,0 +1,85 @@
+package session
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+	"time"
+
+	"github.com/scottgl9/thop/internal/logger"
+	"github.com/scottgl9/thop/internal/state"
+)
+
+// SaveSnapshot records the active session's working directory and
+// environment in the state file under name, replacing any snapshot with that
+// name
+func (m *Manager) SaveSnapshot(name string) (state.Snapshot, error) {
+	if m.state == nil {
+		return state.Snapshot{}, fmt.Errorf("snapshots need a state file")
+	}
+	session := m.GetActiveSession()
+	if session == nil {
+		return state.Snapshot{}, &Error{
+			Code:    ErrSessionNotFound,
+			Message: "No active session",
+		}
+	}
+
+	snapshot := state.Snapshot{
+		Session:   session.Name(),
+		CWD:       session.GetCWD(),
+		Env:       session.GetEnv(),
+		CreatedAt: time.Now(),
+	}
+	if err := m.state.SaveSnapshot(name, snapshot); err != nil {
+		return state.Snapshot{}, fmt.Errorf("failed to save snapshot: %w", err)
+	}
+	logger.Info("saved snapshot %q of session %q", name, snapshot.Session)
+	return snapshot, nil
+}
+
+// RestoreSnapshot returns the active session to a saved snapshot by
+// re-issuing cd to its working directory and re-exporting its environment.
+// Variables set since the snapshot was taken are left in place.
+func (m *Manager) RestoreSnapshot(name string) (state.Snapshot, error) {
+	if m.state == nil {
+		return state.Snapshot{}, fmt.Errorf("snapshots need a state file")
+	}
+	snapshot, ok := m.state.GetSnapshot(name)
+	if !ok {
+		return state.Snapshot{}, fmt.Errorf("snapshot '%s' not found", name)
+	}
+
+	if snapshot.CWD != "" {
+		result, err := m.Execute("cd " + snapshot.CWD)
+		if err != nil {
+			return snapshot, err
+		}
+		if result.ExitCode != 0 {
+			return snapshot, fmt.Errorf("failed to restore working directory: %s", strings.TrimSpace(result.Stderr))
+		}
+	}
+
+	keys := make([]string, 0, len(snapshot.Env))
+	for key := range snapshot.Env {
+		keys = append(keys, key)
+	}
+	sort.Strings(keys)
+	sessionName := m.GetActiveSessionName()
+	for _, key := range keys {
+		if err := m.SetEnvOn(sessionName, key, snapshot.Env[key]); err != nil {
+			return snapshot, fmt.Errorf("failed to restore %s: %w", key, err)
+		}
+	}
+
+	logger.Info("restored snapshot %q on session %q", name, sessionName)
+	return snapshot, nil
+}
+
+// Snapshots returns the saved snapshots by name
+func (m *Manager) Snapshots() map[string]state.Snapshot {
+	if m.state == nil {
+		return nil
+	}
+	return m.state.GetSnapshots()
+}
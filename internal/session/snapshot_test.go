@@ -0,0 +1,72 @@
+package session
+
+import (
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/scottgl9/thop/internal/state"
+)
+
+func TestSnapshotSaveAndRestore(t *testing.T) {
+	mgr, tmpDir := createTestManager(t)
+	first, _ := filepath.EvalSymlinks(t.TempDir())
+	second, _ := filepath.EvalSymlinks(t.TempDir())
+
+	if _, err := mgr.Execute("cd " + first); err != nil {
+		t.Fatalf("cd failed: %v", err)
+	}
+	if err := mgr.SetSessionEnv("APP_ENV", "staging"); err != nil {
+		t.Fatalf("SetSessionEnv failed: %v", err)
+	}
+	snapshot, err := mgr.SaveSnapshot("before-deploy")
+	if err != nil {
+		t.Fatalf("SaveSnapshot failed: %v", err)
+	}
+	if snapshot.Session != "local" || snapshot.CWD != first || snapshot.Env["APP_ENV"] != "staging" {
+		t.Errorf("snapshot = %+v", snapshot)
+	}
+
+	// Mutate the session
+	if _, err := mgr.Execute("cd " + second); err != nil {
+		t.Fatalf("cd failed: %v", err)
+	}
+	if err := mgr.SetSessionEnv("APP_ENV", "production"); err != nil {
+		t.Fatalf("SetSessionEnv failed: %v", err)
+	}
+
+	if _, err := mgr.RestoreSnapshot("before-deploy"); err != nil {
+		t.Fatalf("RestoreSnapshot failed: %v", err)
+	}
+	sess := mgr.GetActiveSession()
+	if sess.GetCWD() != first {
+		t.Errorf("cwd after restore = %q, want %q", sess.GetCWD(), first)
+	}
+	if got := sess.GetEnv()["APP_ENV"]; got != "staging" {
+		t.Errorf("APP_ENV after restore = %q, want staging", got)
+	}
+	result, err := mgr.Execute("echo $APP_ENV")
+	if err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	if got := strings.TrimSpace(result.Stdout); got != "staging" {
+		t.Errorf("commands see APP_ENV = %q, want staging", got)
+	}
+
+	// Snapshots persist to the state file
+	reloaded := state.NewManager(filepath.Join(tmpDir, "state.json"))
+	if err := reloaded.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	saved, ok := reloaded.GetSnapshot("before-deploy")
+	if !ok || saved.CWD != first || saved.Env["APP_ENV"] != "staging" {
+		t.Errorf("persisted snapshot = %+v, %v", saved, ok)
+	}
+}
+
+func TestRestoreMissingSnapshot(t *testing.T) {
+	mgr, _ := createTestManager(t)
+	if _, err := mgr.RestoreSnapshot("nope"); err == nil {
+		t.Error("expected restoring a missing snapshot to fail")
+	}
+}
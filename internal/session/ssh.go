@@ -3,17 +3,22 @@ package session
 import (
 	"bytes"
 	"context"
+	"crypto/x509"
+	"errors"
 	"fmt"
 	"io"
 	"net"
 	"os"
 	"os/signal"
+	"path"
 	"path/filepath"
+	"strconv"
 	"strings"
 	"syscall"
 	"time"
 
 	"github.com/pkg/sftp"
+	"github.com/scottgl9/thop/internal/config"
 	"github.com/scottgl9/thop/internal/logger"
 	"golang.org/x/crypto/ssh"
 	"golang.org/x/crypto/ssh/agent"
@@ -22,6 +27,10 @@ import (
 	"golang.org/x/term"
 )
 
+// forwardAgentToRemote registers the agent forwarding channel handler on a client.
+// It is a variable so tests can observe forwarding setup without a live agent.
+var forwardAgentToRemote = agent.ForwardToRemote
+
 // SSHSession represents an SSH session to a remote host
 type SSHSession struct {
 	name                  string
@@ -30,17 +39,29 @@ type SSHSession struct {
 	user                  string
 	keyFile               string
 	password              string // Password for authentication (set via /auth command)
+	passphrase            string // Passphrase for encrypted private keys (set via SetPassphrase)
+	lockedKey             string // Encrypted key skipped on the last connect for want of a (correct) passphrase
 	jumpHost              string // Jump host for ProxyJump (format: user@host:port or just host)
 	agentForwarding       bool   // Whether to forward SSH agent to remote
 	insecureIgnoreHostKey bool   // Skip host key verification (for testing only)
 	client                *ssh.Client
 	jumpClient            *ssh.Client // Jump host client (if using jump host)
+	authMethod            string      // Auth method used by the current connection ("agent", "key:<path>", "password")
+	authAttempt           string      // Auth method most recently offered during the handshake
 	cwd                   string
 	env                   map[string]string
 	connected             bool
 	connectTimeout        time.Duration
 	commandTimeout        time.Duration
 	startupCommands       []string
+	keepaliveInterval     time.Duration     // Interval between keepalive requests (0 disables)
+	keepaliveStop         chan struct{}     // Closed on disconnect to stop the keepalive loop
+	remoteShell           string            // Login shell detected on connect (e.g. "bash", "fish")
+	locale                string            // Exported as LC_ALL and LANG for commands ("" leaves the remote default)
+	configEnv             map[string]string // Environment variables from config
+	envFile               string            // Dotenv-style file read on each connect (configEnv wins on conflicts)
+	runAs                 string            // User commands run as via sudo -u ("" for the login user)
+	shell                 string            // Shell commands are run with as "<shell> -c" ("" uses the login shell)
 }
 
 // SSHConfig contains SSH session configuration
@@ -50,19 +71,26 @@ type SSHConfig struct {
 	Port                  int
 	User                  string
 	KeyFile               string
-	Password              string        // Optional, for auth command
-	PasswordEnv           string        // Environment variable containing password
-	PasswordFile          string        // File containing password (must be 0600)
-	JumpHost              string        // Jump host for ProxyJump (format: user@host:port or just host)
-	AgentForwarding       bool          // Whether to forward SSH agent to remote
-	InsecureIgnoreHostKey bool          // Skip host key verification (for testing only)
-	ConnectTimeout        time.Duration // Connection timeout (default 30s)
-	Timeout               time.Duration // Command timeout (default 300s)
-	StartupCommands       []string      // Commands to run after connecting
+	Password              string            // Optional, for auth command
+	PasswordEnv           string            // Environment variable containing password
+	PasswordFile          string            // File containing password (must be 0600)
+	JumpHost              string            // Jump host for ProxyJump (format: user@host:port or just host)
+	AgentForwarding       bool              // Whether to forward SSH agent to remote
+	InsecureIgnoreHostKey bool              // Skip host key verification (for testing only)
+	ConnectTimeout        time.Duration     // Connection timeout (default 30s)
+	Timeout               time.Duration     // Command timeout (default 300s)
+	StartupCommands       []string          // Commands to run after connecting
+	KeepaliveInterval     time.Duration     // Interval between keepalive requests (0 disables)
+	Locale                string            // Exported as LC_ALL and LANG for commands ("" leaves the remote default)
+	Env                   map[string]string // Environment variables from config
+	EnvFile               string            // Dotenv-style file read on each connect (Env wins on conflicts)
+	RunAs                 string            // User commands run as via sudo -u ("" for the login user)
+	Shell                 string            // Shell commands are run with as "<shell> -c" ("" uses the login shell)
 }
 
 // NewSSHSession creates a new SSH session
 func NewSSHSession(cfg SSHConfig) *SSHSession {
+	cfg.Host, cfg.Port = splitHostPort(cfg.Host, cfg.Port)
 	if cfg.Port == 0 {
 		cfg.Port = 22
 	}
@@ -123,6 +151,12 @@ func NewSSHSession(cfg SSHConfig) *SSHSession {
 		connectTimeout:        cfg.ConnectTimeout,
 		commandTimeout:        cfg.Timeout,
 		startupCommands:       cfg.StartupCommands,
+		keepaliveInterval:     cfg.KeepaliveInterval,
+		locale:                cfg.Locale,
+		configEnv:             cfg.Env,
+		envFile:               cfg.EnvFile,
+		runAs:                 cfg.RunAs,
+		shell:                 cfg.Shell,
 	}
 
 	return session
@@ -155,6 +189,9 @@ func (s *SSHSession) Connect() error {
 
 	if len(authMethods) == 0 {
 		logger.Warn("SSH no authentication methods available for %q", s.name)
+		if s.lockedKey != "" {
+			return s.passphraseRequiredError()
+		}
 		return &Error{
 			Code:       ErrAuthPasswordRequired,
 			Message:    fmt.Sprintf("No authentication methods available for %s", s.name),
@@ -181,15 +218,16 @@ func (s *SSHSession) Connect() error {
 	}
 
 	// Connect (with or without jump host)
-	addr := fmt.Sprintf("%s:%d", s.host, s.port)
+	addr := s.address()
 	var client *ssh.Client
+	s.authAttempt = ""
 
 	if s.jumpHost != "" {
 		// Connect via jump host
 		client, err = s.connectViaJumpHost(addr, config)
 	} else {
 		// Direct connection
-		client, err = ssh.Dial("tcp", addr, config)
+		client, err = dialSSH(s.host, s.port, config)
 	}
 
 	if err != nil {
@@ -199,7 +237,24 @@ func (s *SSHSession) Connect() error {
 
 	s.client = client
 	s.connected = true
-	logger.Debug("SSH connection established to %s", addr)
+	// The client stops offering methods once one succeeds, so the last
+	// attempted method is the one that authenticated the connection
+	s.authMethod = s.authAttempt
+	logger.Debug("SSH connection established to %s (auth: %s)", addr, s.authMethod)
+
+	// Keep idle connections alive and detect dead ones between commands
+	if s.keepaliveInterval > 0 {
+		s.keepaliveStop = make(chan struct{})
+		go runKeepalive(client, s.name, s.keepaliveInterval, s.keepaliveStop)
+	}
+
+	// Handle agent channels opened by the remote so forwarding requests work
+	if s.agentForwarding {
+		s.setupAgentForwarding()
+	}
+
+	// Detect the login shell before anything is wrapped in POSIX syntax
+	s.detectRemoteShell()
 
 	// Get initial working directory
 	result, err := s.executeRaw("pwd")
@@ -210,9 +265,20 @@ func (s *SSHSession) Connect() error {
 		s.cwd = "~"
 	}
 
-	// Run startup commands
+	// Apply env_file and inline env from config, re-reading the file on every
+	// connect, so startup commands see them too
+	if err := s.applyConfigEnv(); err != nil {
+		_ = s.Disconnect()
+		return err
+	}
+
+	// Run startup commands; a failing one leaves the session in an unexpected
+	// state, so the connection is torn down rather than used
 	if len(s.startupCommands) > 0 {
-		s.runStartupCommands()
+		if err := s.runStartupCommands(); err != nil {
+			_ = s.Disconnect()
+			return err
+		}
 	}
 
 	return nil
@@ -246,8 +312,8 @@ func (s *SSHSession) connectViaJumpHost(targetAddr string, targetConfig *ssh.Cli
 	}
 
 	// Connect to jump host
-	jumpAddr := fmt.Sprintf("%s:%d", jumpHostAddr, jumpPort)
-	jumpClient, err := ssh.Dial("tcp", jumpAddr, jumpConfig)
+	jumpAddr := net.JoinHostPort(jumpHostAddr, strconv.Itoa(jumpPort))
+	jumpClient, err := dialSSH(jumpHostAddr, jumpPort, jumpConfig)
 	if err != nil {
 		return nil, fmt.Errorf("failed to connect to jump host %s: %w", jumpAddr, err)
 	}
@@ -290,42 +356,179 @@ func (s *SSHSession) parseJumpHost(jumpHost string) (user, host string, port int
 		user = s.user
 	}
 
-	// Check for :port
-	if idx := strings.LastIndex(jumpHost, ":"); idx != -1 {
-		host = jumpHost[:idx]
-		if p, err := fmt.Sscanf(jumpHost[idx+1:], "%d", &port); err != nil || p != 1 {
-			port = 22
-		}
-	} else {
-		host = jumpHost
+	// Check for :port ([v6]:port, host:port, or a bare IPv6 literal)
+	host, port = splitHostPort(jumpHost, 22)
+	if port == 0 {
+		port = 22
 	}
 
 	return user, host, port
 }
 
-// runStartupCommands executes the configured startup commands
-func (s *SSHSession) runStartupCommands() {
+// splitHostPort separates an optional port from a configured host. It accepts
+// "host", "host:port", "[v6]:port", "[v6]" and bare IPv6 literals like "::1".
+// If host carries no port, port is returned unchanged.
+func splitHostPort(host string, port int) (string, int) {
+	if h, p, err := net.SplitHostPort(host); err == nil {
+		if n, err := strconv.Atoi(p); err == nil {
+			return h, n
+		}
+		return h, port
+	}
+	return strings.TrimSuffix(strings.TrimPrefix(host, "["), "]"), port
+}
+
+// address returns the host:port dial address, bracketing IPv6 literals
+func (s *SSHSession) address() string {
+	return net.JoinHostPort(s.host, strconv.Itoa(s.port))
+}
+
+// dialSSH resolves host and tries each address in order, giving every address
+// the full connect timeout, then performs the SSH handshake on the first
+// connection that succeeds
+func dialSSH(host string, port int, config *ssh.ClientConfig) (*ssh.Client, error) {
+	conn, err := dialTCP(host, port, config.Timeout)
+	if err != nil {
+		return nil, err
+	}
+
+	addr := net.JoinHostPort(host, strconv.Itoa(port))
+	ncc, chans, reqs, err := ssh.NewClientConn(conn, addr, config)
+	if err != nil {
+		conn.Close()
+		return nil, err
+	}
+	return ssh.NewClient(ncc, chans, reqs), nil
+}
+
+// dialTCP connects to the first reachable address host resolves to
+func dialTCP(host string, port int, timeout time.Duration) (net.Conn, error) {
+	addrs, err := net.DefaultResolver.LookupHost(context.Background(), host)
+	if err != nil {
+		return nil, err
+	}
+
+	var lastErr error
+	for _, ip := range addrs {
+		addr := net.JoinHostPort(ip, strconv.Itoa(port))
+		conn, err := net.DialTimeout("tcp", addr, timeout)
+		if err == nil {
+			return conn, nil
+		}
+		logger.Debug("SSH dial %s failed: %v", addr, err)
+		lastErr = err
+	}
+	return nil, lastErr
+}
+
+// setupAgentForwarding forwards agent channels opened by the remote host to the
+// local SSH agent. Each command channel still requests forwarding separately.
+func (s *SSHSession) setupAgentForwarding() {
+	socket := os.Getenv("SSH_AUTH_SOCK")
+	if socket == "" {
+		logger.Warn("SSH agent forwarding enabled for %q but SSH_AUTH_SOCK is not set", s.name)
+		return
+	}
+
+	if err := forwardAgentToRemote(s.client, socket); err != nil {
+		logger.Warn("SSH agent forwarding setup failed for %q: %v", s.name, err)
+		return
+	}
+
+	logger.Debug("SSH agent forwarding enabled for session %q", s.name)
+}
+
+// applyConfigEnv sets the environment variables configured for the session
+func (s *SSHSession) applyConfigEnv() error {
+	env, err := config.ResolveEnv(s.envFile, s.configEnv)
+	if err != nil {
+		return &Error{
+			Code:       ErrConnectionFailed,
+			Message:    fmt.Sprintf("Session %s: %v", s.name, err),
+			Session:    s.name,
+			Host:       s.host,
+			Suggestion: "Check the env_file setting for this session",
+		}
+	}
+	for k, v := range env {
+		s.env[k] = v
+	}
+	return nil
+}
+
+// runStartupCommands executes the configured startup commands in order through
+// Execute, so cd updates the tracked cwd. It stops at the first failure.
+func (s *SSHSession) runStartupCommands() error {
 	logger.Debug("SSH running %d startup command(s) on session %q", len(s.startupCommands), s.name)
 	for _, cmd := range s.startupCommands {
 		logger.Debug("SSH startup command: %s", cmd)
-		result, err := s.executeRaw(cmd)
+		// Each command runs in a new shell, so an assignment is run with a
+		// printf of the values it set, which are kept in the session env.
+		// The remote shell expands them, as with export PATH=$PATH:/opt/bin.
+		var exported []string
+		if env, ok := parseEnvAssignments(strings.TrimSpace(cmd), func(string) string { return "" }); ok {
+			for k := range env {
+				exported = append(exported, k)
+			}
+		}
+		run := cmd
+		if len(exported) > 0 {
+			run += " && printf '%s\\0'"
+			for _, k := range exported {
+				run += fmt.Sprintf(` "$%s"`, k)
+			}
+		}
+		result, err := s.Execute(run)
 		if err != nil {
 			logger.Warn("SSH startup command failed: %s - %v", cmd, err)
-			continue
+			return &Error{
+				Code:       ErrConnectionFailed,
+				Message:    fmt.Sprintf("Startup command %q failed on %s: %v", cmd, s.name, err),
+				Session:    s.name,
+				Host:       s.host,
+				Suggestion: fmt.Sprintf("Check startup_commands for session %s in your config", s.name),
+			}
 		}
 		if result.ExitCode != 0 {
 			logger.Warn("SSH startup command exited with code %d: %s", result.ExitCode, cmd)
+			message := fmt.Sprintf("Startup command %q exited with code %d on %s", cmd, result.ExitCode, s.name)
+			if stderr := strings.TrimSpace(result.Stderr); stderr != "" {
+				message += ": " + stderr
+			}
+			return &Error{
+				Code:       ErrConnectionFailed,
+				Message:    message,
+				Session:    s.name,
+				Host:       s.host,
+				Suggestion: fmt.Sprintf("Check startup_commands for session %s in your config", s.name),
+			}
+		}
+		if len(exported) > 0 {
+			values := strings.Split(result.Stdout, "\x00")
+			if len(values) > len(exported) {
+				values = values[len(values)-1-len(exported) : len(values)-1]
+				for i, k := range exported {
+					s.env[k] = values[i]
+				}
+			}
 		}
 	}
+	return nil
 }
 
 // Disconnect closes the SSH connection
 func (s *SSHSession) Disconnect() error {
+	if s.keepaliveStop != nil {
+		close(s.keepaliveStop)
+		s.keepaliveStop = nil
+	}
+
 	if s.client != nil {
 		logger.Debug("SSH disconnecting from %s@%s", s.user, s.host)
 		err := s.client.Close()
 		s.client = nil
 		s.connected = false
+		s.authMethod = ""
 
 		// Also close jump client if present
 		if s.jumpClient != nil {
@@ -339,6 +542,101 @@ func (s *SSHSession) Disconnect() error {
 	return nil
 }
 
+// runKeepalive sends an OpenSSH keepalive request every interval until stop is
+// closed. If the server does not answer within an interval the client is
+// closed, so the next command fails fast and the manager can reconnect.
+func runKeepalive(client *ssh.Client, name string, interval time.Duration, stop <-chan struct{}) {
+	ticker := time.NewTicker(interval)
+	defer ticker.Stop()
+
+	for {
+		select {
+		case <-stop:
+			return
+		case <-ticker.C:
+		}
+
+		errCh := make(chan error, 1)
+		go func() {
+			_, _, err := client.SendRequest("keepalive@openssh.com", true, nil)
+			errCh <- err
+		}()
+
+		var err error
+		select {
+		case <-stop:
+			return
+		case err = <-errCh:
+		case <-time.After(interval):
+			err = fmt.Errorf("no reply within %v", interval)
+		}
+		if err != nil {
+			logger.Warn("SSH keepalive failed for %q, closing connection: %v", name, err)
+			client.Close()
+			return
+		}
+	}
+}
+
+// detectRemoteShell records the account's login shell. The probe runs
+// verbatim since "echo $SHELL" means the same thing in POSIX shells, fish and csh.
+func (s *SSHSession) detectRemoteShell() {
+	s.remoteShell = ""
+	result, err := s.ExecuteVerbatim(context.Background(), "echo $SHELL")
+	if err != nil || result.ExitCode != 0 {
+		logger.Debug("SSH could not detect login shell on %q, assuming POSIX", s.name)
+		return
+	}
+
+	s.remoteShell = parseRemoteShell(result.Stdout)
+	if s.shell != "" {
+		logger.Debug("SSH login shell on %q: %s; commands will be run via %s -c", s.name, s.remoteShell, s.shell)
+	} else if !isPOSIXShell(s.remoteShell) {
+		logger.Warn("SSH session %q has non-POSIX login shell %q; commands will be run via sh -c", s.name, s.remoteShell)
+	} else {
+		logger.Debug("SSH login shell on %q: %s", s.name, s.remoteShell)
+	}
+}
+
+// parseRemoteShell extracts the shell name from the output of "echo $SHELL"
+// or "echo $0", e.g. "/usr/bin/fish" -> "fish" and "-bash" -> "bash"
+func parseRemoteShell(output string) string {
+	lines := strings.Split(strings.TrimSpace(output), "\n")
+	shell := strings.TrimSpace(lines[len(lines)-1])
+	if shell == "" {
+		return ""
+	}
+	return strings.TrimPrefix(path.Base(shell), "-")
+}
+
+// isPOSIXShell reports whether thop's "cd ... && export ...;" wrapping works
+// in the named shell. Unknown or undetected shells are assumed to be POSIX.
+func isPOSIXShell(shell string) bool {
+	switch shell {
+	case "fish", "csh", "tcsh", "nu", "xonsh", "elvish", "pwsh", "rc":
+		return false
+	}
+	return true
+}
+
+// wrapForShell runs a POSIX command string via the configured shell's -c, or
+// when no shell is configured, via sh -c if the login shell would not
+// understand it
+func (s *SSHSession) wrapForShell(cmdStr string) string {
+	if s.shell != "" {
+		return s.shell + " -c " + ShellQuote(cmdStr)
+	}
+	if isPOSIXShell(s.remoteShell) {
+		return cmdStr
+	}
+	return "sh -c " + ShellQuote(cmdStr)
+}
+
+// RemoteShell returns the login shell detected on connect ("" if unknown)
+func (s *SSHSession) RemoteShell() string {
+	return s.remoteShell
+}
+
 // IsConnected returns true if connected
 func (s *SSHSession) IsConnected() bool {
 	return s.connected && s.client != nil
@@ -380,17 +678,7 @@ func (s *SSHSession) Reconnect() error {
 	}
 
 	// Restore saved state
-	if savedCwd != "" && savedCwd != "~" {
-		// Don't use Execute() as it would run the command
-		// Just set the cwd directly and verify it exists
-		result, cdErr := s.executeRaw(fmt.Sprintf("cd %s && pwd", savedCwd))
-		if cdErr == nil && result.ExitCode == 0 {
-			s.cwd = strings.TrimSpace(result.Stdout)
-			logger.Debug("SSH restored cwd to %s", s.cwd)
-		} else {
-			logger.Debug("SSH could not restore cwd %s: %v", savedCwd, cdErr)
-		}
-	}
+	s.restoreCWD(savedCwd)
 
 	// Restore environment
 	for k, v := range savedEnv {
@@ -400,6 +688,23 @@ func (s *SSHSession) Reconnect() error {
 	return nil
 }
 
+// restoreCWD returns to a previously saved cwd after connecting, if it still exists
+func (s *SSHSession) restoreCWD(savedCwd string) {
+	if savedCwd == "" || savedCwd == "~" || savedCwd == s.cwd {
+		return
+	}
+
+	// Don't use Execute() as it would run the command
+	// Just set the cwd directly and verify it exists
+	result, cdErr := s.executeRaw(fmt.Sprintf("cd %s && pwd", savedCwd))
+	if cdErr == nil && result.ExitCode == 0 {
+		s.cwd = strings.TrimSpace(result.Stdout)
+		logger.Debug("SSH restored cwd to %s", s.cwd)
+	} else {
+		logger.Debug("SSH could not restore cwd %s: %v", savedCwd, cdErr)
+	}
+}
+
 // Execute runs a command over SSH
 func (s *SSHSession) Execute(cmdStr string) (*ExecuteResult, error) {
 	ctx := context.Background()
@@ -440,7 +745,16 @@ func (s *SSHSession) executeRaw(cmdStr string) (*ExecuteResult, error) {
 func (s *SSHSession) executeRawWithContext(ctx context.Context, cmdStr string) (*ExecuteResult, error) {
 	session, err := s.client.NewSession()
 	if err != nil {
-		return nil, fmt.Errorf("failed to create session: %w", err)
+		// The transport is gone; mark it so the manager can reconnect
+		s.connected = false
+		return nil, &Error{
+			Code:       ErrSessionDisconnected,
+			Message:    fmt.Sprintf("Connection to %s lost: %v", s.name, err),
+			Session:    s.name,
+			Host:       s.host,
+			Retryable:  true,
+			Suggestion: fmt.Sprintf("Use /connect %s to reconnect", s.name),
+		}
 	}
 	defer session.Close()
 
@@ -460,6 +774,9 @@ func (s *SSHSession) executeRawWithContext(ctx context.Context, cmdStr string) (
 	envPrefix.WriteString("export TERM=${TERM:-xterm-256color}; ")
 	envPrefix.WriteString("export CLICOLOR=1; ")
 	envPrefix.WriteString("export CLICOLOR_FORCE=1; ")
+	if s.locale != "" {
+		envPrefix.WriteString(fmt.Sprintf("export LC_ALL=%[1]s LANG=%[1]s; ", ShellQuote(s.locale)))
+	}
 
 	// Add user-defined environment variables
 	for k, v := range s.env {
@@ -471,11 +788,14 @@ func (s *SSHSession) executeRawWithContext(ctx context.Context, cmdStr string) (
 	if envPrefix.Len() > 0 {
 		cmdStr = envPrefix.String() + cmdStr
 	}
+	// With run_as, the exports and cd go inside the sudo shell since sudo resets the environment
+	cmdStr = s.wrapForShell(runAsCommand(s.runAs, cmdStr))
 
-	// Capture output
+	// Capture output, watching for a password or confirmation prompt
 	var stdout, stderr bytes.Buffer
-	session.Stdout = &stdout
-	session.Stderr = &stderr
+	prompt := newPromptWatcher()
+	session.Stdout = prompt.Writer(&stdout)
+	session.Stderr = prompt.Writer(&stderr)
 
 	// Create a channel for command completion
 	done := make(chan error, 1)
@@ -491,6 +811,18 @@ func (s *SSHSession) executeRawWithContext(ctx context.Context, cmdStr string) (
 	case runErr = <-done:
 		// Command completed
 	case <-ctx.Done():
+		if ctx.Err() == context.DeadlineExceeded {
+			// Caller-supplied deadline (e.g. a per-call timeout) expired
+			logger.Warn("SSH command exceeded caller deadline on %q", s.name)
+			session.Close()
+			return nil, &Error{
+				Code:      ErrCommandTimeout,
+				Message:   "Command timed out (deadline exceeded)",
+				Session:   s.name,
+				Host:      s.host,
+				Retryable: true,
+			}
+		}
 		// Context canceled (user interrupt)
 		logger.Debug("SSH command interrupted on %q", s.name)
 		// Send SIGINT to the remote process
@@ -498,10 +830,24 @@ func (s *SSHSession) executeRawWithContext(ctx context.Context, cmdStr string) (
 		// Give a brief moment for clean termination
 		time.Sleep(100 * time.Millisecond)
 		session.Close()
-		return &ExecuteResult{
+		interrupted := &ExecuteResult{
 			Stderr:   "^C\n",
 			ExitCode: 130, // Standard exit code for SIGINT
-		}, nil
+		}
+		// Keep the output produced before the interrupt, once the channel has
+		// stopped writing to the buffers
+		select {
+		case <-done:
+			interrupted.Stdout = stdout.String()
+			interrupted.Stderr = stderr.String() + interrupted.Stderr
+		case <-time.After(time.Second):
+		}
+		return interrupted, nil
+	case <-prompt.Found():
+		// The command would hang waiting for input nobody can type
+		logger.Warn("SSH command stopped at an input prompt on %q", s.name)
+		session.Close()
+		return nil, prompt.Err(s.name, s.host)
 	case <-time.After(s.commandTimeout):
 		// Timeout - close the session to kill the command
 		logger.Warn("SSH command timed out after %s on %q", s.commandTimeout, s.name)
@@ -637,7 +983,7 @@ func (s *SSHSession) ExecuteInteractive(cmdStr string) (int, error) {
 		escapedVal := strings.ReplaceAll(v, "'", "'\\''")
 		envPrefix.WriteString(fmt.Sprintf("export %s='%s'; ", k, escapedVal))
 	}
-	fullCmd = envPrefix.String() + fullCmd
+	fullCmd = s.wrapForShell(envPrefix.String() + fullCmd)
 
 	// Start the command (non-blocking)
 	if startErr := session.Start(fullCmd); startErr != nil {
@@ -803,9 +1149,30 @@ func (s *SSHSession) HasPassword() bool {
 	return s.password != ""
 }
 
+// SetPassphrase sets the passphrase used to decrypt encrypted private keys
+func (s *SSHSession) SetPassphrase(passphrase string) {
+	s.passphrase = passphrase
+}
+
+// passphraseRequiredError reports that an encrypted key could not be used
+func (s *SSHSession) passphraseRequiredError() *Error {
+	message := fmt.Sprintf("Private key %s is encrypted and needs a passphrase", s.lockedKey)
+	if s.passphrase != "" {
+		message = fmt.Sprintf("Incorrect passphrase for private key %s", s.lockedKey)
+	}
+	return &Error{
+		Code:       ErrAuthPassphraseRequired,
+		Message:    message,
+		Session:    s.name,
+		Host:       s.host,
+		Suggestion: fmt.Sprintf("Use /connect %s in interactive mode to enter the key passphrase", s.name),
+	}
+}
+
 // getAuthMethods returns available authentication methods
 func (s *SSHSession) getAuthMethods() ([]ssh.AuthMethod, error) {
 	var methods []ssh.AuthMethod
+	s.lockedKey = ""
 
 	// Try SSH agent first
 	if agentAuth := s.getAgentAuth(); agentAuth != nil {
@@ -816,6 +1183,8 @@ func (s *SSHSession) getAuthMethods() ([]ssh.AuthMethod, error) {
 	if s.keyFile != "" {
 		if keyAuth, err := s.getKeyAuth(s.keyFile); err == nil {
 			methods = append(methods, keyAuth)
+		} else if isKeyPassphraseError(err) {
+			s.lockedKey = s.keyFile
 		}
 	}
 
@@ -834,13 +1203,19 @@ func (s *SSHSession) getAuthMethods() ([]ssh.AuthMethod, error) {
 		if _, err := os.Stat(keyPath); err == nil {
 			if keyAuth, err := s.getKeyAuth(keyPath); err == nil {
 				methods = append(methods, keyAuth)
+			} else if isKeyPassphraseError(err) && s.lockedKey == "" {
+				s.lockedKey = keyPath
 			}
 		}
 	}
 
 	// Try password authentication if password is set
 	if s.password != "" {
-		methods = append(methods, ssh.Password(s.password))
+		password := s.password
+		methods = append(methods, ssh.PasswordCallback(func() (string, error) {
+			s.authAttempt = "password"
+			return password, nil
+		}))
 		logger.Debug("SSH using password authentication for session %q", s.name)
 	}
 
@@ -860,11 +1235,16 @@ func (s *SSHSession) getAgentAuth() ssh.AuthMethod {
 	}
 
 	agentClient := agent.NewClient(conn)
-	return ssh.PublicKeysCallback(agentClient.Signers)
+	return ssh.PublicKeysCallback(func() ([]ssh.Signer, error) {
+		s.authAttempt = "agent"
+		return agentClient.Signers()
+	})
 }
 
 // getKeyAuth returns key file authentication
 func (s *SSHSession) getKeyAuth(keyPath string) (ssh.AuthMethod, error) {
+	label := "key:" + keyPath
+
 	// Expand ~ in path
 	if strings.HasPrefix(keyPath, "~") {
 		home, _ := os.UserHomeDir()
@@ -877,12 +1257,25 @@ func (s *SSHSession) getKeyAuth(keyPath string) (ssh.AuthMethod, error) {
 	}
 
 	signer, err := ssh.ParsePrivateKey(key)
+	var missing *ssh.PassphraseMissingError
+	if errors.As(err, &missing) && s.passphrase != "" {
+		signer, err = ssh.ParsePrivateKeyWithPassphrase(key, []byte(s.passphrase))
+	}
 	if err != nil {
-		// Key might be encrypted
 		return nil, err
 	}
 
-	return ssh.PublicKeys(signer), nil
+	return ssh.PublicKeysCallback(func() ([]ssh.Signer, error) {
+		s.authAttempt = label
+		return []ssh.Signer{signer}, nil
+	}), nil
+}
+
+// isKeyPassphraseError reports whether a key failed to parse because it is
+// encrypted and the passphrase was missing or wrong
+func isKeyPassphraseError(err error) bool {
+	var missing *ssh.PassphraseMissingError
+	return errors.As(err, &missing) || errors.Is(err, x509.IncorrectPasswordError)
 }
 
 // getHostKeyCallback returns the host key callback
@@ -964,6 +1357,9 @@ func (s *SSHSession) wrapConnectionError(err error) error {
 	}
 
 	if strings.Contains(errStr, "unable to authenticate") || strings.Contains(errStr, "no supported methods") {
+		if s.lockedKey != "" {
+			return s.passphraseRequiredError()
+		}
 		return &Error{
 			Code:       ErrAuthPasswordRequired,
 			Message:    fmt.Sprintf("Authentication failed for %s@%s", s.user, s.host),
@@ -987,26 +1383,60 @@ func (s *SSHSession) Host() string {
 	return s.host
 }
 
-// FetchHostKey fetches and returns the host key and fingerprint for the session
-func (s *SSHSession) FetchHostKey() (keyType string, fingerprint string, err error) {
-	addr := fmt.Sprintf("%s:%d", s.host, s.port)
+// HostKeyStatus describes a server host key relative to ~/.ssh/known_hosts
+type HostKeyStatus int
+
+const (
+	HostKeyUnknown  HostKeyStatus = iota // No entry for the host
+	HostKeyTrusted                       // An entry matches the key
+	HostKeyMismatch                      // The host has entries, but for a different key
+)
+
+// knownHostsPath returns the path to the user's known_hosts file
+func knownHostsPath() string {
+	home, _ := os.UserHomeDir()
+	return filepath.Join(home, ".ssh", "known_hosts")
+}
+
+// knownHostsEntry returns the host pattern written to known_hosts for the
+// session: host for port 22, [host]:port otherwise
+func (s *SSHSession) knownHostsEntry() string {
+	return knownhosts.Normalize(s.address())
+}
+
+// ProbeHostKey connects to the server just far enough to receive its host
+// key, without authenticating, and checks it against known_hosts
+func (s *SSHSession) ProbeHostKey() (ssh.PublicKey, HostKeyStatus, error) {
+	addr := s.address()
+
+	// A missing or unreadable known_hosts simply means the key is unknown
+	known, _ := knownhosts.New(knownHostsPath())
 
-	// Create a config that accepts any host key (to fetch it)
 	var fetchedKey ssh.PublicKey
+	status := HostKeyUnknown
 	config := &ssh.ClientConfig{
 		User: s.user,
 		Auth: []ssh.AuthMethod{}, // No auth needed just to fetch key
 		HostKeyCallback: func(hostname string, remote net.Addr, key ssh.PublicKey) error {
 			fetchedKey = key
+			if known != nil {
+				var keyErr *knownhosts.KeyError
+				err := known(hostname, remote, key)
+				if err == nil {
+					status = HostKeyTrusted
+				} else if errors.As(err, &keyErr) && len(keyErr.Want) > 0 {
+					status = HostKeyMismatch
+				}
+			}
 			return nil // Accept the key to fetch it
 		},
 		Timeout: 10 * time.Second,
 	}
 
 	// Try to connect - will fail auth but we'll get the host key
-	conn, err := net.DialTimeout("tcp", addr, config.Timeout)
+	conn, err := dialTCP(s.host, s.port, config.Timeout)
 	if err != nil {
-		return "", "", fmt.Errorf("failed to connect to %s: %w", addr, err)
+		return nil, HostKeyUnknown, fmt.Errorf("failed to connect to %s: %w", addr, err)
 	}
 
 	// Do SSH handshake to get the host key
@@ -1017,68 +1447,68 @@ func (s *SSHSession) FetchHostKey() (keyType string, fingerprint string, err err
 	conn.Close()
 
 	if fetchedKey == nil {
-		return "", "", fmt.Errorf("failed to fetch host key")
+		return nil, HostKeyUnknown, fmt.Errorf("failed to fetch host key")
 	}
 
-	keyType = fetchedKey.Type()
-	fingerprint = ssh.FingerprintSHA256(fetchedKey)
-
-	return keyType, fingerprint, nil
+	return fetchedKey, status, nil
 }
 
-// AddHostKey adds the host's key to known_hosts
-func (s *SSHSession) AddHostKey() error {
-	addr := fmt.Sprintf("%s:%d", s.host, s.port)
-	home, _ := os.UserHomeDir()
-	knownHostsPath := filepath.Join(home, ".ssh", "known_hosts")
-
-	// Ensure .ssh directory exists
-	sshDir := filepath.Dir(knownHostsPath)
-	if err := os.MkdirAll(sshDir, 0700); err != nil {
-		return fmt.Errorf("failed to create .ssh directory: %w", err)
+// FetchHostKey fetches and returns the host key and fingerprint for the session
+func (s *SSHSession) FetchHostKey() (keyType string, fingerprint string, err error) {
+	key, _, err := s.ProbeHostKey()
+	if err != nil {
+		return "", "", err
 	}
+	return key.Type(), ssh.FingerprintSHA256(key), nil
+}
 
-	// Fetch the host key
-	var fetchedKey ssh.PublicKey
-	config := &ssh.ClientConfig{
-		User: s.user,
-		Auth: []ssh.AuthMethod{},
-		HostKeyCallback: func(hostname string, remote net.Addr, key ssh.PublicKey) error {
-			fetchedKey = key
-			return nil
-		},
-		Timeout: 10 * time.Second,
+// HostKeyMismatchError reports that known_hosts holds a different key for the
+// host, with a suggestion for removing the stale entry
+func (s *SSHSession) HostKeyMismatchError() error {
+	entry := s.knownHostsEntry()
+	return &Error{
+		Code:       ErrHostKeyChanged,
+		Message:    fmt.Sprintf("Host key for %s does not match the entry in known_hosts. This could indicate a MITM attack.", entry),
+		Session:    s.name,
+		Host:       s.host,
+		Suggestion: fmt.Sprintf("If the change is expected, remove the old entry with: ssh-keygen -R '%s'", entry),
 	}
+}
 
-	conn, err := net.DialTimeout("tcp", addr, config.Timeout)
+// TrustHostKey appends key to known_hosts for the session's host. The server
+// is probed again so the key written is the one the user confirmed; an
+// existing entry with a different key is never overwritten.
+func (s *SSHSession) TrustHostKey(key ssh.PublicKey) error {
+	current, status, err := s.ProbeHostKey()
 	if err != nil {
-		return fmt.Errorf("failed to connect to %s: %w", addr, err)
+		return err
 	}
-
-	sshConn, _, _, _ := ssh.NewClientConn(conn, addr, config)
-	if sshConn != nil {
-		sshConn.Close()
+	if !bytes.Equal(current.Marshal(), key.Marshal()) {
+		return &Error{
+			Code:    ErrHostKeyChanged,
+			Message: fmt.Sprintf("Host key for %s changed since it was fetched", s.knownHostsEntry()),
+			Session: s.name,
+			Host:    s.host,
+		}
 	}
-	conn.Close()
 
-	if fetchedKey == nil {
-		return fmt.Errorf("failed to fetch host key")
+	switch status {
+	case HostKeyTrusted:
+		logger.Debug("host key for %s already in known_hosts", s.knownHostsEntry())
+		return nil
+	case HostKeyMismatch:
+		return s.HostKeyMismatchError()
 	}
 
-	// Format the host entry
-	// If using non-standard port, format is [host]:port
-	var hostEntry string
-	if s.port != 22 {
-		hostEntry = fmt.Sprintf("[%s]:%d", s.host, s.port)
-	} else {
-		hostEntry = s.host
+	knownHosts := knownHostsPath()
+	if err := os.MkdirAll(filepath.Dir(knownHosts), 0700); err != nil {
+		return fmt.Errorf("failed to create .ssh directory: %w", err)
 	}
 
-	// Create the known_hosts line
-	line := knownhosts.Line([]string{hostEntry}, fetchedKey)
+	entry := s.knownHostsEntry()
+	line := knownhosts.Line([]string{entry}, key)
 
-	// Append to known_hosts
-	f, err := os.OpenFile(knownHostsPath, os.O_APPEND|os.O_CREATE|os.O_WRONLY, 0600)
+	f, err := os.OpenFile(knownHosts, os.O_APPEND|os.O_CREATE|os.O_WRONLY, 0600)
 	if err != nil {
 		return fmt.Errorf("failed to open known_hosts: %w", err)
 	}
@@ -1088,10 +1518,19 @@ func (s *SSHSession) AddHostKey() error {
 		return fmt.Errorf("failed to write to known_hosts: %w", err)
 	}
 
-	logger.Info("added host key for %s to known_hosts", hostEntry)
+	logger.Info("added host key for %s to known_hosts", entry)
 	return nil
 }
 
+// AddHostKey fetches the host's key and adds it to known_hosts
+func (s *SSHSession) AddHostKey() error {
+	key, _, err := s.ProbeHostKey()
+	if err != nil {
+		return err
+	}
+	return s.TrustHostKey(key)
+}
+
 // Port returns the SSH port
 func (s *SSHSession) Port() int {
 	return s.port
@@ -1102,6 +1541,17 @@ func (s *SSHSession) User() string {
 	return s.user
 }
 
+// AuthMethod returns the auth method used by the current connection
+// ("agent", "key:<path>" or "password"), or "" when not connected
+func (s *SSHSession) AuthMethod() string {
+	return s.authMethod
+}
+
+// AgentForwarding returns true if SSH agent forwarding is enabled
+func (s *SSHSession) AgentForwarding() bool {
+	return s.agentForwarding
+}
+
 // UploadFile uploads a local file to the remote server
 func (s *SSHSession) UploadFile(localPath, remotePath string) error {
 	if !s.IsConnected() {
@@ -1198,6 +1648,62 @@ func (s *SSHSession) DownloadFile(remotePath, localPath string) error {
 	return nil
 }
 
+// CopyFileTo streams a file from this session to another SSH session over
+// SFTP without buffering it in memory. It returns the number of bytes copied.
+func (s *SSHSession) CopyFileTo(dst *SSHSession, srcPath, dstPath string) (int64, error) {
+	if !s.IsConnected() {
+		return 0, fmt.Errorf("source session is not connected")
+	}
+	if !dst.IsConnected() {
+		return 0, fmt.Errorf("destination session is not connected")
+	}
+
+	srcClient, err := sftp.NewClient(s.client)
+	if err != nil {
+		return 0, fmt.Errorf("failed to create SFTP client: %w", err)
+	}
+	defer srcClient.Close()
+
+	dstClient, err := sftp.NewClient(dst.client)
+	if err != nil {
+		return 0, fmt.Errorf("failed to create SFTP client: %w", err)
+	}
+	defer dstClient.Close()
+
+	srcFile, err := srcClient.Open(srcPath)
+	if err != nil {
+		return 0, fmt.Errorf("failed to open remote file: %w", err)
+	}
+	defer srcFile.Close()
+
+	srcInfo, err := srcFile.Stat()
+	if err != nil {
+		return 0, fmt.Errorf("failed to stat remote file: %w", err)
+	}
+
+	dstFile, err := dstClient.Create(dstPath)
+	if err != nil {
+		return 0, fmt.Errorf("failed to create remote file: %w", err)
+	}
+
+	bytesWritten, err := io.Copy(dstFile, srcFile)
+	if err != nil {
+		_ = dstFile.Close()
+		return bytesWritten, fmt.Errorf("failed to copy file: %w", err)
+	}
+	// SFTP writes can fail on close, e.g. when the remote disk is full
+	if err := dstFile.Close(); err != nil {
+		return bytesWritten, fmt.Errorf("failed to write remote file: %w", err)
+	}
+
+	if err := dstClient.Chmod(dstPath, srcInfo.Mode()); err != nil {
+		logger.Warn("failed to set permissions on remote file: %v", err)
+	}
+
+	logger.Debug("copied %d bytes from %s:%s to %s:%s", bytesWritten, s.host, srcPath, dst.host, dstPath)
+	return bytesWritten, nil
+}
+
 // ReadFile reads a file from the remote server and returns its contents
 func (s *SSHSession) ReadFile(remotePath string) ([]byte, error) {
 	if !s.IsConnected() {
@@ -1227,7 +1733,8 @@ func (s *SSHSession) ReadFile(remotePath string) ([]byte, error) {
 	return contents, nil
 }
 
-// WriteFile writes data to a file on the remote server
+// WriteFile writes data to a file on the remote server. A zero perm leaves
+// the mode the server chose on creation, which honors the remote umask.
 func (s *SSHSession) WriteFile(remotePath string, data []byte, perm os.FileMode) error {
 	if !s.IsConnected() {
 		return fmt.Errorf("session is not connected")
@@ -1247,15 +1754,74 @@ func (s *SSHSession) WriteFile(remotePath string, data []byte, perm os.FileMode)
 	}
 	defer remoteFile.Close()
 
+	// Set permissions before writing so the content is never exposed with a wider mode
+	if perm != 0 {
+		if err := remoteFile.Chmod(perm); err != nil {
+			return fmt.Errorf("failed to set permissions on remote file: %w", err)
+		}
+	}
+
 	// Write data
 	if _, err := remoteFile.Write(data); err != nil {
 		return fmt.Errorf("failed to write to remote file: %w", err)
 	}
 
-	// Set permissions
-	if err := sftpClient.Chmod(remotePath, perm); err != nil {
-		logger.Warn("failed to set permissions on remote file: %v", err)
+	return nil
+}
+
+// ReplaceFile writes data to a temporary file next to remotePath and renames
+// it into place, so the file is never left half-written. A zero perm leaves
+// the mode the server chose on creation.
+func (s *SSHSession) ReplaceFile(remotePath string, data []byte, perm os.FileMode) error {
+	if !s.IsConnected() {
+		return fmt.Errorf("session is not connected")
+	}
+
+	sftpClient, err := sftp.NewClient(s.client)
+	if err != nil {
+		return fmt.Errorf("failed to create SFTP client: %w", err)
 	}
+	defer sftpClient.Close()
 
+	tmpPath := path.Join(path.Dir(remotePath), fmt.Sprintf(".%s.thop-%d", path.Base(remotePath), time.Now().UnixNano()))
+	tmpFile, err := sftpClient.OpenFile(tmpPath, os.O_WRONLY|os.O_CREATE|os.O_EXCL)
+	if err != nil {
+		return fmt.Errorf("failed to create remote temp file: %w", err)
+	}
+	if perm != 0 {
+		err = tmpFile.Chmod(perm)
+	}
+	if err == nil {
+		_, err = tmpFile.Write(data)
+	}
+	if closeErr := tmpFile.Close(); err == nil {
+		err = closeErr
+	}
+	if err == nil {
+		err = sftpClient.PosixRename(tmpPath, remotePath)
+	}
+	if err != nil {
+		_ = sftpClient.Remove(tmpPath)
+		return fmt.Errorf("failed to replace remote file: %w", err)
+	}
 	return nil
 }
+
+// FileMode returns the permission bits of a remote file via SFTP
+func (s *SSHSession) FileMode(remotePath string) (os.FileMode, error) {
+	if !s.IsConnected() {
+		return 0, fmt.Errorf("session is not connected")
+	}
+
+	sftpClient, err := sftp.NewClient(s.client)
+	if err != nil {
+		return 0, fmt.Errorf("failed to create SFTP client: %w", err)
+	}
+	defer sftpClient.Close()
+
+	info, err := sftpClient.Stat(remotePath)
+	if err != nil {
+		return 0, fmt.Errorf("failed to stat remote file: %w", err)
+	}
+	return info.Mode().Perm(), nil
+}
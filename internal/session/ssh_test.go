@@ -1,9 +1,26 @@
 package session
 
 import (
+	"bytes"
+	"context"
+	"crypto/ed25519"
+	"crypto/rand"
+	"crypto/sha256"
+	"encoding/base64"
+	"encoding/pem"
+	"fmt"
+	"io"
+	"net"
 	"os"
 	"path/filepath"
+	"strings"
+	"sync"
 	"testing"
+	"time"
+
+	"github.com/pkg/sftp"
+	"golang.org/x/crypto/ssh"
+	"golang.org/x/crypto/ssh/knownhosts"
 )
 
 func TestNewSSHSessionPasswordEnv(t *testing.T) {
@@ -204,3 +221,1480 @@ func TestSSHSessionDefaultPort(t *testing.T) {
 		t.Error("Expected session to be created")
 	}
 }
+
+func TestSSHSessionAgentForwarding(t *testing.T) {
+	session := NewSSHSession(SSHConfig{
+		Name:            "test",
+		Host:            "example.com",
+		User:            "testuser",
+		AgentForwarding: true,
+	})
+	if !session.AgentForwarding() {
+		t.Fatal("Expected agent forwarding to be enabled")
+	}
+
+	var gotSocket string
+	calls := 0
+	oldForward := forwardAgentToRemote
+	forwardAgentToRemote = func(client *ssh.Client, addr string) error {
+		calls++
+		gotSocket = addr
+		return nil
+	}
+	defer func() { forwardAgentToRemote = oldForward }()
+
+	oldSock := os.Getenv("SSH_AUTH_SOCK")
+	defer os.Setenv("SSH_AUTH_SOCK", oldSock)
+
+	// Forwarding is registered against the local agent socket
+	os.Setenv("SSH_AUTH_SOCK", "/tmp/thop-test-agent.sock")
+	session.setupAgentForwarding()
+	if calls != 1 {
+		t.Fatalf("Expected forwarding to be requested once, got %d", calls)
+	}
+	if gotSocket != "/tmp/thop-test-agent.sock" {
+		t.Errorf("Expected socket '/tmp/thop-test-agent.sock', got %q", gotSocket)
+	}
+
+	// Without an agent there is nothing to forward to
+	os.Unsetenv("SSH_AUTH_SOCK")
+	session.setupAgentForwarding()
+	if calls != 1 {
+		t.Errorf("Expected no forwarding without SSH_AUTH_SOCK, got %d calls", calls)
+	}
+}
+
+func TestSSHSessionAgentForwardingDisabledByDefault(t *testing.T) {
+	session := NewSSHSession(SSHConfig{
+		Name: "test",
+		Host: "example.com",
+		User: "testuser",
+	})
+	if session.AgentForwarding() {
+		t.Error("Expected agent forwarding to be disabled by default")
+	}
+}
+
+// startTestSSHServer runs an in-process SSH server on a loopback port that
+// authenticates with cfg and rejects all channels. It returns the port.
+func startTestSSHServer(t *testing.T, cfg *ssh.ServerConfig) int {
+	t.Helper()
+	return startTestSSHServerWithHandler(t, cfg, func(ch ssh.NewChannel) {
+		ch.Reject(ssh.Prohibited, "not supported in tests")
+	})
+}
+
+// startTestSSHServerWithHandler is like startTestSSHServer but passes each
+// incoming channel to handle
+func startTestSSHServerWithHandler(t *testing.T, cfg *ssh.ServerConfig, handle func(ssh.NewChannel)) int {
+	t.Helper()
+	return startTestSSHServerWithRequests(t, cfg, handle, ssh.DiscardRequests)
+}
+
+// startTestSSHServerWithRequests is like startTestSSHServerWithHandler but
+// also passes global requests on each connection to handleReqs
+func startTestSSHServerWithRequests(t *testing.T, cfg *ssh.ServerConfig, handle func(ssh.NewChannel), handleReqs func(<-chan *ssh.Request)) int {
+	t.Helper()
+
+	_, hostPriv, err := ed25519.GenerateKey(rand.Reader)
+	if err != nil {
+		t.Fatalf("failed to generate host key: %v", err)
+	}
+	hostSigner, err := ssh.NewSignerFromKey(hostPriv)
+	if err != nil {
+		t.Fatalf("failed to create host signer: %v", err)
+	}
+	cfg.AddHostKey(hostSigner)
+
+	listener, err := net.Listen("tcp", "127.0.0.1:0")
+	if err != nil {
+		t.Fatalf("failed to listen: %v", err)
+	}
+	t.Cleanup(func() { listener.Close() })
+
+	go func() {
+		for {
+			conn, err := listener.Accept()
+			if err != nil {
+				return
+			}
+			go func() {
+				_, chans, reqs, err := ssh.NewServerConn(conn, cfg)
+				if err != nil {
+					conn.Close()
+					return
+				}
+				go handleReqs(reqs)
+				for ch := range chans {
+					go handle(ch)
+				}
+			}()
+		}
+	}()
+
+	return listener.Addr().(*net.TCPAddr).Port
+}
+
+// writeTestKey writes a fresh unencrypted ed25519 private key to dir and
+// returns its path and public key
+func writeTestKey(t *testing.T, dir string) (string, ssh.PublicKey) {
+	t.Helper()
+
+	pub, priv, err := ed25519.GenerateKey(rand.Reader)
+	if err != nil {
+		t.Fatalf("failed to generate key: %v", err)
+	}
+	block, err := ssh.MarshalPrivateKey(priv, "")
+	if err != nil {
+		t.Fatalf("failed to marshal key: %v", err)
+	}
+	keyPath := filepath.Join(dir, "id_test")
+	if err := os.WriteFile(keyPath, pem.EncodeToMemory(block), 0600); err != nil {
+		t.Fatalf("failed to write key: %v", err)
+	}
+	sshPub, err := ssh.NewPublicKey(pub)
+	if err != nil {
+		t.Fatalf("failed to convert public key: %v", err)
+	}
+	return keyPath, sshPub
+}
+
+func TestSSHSessionAuthMethodKey(t *testing.T) {
+	// Isolate from the user's agent and default keys
+	tmpDir := t.TempDir()
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+
+	keyPath, pub := writeTestKey(t, tmpDir)
+	port := startTestSSHServer(t, &ssh.ServerConfig{
+		PublicKeyCallback: func(conn ssh.ConnMetadata, key ssh.PublicKey) (*ssh.Permissions, error) {
+			if bytes.Equal(key.Marshal(), pub.Marshal()) {
+				return nil, nil
+			}
+			return nil, ssh.ErrNoAuth
+		},
+	})
+
+	session := NewSSHSession(SSHConfig{
+		Name:                  "test",
+		Host:                  "127.0.0.1",
+		Port:                  port,
+		User:                  "testuser",
+		KeyFile:               keyPath,
+		InsecureIgnoreHostKey: true,
+	})
+
+	if session.AuthMethod() != "" {
+		t.Errorf("expected empty auth method before connect, got %q", session.AuthMethod())
+	}
+
+	if err := session.Connect(); err != nil {
+		t.Fatalf("Connect failed: %v", err)
+	}
+	defer session.Disconnect()
+
+	if expected := "key:" + keyPath; session.AuthMethod() != expected {
+		t.Errorf("expected auth method %q, got %q", expected, session.AuthMethod())
+	}
+
+	session.Disconnect()
+	if session.AuthMethod() != "" {
+		t.Errorf("expected auth method to be cleared on disconnect, got %q", session.AuthMethod())
+	}
+}
+
+func TestSSHSessionEncryptedKeyPassphrase(t *testing.T) {
+	tmpDir := t.TempDir()
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+
+	pub, priv, err := ed25519.GenerateKey(rand.Reader)
+	if err != nil {
+		t.Fatalf("failed to generate key: %v", err)
+	}
+	block, err := ssh.MarshalPrivateKeyWithPassphrase(priv, "", []byte("s3cret"))
+	if err != nil {
+		t.Fatalf("failed to marshal key: %v", err)
+	}
+	keyPath := filepath.Join(tmpDir, "id_encrypted")
+	if err := os.WriteFile(keyPath, pem.EncodeToMemory(block), 0600); err != nil {
+		t.Fatalf("failed to write key: %v", err)
+	}
+	sshPub, err := ssh.NewPublicKey(pub)
+	if err != nil {
+		t.Fatalf("failed to convert public key: %v", err)
+	}
+
+	port := startTestSSHServer(t, &ssh.ServerConfig{
+		PublicKeyCallback: func(conn ssh.ConnMetadata, key ssh.PublicKey) (*ssh.Permissions, error) {
+			if bytes.Equal(key.Marshal(), sshPub.Marshal()) {
+				return nil, nil
+			}
+			return nil, ssh.ErrNoAuth
+		},
+	})
+
+	session := NewSSHSession(SSHConfig{
+		Name:                  "test",
+		Host:                  "127.0.0.1",
+		Port:                  port,
+		User:                  "testuser",
+		KeyFile:               keyPath,
+		InsecureIgnoreHostKey: true,
+	})
+
+	err = session.Connect()
+	sessionErr, ok := err.(*Error)
+	if !ok || sessionErr.Code != ErrAuthPassphraseRequired {
+		t.Fatalf("expected %s without a passphrase, got %v", ErrAuthPassphraseRequired, err)
+	}
+
+	session.SetPassphrase("wrong")
+	err = session.Connect()
+	sessionErr, ok = err.(*Error)
+	if !ok || sessionErr.Code != ErrAuthPassphraseRequired || !strings.Contains(sessionErr.Message, "Incorrect passphrase") {
+		t.Fatalf("expected an incorrect passphrase error, got %v", err)
+	}
+
+	session.SetPassphrase("s3cret")
+	if err := session.Connect(); err != nil {
+		t.Fatalf("Connect with passphrase failed: %v", err)
+	}
+	defer session.Disconnect()
+
+	if expected := "key:" + keyPath; session.AuthMethod() != expected {
+		t.Errorf("expected auth method %q, got %q", expected, session.AuthMethod())
+	}
+}
+
+func TestSSHSessionAuthMethodPassword(t *testing.T) {
+	tmpDir := t.TempDir()
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+
+	port := startTestSSHServer(t, &ssh.ServerConfig{
+		PasswordCallback: func(conn ssh.ConnMetadata, password []byte) (*ssh.Permissions, error) {
+			if string(password) == "secret" {
+				return nil, nil
+			}
+			return nil, ssh.ErrNoAuth
+		},
+	})
+
+	session := NewSSHSession(SSHConfig{
+		Name:                  "test",
+		Host:                  "127.0.0.1",
+		Port:                  port,
+		User:                  "testuser",
+		Password:              "secret",
+		InsecureIgnoreHostKey: true,
+	})
+
+	if err := session.Connect(); err != nil {
+		t.Fatalf("Connect failed: %v", err)
+	}
+	defer session.Disconnect()
+
+	if session.AuthMethod() != "password" {
+		t.Errorf("expected auth method 'password', got %q", session.AuthMethod())
+	}
+}
+
+func TestSSHSessionExecuteInteractiveRequestsPty(t *testing.T) {
+	tmpDir := t.TempDir()
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+	t.Setenv("TERM", "xterm-test")
+
+	type execRequest struct {
+		command string
+		ptyTerm string
+	}
+	var mu sync.Mutex
+	var execs []execRequest
+
+	port := startTestSSHServerWithHandler(t, &ssh.ServerConfig{
+		PasswordCallback: func(conn ssh.ConnMetadata, password []byte) (*ssh.Permissions, error) {
+			return nil, nil
+		},
+	}, func(newCh ssh.NewChannel) {
+		if newCh.ChannelType() != "session" {
+			newCh.Reject(ssh.UnknownChannelType, "session only")
+			return
+		}
+		ch, reqs, err := newCh.Accept()
+		if err != nil {
+			return
+		}
+		defer ch.Close()
+
+		ptyTerm := ""
+		for req := range reqs {
+			switch req.Type {
+			case "pty-req":
+				var pty struct {
+					Term                         string
+					Columns, Rows, Width, Height uint32
+					Modes                        string
+				}
+				_ = ssh.Unmarshal(req.Payload, &pty)
+				ptyTerm = pty.Term
+				_ = req.Reply(true, nil)
+			case "exec":
+				var exec struct{ Command string }
+				_ = ssh.Unmarshal(req.Payload, &exec)
+				_ = req.Reply(true, nil)
+
+				mu.Lock()
+				execs = append(execs, execRequest{exec.Command, ptyTerm})
+				mu.Unlock()
+
+				status := uint32(0)
+				if strings.HasSuffix(exec.Command, "pwd") {
+					_, _ = ch.Write([]byte("/home/testuser\n"))
+				} else {
+					_, _ = ch.Write([]byte("remote output\n"))
+					status = 3
+				}
+				_, _ = ch.SendRequest("exit-status", false, ssh.Marshal(struct{ Status uint32 }{status}))
+				return
+			default:
+				_ = req.Reply(false, nil)
+			}
+		}
+	})
+
+	session := NewSSHSession(SSHConfig{
+		Name:                  "test",
+		Host:                  "127.0.0.1",
+		Port:                  port,
+		User:                  "testuser",
+		Password:              "secret",
+		InsecureIgnoreHostKey: true,
+	})
+	if err := session.Connect(); err != nil {
+		t.Fatalf("Connect failed: %v", err)
+	}
+	defer session.Disconnect()
+
+	// Run with non-terminal stdin/stdout so raw mode is skipped
+	devNull, err := os.Open(os.DevNull)
+	if err != nil {
+		t.Fatal(err)
+	}
+	defer devNull.Close()
+	r, w, err := os.Pipe()
+	if err != nil {
+		t.Fatal(err)
+	}
+	oldStdin, oldStdout := os.Stdin, os.Stdout
+	os.Stdin, os.Stdout = devNull, w
+	exitCode, execErr := session.ExecuteInteractive("top")
+	os.Stdin, os.Stdout = oldStdin, oldStdout
+	w.Close()
+	output, _ := io.ReadAll(r)
+
+	if execErr != nil {
+		t.Fatalf("ExecuteInteractive failed: %v", execErr)
+	}
+	if exitCode != 3 {
+		t.Errorf("expected exit code 3, got %d", exitCode)
+	}
+	if !strings.Contains(string(output), "remote output") {
+		t.Errorf("expected remote output to be bridged to stdout, got %q", output)
+	}
+
+	mu.Lock()
+	defer mu.Unlock()
+	if len(execs) != 2 {
+		t.Fatalf("expected pwd and interactive execs, got %+v", execs)
+	}
+	if execs[0].ptyTerm != "" {
+		t.Errorf("expected no PTY for non-interactive pwd, got %q", execs[0].ptyTerm)
+	}
+	interactive := execs[1]
+	if interactive.ptyTerm != "xterm-test" {
+		t.Errorf("expected PTY with TERM xterm-test, got %q", interactive.ptyTerm)
+	}
+	if !strings.Contains(interactive.command, "cd /home/testuser && top") {
+		t.Errorf("expected command to run in the session cwd, got %q", interactive.command)
+	}
+}
+
+func TestSSHSessionConnectsViaJumpHost(t *testing.T) {
+	tmpDir := t.TempDir()
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+
+	var mu sync.Mutex
+	var bastionUsers []string
+	var forwarded []string
+
+	bastionPort := startTestSSHServerWithHandler(t, &ssh.ServerConfig{
+		PasswordCallback: func(conn ssh.ConnMetadata, password []byte) (*ssh.Permissions, error) {
+			mu.Lock()
+			bastionUsers = append(bastionUsers, conn.User())
+			mu.Unlock()
+			return nil, nil
+		},
+	}, func(newCh ssh.NewChannel) {
+		// Record the forwarding request, then refuse it
+		if newCh.ChannelType() == "direct-tcpip" {
+			var target struct {
+				Host       string
+				Port       uint32
+				OriginHost string
+				OriginPort uint32
+			}
+			_ = ssh.Unmarshal(newCh.ExtraData(), &target)
+			mu.Lock()
+			forwarded = append(forwarded, net.JoinHostPort(target.Host, fmt.Sprint(target.Port)))
+			mu.Unlock()
+		}
+		newCh.Reject(ssh.ConnectionFailed, "target unreachable in tests")
+	})
+
+	session := NewSSHSession(SSHConfig{
+		Name:                  "internal",
+		Host:                  "10.0.0.5",
+		Port:                  2222,
+		User:                  "deploy",
+		Password:              "secret",
+		JumpHost:              fmt.Sprintf("jumper@127.0.0.1:%d", bastionPort),
+		InsecureIgnoreHostKey: true,
+	})
+
+	err := session.Connect()
+	if err == nil {
+		session.Disconnect()
+		t.Fatal("expected connect to fail when the bastion refuses forwarding")
+	}
+	if !strings.Contains(err.Error(), "jump host") {
+		t.Errorf("expected error to mention the jump host, got %v", err)
+	}
+
+	mu.Lock()
+	defer mu.Unlock()
+	if len(bastionUsers) != 1 || bastionUsers[0] != "jumper" {
+		t.Errorf("expected bastion login as 'jumper', got %v", bastionUsers)
+	}
+	if len(forwarded) != 1 || forwarded[0] != "10.0.0.5:2222" {
+		t.Errorf("expected forwarding to 10.0.0.5:2222 through the bastion, got %v", forwarded)
+	}
+}
+
+// startTestExecServer runs a test SSH server accepting any password whose
+// exec requests are answered by run, which returns stdout and an exit status
+func startTestExecServer(t *testing.T, run func(cmd string) (string, uint32)) int {
+	t.Helper()
+	return startTestSSHServerWithHandler(t, &ssh.ServerConfig{
+		PasswordCallback: func(conn ssh.ConnMetadata, password []byte) (*ssh.Permissions, error) {
+			return nil, nil
+		},
+	}, testExecHandler(run))
+}
+
+// testExecHandler answers exec requests on session channels using run
+func testExecHandler(run func(cmd string) (string, uint32)) func(ssh.NewChannel) {
+	return func(newCh ssh.NewChannel) {
+		if newCh.ChannelType() != "session" {
+			newCh.Reject(ssh.UnknownChannelType, "session only")
+			return
+		}
+		ch, reqs, err := newCh.Accept()
+		if err != nil {
+			return
+		}
+		defer ch.Close()
+
+		for req := range reqs {
+			if req.Type != "exec" {
+				_ = req.Reply(false, nil)
+				continue
+			}
+			var exec struct{ Command string }
+			_ = ssh.Unmarshal(req.Payload, &exec)
+			_ = req.Reply(true, nil)
+
+			stdout, status := run(exec.Command)
+			_, _ = ch.Write([]byte(stdout))
+			_, _ = ch.SendRequest("exit-status", false, ssh.Marshal(struct{ Status uint32 }{status}))
+			return
+		}
+	}
+}
+
+func TestSSHSessionStartupCommands(t *testing.T) {
+	tmpDir := t.TempDir()
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+
+	port := startTestExecServer(t, func(cmd string) (string, uint32) {
+		switch {
+		case strings.HasSuffix(cmd, "cd /tmp && pwd"):
+			return "/tmp\n", 0
+		case strings.HasSuffix(cmd, "pwd"):
+			return "/home/testuser\n", 0
+		case strings.HasSuffix(cmd, "false"):
+			return "", 1
+		case strings.HasSuffix(cmd, `export PATH=$PATH:/custom/bin && printf '%s\0' "$PATH"`):
+			return "/usr/bin:/custom/bin\x00", 0
+		}
+		return "", 0
+	})
+
+	newSession := func(startup []string) *SSHSession {
+		return NewSSHSession(SSHConfig{
+			Name:                  "test",
+			Host:                  "127.0.0.1",
+			Port:                  port,
+			User:                  "testuser",
+			Password:              "secret",
+			InsecureIgnoreHostKey: true,
+			StartupCommands:       startup,
+		})
+	}
+
+	// cd in a startup command is tracked as the session cwd
+	session := newSession([]string{"cd /tmp"})
+	if err := session.Connect(); err != nil {
+		t.Fatalf("Connect failed: %v", err)
+	}
+	if session.GetCWD() != "/tmp" {
+		t.Errorf("expected cwd /tmp after startup commands, got %q", session.GetCWD())
+	}
+	session.Disconnect()
+
+	// An export in a startup command is kept in the session env, as expanded remotely
+	session = newSession([]string{"export PATH=$PATH:/custom/bin"})
+	if err := session.Connect(); err != nil {
+		t.Fatalf("Connect failed: %v", err)
+	}
+	if got := session.GetEnv()["PATH"]; got != "/usr/bin:/custom/bin" {
+		t.Errorf("expected PATH from the startup export, got %q", got)
+	}
+	session.Disconnect()
+
+	// A failing startup command fails the connection
+	session = newSession([]string{"cd /tmp", "false", "echo unreachable"})
+	err := session.Connect()
+	sessionErr, ok := err.(*Error)
+	if !ok {
+		t.Fatalf("expected *Error, got %T: %v", err, err)
+	}
+	if !strings.Contains(sessionErr.Message, `"false"`) {
+		t.Errorf("expected error to name the failing command, got %q", sessionErr.Message)
+	}
+	if session.IsConnected() {
+		t.Error("expected session to be disconnected after a failed startup command")
+	}
+}
+
+// startTestSFTPServer runs a test SSH server accepting any password that
+// serves the local filesystem over the sftp subsystem
+func startTestSFTPServer(t *testing.T) int {
+	t.Helper()
+	return startTestSSHServerWithHandler(t, &ssh.ServerConfig{
+		PasswordCallback: func(conn ssh.ConnMetadata, password []byte) (*ssh.Permissions, error) {
+			return nil, nil
+		},
+	}, func(newCh ssh.NewChannel) {
+		if newCh.ChannelType() != "session" {
+			newCh.Reject(ssh.UnknownChannelType, "session only")
+			return
+		}
+		ch, reqs, err := newCh.Accept()
+		if err != nil {
+			return
+		}
+		defer ch.Close()
+
+		for req := range reqs {
+			var subsystem struct{ Name string }
+			if req.Type == "subsystem" && ssh.Unmarshal(req.Payload, &subsystem) == nil && subsystem.Name == "sftp" {
+				_ = req.Reply(true, nil)
+				server, err := sftp.NewServer(ch)
+				if err != nil {
+					return
+				}
+				_ = server.Serve()
+				server.Close()
+				return
+			}
+			_ = req.Reply(false, nil)
+		}
+	})
+}
+
+func TestSSHSessionFileTransferIsByteExact(t *testing.T) {
+	tmpDir := t.TempDir()
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+
+	port := startTestSFTPServer(t)
+	newSession := func(name string) *SSHSession {
+		session := NewSSHSession(SSHConfig{
+			Name:                  name,
+			Host:                  "127.0.0.1",
+			Port:                  port,
+			User:                  "testuser",
+			Password:              "secret",
+			InsecureIgnoreHostKey: true,
+		})
+		if err := session.Connect(); err != nil {
+			t.Fatalf("Connect failed: %v", err)
+		}
+		t.Cleanup(func() { session.Disconnect() })
+		return session
+	}
+	src := newSession("src")
+	dst := newSession("dst")
+
+	// A PNG header followed by several MB of binary data, including bytes
+	// that would break text-based transfers
+	data := append([]byte("\x89PNG\r\n\x1a\n\x00THOP_EOF\n"), make([]byte, 5<<20)...)
+	if _, err := rand.Read(data[20:]); err != nil {
+		t.Fatal(err)
+	}
+	localPath := filepath.Join(tmpDir, "image.png")
+	if err := os.WriteFile(localPath, data, 0644); err != nil {
+		t.Fatal(err)
+	}
+
+	uploaded := filepath.Join(tmpDir, "uploaded.png")
+	if err := src.UploadFile(localPath, uploaded); err != nil {
+		t.Fatalf("UploadFile failed: %v", err)
+	}
+
+	copied := filepath.Join(tmpDir, "copied.png")
+	n, err := src.CopyFileTo(dst, uploaded, copied)
+	if err != nil {
+		t.Fatalf("CopyFileTo failed: %v", err)
+	}
+	if n != int64(len(data)) {
+		t.Errorf("expected %d bytes copied, got %d", len(data), n)
+	}
+
+	downloaded := filepath.Join(tmpDir, "downloaded.png")
+	if err := dst.DownloadFile(copied, downloaded); err != nil {
+		t.Fatalf("DownloadFile failed: %v", err)
+	}
+
+	got, err := os.ReadFile(downloaded)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if !bytes.Equal(got, data) {
+		t.Error("file did not round-trip byte-identically")
+	}
+}
+
+func TestSSHSessionReadWriteFileRelativeToCWD(t *testing.T) {
+	tmpDir := t.TempDir()
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+
+	port := startTestSFTPServer(t)
+	session := NewSSHSession(SSHConfig{
+		Name:                  "test",
+		Host:                  "127.0.0.1",
+		Port:                  port,
+		User:                  "testuser",
+		Password:              "secret",
+		InsecureIgnoreHostKey: true,
+	})
+	if err := session.Connect(); err != nil {
+		t.Fatalf("Connect failed: %v", err)
+	}
+	defer session.Disconnect()
+	_ = session.SetCWD(tmpDir)
+
+	data := []byte("\x00\xff\r\n\x1b[0mbinary\x00")
+	target := FilePath(session, "blob.bin")
+	if err := session.WriteFile(target, data, 0); err != nil {
+		t.Fatalf("WriteFile failed: %v", err)
+	}
+
+	info, err := os.Stat(filepath.Join(tmpDir, "blob.bin"))
+	if err != nil {
+		t.Fatalf("expected file in the session cwd: %v", err)
+	}
+	if info.Mode().Perm()&0002 != 0 {
+		t.Errorf("new file should not be world-writable, got %o", info.Mode().Perm())
+	}
+
+	got, err := session.ReadFile(FilePath(session, "blob.bin"))
+	if err != nil {
+		t.Fatalf("ReadFile failed: %v", err)
+	}
+	if !bytes.Equal(got, data) {
+		t.Errorf("expected %q, got %q", data, got)
+	}
+}
+
+func TestSSHSessionReplaceFile(t *testing.T) {
+	tmpDir := t.TempDir()
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+
+	port := startTestSFTPServer(t)
+	session := NewSSHSession(SSHConfig{
+		Name:                  "test",
+		Host:                  "127.0.0.1",
+		Port:                  port,
+		User:                  "testuser",
+		Password:              "secret",
+		InsecureIgnoreHostKey: true,
+	})
+	if err := session.Connect(); err != nil {
+		t.Fatalf("Connect failed: %v", err)
+	}
+	defer session.Disconnect()
+
+	target := filepath.Join(tmpDir, "app.conf")
+	if err := os.WriteFile(target, []byte("port = 80\n"), 0640); err != nil {
+		t.Fatal(err)
+	}
+	if err := session.ReplaceFile(target, []byte("port = 8080\n"), 0640); err != nil {
+		t.Fatalf("ReplaceFile failed: %v", err)
+	}
+
+	got, err := os.ReadFile(target)
+	if err != nil || string(got) != "port = 8080\n" {
+		t.Errorf("expected the new content, got %q, %v", got, err)
+	}
+	if info, err := os.Stat(target); err != nil || info.Mode().Perm() != 0640 {
+		t.Errorf("expected mode 0640, got %v, %v", info, err)
+	}
+	entries, err := os.ReadDir(tmpDir)
+	if err != nil {
+		t.Fatal(err)
+	}
+	for _, entry := range entries {
+		if strings.Contains(entry.Name(), ".thop-") {
+			t.Errorf("temp file %s left behind", entry.Name())
+		}
+	}
+
+	// The manager routes SSH sessions to the SFTP replace
+	mgr, _ := createTestManager(t)
+	mgr.sessions["remote"] = session
+	resolved, err := mgr.ReplaceFile("remote", target, []byte("port = 9090\n"), 0)
+	if err != nil {
+		t.Fatalf("Manager.ReplaceFile failed: %v", err)
+	}
+	if resolved != target {
+		t.Errorf("resolved path = %q, want %q", resolved, target)
+	}
+	if got, err := os.ReadFile(target); err != nil || string(got) != "port = 9090\n" {
+		t.Errorf("expected the new content, got %q, %v", got, err)
+	}
+}
+
+func TestSSHSessionCommandTimeout(t *testing.T) {
+	tmpDir := t.TempDir()
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+
+	// Commands other than pwd never finish until the test ends
+	release := make(chan struct{})
+	port := startTestExecServer(t, func(cmd string) (string, uint32) {
+		if strings.HasSuffix(cmd, "pwd") {
+			return "/home/testuser\n", 0
+		}
+		<-release
+		return "", 0
+	})
+	t.Cleanup(func() { close(release) })
+
+	session := NewSSHSession(SSHConfig{
+		Name:                  "test",
+		Host:                  "127.0.0.1",
+		Port:                  port,
+		User:                  "testuser",
+		Password:              "secret",
+		InsecureIgnoreHostKey: true,
+		Timeout:               200 * time.Millisecond,
+	})
+	if err := session.Connect(); err != nil {
+		t.Fatalf("Connect failed: %v", err)
+	}
+	defer session.Disconnect()
+
+	expectTimeout := func(t *testing.T, err error) {
+		t.Helper()
+		sessionErr, ok := err.(*Error)
+		if !ok {
+			t.Fatalf("expected *Error, got %T: %v", err, err)
+		}
+		if sessionErr.Code != ErrCommandTimeout {
+			t.Errorf("expected code %s, got %s", ErrCommandTimeout, sessionErr.Code)
+		}
+	}
+
+	// The configured command timeout applies
+	start := time.Now()
+	_, err := session.Execute("sleep 60")
+	expectTimeout(t, err)
+	if elapsed := time.Since(start); elapsed > 5*time.Second {
+		t.Errorf("expected timeout after ~200ms, took %s", elapsed)
+	}
+
+	// A shorter per-call deadline overrides it
+	session.commandTimeout = time.Minute
+	ctx, cancel := context.WithTimeout(context.Background(), 200*time.Millisecond)
+	defer cancel()
+	start = time.Now()
+	_, err = session.ExecuteWithContext(ctx, "sleep 60")
+	expectTimeout(t, err)
+	if elapsed := time.Since(start); elapsed > 5*time.Second {
+		t.Errorf("expected per-call timeout after ~200ms, took %s", elapsed)
+	}
+}
+
+func TestManagerExecuteOnReconnectsDroppedSession(t *testing.T) {
+	mgr, tmpDir := createTestManager(t)
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+
+	// Reject the first reconnect so the second attempt is the one that succeeds
+	var mu sync.Mutex
+	logins := 0
+	port := startTestSSHServerWithHandler(t, &ssh.ServerConfig{
+		PasswordCallback: func(conn ssh.ConnMetadata, password []byte) (*ssh.Permissions, error) {
+			mu.Lock()
+			defer mu.Unlock()
+			logins++
+			if logins == 2 {
+				return nil, fmt.Errorf("transient failure")
+			}
+			return nil, nil
+		},
+	}, testExecHandler(func(cmd string) (string, uint32) {
+		if strings.HasSuffix(cmd, "pwd") {
+			return "/home/testuser\n", 0
+		}
+		return "hello\n", 0
+	}))
+
+	session := NewSSHSession(SSHConfig{
+		Name:                  "flaky",
+		Host:                  "127.0.0.1",
+		Port:                  port,
+		User:                  "testuser",
+		Password:              "secret",
+		InsecureIgnoreHostKey: true,
+	})
+	if err := session.Connect(); err != nil {
+		t.Fatalf("Connect failed: %v", err)
+	}
+	defer session.Disconnect()
+
+	mgr.sessions["flaky"] = session
+	mgr.reconnectBackoff = 10 * time.Millisecond
+
+	// Drop the transport underneath the session without telling it
+	session.client.Close()
+
+	result, err := mgr.ExecuteOn("flaky", "echo hello")
+	if err != nil {
+		t.Fatalf("expected command to succeed after reconnect, got %v", err)
+	}
+	if result.Stdout != "hello\n" {
+		t.Errorf("expected stdout 'hello\\n', got %q", result.Stdout)
+	}
+	if !session.IsConnected() {
+		t.Error("expected session to be connected after reconnect")
+	}
+
+	mu.Lock()
+	defer mu.Unlock()
+	if logins != 3 {
+		t.Errorf("expected initial login plus two reconnect attempts, got %d logins", logins)
+	}
+}
+
+func TestSSHSessionConnectStartsKeepalive(t *testing.T) {
+	tmpDir := t.TempDir()
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+
+	keepalives := make(chan struct{}, 16)
+	port := startTestSSHServerWithRequests(t, &ssh.ServerConfig{
+		PasswordCallback: func(conn ssh.ConnMetadata, password []byte) (*ssh.Permissions, error) {
+			return nil, nil
+		},
+	}, testExecHandler(func(cmd string) (string, uint32) {
+		return "/home/testuser\n", 0
+	}), func(reqs <-chan *ssh.Request) {
+		for req := range reqs {
+			if req.Type == "keepalive@openssh.com" {
+				select {
+				case keepalives <- struct{}{}:
+				default:
+				}
+			}
+			if req.WantReply {
+				_ = req.Reply(true, nil)
+			}
+		}
+	})
+
+	session := NewSSHSession(SSHConfig{
+		Name:                  "test",
+		Host:                  "127.0.0.1",
+		Port:                  port,
+		User:                  "testuser",
+		Password:              "secret",
+		InsecureIgnoreHostKey: true,
+		KeepaliveInterval:     20 * time.Millisecond,
+	})
+	if err := session.Connect(); err != nil {
+		t.Fatalf("Connect failed: %v", err)
+	}
+	defer session.Disconnect()
+
+	if session.keepaliveStop == nil {
+		t.Fatal("expected Connect to start the keepalive loop")
+	}
+	for i := 0; i < 2; i++ {
+		select {
+		case <-keepalives:
+		case <-time.After(2 * time.Second):
+			t.Fatalf("expected keepalive request %d within 2s", i+1)
+		}
+	}
+
+	if err := session.Disconnect(); err != nil {
+		t.Fatalf("Disconnect failed: %v", err)
+	}
+	if session.keepaliveStop != nil {
+		t.Error("expected Disconnect to stop the keepalive loop")
+	}
+}
+
+func TestSSHSessionExecuteVerbatimHasNoPrefix(t *testing.T) {
+	tmpDir := t.TempDir()
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+
+	var mu sync.Mutex
+	var executed []string
+	port := startTestExecServer(t, func(cmd string) (string, uint32) {
+		mu.Lock()
+		executed = append(executed, cmd)
+		mu.Unlock()
+		if strings.HasSuffix(cmd, "pwd") {
+			return "/srv\n", 0
+		}
+		return "ok\n", 0
+	})
+
+	session := NewSSHSession(SSHConfig{
+		Name:                  "test",
+		Host:                  "127.0.0.1",
+		Port:                  port,
+		User:                  "testuser",
+		Password:              "secret",
+		InsecureIgnoreHostKey: true,
+	})
+	if err := session.Connect(); err != nil {
+		t.Fatalf("Connect failed: %v", err)
+	}
+	defer session.Disconnect()
+	session.SetEnv("FOO", "bar")
+
+	// A normal command is wrapped with exports and a cd to the tracked cwd
+	if _, err := session.Execute("echo hi"); err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	result, err := session.ExecuteVerbatim(context.Background(), "echo  hi")
+	if err != nil {
+		t.Fatalf("ExecuteVerbatim failed: %v", err)
+	}
+	if result.Stdout != "ok\n" || result.ExitCode != 0 {
+		t.Errorf("unexpected result: %+v", result)
+	}
+
+	mu.Lock()
+	defer mu.Unlock()
+	wrapped := executed[len(executed)-2]
+	if !strings.Contains(wrapped, "export FOO=") || !strings.Contains(wrapped, "cd /srv") {
+		t.Errorf("expected normal execution to be wrapped, got %q", wrapped)
+	}
+	if raw := executed[len(executed)-1]; raw != "echo  hi" {
+		t.Errorf("expected raw command to be sent verbatim, got %q", raw)
+	}
+}
+
+func TestSplitHostPort(t *testing.T) {
+	tests := []struct {
+		host     string
+		port     int
+		wantHost string
+		wantPort int
+	}{
+		{"example.com", 22, "example.com", 22},
+		{"example.com:2222", 22, "example.com", 2222},
+		{"::1", 22, "::1", 22},
+		{"fe80::1", 2200, "fe80::1", 2200},
+		{"[::1]", 22, "::1", 22},
+		{"[::1]:2222", 22, "::1", 2222},
+		{"[fe80::1]:22", 0, "fe80::1", 22},
+	}
+
+	for _, tt := range tests {
+		host, port := splitHostPort(tt.host, tt.port)
+		if host != tt.wantHost || port != tt.wantPort {
+			t.Errorf("splitHostPort(%q, %d) = (%q, %d), want (%q, %d)", tt.host, tt.port, host, port, tt.wantHost, tt.wantPort)
+		}
+	}
+}
+
+func TestParseJumpHostIPv6(t *testing.T) {
+	session := NewSSHSession(SSHConfig{Name: "test", Host: "example.com", User: "me"})
+
+	user, host, port := session.parseJumpHost("admin@[::1]:2222")
+	if user != "admin" || host != "::1" || port != 2222 {
+		t.Errorf("expected admin@::1:2222, got %s@%s:%d", user, host, port)
+	}
+
+	user, host, port = session.parseJumpHost("fe80::1")
+	if user != "me" || host != "fe80::1" || port != 22 {
+		t.Errorf("expected me@fe80::1:22, got %s@%s:%d", user, host, port)
+	}
+}
+
+func TestSSHSessionConnectsToIPv6Literal(t *testing.T) {
+	tmpDir := t.TempDir()
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+
+	listener, err := net.Listen("tcp", "[::1]:0")
+	if err != nil {
+		t.Skipf("IPv6 loopback unavailable: %v", err)
+	}
+	defer listener.Close()
+	port := listener.Addr().(*net.TCPAddr).Port
+
+	// Not an SSH server: it only records that a connection arrived
+	accepted := make(chan struct{}, 8)
+	go func() {
+		for {
+			conn, err := listener.Accept()
+			if err != nil {
+				return
+			}
+			accepted <- struct{}{}
+			conn.Close()
+		}
+	}()
+
+	tests := []struct {
+		host string
+		port int
+	}{
+		{"::1", port},
+		{"[::1]", port},
+		{fmt.Sprintf("[::1]:%d", port), 22},
+	}
+
+	for _, tt := range tests {
+		session := NewSSHSession(SSHConfig{
+			Name:                  "v6",
+			Host:                  tt.host,
+			Port:                  tt.port,
+			User:                  "testuser",
+			Password:              "secret",
+			InsecureIgnoreHostKey: true,
+			ConnectTimeout:        2 * time.Second,
+		})
+		if session.Host() != "::1" || session.Port() != port {
+			t.Errorf("host %q: expected ::1 port %d, got %s port %d", tt.host, port, session.Host(), session.Port())
+		}
+
+		if err := session.Connect(); err == nil {
+			session.Disconnect()
+			t.Fatalf("host %q: expected handshake to fail against a non-SSH listener", tt.host)
+		}
+		select {
+		case <-accepted:
+		case <-time.After(2 * time.Second):
+			t.Errorf("host %q: expected a connection attempt to [::1]:%d", tt.host, port)
+		}
+	}
+}
+
+func TestSSHSessionFetchHostKeyFingerprint(t *testing.T) {
+	tmpDir := t.TempDir()
+	t.Setenv("HOME", tmpDir)
+
+	port := startTestSSHServer(t, &ssh.ServerConfig{NoClientAuth: true})
+	session := NewSSHSession(SSHConfig{Name: "test", Host: "127.0.0.1", Port: port, User: "testuser"})
+
+	key, status, err := session.ProbeHostKey()
+	if err != nil {
+		t.Fatalf("ProbeHostKey failed: %v", err)
+	}
+	if status != HostKeyUnknown {
+		t.Errorf("expected unknown status without known_hosts, got %v", status)
+	}
+
+	keyType, fingerprint, err := session.FetchHostKey()
+	if err != nil {
+		t.Fatalf("FetchHostKey failed: %v", err)
+	}
+	if keyType != ssh.KeyAlgoED25519 {
+		t.Errorf("expected key type %s, got %s", ssh.KeyAlgoED25519, keyType)
+	}
+
+	// OpenSSH format: "SHA256:" followed by unpadded base64 of the key digest
+	sum := sha256.Sum256(key.Marshal())
+	expected := "SHA256:" + base64.RawStdEncoding.EncodeToString(sum[:])
+	if fingerprint != expected {
+		t.Errorf("expected fingerprint %s, got %s", expected, fingerprint)
+	}
+	if strings.HasSuffix(fingerprint, "=") {
+		t.Errorf("fingerprint should not be padded: %s", fingerprint)
+	}
+}
+
+func TestSSHSessionTrustHostKey(t *testing.T) {
+	tmpDir := t.TempDir()
+	t.Setenv("HOME", tmpDir)
+	knownHosts := filepath.Join(tmpDir, ".ssh", "known_hosts")
+
+	port := startTestSSHServer(t, &ssh.ServerConfig{NoClientAuth: true})
+	session := NewSSHSession(SSHConfig{Name: "test", Host: "127.0.0.1", Port: port, User: "testuser"})
+
+	key, _, err := session.ProbeHostKey()
+	if err != nil {
+		t.Fatalf("ProbeHostKey failed: %v", err)
+	}
+	if err := session.TrustHostKey(key); err != nil {
+		t.Fatalf("TrustHostKey failed: %v", err)
+	}
+	// Trusting again is a no-op rather than a duplicate entry
+	if err := session.TrustHostKey(key); err != nil {
+		t.Fatalf("second TrustHostKey failed: %v", err)
+	}
+
+	data, err := os.ReadFile(knownHosts)
+	if err != nil {
+		t.Fatalf("failed to read known_hosts: %v", err)
+	}
+	lines := strings.Split(strings.TrimSpace(string(data)), "\n")
+	if len(lines) != 1 || !strings.HasPrefix(lines[0], fmt.Sprintf("[127.0.0.1]:%d ", port)) {
+		t.Fatalf("expected a single [host]:port entry, got:\n%s", data)
+	}
+	if _, status, _ := session.ProbeHostKey(); status != HostKeyTrusted {
+		t.Errorf("expected trusted status after TrustHostKey, got %v", status)
+	}
+
+	// Replace the entry with a different key: trusting must be refused
+	otherPub, _, err := ed25519.GenerateKey(rand.Reader)
+	if err != nil {
+		t.Fatalf("failed to generate key: %v", err)
+	}
+	otherKey, err := ssh.NewPublicKey(otherPub)
+	if err != nil {
+		t.Fatalf("failed to convert key: %v", err)
+	}
+	entry := knownhosts.Line([]string{fmt.Sprintf("[127.0.0.1]:%d", port)}, otherKey)
+	if err := os.WriteFile(knownHosts, []byte(entry+"\n"), 0600); err != nil {
+		t.Fatalf("failed to write known_hosts: %v", err)
+	}
+
+	if _, status, _ := session.ProbeHostKey(); status != HostKeyMismatch {
+		t.Errorf("expected mismatch status, got %v", status)
+	}
+	err = session.TrustHostKey(key)
+	sessionErr, ok := err.(*Error)
+	if !ok || sessionErr.Code != ErrHostKeyChanged {
+		t.Fatalf("expected ErrHostKeyChanged, got %v", err)
+	}
+	if !strings.Contains(sessionErr.Suggestion, "ssh-keygen -R") {
+		t.Errorf("expected suggestion to explain removing the old entry, got %q", sessionErr.Suggestion)
+	}
+
+	data, _ = os.ReadFile(knownHosts)
+	if string(data) != entry+"\n" {
+		t.Errorf("known_hosts should be unchanged after a refused trust, got:\n%s", data)
+	}
+}
+
+func TestParseRemoteShell(t *testing.T) {
+	tests := []struct {
+		output string
+		shell  string
+		posix  bool
+	}{
+		{"/bin/bash\n", "bash", true},
+		{"/usr/bin/fish\n", "fish", false},
+		{"/bin/tcsh", "tcsh", false},
+		{"-zsh\n", "zsh", true},
+		{"Welcome!\n/bin/sh\n", "sh", true},
+		{"\n", "", true},
+	}
+
+	for _, tt := range tests {
+		shell := parseRemoteShell(tt.output)
+		if shell != tt.shell {
+			t.Errorf("parseRemoteShell(%q) = %q, want %q", tt.output, shell, tt.shell)
+		}
+		if got := isPOSIXShell(shell); got != tt.posix {
+			t.Errorf("isPOSIXShell(%q) = %v, want %v", shell, got, tt.posix)
+		}
+	}
+}
+
+func TestSSHSessionWrapsCommandsForFish(t *testing.T) {
+	tmpDir := t.TempDir()
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+
+	var mu sync.Mutex
+	var last string
+	port := startTestExecServer(t, func(cmd string) (string, uint32) {
+		mu.Lock()
+		last = cmd
+		mu.Unlock()
+		if cmd == "echo $SHELL" {
+			return "/usr/bin/fish\n", 0
+		}
+		return "/home/testuser\n", 0
+	})
+
+	session := NewSSHSession(SSHConfig{
+		Name:                  "test",
+		Host:                  "127.0.0.1",
+		Port:                  port,
+		User:                  "testuser",
+		Password:              "secret",
+		InsecureIgnoreHostKey: true,
+	})
+	if err := session.Connect(); err != nil {
+		t.Fatalf("Connect failed: %v", err)
+	}
+	defer session.Disconnect()
+
+	if session.RemoteShell() != "fish" {
+		t.Errorf("expected detected shell 'fish', got %q", session.RemoteShell())
+	}
+	if _, err := session.Execute("ls"); err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+
+	mu.Lock()
+	defer mu.Unlock()
+	if !strings.HasPrefix(last, "sh -c '") || !strings.Contains(last, "cd /home/testuser && ls") {
+		t.Errorf("expected command wrapped in sh -c for fish, got %q", last)
+	}
+}
+
+func TestSSHSessionWrapForShell(t *testing.T) {
+	tests := []struct {
+		name        string
+		shell       string
+		remoteShell string
+		want        string
+	}{
+		{"POSIX login shell", "", "bash", "cd /srv && ls"},
+		{"non-POSIX login shell", "", "csh", `sh -c 'cd /srv && ls'`},
+		{"configured shell", "/bin/bash", "csh", `/bin/bash -c 'cd /srv && ls'`},
+		{"configured shell on POSIX login shell", "/bin/zsh", "bash", `/bin/zsh -c 'cd /srv && ls'`},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			session := NewSSHSession(SSHConfig{Name: "test", Host: "example.com", Shell: tt.shell})
+			session.remoteShell = tt.remoteShell
+			if got := session.wrapForShell("cd /srv && ls"); got != tt.want {
+				t.Errorf("wrapForShell() = %q, want %q", got, tt.want)
+			}
+		})
+	}
+}
+
+func TestManagerConnectRestoresEnvAndCWD(t *testing.T) {
+	mgr, tmpDir := createTestManager(t)
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+
+	var mu sync.Mutex
+	var last string
+	port := startTestExecServer(t, func(cmd string) (string, uint32) {
+		mu.Lock()
+		last = cmd
+		mu.Unlock()
+		switch {
+		case strings.HasSuffix(cmd, "cd /srv && pwd"):
+			return "/srv\n", 0
+		case strings.HasSuffix(cmd, "pwd"):
+			return "/home/testuser\n", 0
+		}
+		return "", 0
+	})
+
+	newSession := func() *SSHSession {
+		return NewSSHSession(SSHConfig{
+			Name:                  "remote",
+			Host:                  "127.0.0.1",
+			Port:                  port,
+			User:                  "testuser",
+			Password:              "secret",
+			InsecureIgnoreHostKey: true,
+		})
+	}
+
+	mgr.sessions["remote"] = newSession()
+	if err := mgr.Connect("remote"); err != nil {
+		t.Fatalf("Connect failed: %v", err)
+	}
+	if err := mgr.SetActiveSession("remote"); err != nil {
+		t.Fatalf("SetActiveSession failed: %v", err)
+	}
+	if err := mgr.SetSessionEnv("FOO", "bar"); err != nil {
+		t.Fatalf("SetSessionEnv failed: %v", err)
+	}
+	if _, err := mgr.Execute("cd /srv"); err != nil {
+		t.Fatalf("cd failed: %v", err)
+	}
+	if err := mgr.Disconnect("remote"); err != nil {
+		t.Fatalf("Disconnect failed: %v", err)
+	}
+
+	// A fresh session object has no in-memory env or cwd, as after a restart
+	session := newSession()
+	mgr.sessions["remote"] = session
+	if err := mgr.Connect("remote"); err != nil {
+		t.Fatalf("reconnect failed: %v", err)
+	}
+	defer session.Disconnect()
+
+	if got := session.GetEnv()["FOO"]; got != "bar" {
+		t.Errorf("expected FOO=bar to be restored, got %q", got)
+	}
+	if session.GetCWD() != "/srv" {
+		t.Errorf("expected cwd /srv to be restored, got %q", session.GetCWD())
+	}
+
+	if _, err := mgr.Execute("ls"); err != nil {
+		t.Fatalf("Execute failed: %v", err)
+	}
+	mu.Lock()
+	defer mu.Unlock()
+	if !strings.Contains(last, "export FOO='bar'") || !strings.Contains(last, "cd /srv && ls") {
+		t.Errorf("expected restored env and cwd in command, got %q", last)
+	}
+}
+
+func TestManagerConnectionLimit(t *testing.T) {
+	mgr, tmpDir := createTestManager(t)
+	t.Setenv("HOME", tmpDir)
+	t.Setenv("SSH_AUTH_SOCK", "")
+	mgr.config.Settings.MaxConnections = 10
+
+	port := startTestExecServer(t, func(cmd string) (string, uint32) {
+		return "/home/testuser\n", 0
+	})
+
+	names := make([]string, 11)
+	for i := range names {
+		names[i] = fmt.Sprintf("host%02d", i+1)
+		mgr.sessions[names[i]] = NewSSHSession(SSHConfig{
+			Name:                  names[i],
+			Host:                  "127.0.0.1",
+			Port:                  port,
+			User:                  "testuser",
+			Password:              "secret",
+			InsecureIgnoreHostKey: true,
+		})
+	}
+	defer func() {
+		for _, name := range names {
+			_ = mgr.Disconnect(name)
+		}
+	}()
+
+	for _, name := range names[:10] {
+		if err := mgr.Connect(name); err != nil {
+			t.Fatalf("connect %s within the limit failed: %v", name, err)
+		}
+	}
+
+	err := mgr.Connect(names[10])
+	sessionErr, ok := err.(*Error)
+	if !ok || sessionErr.Code != ErrConnectionLimit {
+		t.Fatalf("expected ErrConnectionLimit for the 11th connect, got %v", err)
+	}
+	if !strings.Contains(sessionErr.Suggestion, "host01") {
+		t.Errorf("expected suggestion to name connected sessions, got %q", sessionErr.Suggestion)
+	}
+
+	// Reconnecting an already connected session does not count against the limit
+	if err := mgr.Connect(names[0]); err != nil {
+		t.Errorf("expected connect of an already connected session to succeed, got %v", err)
+	}
+
+	if err := mgr.Disconnect(names[0]); err != nil {
+		t.Fatalf("Disconnect failed: %v", err)
+	}
+	if err := mgr.Connect(names[10]); err != nil {
+		t.Errorf("expected connect to succeed after freeing a slot, got %v", err)
+	}
+}
+
+func TestManagerTailSSHRelativeToCWD(t *testing.T) {
+	var mu sync.Mutex
+	var commands []string
+	port := startTestExecServer(t, func(cmd string) (string, uint32) {
+		mu.Lock()
+		commands = append(commands, cmd)
+		mu.Unlock()
+		if strings.HasSuffix(cmd, "pwd") {
+			return "/home/testuser\n", 0
+		}
+		return "last line\n", 0
+	})
+
+	mgr, _ := createTestManager(t)
+	session := NewSSHSession(SSHConfig{
+		Name:                  "remote",
+		Host:                  "127.0.0.1",
+		Port:                  port,
+		User:                  "testuser",
+		Password:              "secret",
+		InsecureIgnoreHostKey: true,
+	})
+	mgr.sessions["remote"] = session
+	if err := mgr.Connect("remote"); err != nil {
+		t.Fatalf("Connect failed: %v", err)
+	}
+	defer session.Disconnect()
+	// The shell command runs after cd into a cwd other than home
+	_ = session.SetCWD("/var/log")
+
+	tests := []struct {
+		path string
+		want string
+	}{
+		{"~/app.log", "cd /var/log && tail -n 5 -- ~/'app.log'"},
+		{"app.log", "cd /var/log && tail -n 5 -- 'app.log'"},
+		{"/srv/app.log", "cd /var/log && tail -n 5 -- '/srv/app.log'"},
+	}
+	for _, tt := range tests {
+		data, _, err := mgr.Tail("remote", tt.path, 5)
+		if err != nil {
+			t.Fatalf("Tail(%q) failed: %v", tt.path, err)
+		}
+		if string(data) != "last line\n" {
+			t.Errorf("Tail(%q) = %q", tt.path, data)
+		}
+		mu.Lock()
+		last := commands[len(commands)-1]
+		mu.Unlock()
+		if !strings.HasSuffix(last, tt.want) {
+			t.Errorf("Tail(%q) ran %q, want suffix %q", tt.path, last, tt.want)
+		}
+	}
+}
@@ -0,0 +1,190 @@
+package session
+
+import (
+	"context"
+	"fmt"
+	"io"
+	"os"
+	"os/exec"
+	"strings"
+	"time"
+
+	"github.com/scottgl9/thop/internal/logger"
+	"golang.org/x/crypto/ssh"
+)
+
+// StreamExecutor is implemented by sessions that can run a command while
+// passing its output through as it is produced, for commands such as tail -f
+// that never finish on their own. No command timeout applies: the command runs
+// until it exits or ctx is canceled, which reports exit code 130.
+type StreamExecutor interface {
+	ExecuteStream(ctx context.Context, cmd string, stdout, stderr io.Writer) (int, error)
+}
+
+// ExecuteStream runs cmdStr in the session's working directory and
+// environment, writing its output to stdout and stderr as it arrives
+func (s *LocalSession) ExecuteStream(ctx context.Context, cmdStr string, stdout, stderr io.Writer) (int, error) {
+	if s.persistent != nil && s.runAs == "" && s.persistent.mu.TryLock() {
+		defer s.persistent.mu.Unlock()
+		exitCode, err := s.runPersistent(ctx, cmdStr, stdout, stderr)
+		if ctx.Err() != nil {
+			return 130, nil
+		}
+		return exitCode, err
+	}
+
+	cmd := exec.CommandContext(ctx, s.shell, "-c", s.runAsWrap(cmdStr))
+	cmd.Dir = s.cwd
+	killGroupOnCancel(cmd)
+	cmd.WaitDelay = time.Second
+	cmd.Env = append(os.Environ(), localeEnv(s.locale)...)
+	for k, v := range s.env {
+		cmd.Env = append(cmd.Env, k+"="+v)
+	}
+	cmd.Stdout = stdout
+	cmd.Stderr = stderr
+
+	err := cmd.Run()
+	if ctx.Err() != nil {
+		return 130, nil
+	}
+	if err != nil {
+		if exitErr, ok := err.(*exec.ExitError); ok {
+			return exitErr.ExitCode(), nil
+		}
+		return 1, err
+	}
+	return 0, nil
+}
+
+// ExecuteStream runs cmdStr on a new SSH channel in the session's working
+// directory and environment, writing its output to stdout and stderr as it
+// arrives
+func (s *SSHSession) ExecuteStream(ctx context.Context, cmdStr string, stdout, stderr io.Writer) (int, error) {
+	if !s.IsConnected() {
+		return 1, &Error{
+			Code:       ErrSessionDisconnected,
+			Message:    fmt.Sprintf("Session %s is not connected", s.name),
+			Session:    s.name,
+			Retryable:  true,
+			Suggestion: fmt.Sprintf("Use /connect %s to reconnect", s.name),
+		}
+	}
+
+	session, err := s.client.NewSession()
+	if err != nil {
+		return 1, fmt.Errorf("failed to create session: %w", err)
+	}
+	defer session.Close()
+
+	var prefix strings.Builder
+	if s.locale != "" {
+		prefix.WriteString(fmt.Sprintf("export LC_ALL=%[1]s LANG=%[1]s; ", ShellQuote(s.locale)))
+	}
+	for k, v := range s.env {
+		prefix.WriteString(fmt.Sprintf("export %s=%s; ", k, ShellQuote(v)))
+	}
+	if s.cwd != "" && s.cwd != "~" {
+		prefix.WriteString(fmt.Sprintf("cd %s && ", s.cwd))
+	}
+
+	session.Stdout = stdout
+	session.Stderr = stderr
+
+	done := make(chan error, 1)
+	go func() {
+		done <- session.Run(s.wrapForShell(runAsCommand(s.runAs, prefix.String()+cmdStr)))
+	}()
+
+	select {
+	case runErr := <-done:
+		if runErr == nil {
+			return 0, nil
+		}
+		if exitErr, ok := runErr.(*ssh.ExitError); ok {
+			return exitErr.ExitStatus(), nil
+		}
+		return 1, runErr
+	case <-ctx.Done():
+		_ = session.Signal(ssh.SIGINT)
+		session.Close()
+		return 130, nil
+	}
+}
+
+// ExecuteStreaming runs cmd on the active session like Execute, but writes
+// its output to stdout and stderr as it is produced instead of when the
+// command finishes. The session's command timeout still applies. cd commands,
+// which update the tracked cwd, variable assignments on local sessions, which
+// update the session env, and sessions that can't stream or aren't
+// connected (and may need reconnecting) go through Execute, with the output
+// written once the command is done.
+func (m *Manager) ExecuteStreaming(ctx context.Context, cmd string, stdout, stderr io.Writer) (int, error) {
+	session := m.GetActiveSession()
+	if session == nil {
+		logger.Warn("execute failed: no active session")
+		return 1, &Error{
+			Code:    ErrSessionNotFound,
+			Message: "No active session",
+		}
+	}
+
+	streamer, ok := session.(StreamExecutor)
+	_, local := session.(*LocalSession)
+	if !ok || !session.IsConnected() || isCDCommand(cmd) || local && isEnvAssignment(cmd) {
+		result, err := m.ExecuteWithContext(ctx, cmd)
+		if err != nil {
+			return 1, err
+		}
+		_, _ = io.WriteString(stdout, result.Stdout)
+		_, _ = io.WriteString(stderr, result.Stderr)
+		return result.ExitCode, nil
+	}
+
+	if err := m.checkRestriction(session.Name(), cmd); err != nil {
+		return 1, err
+	}
+
+	execCtx, cancel := context.WithTimeout(ctx, m.commandTimeout)
+	defer cancel()
+
+	// runCtx is canceled early if the command stops at a password or confirmation prompt
+	runCtx, stop := context.WithCancel(execCtx)
+	defer stop()
+	prompt := newPromptWatcher()
+	go func() {
+		select {
+		case <-prompt.Found():
+			stop()
+		case <-runCtx.Done():
+		}
+	}()
+
+	logger.Debug("streaming on session %q: %s", session.Name(), cmd)
+	exitCode, err := streamer.ExecuteStream(runCtx, cmd, prompt.Writer(stdout), prompt.Writer(stderr))
+	if prompt.Detected() {
+		logger.Warn("command stopped at an input prompt on %q", session.Name())
+		host := ""
+		if sshSession, ok := session.(*SSHSession); ok {
+			host = sshSession.Host()
+		}
+		return 1, prompt.Err(session.Name(), host)
+	}
+	if err == nil && ctx.Err() == nil && execCtx.Err() == context.DeadlineExceeded {
+		logger.Warn("command timed out after %s on %q", m.commandTimeout, session.Name())
+		return exitCode, &Error{
+			Code:      ErrCommandTimeout,
+			Message:   "Command timed out after " + m.commandTimeout.String(),
+			Session:   session.Name(),
+			Retryable: true,
+		}
+	}
+	return exitCode, err
+}
+
+// isCDCommand reports whether cmd is a cd command, which sessions handle
+// themselves to track the working directory
+func isCDCommand(cmd string) bool {
+	trimmed := strings.TrimSpace(cmd)
+	return trimmed == "cd" || strings.HasPrefix(trimmed, "cd ")
+}
@@ -0,0 +1,67 @@
+package session
+
+import (
+	"bytes"
+	"context"
+	"strings"
+	"sync"
+	"testing"
+	"time"
+)
+
+// timedWriter records the time of each write
+type timedWriter struct {
+	mu     sync.Mutex
+	buf    bytes.Buffer
+	writes []time.Time
+}
+
+func (w *timedWriter) Write(p []byte) (int, error) {
+	w.mu.Lock()
+	defer w.mu.Unlock()
+	w.writes = append(w.writes, time.Now())
+	return w.buf.Write(p)
+}
+
+func TestManagerExecuteStreamingWritesIncrementally(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	var stdout timedWriter
+	var stderr bytes.Buffer
+	start := time.Now()
+	exitCode, err := mgr.ExecuteStreaming(context.Background(), "for i in 1 2 3; do echo $i; sleep 0.3; done", &stdout, &stderr)
+	finished := time.Now()
+	if err != nil {
+		t.Fatalf("ExecuteStreaming failed: %v", err)
+	}
+	if exitCode != 0 {
+		t.Errorf("expected exit code 0, got %d", exitCode)
+	}
+	if got := stdout.buf.String(); got != "1\n2\n3\n" {
+		t.Errorf("expected 1 2 3 on separate lines, got %q", got)
+	}
+
+	if len(stdout.writes) < 3 {
+		t.Fatalf("expected a write per line, got %d writes", len(stdout.writes))
+	}
+	// The first line must arrive well before the command finishes
+	if first := stdout.writes[0].Sub(start); first > finished.Sub(start)-400*time.Millisecond {
+		t.Errorf("first output arrived after %v of %v; expected it to be streamed", first, finished.Sub(start))
+	}
+}
+
+func TestManagerExecuteStreamingTracksCD(t *testing.T) {
+	mgr, _ := createTestManager(t)
+	dir := t.TempDir()
+
+	var stdout, stderr bytes.Buffer
+	if _, err := mgr.ExecuteStreaming(context.Background(), "cd "+dir, &stdout, &stderr); err != nil {
+		t.Fatalf("cd failed: %v", err)
+	}
+	if _, err := mgr.ExecuteStreaming(context.Background(), "pwd", &stdout, &stderr); err != nil {
+		t.Fatalf("pwd failed: %v", err)
+	}
+	if !strings.HasSuffix(strings.TrimSpace(stdout.String()), dir) {
+		t.Errorf("expected pwd to print %s, got %q", dir, stdout.String())
+	}
+}
@@ -0,0 +1,142 @@
+package session
+
+import (
+	"context"
+	"fmt"
+	"io"
+	"os"
+	"strings"
+)
+
+// DefaultTailLines is the number of lines /tail shows when none is given
+const DefaultTailLines = 10
+
+// tailChunkSize is how much of a file lastLines reads per step back from the end
+const tailChunkSize = 8192
+
+// lastLines returns the last n lines of r, whose total size is size. It reads
+// backwards from the end in chunks, so only the tail of a large file is read.
+func lastLines(r io.ReaderAt, size int64, n int) ([]byte, error) {
+	if n <= 0 || size <= 0 {
+		return nil, nil
+	}
+
+	buf := make([]byte, tailChunkSize)
+	start := int64(0)
+	newlines := 0
+	end := size
+scan:
+	for end > 0 {
+		chunk := min(int64(len(buf)), end)
+		offset := end - chunk
+		if _, err := r.ReadAt(buf[:chunk], offset); err != nil && err != io.EOF {
+			return nil, err
+		}
+		for i := chunk - 1; i >= 0; i-- {
+			// A trailing newline ends the last line rather than starting a new one
+			if buf[i] != '\n' || offset+i == size-1 {
+				continue
+			}
+			newlines++
+			if newlines == n {
+				start = offset + i + 1
+				break scan
+			}
+		}
+		end = offset
+	}
+
+	out := make([]byte, size-start)
+	if _, err := r.ReadAt(out, start); err != nil && err != io.EOF {
+		return nil, err
+	}
+	return out, nil
+}
+
+// shellPath quotes a path spec for a command run in the session's cwd, leaving
+// a leading ~ unquoted so the remote shell expands it to the home directory.
+// FilePath resolves relative to home for SFTP, which is not where a shell
+// command runs once it has changed into the session cwd.
+func shellPath(p string) string {
+	if p == "~" {
+		return p
+	}
+	if rest, ok := strings.CutPrefix(p, "~/"); ok {
+		return "~/" + ShellQuote(rest)
+	}
+	return ShellQuote(p)
+}
+
+// Tail returns the last n lines of a file on the named session (the active
+// session if name is empty) and the resolved path. Local files are read
+// backwards from the end; SSH sessions run tail -n on the remote host.
+func (m *Manager) Tail(sessionName, path string, n int) ([]byte, string, error) {
+	session, err := m.connectedSession(sessionName)
+	if err != nil {
+		return nil, "", err
+	}
+
+	spec := path
+	// Resolve the path the way a shell command on the session would
+	path = FilePath(session, path)
+
+	if _, ok := session.(*SSHSession); ok {
+		result, err := session.Execute(fmt.Sprintf("tail -n %d -- %s", n, shellPath(spec)))
+		if err != nil {
+			return nil, path, err
+		}
+		if result.ExitCode != 0 {
+			return nil, path, fmt.Errorf("failed to read file: %s", strings.TrimSpace(result.Stderr))
+		}
+		return []byte(result.Stdout), path, nil
+	}
+
+	f, err := os.Open(path)
+	if err != nil {
+		return nil, path, fmt.Errorf("failed to read file: %w", err)
+	}
+	defer f.Close()
+
+	info, err := f.Stat()
+	if err != nil {
+		return nil, path, fmt.Errorf("failed to read file: %w", err)
+	}
+	if info.IsDir() {
+		return nil, path, fmt.Errorf("failed to read file: %s is a directory", path)
+	}
+
+	data, err := lastLines(f, info.Size(), n)
+	if err != nil {
+		return nil, path, fmt.Errorf("failed to read file: %w", err)
+	}
+	return data, path, nil
+}
+
+// FollowFile writes the last n lines of a file on the named session (the
+// active session if name is empty) to stdout, then streams lines appended to
+// it until ctx is canceled
+func (m *Manager) FollowFile(ctx context.Context, sessionName, path string, n int, stdout, stderr io.Writer) error {
+	session, err := m.connectedSession(sessionName)
+	if err != nil {
+		return err
+	}
+
+	executor, ok := session.(StreamExecutor)
+	if !ok {
+		return fmt.Errorf("session '%s' does not support streaming output", session.Name())
+	}
+
+	if session.Type() == "local" {
+		path = ShellQuote(FilePath(session, path))
+	} else {
+		path = shellPath(path)
+	}
+	exitCode, err := executor.ExecuteStream(ctx, fmt.Sprintf("tail -n %d -f -- %s", n, path), stdout, stderr)
+	if err != nil {
+		return err
+	}
+	if exitCode != 0 && exitCode != 130 {
+		return fmt.Errorf("tail exited with status %d", exitCode)
+	}
+	return nil
+}
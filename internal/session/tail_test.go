@@ -0,0 +1,73 @@
+package session
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+func TestLastLines(t *testing.T) {
+	// Long enough that the wanted lines span several read chunks
+	var long strings.Builder
+	for i := 1; i <= 5000; i++ {
+		fmt.Fprintf(&long, "line %d\n", i)
+	}
+
+	tests := []struct {
+		name    string
+		content string
+		n       int
+		want    string
+	}{
+		{"trailing newline", "a\nb\nc\n", 2, "b\nc\n"},
+		{"no trailing newline", "a\nb\nc", 2, "b\nc"},
+		{"fewer lines than n", "a\nb\n", 5, "a\nb\n"},
+		{"exactly n lines", "a\nb\n", 2, "a\nb\n"},
+		{"empty file", "", 3, ""},
+		{"zero lines", "a\nb\n", 0, ""},
+		{"blank lines count", "a\n\n\n", 2, "\n\n"},
+		{"spans chunks", long.String(), 3000, long.String()[strings.Index(long.String(), "line 2001\n"):]},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			r := strings.NewReader(tt.content)
+			got, err := lastLines(r, int64(len(tt.content)), tt.n)
+			if err != nil {
+				t.Fatalf("lastLines failed: %v", err)
+			}
+			if string(got) != tt.want {
+				t.Errorf("lastLines(%d) = %q, want %q", tt.n, got, tt.want)
+			}
+		})
+	}
+}
+
+func TestManagerTailLocal(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	var content strings.Builder
+	for i := 1; i <= 20; i++ {
+		fmt.Fprintf(&content, "line %d\n", i)
+	}
+	path := filepath.Join(t.TempDir(), "app.log")
+	if err := os.WriteFile(path, []byte(content.String()), 0644); err != nil {
+		t.Fatal(err)
+	}
+
+	data, resolved, err := mgr.Tail("local", path, 5)
+	if err != nil {
+		t.Fatalf("Tail failed: %v", err)
+	}
+	if resolved != path {
+		t.Errorf("resolved path = %q, want %q", resolved, path)
+	}
+	if want := "line 16\nline 17\nline 18\nline 19\nline 20\n"; string(data) != want {
+		t.Errorf("Tail(-n 5) = %q, want %q", data, want)
+	}
+
+	if _, _, err := mgr.Tail("local", filepath.Join(t.TempDir(), "missing.log"), 5); err == nil {
+		t.Error("expected an error for a missing file")
+	}
+}
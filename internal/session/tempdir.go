@@ -0,0 +1,115 @@
+package session
+
+import (
+	"fmt"
+	"os"
+	"strings"
+
+	"github.com/scottgl9/thop/internal/logger"
+)
+
+// remoteMktempCommand creates a temp directory on a remote host
+const remoteMktempCommand = `mktemp -d "${TMPDIR:-/tmp}/thop-XXXXXXXX"`
+
+// CreateTempDir creates a scratch directory on the named session and tracks
+// it so it can be removed later with CleanupTempDirs
+func (m *Manager) CreateTempDir(sessionName string) (string, error) {
+	session, err := m.lookupSession(sessionName)
+	if err != nil {
+		return "", err
+	}
+	sessionName = session.Name()
+
+	var dir string
+	if session.Type() == "local" {
+		dir, err = os.MkdirTemp("", "thop-")
+		if err != nil {
+			return "", fmt.Errorf("failed to create temp directory: %w", err)
+		}
+	} else {
+		if !session.IsConnected() {
+			return "", notConnectedError(sessionName)
+		}
+		result, err := session.Execute(remoteMktempCommand)
+		if err != nil {
+			return "", err
+		}
+		dir = strings.TrimSpace(result.Stdout)
+		if result.ExitCode != 0 || dir == "" {
+			return "", fmt.Errorf("failed to create temp directory: %s", strings.TrimSpace(result.Stderr))
+		}
+	}
+
+	m.mu.Lock()
+	m.tempDirs[sessionName] = append(m.tempDirs[sessionName], dir)
+	m.mu.Unlock()
+
+	logger.Debug("created temp directory %s on session %q", dir, sessionName)
+	return dir, nil
+}
+
+// TempDirs returns the temp directories tracked for the named session
+func (m *Manager) TempDirs(sessionName string) []string {
+	m.mu.RLock()
+	defer m.mu.RUnlock()
+
+	dirs := make([]string, len(m.tempDirs[sessionName]))
+	copy(dirs, m.tempDirs[sessionName])
+	return dirs
+}
+
+// CleanupTempDirs removes all tracked temp directories for the named session
+// and returns the ones removed. Removal runs on the session directly so it is
+// not subject to restricted mode. Directories that fail to be removed stay
+// tracked and the first error is returned.
+func (m *Manager) CleanupTempDirs(sessionName string) ([]string, error) {
+	session, err := m.lookupSession(sessionName)
+	if err != nil {
+		return nil, err
+	}
+	sessionName = session.Name()
+
+	var removed, remaining []string
+	var firstErr error
+	for _, dir := range m.TempDirs(sessionName) {
+		if err := removeTempDir(session, dir); err != nil {
+			logger.Warn("failed to remove temp directory %s on session %q: %v", dir, sessionName, err)
+			remaining = append(remaining, dir)
+			if firstErr == nil {
+				firstErr = err
+			}
+			continue
+		}
+		removed = append(removed, dir)
+	}
+
+	m.mu.Lock()
+	if len(remaining) > 0 {
+		m.tempDirs[sessionName] = remaining
+	} else {
+		delete(m.tempDirs, sessionName)
+	}
+	m.mu.Unlock()
+
+	logger.Debug("removed %d temp directories on session %q", len(removed), sessionName)
+	return removed, firstErr
+}
+
+// removeTempDir deletes a temp directory on a session
+func removeTempDir(session Session, dir string) error {
+	if session.Type() == "local" {
+		return os.RemoveAll(dir)
+	}
+
+	if !session.IsConnected() {
+		return fmt.Errorf("session '%s' is not connected", session.Name())
+	}
+	result, err := session.Execute("rm -rf -- " + ShellQuote(dir))
+	if err != nil {
+		return err
+	}
+	if result.ExitCode != 0 {
+		return fmt.Errorf("rm exited with code %d: %s", result.ExitCode, strings.TrimSpace(result.Stderr))
+	}
+	return nil
+}
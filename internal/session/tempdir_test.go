@@ -0,0 +1,103 @@
+package session
+
+import (
+	"os"
+	"testing"
+)
+
+func TestCreateTempDirLocal(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	dir, err := mgr.CreateTempDir("local")
+	if err != nil {
+		t.Fatalf("CreateTempDir failed: %v", err)
+	}
+	defer os.RemoveAll(dir)
+
+	if info, err := os.Stat(dir); err != nil || !info.IsDir() {
+		t.Fatalf("expected temp directory %s to exist", dir)
+	}
+
+	dirs := mgr.TempDirs("local")
+	if len(dirs) != 1 || dirs[0] != dir {
+		t.Errorf("expected tracked dirs [%s], got %v", dir, dirs)
+	}
+
+	// Other sessions are tracked separately
+	if len(mgr.TempDirs("testserver")) != 0 {
+		t.Error("expected no tracked dirs for testserver")
+	}
+}
+
+func TestCreateTempDirErrors(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	_, err := mgr.CreateTempDir("nonexistent")
+	if sessionErr, ok := err.(*Error); !ok || sessionErr.Code != ErrSessionNotFound {
+		t.Errorf("expected ErrSessionNotFound, got %v", err)
+	}
+
+	_, err = mgr.CreateTempDir("testserver")
+	if sessionErr, ok := err.(*Error); !ok || sessionErr.Code != ErrSessionDisconnected {
+		t.Errorf("expected ErrSessionDisconnected, got %v", err)
+	}
+}
+
+func TestCleanupTempDirs(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	var dirs []string
+	for i := 0; i < 2; i++ {
+		dir, err := mgr.CreateTempDir("local")
+		if err != nil {
+			t.Fatalf("CreateTempDir failed: %v", err)
+		}
+		// Put something in it so removal must be recursive
+		if err := os.WriteFile(dir+"/scratch.txt", []byte("data"), 0644); err != nil {
+			t.Fatal(err)
+		}
+		dirs = append(dirs, dir)
+	}
+
+	// Cleanup is internal and not subject to restricted mode
+	mgr.SetRestrictedMode(true)
+
+	removed, err := mgr.CleanupTempDirs("local")
+	if err != nil {
+		t.Fatalf("CleanupTempDirs failed: %v", err)
+	}
+	if len(removed) != len(dirs) {
+		t.Errorf("expected %d removed dirs, got %v", len(dirs), removed)
+	}
+	for _, dir := range dirs {
+		if _, err := os.Stat(dir); !os.IsNotExist(err) {
+			t.Errorf("expected %s to be removed", dir)
+		}
+	}
+	if len(mgr.TempDirs("local")) != 0 {
+		t.Error("expected no tracked dirs after cleanup")
+	}
+
+	// Cleanup with nothing tracked is a no-op
+	removed, err = mgr.CleanupTempDirs("local")
+	if err != nil || len(removed) != 0 {
+		t.Errorf("expected empty cleanup, got %v, %v", removed, err)
+	}
+}
+
+func TestShellQuote(t *testing.T) {
+	tests := []struct {
+		in       string
+		expected string
+	}{
+		{"/tmp/thop-abc", "'/tmp/thop-abc'"},
+		{"it's", `'it'\''s'`},
+		{"", "''"},
+	}
+
+	for _, tt := range tests {
+		if got := ShellQuote(tt.in); got != tt.expected {
+			t.Errorf("ShellQuote(%q) = %q, expected %q", tt.in, got, tt.expected)
+		}
+	}
+}
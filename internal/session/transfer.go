@@ -0,0 +1,137 @@
+package session
+
+import (
+	"fmt"
+	"os"
+	"path"
+	"strings"
+)
+
+// ParseFileSpec parses a file specification in the format "session:path" or
+// just "path". An empty session means the active session.
+func ParseFileSpec(spec string) (session, path string) {
+	// Handle Windows-style paths (C:\...) by checking if it looks like a drive letter
+	if len(spec) >= 2 && spec[1] == ':' && (spec[0] >= 'A' && spec[0] <= 'Z' || spec[0] >= 'a' && spec[0] <= 'z') {
+		// Windows absolute path
+		return "", spec
+	}
+
+	// Look for session:path format
+	idx := strings.Index(spec, ":")
+	if idx > 0 {
+		return spec[:idx], spec[idx+1:]
+	}
+
+	// Just a path, no session specified
+	return "", spec
+}
+
+// resolveFileSpec parses spec and resolves its session name: an empty
+// session is the active session and "remote" is an alias for the active
+// session when it is an SSH session
+func (m *Manager) resolveFileSpec(spec, role string) (Session, string, error) {
+	name, path := ParseFileSpec(spec)
+	active := m.GetActiveSessionName()
+	if name == "" {
+		name = active
+	}
+	if name == "remote" {
+		if active == "local" {
+			return nil, "", fmt.Errorf("no remote session active - use session name instead")
+		}
+		name = active
+	}
+
+	session, ok := m.GetSession(name)
+	if !ok {
+		return nil, "", &Error{
+			Code:    ErrSessionNotFound,
+			Message: fmt.Sprintf("%s session '%s' not found", role, name),
+			Session: name,
+		}
+	}
+	return session, path, nil
+}
+
+// copyDestination returns the path a file copied from src is written to on
+// sess. A destination that is empty, "." or ends in "/" names a directory, so
+// the source file name is kept. Relative paths are resolved against the
+// session's cwd.
+func copyDestination(sess Session, dst, src string) string {
+	if dst == "" || dst == "." || strings.HasSuffix(dst, "/") {
+		dst = path.Join(dst, path.Base(src))
+	}
+	return FilePath(sess, dst)
+}
+
+// CopyFile copies a file between sessions over SFTP. src and dst are file
+// specs as accepted by ParseFileSpec; disconnected SSH endpoints are connected
+// first. Relative paths are resolved against each session's cwd, and a
+// destination of "." copies into the destination's cwd under the source file
+// name. It returns the number of bytes copied. Copying from a local session to
+// a local session is not supported.
+func (m *Manager) CopyFile(src, dst string) (int64, error) {
+	srcSess, srcPath, err := m.resolveFileSpec(src, "source")
+	if err != nil {
+		return 0, err
+	}
+	dstSess, dstPath, err := m.resolveFileSpec(dst, "destination")
+	if err != nil {
+		return 0, err
+	}
+	return m.copyResolved(srcSess, FilePath(srcSess, srcPath), dstSess, copyDestination(dstSess, dstPath, srcPath))
+}
+
+// copyResolved copies srcPath on srcSess to dstPath on dstSess, where both
+// paths are already resolved with FilePath
+func (m *Manager) copyResolved(srcSess Session, srcPath string, dstSess Session, dstPath string) (int64, error) {
+	srcSSH, srcIsSSH := srcSess.(*SSHSession)
+	dstSSH, dstIsSSH := dstSess.(*SSHSession)
+	if !srcIsSSH && !dstIsSSH {
+		return 0, &Error{
+			Code:       ErrCopyUnsupported,
+			Message:    "both source and destination are local",
+			Suggestion: "Use a regular cp command for local copies",
+		}
+	}
+
+	for _, endpoint := range []*SSHSession{srcSSH, dstSSH} {
+		if endpoint != nil && !endpoint.IsConnected() {
+			if err := m.Connect(endpoint.Name()); err != nil {
+				return 0, err
+			}
+		}
+	}
+
+	switch {
+	case !srcIsSSH:
+		// Upload: local -> remote
+		info, err := os.Stat(srcPath)
+		if err != nil {
+			return 0, fmt.Errorf("failed to stat local file: %w", err)
+		}
+		if err := dstSSH.UploadFile(srcPath, dstPath); err != nil {
+			return 0, err
+		}
+		return info.Size(), nil
+
+	case !dstIsSSH:
+		// Download: remote -> local
+		if err := srcSSH.DownloadFile(srcPath, dstPath); err != nil {
+			return 0, err
+		}
+		info, err := os.Stat(dstPath)
+		if err != nil {
+			return 0, fmt.Errorf("failed to stat local file: %w", err)
+		}
+		return info.Size(), nil
+
+	default:
+		// Remote to remote: stream over SFTP between the two sessions
+		copied, err := srcSSH.CopyFileTo(dstSSH, srcPath, dstPath)
+		if err != nil {
+			return copied, fmt.Errorf("failed to copy from %s to %s: %w", srcSSH.Name(), dstSSH.Name(), err)
+		}
+		return copied, nil
+	}
+}
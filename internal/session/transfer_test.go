@@ -0,0 +1,81 @@
+package session
+
+import (
+	"testing"
+)
+
+func TestParseFileSpec(t *testing.T) {
+	tests := []struct {
+		input           string
+		expectedSession string
+		expectedPath    string
+	}{
+		{"local:/path/to/file", "local", "/path/to/file"},
+		{"remote:/home/user/file", "remote", "/home/user/file"},
+		{"server1:/etc/config", "server1", "/etc/config"},
+		{"/absolute/path", "", "/absolute/path"},
+		{"relative/path", "", "relative/path"},
+		{"C:/Windows/path", "", "C:/Windows/path"},     // Windows path
+		{"D:\\Windows\\path", "", "D:\\Windows\\path"}, // Windows path with backslash
+	}
+
+	for _, tt := range tests {
+		sess, path := ParseFileSpec(tt.input)
+		if sess != tt.expectedSession {
+			t.Errorf("ParseFileSpec(%q) session = %q, want %q", tt.input, sess, tt.expectedSession)
+		}
+		if path != tt.expectedPath {
+			t.Errorf("ParseFileSpec(%q) path = %q, want %q", tt.input, path, tt.expectedPath)
+		}
+	}
+}
+
+func TestManagerCopyFileLocalToLocal(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	for _, tt := range []struct{ src, dst string }{
+		{"local:/tmp/a", "local:/tmp/b"},
+		{"/tmp/a", "/tmp/b"},
+	} {
+		_, err := mgr.CopyFile(tt.src, tt.dst)
+		sessionErr, ok := err.(*Error)
+		if !ok || sessionErr.Code != ErrCopyUnsupported {
+			t.Errorf("CopyFile(%q, %q): expected %s, got %v", tt.src, tt.dst, ErrCopyUnsupported, err)
+		}
+	}
+
+	_, err := mgr.CopyFile("missing:/tmp/a", "local:/tmp/b")
+	if sessionErr, ok := err.(*Error); !ok || sessionErr.Code != ErrSessionNotFound {
+		t.Errorf("expected %s for an unknown session, got %v", ErrSessionNotFound, err)
+	}
+}
+
+func TestCopyDestination(t *testing.T) {
+	remote := NewSSHSession(SSHConfig{Name: "remote", Host: "example.com", User: "deploy"})
+	_ = remote.SetCWD("/srv/app")
+
+	tests := []struct {
+		name string
+		dst  string
+		src  string
+		want string
+	}{
+		{"relative", "logs/app.log", "/tmp/app.log", "/srv/app/logs/app.log"},
+		{"dot keeps file name", ".", "/tmp/app.log", "/srv/app/app.log"},
+		{"empty keeps file name", "", "build/out.tar", "/srv/app/out.tar"},
+		{"directory keeps file name", "releases/", "out.tar", "/srv/app/releases/out.tar"},
+		{"absolute", "/etc/app.conf", "app.conf", "/etc/app.conf"},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if got := copyDestination(remote, tt.dst, tt.src); got != tt.want {
+				t.Errorf("copyDestination(%q, %q) = %q, want %q", tt.dst, tt.src, got, tt.want)
+			}
+		})
+	}
+
+	_ = remote.SetCWD("~")
+	if got := copyDestination(remote, ".", "/tmp/app.log"); got != "app.log" {
+		t.Errorf("copyDestination with home cwd = %q, want %q", got, "app.log")
+	}
+}
@@ -0,0 +1,167 @@
+package session
+
+import (
+	"bytes"
+	"context"
+	"fmt"
+	"os/exec"
+	"time"
+
+	"github.com/scottgl9/thop/internal/logger"
+	"golang.org/x/crypto/ssh"
+)
+
+// VerbatimExecutor is implemented by sessions that can run a command exactly
+// as given: in a fresh process or channel, without the cwd prefix, exported
+// session environment, or sudo prompt detection applied by ExecuteWithContext.
+// It exists to debug thop's own command wrapping.
+type VerbatimExecutor interface {
+	ExecuteVerbatim(ctx context.Context, cmd string) (*ExecuteResult, error)
+}
+
+// ExecuteVerbatim runs cmdStr with the session shell in a fresh process that
+// inherits thop's own working directory and environment
+func (s *LocalSession) ExecuteVerbatim(ctx context.Context, cmdStr string) (*ExecuteResult, error) {
+	execCtx, cancel := context.WithTimeout(ctx, s.timeout)
+	defer cancel()
+
+	cmd := exec.CommandContext(execCtx, s.shell, "-c", cmdStr)
+	cmd.WaitDelay = time.Second
+
+	var stdout, stderr bytes.Buffer
+	cmd.Stdout = &stdout
+	cmd.Stderr = &stderr
+
+	err := cmd.Run()
+	result := &ExecuteResult{
+		Stdout: stdout.String(),
+		Stderr: stderr.String(),
+	}
+
+	if err != nil {
+		if ctx.Err() == context.Canceled {
+			return &ExecuteResult{Stderr: "^C\n", ExitCode: 130}, nil
+		}
+		if execCtx.Err() == context.DeadlineExceeded {
+			// A caller deadline (e.g. an MCP per-call timeout) may expire
+			// before the session timeout
+			message := "Command timed out after " + s.timeout.String()
+			if ctx.Err() == context.DeadlineExceeded {
+				message = "Command timed out (deadline exceeded)"
+			}
+			logger.Warn("local raw command timed out on %q: %s", s.name, message)
+			return nil, &Error{
+				Code:      ErrCommandTimeout,
+				Message:   message,
+				Session:   s.name,
+				Retryable: true,
+			}
+		}
+		if exitErr, ok := err.(*exec.ExitError); ok {
+			result.ExitCode = exitErr.ExitCode()
+		} else {
+			return nil, err
+		}
+	}
+
+	return result, nil
+}
+
+// ExecuteVerbatim runs cmdStr on a new SSH channel exactly as given
+func (s *SSHSession) ExecuteVerbatim(ctx context.Context, cmdStr string) (*ExecuteResult, error) {
+	if !s.IsConnected() {
+		return nil, &Error{
+			Code:       ErrSessionDisconnected,
+			Message:    fmt.Sprintf("Session %s is not connected", s.name),
+			Session:    s.name,
+			Retryable:  true,
+			Suggestion: fmt.Sprintf("Use /connect %s to reconnect", s.name),
+		}
+	}
+
+	session, err := s.client.NewSession()
+	if err != nil {
+		return nil, fmt.Errorf("failed to create session: %w", err)
+	}
+	defer session.Close()
+
+	var stdout, stderr bytes.Buffer
+	session.Stdout = &stdout
+	session.Stderr = &stderr
+
+	done := make(chan error, 1)
+	go func() {
+		done <- session.Run(cmdStr)
+	}()
+
+	var runErr error
+	select {
+	case runErr = <-done:
+	case <-ctx.Done():
+		session.Close()
+		if ctx.Err() == context.DeadlineExceeded {
+			return nil, &Error{
+				Code:      ErrCommandTimeout,
+				Message:   "Command timed out (deadline exceeded)",
+				Session:   s.name,
+				Host:      s.host,
+				Retryable: true,
+			}
+		}
+		return &ExecuteResult{Stderr: "^C\n", ExitCode: 130}, nil
+	case <-time.After(s.commandTimeout):
+		session.Close()
+		return nil, &Error{
+			Code:      ErrCommandTimeout,
+			Message:   fmt.Sprintf("Command timed out after %s", s.commandTimeout),
+			Session:   s.name,
+			Host:      s.host,
+			Retryable: true,
+		}
+	}
+
+	result := &ExecuteResult{
+		Stdout: stdout.String(),
+		Stderr: stderr.String(),
+	}
+	if runErr != nil {
+		if exitErr, ok := runErr.(*ssh.ExitError); ok {
+			result.ExitCode = exitErr.ExitStatus()
+		} else {
+			return nil, runErr
+		}
+	}
+
+	return result, nil
+}
+
+// ExecuteVerbatim runs cmd on the named session (the active session if name
+// is empty) with no thop processing at all. Since it also skips restriction
+// checks, it is refused whenever restrictions apply to the session.
+func (m *Manager) ExecuteVerbatim(ctx context.Context, sessionName, cmd string) (*ExecuteResult, error) {
+	session, err := m.lookupSession(sessionName)
+	if err != nil {
+		return nil, err
+	}
+	sessionName = session.Name()
+
+	if m.IsRestrictedMode() || m.IsSessionRestricted(sessionName) {
+		return nil, &Error{
+			Code:       ErrCommandRestricted,
+			Message:    "Raw execution is not allowed in restricted mode",
+			Session:    sessionName,
+			Suggestion: "Run the command normally so restriction rules are applied",
+		}
+	}
+
+	executor, ok := session.(VerbatimExecutor)
+	if !ok {
+		return nil, fmt.Errorf("session '%s' does not support raw execution", sessionName)
+	}
+
+	logger.Debug("executing raw on session %q: %s", sessionName, cmd)
+	// Raw commands skip the rules, so record them along with any rule they match
+	_, rule := m.restriction.Match(cmd)
+	m.audit(AuditRaw, sessionName, cmd, rule)
+	return executor.ExecuteVerbatim(ctx, cmd)
+}
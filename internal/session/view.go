@@ -0,0 +1,85 @@
+package session
+
+import (
+	"fmt"
+	"sync"
+
+	"github.com/scottgl9/thop/internal/logger"
+)
+
+// View is one client's choice of active session over a Manager's shared
+// sessions. The MCP server gives each client its own View, so an agent
+// switching sessions doesn't change where another agent's commands run.
+// Connections, working directories and environments stay shared, and
+// switching a view is not persisted to the state file.
+type View struct {
+	manager *Manager
+
+	mu     sync.RWMutex
+	active string
+}
+
+// NewView returns a view whose active session starts out as the manager's
+func (m *Manager) NewView() *View {
+	return &View{
+		manager: m,
+		active:  m.GetActiveSessionName(),
+	}
+}
+
+// ActiveSessionName returns the name of the view's active session
+func (v *View) ActiveSessionName() string {
+	v.mu.RLock()
+	defer v.mu.RUnlock()
+
+	return v.active
+}
+
+// ActiveSession returns the view's active session
+func (v *View) ActiveSession() Session {
+	session, _ := v.manager.GetSession(v.ActiveSessionName())
+	return session
+}
+
+// SetActiveSession changes the view's active session, leaving the manager's
+// and other views' unchanged
+func (v *View) SetActiveSession(name string) error {
+	if !v.manager.HasSession(name) {
+		return &Error{
+			Code:    ErrSessionNotFound,
+			Message: fmt.Sprintf("Session '%s' not found", name),
+			Session: name,
+		}
+	}
+
+	v.mu.Lock()
+	logger.Debug("view switching active session from %q to %q", v.active, name)
+	v.active = name
+	v.mu.Unlock()
+
+	v.manager.emit(SessionEvent{Type: EventActivated, Session: name, View: v})
+	return nil
+}
+
+// ListSessions returns information about all sessions, with Active marking
+// the view's active session
+func (v *View) ListSessions() []SessionInfo {
+	active := v.ActiveSessionName()
+	sessions := v.manager.ListSessions()
+	for i := range sessions {
+		sessions[i].Active = sessions[i].Name == active
+	}
+	return sessions
+}
+
+// QualifyFileSpec names the view's active session in a file spec that relies
+// on the active session (no session, or "remote"), so the manager resolves
+// it against this view rather than its own active session
+func (v *View) QualifyFileSpec(spec string) string {
+	name, path := ParseFileSpec(spec)
+	active := v.ActiveSessionName()
+	if name == "" || (name == "remote" && active != "local") {
+		return active + ":" + path
+	}
+	return spec
+}
@@ -0,0 +1,64 @@
+package session
+
+import (
+	"testing"
+)
+
+func TestViewsHaveIndependentActiveSessions(t *testing.T) {
+	mgr, _ := createTestManager(t)
+
+	first := mgr.NewView()
+	second := mgr.NewView()
+	if first.ActiveSessionName() != "local" || second.ActiveSessionName() != "local" {
+		t.Fatalf("expected views to start on the manager's active session")
+	}
+
+	if err := first.SetActiveSession("testserver"); err != nil {
+		t.Fatalf("SetActiveSession failed: %v", err)
+	}
+	if got := first.ActiveSessionName(); got != "testserver" {
+		t.Errorf("expected first view on testserver, got %q", got)
+	}
+	if got := second.ActiveSessionName(); got != "local" {
+		t.Errorf("expected second view to stay on local, got %q", got)
+	}
+	if got := mgr.GetActiveSessionName(); got != "local" {
+		t.Errorf("expected manager to stay on local, got %q", got)
+	}
+	if sess := first.ActiveSession(); sess == nil || sess.Name() != "testserver" {
+		t.Errorf("expected first view's active session to be testserver, got %v", sess)
+	}
+
+	for _, info := range second.ListSessions() {
+		if info.Active != (info.Name == "local") {
+			t.Errorf("second view marks %s active=%v", info.Name, info.Active)
+		}
+	}
+
+	if err := second.SetActiveSession("missing"); err == nil {
+		t.Error("expected an error switching to an unknown session")
+	}
+}
+
+func TestViewQualifyFileSpec(t *testing.T) {
+	mgr, _ := createTestManager(t)
+	view := mgr.NewView()
+
+	if got := view.QualifyFileSpec("remote:/tmp/a"); got != "remote:/tmp/a" {
+		t.Errorf("expected remote to be left alone while local is active, got %q", got)
+	}
+
+	if err := view.SetActiveSession("testserver"); err != nil {
+		t.Fatalf("SetActiveSession failed: %v", err)
+	}
+	tests := map[string]string{
+		"/tmp/a":        "testserver:/tmp/a",
+		"remote:/tmp/a": "testserver:/tmp/a",
+		"local:/tmp/a":  "local:/tmp/a",
+	}
+	for spec, want := range tests {
+		if got := view.QualifyFileSpec(spec); got != want {
+			t.Errorf("QualifyFileSpec(%q) = %q, want %q", spec, got, want)
+		}
+	}
+}
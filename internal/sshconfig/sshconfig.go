@@ -2,11 +2,18 @@ package sshconfig
 
 import (
 	"bufio"
+	"fmt"
+	"math"
 	"os"
 	"path/filepath"
+	"sort"
 	"strings"
 )
 
+// maxIncludeDepth bounds nested Include directives, as in OpenSSH, so a file
+// that includes itself fails instead of recursing forever
+const maxIncludeDepth = 16
+
 // HostConfig represents SSH configuration for a host
 type HostConfig struct {
 	Host         string
@@ -21,6 +28,15 @@ type HostConfig struct {
 // Config holds parsed SSH configuration
 type Config struct {
 	Hosts map[string]*HostConfig
+
+	blocks []*hostBlock // every Host block in file order, including wildcards
+}
+
+// hostBlock is one Host block with the patterns it applies to
+type hostBlock struct {
+	patterns        []string
+	config          *HostConfig
+	forwardAgentSet bool // ForwardAgent was given, so "no" overrides broader blocks
 }
 
 // Load loads SSH configuration from the default location
@@ -34,25 +50,41 @@ func Load() (*Config, error) {
 	return LoadFromFile(configPath)
 }
 
-// LoadFromFile loads SSH configuration from a specific file
+// LoadFromFile loads SSH configuration from a specific file. Include
+// directives are followed recursively, with relative paths and globs resolved
+// against the directory of path (~/.ssh for the default config).
 func LoadFromFile(path string) (*Config, error) {
-	config := &Config{
-		Hosts: make(map[string]*HostConfig),
+	p := &parser{
+		config:  &Config{Hosts: make(map[string]*HostConfig)},
+		baseDir: filepath.Dir(path),
 	}
 
-	file, err := os.Open(path)
-	if err != nil {
+	if err := p.parseFile(path, 0); err != nil {
 		if os.IsNotExist(err) {
-			return config, nil
+			return p.config, nil
 		}
 		return nil, err
 	}
+	return p.config, nil
+}
+
+// parser holds the state shared by a config file and the files it includes
+type parser struct {
+	config  *Config
+	baseDir string
+	current *hostBlock
+}
+
+// parseFile parses one config file into p.config. depth is how many Include
+// directives led to it.
+func (p *parser) parseFile(path string, depth int) error {
+	file, err := os.Open(path)
+	if err != nil {
+		return err
+	}
 	defer file.Close()
 
 	scanner := bufio.NewScanner(file)
-	var currentHost *HostConfig
-	var currentPatterns []string
-
 	for scanner.Scan() {
 		line := strings.TrimSpace(scanner.Text())
 
@@ -77,55 +109,208 @@ func LoadFromFile(path string) (*Config, error) {
 		// Remove quotes if present
 		value = strings.Trim(value, "\"'")
 
-		if key == "host" {
-			// Save previous host config
-			if currentHost != nil {
-				for _, pattern := range currentPatterns {
-					// Skip wildcard patterns
-					if !strings.Contains(pattern, "*") && !strings.Contains(pattern, "?") {
-						config.Hosts[pattern] = currentHost
-					}
+		switch key {
+		case "include":
+			if err := p.include(path, value, depth); err != nil {
+				return err
+			}
+		case "host":
+			patterns := strings.Fields(value)
+			p.current = &hostBlock{
+				patterns: patterns,
+				config:   &HostConfig{Host: patterns[0]},
+			}
+			p.config.blocks = append(p.config.blocks, p.current)
+
+			// Literal aliases can be looked up directly; wildcard and
+			// negated patterns only apply through Resolve
+			for _, pattern := range patterns {
+				if strings.ContainsAny(pattern, "*?!") {
+					continue
 				}
+				if _, exists := p.config.Hosts[pattern]; !exists {
+					p.config.Hosts[pattern] = p.current.config
+				}
+			}
+		case "match":
+			// Match conditions aren't supported; drop the block's options
+			// rather than applying them to the previous Host
+			p.current = &hostBlock{config: &HostConfig{}}
+		default:
+			if p.current != nil {
+				p.current.set(key, value)
+			}
+		}
+	}
+
+	return scanner.Err()
+}
+
+// include parses the files matched by the patterns of an Include directive
+// found in path
+func (p *parser) include(path, value string, depth int) error {
+	if depth >= maxIncludeDepth {
+		return fmt.Errorf("%s: Include nested more than %d levels deep", path, maxIncludeDepth)
+	}
+
+	for _, pattern := range strings.Fields(value) {
+		pattern = expandHome(pattern)
+		if !filepath.IsAbs(pattern) {
+			pattern = filepath.Join(p.baseDir, pattern)
+		}
+
+		// Like OpenSSH, a pattern matching nothing is not an error
+		matches, err := filepath.Glob(pattern)
+		if err != nil {
+			return fmt.Errorf("%s: invalid Include pattern %q: %w", path, pattern, err)
+		}
+		for _, match := range matches {
+			if err := p.parseFile(match, depth+1); err != nil {
+				return err
 			}
+		}
+	}
+	return nil
+}
+
+// set applies one option line to the block
+func (b *hostBlock) set(key, value string) {
+	switch key {
+	case "hostname":
+		b.config.HostName = value
+	case "user":
+		b.config.User = value
+	case "port":
+		b.config.Port = value
+	case "identityfile":
+		b.config.IdentityFile = expandHome(value)
+	case "proxyjump":
+		b.config.ProxyJump = value
+	case "forwardagent":
+		b.config.ForwardAgent = strings.ToLower(value) == "yes"
+		b.forwardAgentSet = true
+	}
+}
 
-			// Start new host block
-			currentPatterns = strings.Fields(value)
-			currentHost = &HostConfig{
-				Host: currentPatterns[0],
+// expandHome expands a leading ~ in path to the home directory
+func expandHome(path string) string {
+	if strings.HasPrefix(path, "~") {
+		home, _ := os.UserHomeDir()
+		return filepath.Join(home, path[1:])
+	}
+	return path
+}
+
+// matchPattern reports whether host matches an ssh_config pattern, where *
+// matches any run of characters and ? matches exactly one
+func matchPattern(pattern, host string) bool {
+	for pattern != "" {
+		switch pattern[0] {
+		case '*':
+			pattern = strings.TrimLeft(pattern, "*")
+			if pattern == "" {
+				return true
 			}
-		} else if currentHost != nil {
-			switch key {
-			case "hostname":
-				currentHost.HostName = value
-			case "user":
-				currentHost.User = value
-			case "port":
-				currentHost.Port = value
-			case "identityfile":
-				// Expand ~ in path
-				if strings.HasPrefix(value, "~") {
-					home, _ := os.UserHomeDir()
-					value = filepath.Join(home, value[1:])
+			for i := 0; i <= len(host); i++ {
+				if matchPattern(pattern, host[i:]) {
+					return true
 				}
-				currentHost.IdentityFile = value
-			case "proxyjump":
-				currentHost.ProxyJump = value
-			case "forwardagent":
-				currentHost.ForwardAgent = strings.ToLower(value) == "yes"
+			}
+			return false
+		case '?':
+			if host == "" {
+				return false
+			}
+		default:
+			if host == "" || host[0] != pattern[0] {
+				return false
 			}
 		}
+		pattern = pattern[1:]
+		host = host[1:]
 	}
+	return host == ""
+}
+
+// patternSpecificity ranks how narrowly a pattern selects hosts: a literal
+// alias beats every wildcard, and otherwise more literal characters win, so
+// web-* is more specific than *
+func patternSpecificity(pattern string) int {
+	if !strings.ContainsAny(pattern, "*?") {
+		return math.MaxInt
+	}
+	return len(pattern) - strings.Count(pattern, "*") - strings.Count(pattern, "?")
+}
 
-	// Save last host config
-	if currentHost != nil {
-		for _, pattern := range currentPatterns {
-			if !strings.Contains(pattern, "*") && !strings.Contains(pattern, "?") {
-				config.Hosts[pattern] = currentHost
+// match reports whether the block applies to host, and how specifically. A
+// matching negated pattern (!bastion) excludes host even if another pattern
+// matches it.
+func (b *hostBlock) match(host string) (int, bool) {
+	specificity, matched := 0, false
+	for _, pattern := range b.patterns {
+		if negated, ok := strings.CutPrefix(pattern, "!"); ok {
+			if matchPattern(negated, host) {
+				return 0, false
 			}
+			continue
+		}
+		if matchPattern(pattern, host) {
+			specificity = max(specificity, patternSpecificity(pattern))
+			matched = true
+		}
+	}
+	return specificity, matched
+}
+
+// Resolve returns the configuration that applies to host, merging every Host
+// block with a pattern matching it. Each option comes from the most specific
+// block that sets it: an exact alias beats wildcard patterns, and web-* beats
+// *. Equally specific blocks keep OpenSSH's first-match-wins order. %h in
+// HostName is replaced with host. It returns nil if no block matches.
+func (c *Config) Resolve(host string) *HostConfig {
+	type match struct {
+		block       *hostBlock
+		specificity int
+	}
+	var matches []match
+	for _, block := range c.blocks {
+		if specificity, ok := block.match(host); ok {
+			matches = append(matches, match{block, specificity})
 		}
 	}
+	if len(matches) == 0 {
+		return nil
+	}
+	sort.SliceStable(matches, func(i, j int) bool {
+		return matches[i].specificity > matches[j].specificity
+	})
 
-	return config, scanner.Err()
+	resolved := &HostConfig{Host: host}
+	forwardAgentSet := false
+	for _, m := range matches {
+		h := m.block.config
+		if resolved.HostName == "" {
+			resolved.HostName = h.HostName
+		}
+		if resolved.User == "" {
+			resolved.User = h.User
+		}
+		if resolved.Port == "" {
+			resolved.Port = h.Port
+		}
+		if resolved.IdentityFile == "" {
+			resolved.IdentityFile = h.IdentityFile
+		}
+		if resolved.ProxyJump == "" {
+			resolved.ProxyJump = h.ProxyJump
+		}
+		if !forwardAgentSet && m.block.forwardAgentSet {
+			resolved.ForwardAgent = h.ForwardAgent
+			forwardAgentSet = true
+		}
+	}
+	resolved.HostName = strings.ReplaceAll(resolved.HostName, "%h", host)
+	return resolved
 }
 
 // GetHost returns the configuration for a host alias
@@ -135,7 +320,7 @@ func (c *Config) GetHost(alias string) *HostConfig {
 
 // ResolveHost returns the actual hostname for an alias
 func (c *Config) ResolveHost(alias string) string {
-	if host := c.Hosts[alias]; host != nil && host.HostName != "" {
+	if host := c.Resolve(alias); host != nil && host.HostName != "" {
 		return host.HostName
 	}
 	return alias
@@ -143,7 +328,7 @@ func (c *Config) ResolveHost(alias string) string {
 
 // ResolveUser returns the user for an alias
 func (c *Config) ResolveUser(alias string) string {
-	if host := c.Hosts[alias]; host != nil && host.User != "" {
+	if host := c.Resolve(alias); host != nil && host.User != "" {
 		return host.User
 	}
 	return ""
@@ -151,7 +336,7 @@ func (c *Config) ResolveUser(alias string) string {
 
 // ResolvePort returns the port for an alias
 func (c *Config) ResolvePort(alias string) string {
-	if host := c.Hosts[alias]; host != nil && host.Port != "" {
+	if host := c.Resolve(alias); host != nil && host.Port != "" {
 		return host.Port
 	}
 	return "22"
@@ -159,7 +344,7 @@ func (c *Config) ResolvePort(alias string) string {
 
 // ResolveIdentityFile returns the identity file for an alias
 func (c *Config) ResolveIdentityFile(alias string) string {
-	if host := c.Hosts[alias]; host != nil && host.IdentityFile != "" {
+	if host := c.Resolve(alias); host != nil && host.IdentityFile != "" {
 		return host.IdentityFile
 	}
 	return ""
@@ -167,7 +352,7 @@ func (c *Config) ResolveIdentityFile(alias string) string {
 
 // ResolveProxyJump returns the ProxyJump for an alias
 func (c *Config) ResolveProxyJump(alias string) string {
-	if host := c.Hosts[alias]; host != nil && host.ProxyJump != "" {
+	if host := c.Resolve(alias); host != nil && host.ProxyJump != "" {
 		return host.ProxyJump
 	}
 	return ""
@@ -175,7 +360,7 @@ func (c *Config) ResolveProxyJump(alias string) string {
 
 // ResolveForwardAgent returns whether agent forwarding is enabled for an alias
 func (c *Config) ResolveForwardAgent(alias string) bool {
-	if host := c.Hosts[alias]; host != nil {
+	if host := c.Resolve(alias); host != nil {
 		return host.ForwardAgent
 	}
 	return false
@@ -260,3 +260,151 @@ Host myserver
 		t.Errorf("expected 'deploy', got '%s'", host.User)
 	}
 }
+
+func TestIncludeChain(t *testing.T) {
+	tmpDir := t.TempDir()
+	if err := os.MkdirAll(filepath.Join(tmpDir, "config.d"), 0755); err != nil {
+		t.Fatal(err)
+	}
+
+	files := map[string]string{
+		"config": `
+Include config.d/*.conf missing/*
+Host main
+    HostName main.example.com
+`,
+		// Relative paths in included files are still resolved against the
+		// directory of the top-level config
+		"config.d/10-web.conf": `
+Host web
+    HostName web.example.com
+Include nested
+`,
+		"config.d/20-db.conf": `
+Host db
+    HostName db.example.com
+`,
+		"nested": `
+Host nested
+    HostName nested.example.com
+`,
+	}
+	for name, content := range files {
+		if err := os.WriteFile(filepath.Join(tmpDir, name), []byte(content), 0644); err != nil {
+			t.Fatal(err)
+		}
+	}
+
+	config, err := LoadFromFile(filepath.Join(tmpDir, "config"))
+	if err != nil {
+		t.Fatalf("LoadFromFile failed: %v", err)
+	}
+	for alias, want := range map[string]string{
+		"main":   "main.example.com",
+		"web":    "web.example.com",
+		"db":     "db.example.com",
+		"nested": "nested.example.com",
+	} {
+		if got := config.ResolveHost(alias); got != want {
+			t.Errorf("ResolveHost(%q) = %q, want %q", alias, got, want)
+		}
+	}
+}
+
+func TestIncludeLoop(t *testing.T) {
+	tmpDir := t.TempDir()
+	configPath := filepath.Join(tmpDir, "config")
+	if err := os.WriteFile(configPath, []byte("Include config\n"), 0644); err != nil {
+		t.Fatal(err)
+	}
+
+	if _, err := LoadFromFile(configPath); err == nil {
+		t.Error("expected an error for a config that includes itself")
+	}
+}
+
+func TestResolveWildcardDefaults(t *testing.T) {
+	tmpDir := t.TempDir()
+	configPath := filepath.Join(tmpDir, "config")
+
+	content := `
+Host *
+    User defaultuser
+    Port 2200
+    ForwardAgent yes
+
+Host web-* !web-legacy
+    User webuser
+    ForwardAgent no
+
+Host web-1
+    HostName 10.0.0.1
+
+Host *.internal
+    HostName %h.corp.example.com
+`
+
+	if err := os.WriteFile(configPath, []byte(content), 0644); err != nil {
+		t.Fatal(err)
+	}
+
+	config, err := LoadFromFile(configPath)
+	if err != nil {
+		t.Fatalf("LoadFromFile failed: %v", err)
+	}
+
+	host := config.Resolve("web-1")
+	if host == nil {
+		t.Fatal("expected web-1 to resolve")
+	}
+	want := HostConfig{Host: "web-1", HostName: "10.0.0.1", User: "webuser", Port: "2200", ForwardAgent: false}
+	if *host != want {
+		t.Errorf("Resolve(web-1) = %+v, want %+v", *host, want)
+	}
+
+	// Host * applies to hosts with no block of their own
+	if got := config.ResolveUser("anything"); got != "defaultuser" {
+		t.Errorf("expected Host * user, got %q", got)
+	}
+	if got := config.ResolvePort("anything"); got != "2200" {
+		t.Errorf("expected Host * port, got %q", got)
+	}
+	if !config.ResolveForwardAgent("anything") {
+		t.Error("expected Host * to enable agent forwarding")
+	}
+
+	// A negated pattern excludes the host from the block
+	if got := config.ResolveUser("web-legacy"); got != "defaultuser" {
+		t.Errorf("expected web-legacy to skip the web-* block, got %q", got)
+	}
+
+	if got := config.ResolveHost("db.internal"); got != "db.internal.corp.example.com" {
+		t.Errorf("expected %%h to expand in HostName, got %q", got)
+	}
+
+	// Wildcards are not listed as hosts
+	if hosts := config.ListHosts(); len(hosts) != 1 || hosts[0] != "web-1" {
+		t.Errorf("expected only web-1 to be listed, got %v", hosts)
+	}
+}
+
+func TestMatchPattern(t *testing.T) {
+	tests := []struct {
+		pattern, host string
+		want          bool
+	}{
+		{"*", "anything", true},
+		{"web-*", "web-1", true},
+		{"web-*", "db-1", false},
+		{"*.example.com", "a.example.com", true},
+		{"*.example.com", "example.com", false},
+		{"db?", "db1", true},
+		{"db?", "db12", false},
+		{"exact", "exact", true},
+	}
+	for _, tt := range tests {
+		if got := matchPattern(tt.pattern, tt.host); got != tt.want {
+			t.Errorf("matchPattern(%q, %q) = %v, want %v", tt.pattern, tt.host, got, tt.want)
+		}
+	}
+}
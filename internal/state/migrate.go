@@ -0,0 +1,41 @@
+package state
+
+import (
+	"fmt"
+
+	"github.com/scottgl9/thop/internal/logger"
+)
+
+// CurrentVersion is the state file format version this build writes
+const CurrentVersion = 1
+
+// migrations upgrade a state from the version it's keyed by to the next one,
+// returning a description of the change for the log
+var migrations = map[int]func(*State) string{}
+
+// migrate upgrades a state read from path to CurrentVersion, logging each
+// step, and reports whether anything changed. A state without a version
+// predates the field and is version 1. A state from a newer thop is an error
+// rather than something to misread.
+func migrate(path string, state *State) (bool, error) {
+	migrated := false
+	if state.Version == 0 {
+		state.Version = 1
+		logger.Info("state file %s has no version, treating it as version 1", path)
+		migrated = true
+	}
+	if state.Version > CurrentVersion {
+		return false, fmt.Errorf("state file %s is version %d, newer than the supported version %d", path, state.Version, CurrentVersion)
+	}
+
+	for state.Version < CurrentVersion {
+		step, ok := migrations[state.Version]
+		if !ok {
+			return false, fmt.Errorf("no migration for state file version %d", state.Version)
+		}
+		logger.Info("migrated state file %s from version %d to %d: %s", path, state.Version, state.Version+1, step(state))
+		state.Version++
+		migrated = true
+	}
+	return migrated, nil
+}
@@ -2,30 +2,70 @@ package state
 
 import (
 	"encoding/json"
+	"errors"
 	"fmt"
 	"os"
 	"path/filepath"
+	"strings"
 	"sync"
 	"syscall"
 	"time"
+
+	"github.com/scottgl9/thop/internal/logger"
 )
 
+// errCorrupt marks a state file that exists but can't be parsed
+var errCorrupt = errors.New("failed to parse state file")
+
 // State represents the shared application state
 type State struct {
-	ActiveSession string                  `json:"active_session"`
-	Sessions      map[string]SessionState `json:"sessions"`
-	UpdatedAt     time.Time               `json:"updated_at"`
+	Version         int                     `json:"version"`
+	ActiveSession   string                  `json:"active_session"`
+	ActiveSessionAt time.Time               `json:"active_session_updated_at,omitzero"`
+	Sessions        map[string]SessionState `json:"sessions"`
+	Removed         map[string]time.Time    `json:"removed_sessions,omitempty"` // When each removed session was removed, so older copies don't bring it back
+	Snapshots       map[string]Snapshot     `json:"snapshots,omitempty"`
+	UpdatedAt       time.Time               `json:"updated_at"`
 }
 
 // SessionState represents the state of a single session
 type SessionState struct {
-	Type      string            `json:"type"`
-	Connected bool              `json:"connected"`
+	Type      string               `json:"type"`
+	Connected bool                 `json:"connected"`
+	CWD       string               `json:"cwd"`
+	Env       map[string]string    `json:"env"`
+	UpdatedAt map[string]time.Time `json:"updated_at,omitempty"` // When each field ("cwd", "env.NAME", ...) was last set, for merging
+}
+
+// Field names in SessionState.UpdatedAt; each environment variable is a
+// field of its own, named by envField
+const (
+	fieldType      = "type"
+	fieldConnected = "connected"
+	fieldCWD       = "cwd"
+)
+
+// envField returns the UpdatedAt field name of an environment variable
+func envField(key string) string {
+	return "env." + key
+}
+
+// tombstoneTTL is how long a removed session's tombstone is kept. Managers
+// holding a copy from before the removal are expected to save within it.
+const tombstoneTTL = 30 * 24 * time.Hour
+
+// Snapshot is a named copy of a session's working directory and environment,
+// saved by /snapshot save
+type Snapshot struct {
+	Session   string            `json:"session"`
 	CWD       string            `json:"cwd"`
 	Env       map[string]string `json:"env"`
+	CreatedAt time.Time         `json:"created_at"`
 }
 
-// Manager handles state persistence
+// Manager handles state persistence. Several managers, in this process or
+// others, may share a state file: each save takes an exclusive lock on
+// path+".lock" and merges with what the others saved before writing.
 type Manager struct {
 	path  string
 	mu    sync.Mutex
@@ -37,6 +77,7 @@ func NewManager(path string) *Manager {
 	return &Manager{
 		path: path,
 		state: &State{
+			Version:       CurrentVersion,
 			ActiveSession: "local",
 			Sessions:      make(map[string]SessionState),
 			UpdatedAt:     time.Now(),
@@ -49,16 +90,23 @@ func (m *Manager) Load() error {
 	m.mu.Lock()
 	defer m.mu.Unlock()
 
-	// Ensure directory exists
-	dir := filepath.Dir(m.path)
-	if err := os.MkdirAll(dir, 0700); err != nil {
-		return fmt.Errorf("failed to create state directory: %w", err)
+	// Exclusive, since a missing file is created with the defaults and an old
+	// one is re-saved once migrated
+	unlock, err := m.lockFile(syscall.LOCK_EX)
+	if err != nil {
+		return err
 	}
+	defer unlock()
 
-	// Check if file exists
-	if _, err := os.Stat(m.path); os.IsNotExist(err) {
+	state, err := m.readState()
+	if err != nil {
+		return err
+	}
+
+	if state == nil {
 		// Initialize with defaults
 		m.state = &State{
+			Version:       CurrentVersion,
 			ActiveSession: "local",
 			Sessions: map[string]SessionState{
 				"local": {
@@ -70,22 +118,17 @@ func (m *Manager) Load() error {
 			},
 			UpdatedAt: time.Now(),
 		}
-		return m.saveWithLock()
+		return m.write()
 	}
 
-	// Read file with lock
-	data, err := m.readWithLock()
+	migrated, err := migrate(m.path, state)
 	if err != nil {
 		return err
 	}
-
-	// Parse JSON
-	var state State
-	if err := json.Unmarshal(data, &state); err != nil {
-		return fmt.Errorf("failed to parse state file: %w", err)
+	m.state = state
+	if migrated {
+		return m.write()
 	}
-
-	m.state = &state
 	return nil
 }
 
@@ -96,59 +139,162 @@ func (m *Manager) Save() error {
 	return m.saveWithLock()
 }
 
-// saveWithLock saves state (caller must hold lock)
+// saveWithLock saves state (caller must hold lock). The state file is
+// re-read under the file lock first and merged with the in-memory state, so
+// changes saved by other managers since this one last read it survive.
 func (m *Manager) saveWithLock() error {
-	m.state.UpdatedAt = time.Now()
-
-	data, err := json.MarshalIndent(m.state, "", "  ")
+	unlock, err := m.lockFile(syscall.LOCK_EX)
 	if err != nil {
-		return fmt.Errorf("failed to marshal state: %w", err)
+		return err
 	}
-
-	return m.writeWithLock(data)
+	defer unlock()
+
+	disk, err := m.readState()
+	if errors.Is(err, errCorrupt) {
+		// Nothing can be merged from it, so save over it rather than failing
+		// every save, keeping the bad copy aside
+		corrupt := m.path + ".corrupt"
+		logger.Warn("%v; moving it to %s and saving over it", err, corrupt)
+		if err := os.Rename(m.path, corrupt); err != nil {
+			logger.Warn("failed to move corrupt state file aside: %v", err)
+		}
+		disk, err = nil, nil
+	}
+	if err != nil {
+		return err
+	}
+	if disk != nil {
+		if _, err := migrate(m.path, disk); err != nil {
+			return err
+		}
+		m.merge(disk)
+	}
+	return m.write()
 }
 
-// readWithLock reads state file with file locking
-func (m *Manager) readWithLock() ([]byte, error) {
-	file, err := os.OpenFile(m.path, os.O_RDONLY, 0600)
-	if err != nil {
-		return nil, fmt.Errorf("failed to open state file: %w", err)
+// merge folds the on-disk state into the in-memory state. Sessions and
+// snapshots are the union of both. Where both have a session, each field
+// keeps the newer value by its updated_at time; a removal wins over fields
+// set before it. The newer snapshot (by created_at) and active session win.
+func (m *Manager) merge(disk *State) {
+	if disk.ActiveSessionAt.After(m.state.ActiveSessionAt) {
+		m.state.ActiveSession = disk.ActiveSession
+		m.state.ActiveSessionAt = disk.ActiveSessionAt
+	}
+
+	if m.state.Sessions == nil {
+		m.state.Sessions = make(map[string]SessionState)
+	}
+	for name, state := range disk.Sessions {
+		if current, ok := m.state.Sessions[name]; ok {
+			state = mergeSessionState(current, state)
+		}
+		m.state.Sessions[name] = state
 	}
-	defer file.Close()
 
-	// Acquire shared lock
-	if err := syscall.Flock(int(file.Fd()), syscall.LOCK_SH); err != nil {
-		return nil, fmt.Errorf("failed to acquire read lock: %w", err)
+	for name, removedAt := range disk.Removed {
+		if removedAt.After(m.state.Removed[name]) {
+			if m.state.Removed == nil {
+				m.state.Removed = make(map[string]time.Time)
+			}
+			m.state.Removed[name] = removedAt
+		}
+	}
+	for name, removedAt := range m.state.Removed {
+		if time.Since(removedAt) > tombstoneTTL {
+			delete(m.state.Removed, name)
+			continue
+		}
+		if state, ok := m.state.Sessions[name]; ok {
+			if kept, ok := setSince(state, removedAt); ok {
+				m.state.Sessions[name] = kept
+			} else {
+				delete(m.state.Sessions, name)
+			}
+		}
 	}
-	defer func() { _ = syscall.Flock(int(file.Fd()), syscall.LOCK_UN) }()
 
-	return os.ReadFile(m.path)
+	for name, snapshot := range disk.Snapshots {
+		if current, ok := m.state.Snapshots[name]; !ok || snapshot.CreatedAt.After(current.CreatedAt) {
+			if m.state.Snapshots == nil {
+				m.state.Snapshots = make(map[string]Snapshot)
+			}
+			m.state.Snapshots[name] = snapshot
+		}
+	}
 }
 
-// writeWithLock writes state file with file locking
-func (m *Manager) writeWithLock(data []byte) error {
-	// Ensure directory exists
+// lockFile takes an advisory lock (syscall.LOCK_SH or LOCK_EX) on the state
+// file's lock file and returns a function releasing it. The state file itself
+// isn't locked since writes replace it.
+func (m *Manager) lockFile(how int) (func(), error) {
 	dir := filepath.Dir(m.path)
 	if err := os.MkdirAll(dir, 0700); err != nil {
-		return fmt.Errorf("failed to create state directory: %w", err)
+		return nil, fmt.Errorf("failed to create state directory: %w", err)
 	}
 
-	file, err := os.OpenFile(m.path, os.O_WRONLY|os.O_CREATE|os.O_TRUNC, 0600)
+	file, err := os.OpenFile(m.path+".lock", os.O_RDWR|os.O_CREATE, 0600)
 	if err != nil {
-		return fmt.Errorf("failed to open state file for writing: %w", err)
+		return nil, fmt.Errorf("failed to open state lock file: %w", err)
+	}
+	if err := syscall.Flock(int(file.Fd()), how); err != nil {
+		_ = file.Close()
+		return nil, fmt.Errorf("failed to lock state file: %w", err)
 	}
-	defer file.Close()
+	return func() {
+		_ = syscall.Flock(int(file.Fd()), syscall.LOCK_UN)
+		_ = file.Close()
+	}, nil
+}
 
-	// Acquire exclusive lock
-	if err := syscall.Flock(int(file.Fd()), syscall.LOCK_EX); err != nil {
-		return fmt.Errorf("failed to acquire write lock: %w", err)
+// readState reads and parses the state file (caller must hold the file
+// lock), returning nil if it doesn't exist
+func (m *Manager) readState() (*State, error) {
+	data, err := os.ReadFile(m.path)
+	if os.IsNotExist(err) {
+		return nil, nil
+	}
+	if err != nil {
+		return nil, fmt.Errorf("failed to read state file: %w", err)
 	}
-	defer func() { _ = syscall.Flock(int(file.Fd()), syscall.LOCK_UN) }()
 
-	if _, err := file.Write(data); err != nil {
-		return fmt.Errorf("failed to write state file: %w", err)
+	var state State
+	if err := json.Unmarshal(data, &state); err != nil {
+		return nil, fmt.Errorf("%w: %v", errCorrupt, err)
 	}
+	return &state, nil
+}
+
+// write writes the in-memory state to the state file (caller must hold the
+// file lock). It writes a temporary file and renames it into place so a
+// reader never sees a partial file.
+func (m *Manager) write() error {
+	m.state.UpdatedAt = time.Now()
+
+	data, err := json.MarshalIndent(m.state, "", "  ")
+	if err != nil {
+		return fmt.Errorf("failed to marshal state: %w", err)
+	}
+
+	tmp, err := os.CreateTemp(filepath.Dir(m.path), "."+filepath.Base(m.path)+".*.tmp")
+	if err != nil {
+		return fmt.Errorf("failed to open state file for writing: %w", err)
+	}
+	tmpPath := tmp.Name()
 
+	if _, err := tmp.Write(data); err != nil {
+		_ = tmp.Close()
+		_ = os.Remove(tmpPath)
+		return fmt.Errorf("failed to write state file: %w", err)
+	}
+	if err := tmp.Close(); err != nil {
+		_ = os.Remove(tmpPath)
+		return fmt.Errorf("failed to write state file: %w", err)
+	}
+	if err := os.Rename(tmpPath, m.path); err != nil {
+		_ = os.Remove(tmpPath)
+		return fmt.Errorf("failed to write state file: %w", err)
+	}
 	return nil
 }
 
@@ -165,6 +311,7 @@ func (m *Manager) SetActiveSession(name string) error {
 	defer m.mu.Unlock()
 
 	m.state.ActiveSession = name
+	m.state.ActiveSessionAt = time.Now()
 	return m.saveWithLock()
 }
 
@@ -185,7 +332,7 @@ func (m *Manager) UpdateSessionState(name string, state SessionState) error {
 	m.mu.Lock()
 	defer m.mu.Unlock()
 
-	m.state.Sessions[name] = state
+	m.setSession(name, state, stateFields(state)...)
 	return m.saveWithLock()
 }
 
@@ -196,12 +343,12 @@ func (m *Manager) SetSessionConnected(name string, connected bool) error {
 
 	if state, ok := m.state.Sessions[name]; ok {
 		state.Connected = connected
-		m.state.Sessions[name] = state
+		m.setSession(name, state, fieldConnected)
 	} else {
-		m.state.Sessions[name] = SessionState{
+		m.setSession(name, SessionState{
 			Connected: connected,
 			Env:       make(map[string]string),
-		}
+		}, fieldConnected)
 	}
 
 	return m.saveWithLock()
@@ -214,17 +361,180 @@ func (m *Manager) SetSessionCWD(name string, cwd string) error {
 
 	if state, ok := m.state.Sessions[name]; ok {
 		state.CWD = cwd
-		m.state.Sessions[name] = state
+		m.setSession(name, state, fieldCWD)
 	} else {
-		m.state.Sessions[name] = SessionState{
+		m.setSession(name, SessionState{
 			CWD: cwd,
 			Env: make(map[string]string),
-		}
+		}, fieldCWD)
+	}
+
+	return m.saveWithLock()
+}
+
+// RenameSession moves a session's state to a new name, along with the active
+// session if it pointed at the old name
+func (m *Manager) RenameSession(oldName, newName string) error {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+
+	if state, ok := m.state.Sessions[oldName]; ok {
+		m.setSession(newName, state, stateFields(state)...)
+		m.remove(oldName)
+	}
+	if m.state.ActiveSession == oldName {
+		m.state.ActiveSession = newName
+		m.state.ActiveSessionAt = time.Now()
 	}
+	return m.saveWithLock()
+}
+
+// SaveSnapshot stores a snapshot under name, replacing any with that name
+func (m *Manager) SaveSnapshot(name string, snapshot Snapshot) error {
+	m.mu.Lock()
+	defer m.mu.Unlock()
 
+	if m.state.Snapshots == nil {
+		m.state.Snapshots = make(map[string]Snapshot)
+	}
+	m.state.Snapshots[name] = snapshot
 	return m.saveWithLock()
 }
 
+// GetSnapshot returns the snapshot saved under name
+func (m *Manager) GetSnapshot(name string) (Snapshot, bool) {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+
+	snapshot, ok := m.state.Snapshots[name]
+	return snapshot, ok
+}
+
+// GetSnapshots returns all saved snapshots
+func (m *Manager) GetSnapshots() map[string]Snapshot {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+
+	// Return a copy
+	snapshots := make(map[string]Snapshot, len(m.state.Snapshots))
+	for k, v := range m.state.Snapshots {
+		snapshots[k] = v
+	}
+	return snapshots
+}
+
+// RemoveSession forgets a session's state
+func (m *Manager) RemoveSession(name string) error {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+
+	m.remove(name)
+	return m.saveWithLock()
+}
+
+// setSession stores a session's state with the given fields stamped as set
+// now, so merges keep the newest value of each (caller must hold lock)
+func (m *Manager) setSession(name string, state SessionState, fields ...string) {
+	now := time.Now()
+	updated := make(map[string]time.Time, len(state.UpdatedAt)+len(fields))
+	for field, at := range state.UpdatedAt {
+		updated[field] = at
+	}
+	for _, field := range fields {
+		updated[field] = now
+	}
+	state.UpdatedAt = updated
+	m.state.Sessions[name] = state
+}
+
+// remove deletes a session's state, leaving a tombstone so merges drop
+// copies of it from before the removal (caller must hold lock)
+func (m *Manager) remove(name string) {
+	delete(m.state.Sessions, name)
+	if m.state.Removed == nil {
+		m.state.Removed = make(map[string]time.Time)
+	}
+	m.state.Removed[name] = time.Now()
+}
+
+// stateFields returns the names of all of a session's fields
+func stateFields(state SessionState) []string {
+	fields := []string{fieldType, fieldConnected, fieldCWD}
+	for key := range state.Env {
+		fields = append(fields, envField(key))
+	}
+	return fields
+}
+
+// mergeSessionState merges two copies of a session's state field by field,
+// keeping whichever value was set later (mine on a tie)
+func mergeSessionState(mine, theirs SessionState) SessionState {
+	newer := func(field string) bool {
+		return theirs.UpdatedAt[field].After(mine.UpdatedAt[field])
+	}
+
+	merged := mine
+	merged.Env = make(map[string]string, len(mine.Env))
+	merged.UpdatedAt = make(map[string]time.Time, len(mine.UpdatedAt))
+	for field, at := range mine.UpdatedAt {
+		merged.UpdatedAt[field] = at
+	}
+	for field, at := range theirs.UpdatedAt {
+		if at.After(merged.UpdatedAt[field]) {
+			merged.UpdatedAt[field] = at
+		}
+	}
+
+	if newer(fieldType) {
+		merged.Type = theirs.Type
+	}
+	if newer(fieldConnected) {
+		merged.Connected = theirs.Connected
+	}
+	if newer(fieldCWD) {
+		merged.CWD = theirs.CWD
+	}
+	for key, value := range mine.Env {
+		merged.Env[key] = value
+	}
+	for key, value := range theirs.Env {
+		if _, ok := mine.Env[key]; !ok || newer(envField(key)) {
+			merged.Env[key] = value
+		}
+	}
+	return merged
+}
+
+// setSince returns a session's state with only the fields set after t,
+// reporting whether there are any
+func setSince(state SessionState, t time.Time) (SessionState, bool) {
+	kept := SessionState{
+		Env:       make(map[string]string),
+		UpdatedAt: make(map[string]time.Time),
+	}
+	for field, at := range state.UpdatedAt {
+		if !at.After(t) {
+			continue
+		}
+		kept.UpdatedAt[field] = at
+		switch field {
+		case fieldType:
+			kept.Type = state.Type
+		case fieldConnected:
+			kept.Connected = state.Connected
+		case fieldCWD:
+			kept.CWD = state.CWD
+		default:
+			if key := strings.TrimPrefix(field, "env."); key != field {
+				if value, ok := state.Env[key]; ok {
+					kept.Env[key] = value
+				}
+			}
+		}
+	}
+	return kept, len(kept.UpdatedAt) > 0
+}
+
 // GetAllSessions returns all session states
 func (m *Manager) GetAllSessions() map[string]SessionState {
 	m.mu.Lock()
@@ -248,11 +558,11 @@ func (m *Manager) SetSessionEnv(name, key, value string) error {
 			state.Env = make(map[string]string)
 		}
 		state.Env[key] = value
-		m.state.Sessions[name] = state
+		m.setSession(name, state, envField(key))
 	} else {
-		m.state.Sessions[name] = SessionState{
+		m.setSession(name, SessionState{
 			Env: map[string]string{key: value},
-		}
+		}, envField(key))
 	}
 
 	return m.saveWithLock()
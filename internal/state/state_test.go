@@ -2,6 +2,7 @@ package state
 
 import (
 	"encoding/json"
+	"fmt"
 	"os"
 	"path/filepath"
 	"testing"
@@ -297,3 +298,344 @@ func TestConcurrentAccess(t *testing.T) {
 		t.Error("expected CWD to be set")
 	}
 }
+
+func TestConcurrentManagers(t *testing.T) {
+	tmpDir := t.TempDir()
+	statePath := filepath.Join(tmpDir, "state.json")
+
+	// Two managers sharing a file, as background jobs and other thop
+	// processes do
+	mgrA := NewManager(statePath)
+	mgrB := NewManager(statePath)
+	if err := mgrA.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	if err := mgrB.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+
+	done := make(chan error, 2)
+	for _, w := range []struct {
+		mgr    *Manager
+		prefix string
+	}{{mgrA, "a"}, {mgrB, "b"}} {
+		go func(mgr *Manager, prefix string) {
+			for i := 0; i < 10; i++ {
+				if err := mgr.SetSessionCWD(fmt.Sprintf("%s%d", prefix, i), "/tmp"); err != nil {
+					done <- err
+					return
+				}
+			}
+			done <- nil
+		}(w.mgr, w.prefix)
+	}
+	for i := 0; i < 2; i++ {
+		if err := <-done; err != nil {
+			t.Fatalf("SetSessionCWD failed: %v", err)
+		}
+	}
+
+	reloaded := NewManager(statePath)
+	if err := reloaded.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	sessions := reloaded.GetAllSessions()
+	for _, prefix := range []string{"a", "b"} {
+		for i := 0; i < 10; i++ {
+			name := fmt.Sprintf("%s%d", prefix, i)
+			if _, ok := sessions[name]; !ok {
+				t.Errorf("session %q was lost", name)
+			}
+		}
+	}
+}
+
+func TestRemoveSessionNotMergedBack(t *testing.T) {
+	tmpDir := t.TempDir()
+	statePath := filepath.Join(tmpDir, "state.json")
+
+	mgr := NewManager(statePath)
+	if err := mgr.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	if err := mgr.SetSessionCWD("old", "/tmp"); err != nil {
+		t.Fatalf("SetSessionCWD failed: %v", err)
+	}
+	if err := mgr.RemoveSession("old"); err != nil {
+		t.Fatalf("RemoveSession failed: %v", err)
+	}
+
+	reloaded := NewManager(statePath)
+	if err := reloaded.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	if _, ok := reloaded.GetSessionState("old"); ok {
+		t.Error("removed session came back from disk")
+	}
+}
+
+func TestSaveMergesSessions(t *testing.T) {
+	tmpDir := t.TempDir()
+	statePath := filepath.Join(tmpDir, "state.json")
+
+	mgrA := NewManager(statePath)
+	mgrB := NewManager(statePath)
+	if err := mgrA.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	if err := mgrB.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+
+	if err := mgrA.SetSessionConnected("x", true); err != nil {
+		t.Fatalf("SetSessionConnected failed: %v", err)
+	}
+	if err := mgrB.SetSessionCWD("y", "/srv"); err != nil {
+		t.Fatalf("SetSessionCWD failed: %v", err)
+	}
+	if err := mgrA.Save(); err != nil {
+		t.Fatalf("Save failed: %v", err)
+	}
+
+	for _, mgr := range []*Manager{mgrA, NewManager(statePath)} {
+		if err := mgr.Load(); err != nil {
+			t.Fatalf("Load failed: %v", err)
+		}
+		if x, ok := mgr.GetSessionState("x"); !ok || !x.Connected {
+			t.Errorf("expected x connected, got %+v", x)
+		}
+		if y, ok := mgr.GetSessionState("y"); !ok || y.CWD != "/srv" {
+			t.Errorf("expected y cwd /srv, got %+v", y)
+		}
+	}
+}
+
+func TestSaveKeepsNewestSession(t *testing.T) {
+	tmpDir := t.TempDir()
+	statePath := filepath.Join(tmpDir, "state.json")
+
+	mgrA := NewManager(statePath)
+	if err := mgrA.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	if err := mgrA.SetSessionCWD("x", "/old"); err != nil {
+		t.Fatalf("SetSessionCWD failed: %v", err)
+	}
+
+	// B reads x before A changes it, so B's copy is stale
+	mgrB := NewManager(statePath)
+	if err := mgrB.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	if err := mgrA.SetSessionCWD("x", "/new"); err != nil {
+		t.Fatalf("SetSessionCWD failed: %v", err)
+	}
+	if err := mgrA.SetActiveSession("x"); err != nil {
+		t.Fatalf("SetActiveSession failed: %v", err)
+	}
+	if err := mgrB.SetSessionCWD("y", "/srv"); err != nil {
+		t.Fatalf("SetSessionCWD failed: %v", err)
+	}
+
+	if x, _ := mgrB.GetSessionState("x"); x.CWD != "/new" {
+		t.Errorf("expected B to pick up x cwd /new, got %q", x.CWD)
+	}
+	if active := mgrB.GetActiveSession(); active != "x" {
+		t.Errorf("expected B to pick up active session x, got %q", active)
+	}
+
+	reloaded := NewManager(statePath)
+	if err := reloaded.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	if x, _ := reloaded.GetSessionState("x"); x.CWD != "/new" {
+		t.Errorf("stale x cwd overwrote the newer one: got %q", x.CWD)
+	}
+	if _, ok := reloaded.GetSessionState("y"); !ok {
+		t.Error("expected y to be saved")
+	}
+}
+
+func TestLoadMigratesUnversionedState(t *testing.T) {
+	tmpDir := t.TempDir()
+	statePath := filepath.Join(tmpDir, "state.json")
+
+	v1 := `{
+  "active_session": "prod",
+  "sessions": {
+    "prod": {"type": "ssh", "connected": true, "cwd": "/var/www", "env": {}}
+  },
+  "updated_at": "2025-01-01T00:00:00Z"
+}`
+	if err := os.WriteFile(statePath, []byte(v1), 0600); err != nil {
+		t.Fatal(err)
+	}
+
+	mgr := NewManager(statePath)
+	if err := mgr.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	if mgr.GetActiveSession() != "prod" {
+		t.Errorf("expected active session 'prod', got '%s'", mgr.GetActiveSession())
+	}
+	if prod, ok := mgr.GetSessionState("prod"); !ok || prod.CWD != "/var/www" {
+		t.Errorf("expected prod cwd /var/www, got %+v", prod)
+	}
+
+	data, err := os.ReadFile(statePath)
+	if err != nil {
+		t.Fatalf("failed to read state file: %v", err)
+	}
+	var state State
+	if err := json.Unmarshal(data, &state); err != nil {
+		t.Fatalf("failed to parse state JSON: %v", err)
+	}
+	if state.Version != CurrentVersion {
+		t.Errorf("expected the state file to be re-saved with version %d, got %d", CurrentVersion, state.Version)
+	}
+}
+
+func TestLoadRejectsNewerVersion(t *testing.T) {
+	tmpDir := t.TempDir()
+	statePath := filepath.Join(tmpDir, "state.json")
+
+	newer := fmt.Sprintf(`{"version": %d, "active_session": "local", "sessions": {}}`, CurrentVersion+1)
+	if err := os.WriteFile(statePath, []byte(newer), 0600); err != nil {
+		t.Fatal(err)
+	}
+
+	if err := NewManager(statePath).Load(); err == nil {
+		t.Error("expected an error loading a state file from a newer version")
+	}
+}
+
+func TestSaveOverCorruptFile(t *testing.T) {
+	tmpDir := t.TempDir()
+	statePath := filepath.Join(tmpDir, "state.json")
+	if err := os.WriteFile(statePath, []byte(`{"active_session": "lo`), 0600); err != nil {
+		t.Fatal(err)
+	}
+
+	// Load fails, and thop carries on with defaults
+	mgr := NewManager(statePath)
+	if err := mgr.Load(); err == nil {
+		t.Fatal("expected Load to fail on a truncated file")
+	}
+
+	if err := mgr.SetSessionCWD("test", "/tmp"); err != nil {
+		t.Fatalf("SetSessionCWD over a corrupt file failed: %v", err)
+	}
+
+	reloaded := NewManager(statePath)
+	if err := reloaded.Load(); err != nil {
+		t.Fatalf("Load after saving over the corrupt file failed: %v", err)
+	}
+	if state, ok := reloaded.GetSessionState("test"); !ok || state.CWD != "/tmp" {
+		t.Errorf("expected the saved session, got %+v", state)
+	}
+	if data, err := os.ReadFile(statePath + ".corrupt"); err != nil || string(data) != `{"active_session": "lo` {
+		t.Errorf("expected the corrupt file to be kept aside, got %q, %v", data, err)
+	}
+}
+
+func TestSaveMergesFieldsOfOneSession(t *testing.T) {
+	tmpDir := t.TempDir()
+	statePath := filepath.Join(tmpDir, "state.json")
+
+	mgrA := NewManager(statePath)
+	if err := mgrA.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	if err := mgrA.SetSessionCWD("x", "/old"); err != nil {
+		t.Fatalf("SetSessionCWD failed: %v", err)
+	}
+	mgrB := NewManager(statePath)
+	if err := mgrB.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+
+	// Each changes a different field of x without seeing the other's change
+	if err := mgrA.SetSessionConnected("x", true); err != nil {
+		t.Fatalf("SetSessionConnected failed: %v", err)
+	}
+	if err := mgrA.SetSessionEnv("x", "A", "1"); err != nil {
+		t.Fatalf("SetSessionEnv failed: %v", err)
+	}
+	if err := mgrB.SetSessionCWD("x", "/new"); err != nil {
+		t.Fatalf("SetSessionCWD failed: %v", err)
+	}
+	if err := mgrB.SetSessionEnv("x", "B", "2"); err != nil {
+		t.Fatalf("SetSessionEnv failed: %v", err)
+	}
+
+	reloaded := NewManager(statePath)
+	if err := reloaded.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	x, ok := reloaded.GetSessionState("x")
+	if !ok {
+		t.Fatal("expected x to be saved")
+	}
+	if !x.Connected || x.CWD != "/new" {
+		t.Errorf("expected connected from A and cwd from B, got %+v", x)
+	}
+	if x.Env["A"] != "1" || x.Env["B"] != "2" {
+		t.Errorf("expected env from both managers, got %v", x.Env)
+	}
+}
+
+func TestRemovalWinsOverOlderCopy(t *testing.T) {
+	tmpDir := t.TempDir()
+	statePath := filepath.Join(tmpDir, "state.json")
+
+	mgrA := NewManager(statePath)
+	if err := mgrA.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	if err := mgrA.SetSessionCWD("gone", "/srv"); err != nil {
+		t.Fatalf("SetSessionCWD failed: %v", err)
+	}
+	if err := mgrA.SetSessionCWD("old", "/opt"); err != nil {
+		t.Fatalf("SetSessionCWD failed: %v", err)
+	}
+
+	// B still has both sessions in memory when A removes and renames them
+	mgrB := NewManager(statePath)
+	if err := mgrB.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	if err := mgrA.RemoveSession("gone"); err != nil {
+		t.Fatalf("RemoveSession failed: %v", err)
+	}
+	if err := mgrA.RenameSession("old", "new"); err != nil {
+		t.Fatalf("RenameSession failed: %v", err)
+	}
+	if err := mgrB.SetSessionCWD("other", "/tmp"); err != nil {
+		t.Fatalf("SetSessionCWD failed: %v", err)
+	}
+
+	reloaded := NewManager(statePath)
+	if err := reloaded.Load(); err != nil {
+		t.Fatalf("Load failed: %v", err)
+	}
+	sessions := reloaded.GetAllSessions()
+	for _, name := range []string{"gone", "old"} {
+		if _, ok := sessions[name]; ok {
+			t.Errorf("session %q came back from an older copy", name)
+		}
+	}
+	if renamed, ok := sessions["new"]; !ok || renamed.CWD != "/opt" {
+		t.Errorf("expected the renamed session to keep its cwd, got %+v", renamed)
+	}
+	if _, ok := sessions["other"]; !ok {
+		t.Error("expected B's own change to be saved")
+	}
+
+	// A session set again after its removal is kept
+	if err := mgrB.SetSessionCWD("gone", "/again"); err != nil {
+		t.Fatalf("SetSessionCWD failed: %v", err)
+	}
+	if gone, ok := mgrB.GetSessionState("gone"); !ok || gone.CWD != "/again" {
+		t.Errorf("expected a session set after its removal to be kept, got %+v", gone)
+	}
+}